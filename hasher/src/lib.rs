@@ -1,8 +1,10 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+pub mod merkle;
 pub mod poseidon;
 pub mod roinput;
+pub use merkle::{MerkleHasher, MerklePath, MerkleTree, SparseMerkleTree};
 pub use mina_curves::pasta::Fp;
 pub use poseidon::{PoseidonHasherKimchi, PoseidonHasherLegacy};
 pub use roinput::ROInput;
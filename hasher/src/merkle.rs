@@ -0,0 +1,445 @@
+//! Merkle trees
+//!
+//! Binary Merkle trees of fixed depth whose nodes are hashed with Poseidon, following
+//! the Mina ledger: the parent of two nodes `left` and `right` of height `h` is the hash
+//! of `[left, right]` with the domain string `MinaMklTree{h:03}`, leaves being at height 0.
+//! Empty subtrees hash to the root of a subtree whose leaves are all the empty leaf (for
+//! the Mina ledger, the hash of the empty account).
+//!
+//! Two trees are provided: the append-only [`MerkleTree`] and the [`SparseMerkleTree`],
+//! which stores only the nodes above non-empty leaves. Both produce [`MerklePath`]s,
+//! which are inclusion proofs of their leaves or, for the empty leaf, exclusion proofs.
+
+use crate::{create_kimchi, create_legacy, poseidon::Poseidon, Fp, Hashable, Hasher, ROInput};
+use mina_poseidon::constants::{
+    PlonkSpongeConstantsKimchi, PlonkSpongeConstantsLegacy, SpongeConstants,
+};
+use std::collections::{BTreeMap, HashMap};
+use thiserror::Error;
+
+/// Depth of the Mina ledger
+pub const MINA_LEDGER_DEPTH: u32 = 35;
+
+/// Maximum depth of a Merkle tree, so that leaf indices fit in a `u64`
+pub const MAX_DEPTH: u32 = 63;
+
+/// Merkle tree errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MerkleTreeError {
+    /// Depth larger than [`MAX_DEPTH`]
+    #[error("the depth {0} exceeds the maximum depth {MAX_DEPTH}")]
+    Depth(u32),
+    /// No room left for another leaf
+    #[error("the tree is full")]
+    Full,
+    /// Leaf index not in the tree
+    #[error("the leaf index {0} is out of range")]
+    Index(u64),
+}
+
+/// Merkle tree result type
+pub type Result<T> = std::result::Result<T, MerkleTreeError>;
+
+/// A node of a Merkle tree, given by its two children
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerkleTreeNode {
+    /// Left child
+    pub left: Fp,
+    /// Right child
+    pub right: Fp,
+}
+
+impl Hashable for MerkleTreeNode {
+    type D = u32;
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new()
+            .append_field(self.left)
+            .append_field(self.right)
+    }
+
+    fn domain_string(height: Self::D) -> Option<String> {
+        format!("MinaMklTree{height:03}").into()
+    }
+}
+
+/// Interface for hashing the nodes of a Merkle tree
+pub trait MerkleHasher {
+    /// Hash of the parent of the nodes `left` and `right` of height `height`
+    fn merge(&mut self, height: u32, left: Fp, right: Fp) -> Fp;
+}
+
+/// Poseidon Merkle tree hasher
+///
+/// Keeps one hasher context per height, so that the domain separation of each height
+/// is only computed once.
+pub struct PoseidonMerkleHasher<SC: SpongeConstants> {
+    hashers: Vec<Poseidon<SC, MerkleTreeNode>>,
+    create: fn(u32) -> Poseidon<SC, MerkleTreeNode>,
+}
+
+/// Merkle tree hasher using the legacy hasher
+pub type MerkleHasherLegacy = PoseidonMerkleHasher<PlonkSpongeConstantsLegacy>;
+
+/// Merkle tree hasher using the kimchi hasher
+pub type MerkleHasherKimchi = PoseidonMerkleHasher<PlonkSpongeConstantsKimchi>;
+
+impl MerkleHasherLegacy {
+    /// Create a Merkle tree hasher using the legacy hasher
+    pub fn legacy() -> Self {
+        PoseidonMerkleHasher {
+            hashers: vec![],
+            create: create_legacy::<MerkleTreeNode>,
+        }
+    }
+}
+
+impl MerkleHasherKimchi {
+    /// Create a Merkle tree hasher using the kimchi hasher
+    pub fn kimchi() -> Self {
+        PoseidonMerkleHasher {
+            hashers: vec![],
+            create: create_kimchi::<MerkleTreeNode>,
+        }
+    }
+}
+
+impl<SC: SpongeConstants> MerkleHasher for PoseidonMerkleHasher<SC> {
+    fn merge(&mut self, height: u32, left: Fp, right: Fp) -> Fp {
+        let height = height as usize;
+        while self.hashers.len() <= height {
+            let hasher = (self.create)(self.hashers.len() as u32);
+            self.hashers.push(hasher);
+        }
+        self.hashers[height].hash(&MerkleTreeNode { left, right })
+    }
+}
+
+/// Hashes of the empty subtrees of each height, up to `depth`
+fn empty_hashes<M: MerkleHasher>(hasher: &mut M, depth: u32, empty_leaf: Fp) -> Vec<Fp> {
+    let mut empty = vec![empty_leaf];
+    for height in 0..depth {
+        let child = empty[height as usize];
+        empty.push(hasher.merge(height, child, child));
+    }
+    empty
+}
+
+/// Check the depth of a tree and compute its capacity
+fn capacity(depth: u32) -> Result<u64> {
+    if depth > MAX_DEPTH {
+        return Err(MerkleTreeError::Depth(depth));
+    }
+    Ok(1 << depth)
+}
+
+/// Path from a leaf to the root of a Merkle tree
+///
+/// The siblings are ordered from the leaf to the root, and the bits of the leaf index
+/// tell whether each node of the path is a left (0) or a right (1) child.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    /// Index of the leaf
+    pub index: u64,
+    /// Siblings of the nodes of the path
+    pub siblings: Vec<Fp>,
+}
+
+impl MerklePath {
+    /// Depth of the tree
+    pub fn depth(&self) -> u32 {
+        self.siblings.len() as u32
+    }
+
+    /// Compute the root of the tree with `leaf` at the end of the path
+    pub fn compute_root<M: MerkleHasher>(&self, hasher: &mut M, leaf: Fp) -> Fp {
+        self.siblings
+            .iter()
+            .enumerate()
+            .fold(leaf, |node, (height, sibling)| {
+                if (self.index >> height) & 1 == 0 {
+                    hasher.merge(height as u32, node, *sibling)
+                } else {
+                    hasher.merge(height as u32, *sibling, node)
+                }
+            })
+    }
+
+    /// Check that `leaf` is at the end of the path in the tree of root `root`
+    ///
+    /// Verifying the empty leaf proves that the leaf index is not used in the tree.
+    pub fn verify<M: MerkleHasher>(&self, hasher: &mut M, leaf: Fp, root: Fp) -> bool {
+        self.compute_root(hasher, leaf) == root
+    }
+}
+
+/// Append-only Merkle tree
+///
+/// Leaves are added from left to right, all the other leaves being empty.
+pub struct MerkleTree<M: MerkleHasher> {
+    hasher: M,
+    depth: u32,
+    capacity: u64,
+    empty: Vec<Fp>,
+    // The nodes of each height that have a non-empty leaf below them, from the leaves
+    // to the root
+    levels: Vec<Vec<Fp>>,
+}
+
+impl<M: MerkleHasher> MerkleTree<M> {
+    /// Create an empty tree of depth `depth`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `depth` exceeds [`MAX_DEPTH`].
+    pub fn new(mut hasher: M, depth: u32, empty_leaf: Fp) -> Result<Self> {
+        let capacity = capacity(depth)?;
+        let empty = empty_hashes(&mut hasher, depth, empty_leaf);
+        Ok(MerkleTree {
+            hasher,
+            depth,
+            capacity,
+            empty,
+            levels: vec![vec![]; depth as usize + 1],
+        })
+    }
+
+    /// Depth of the tree
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Number of leaves appended to the tree
+    pub fn len(&self) -> u64 {
+        self.levels[0].len() as u64
+    }
+
+    /// Whether no leaf has been appended to the tree
+    pub fn is_empty(&self) -> bool {
+        self.levels[0].is_empty()
+    }
+
+    /// Leaves appended to the tree
+    pub fn leaves(&self) -> &[Fp] {
+        &self.levels[0]
+    }
+
+    /// Hasher of the tree
+    pub fn hasher(&mut self) -> &mut M {
+        &mut self.hasher
+    }
+
+    /// Root of the tree
+    pub fn root(&self) -> Fp {
+        self.node(self.depth, 0)
+    }
+
+    /// Append `leaf` to the tree and return its index
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the tree is full.
+    pub fn append(&mut self, leaf: Fp) -> Result<u64> {
+        let index = self.len();
+        if index == self.capacity {
+            return Err(MerkleTreeError::Full);
+        }
+        self.levels[0].push(leaf);
+
+        let mut position = index as usize;
+        for height in 0..self.depth {
+            let parent = position / 2;
+            let left = self.node(height, 2 * parent as u64);
+            let right = self.node(height, 2 * parent as u64 + 1);
+            let node = self.hasher.merge(height, left, right);
+
+            let level = &mut self.levels[height as usize + 1];
+            if parent < level.len() {
+                level[parent] = node;
+            } else {
+                level.push(node);
+            }
+            position = parent;
+        }
+        Ok(index)
+    }
+
+    /// Append all the `leaves` to the tree
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the tree becomes full.
+    pub fn extend<I: IntoIterator<Item = Fp>>(&mut self, leaves: I) -> Result<()> {
+        for leaf in leaves {
+            self.append(leaf)?;
+        }
+        Ok(())
+    }
+
+    /// Path from the leaf `index` to the root, which may be past the appended leaves,
+    /// in which case the path proves that the leaf is empty
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `index` is not smaller than `2^depth`.
+    pub fn witness(&self, index: u64) -> Result<MerklePath> {
+        if index >= self.capacity {
+            return Err(MerkleTreeError::Index(index));
+        }
+        let siblings = (0..self.depth)
+            .map(|height| self.node(height, (index >> height) ^ 1))
+            .collect();
+        Ok(MerklePath { index, siblings })
+    }
+
+    // The node at `position` among the nodes of height `height`
+    fn node(&self, height: u32, position: u64) -> Fp {
+        self.levels[height as usize]
+            .get(position as usize)
+            .copied()
+            .unwrap_or(self.empty[height as usize])
+    }
+}
+
+/// Sparse Merkle tree
+///
+/// Leaves can be set at any index. Only the nodes above non-empty leaves are stored,
+/// so that trees as deep as the Mina ledger can be used.
+pub struct SparseMerkleTree<M: MerkleHasher> {
+    hasher: M,
+    depth: u32,
+    capacity: u64,
+    empty: Vec<Fp>,
+    leaves: BTreeMap<u64, Fp>,
+    // The inner nodes, by height and position, that are not the root of an empty subtree
+    nodes: HashMap<(u32, u64), Fp>,
+}
+
+impl<M: MerkleHasher> SparseMerkleTree<M> {
+    /// Create an empty tree of depth `depth`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `depth` exceeds [`MAX_DEPTH`].
+    pub fn new(mut hasher: M, depth: u32, empty_leaf: Fp) -> Result<Self> {
+        let capacity = capacity(depth)?;
+        let empty = empty_hashes(&mut hasher, depth, empty_leaf);
+        Ok(SparseMerkleTree {
+            hasher,
+            depth,
+            capacity,
+            empty,
+            leaves: BTreeMap::new(),
+            nodes: HashMap::new(),
+        })
+    }
+
+    /// Depth of the tree
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Number of leaves set in the tree
+    pub fn len(&self) -> usize {
+        self.leaves.len()
+    }
+
+    /// Whether no leaf is set in the tree
+    pub fn is_empty(&self) -> bool {
+        self.leaves.is_empty()
+    }
+
+    /// Leaves set in the tree, by increasing index
+    pub fn leaves(&self) -> impl Iterator<Item = (u64, Fp)> + '_ {
+        self.leaves.iter().map(|(index, leaf)| (*index, *leaf))
+    }
+
+    /// Leaf at `index`, if it is set
+    pub fn get(&self, index: u64) -> Option<Fp> {
+        self.leaves.get(&index).copied()
+    }
+
+    /// Hasher of the tree
+    pub fn hasher(&mut self) -> &mut M {
+        &mut self.hasher
+    }
+
+    /// Root of the tree
+    pub fn root(&self) -> Fp {
+        self.node(self.depth, 0)
+    }
+
+    /// Set the leaf at `index` and return the previous one
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `index` is not smaller than `2^depth`.
+    pub fn insert(&mut self, index: u64, leaf: Fp) -> Result<Option<Fp>> {
+        self.check_index(index)?;
+        let previous = self.leaves.insert(index, leaf);
+        self.update_path(index);
+        Ok(previous)
+    }
+
+    /// Unset the leaf at `index` and return it
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `index` is not smaller than `2^depth`.
+    pub fn remove(&mut self, index: u64) -> Result<Option<Fp>> {
+        self.check_index(index)?;
+        let previous = self.leaves.remove(&index);
+        if previous.is_some() {
+            self.update_path(index);
+        }
+        Ok(previous)
+    }
+
+    /// Path from the leaf `index` to the root
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `index` is not smaller than `2^depth`.
+    pub fn witness(&self, index: u64) -> Result<MerklePath> {
+        self.check_index(index)?;
+        let siblings = (0..self.depth)
+            .map(|height| self.node(height, (index >> height) ^ 1))
+            .collect();
+        Ok(MerklePath { index, siblings })
+    }
+
+    fn check_index(&self, index: u64) -> Result<()> {
+        if index >= self.capacity {
+            return Err(MerkleTreeError::Index(index));
+        }
+        Ok(())
+    }
+
+    // Recompute the nodes from the leaf `index` to the root
+    fn update_path(&mut self, index: u64) {
+        let mut position = index;
+        for height in 0..self.depth {
+            let parent = position / 2;
+            let left = self.node(height, 2 * parent);
+            let right = self.node(height, 2 * parent + 1);
+            let node = self.hasher.merge(height, left, right);
+
+            if node == self.empty[height as usize + 1] {
+                self.nodes.remove(&(height + 1, parent));
+            } else {
+                self.nodes.insert((height + 1, parent), node);
+            }
+            position = parent;
+        }
+    }
+
+    // The node at `position` among the nodes of height `height`
+    fn node(&self, height: u32, position: u64) -> Fp {
+        let node = if height == 0 {
+            self.leaves.get(&position)
+        } else {
+            self.nodes.get(&(height, position))
+        };
+        node.copied().unwrap_or(self.empty[height as usize])
+    }
+}
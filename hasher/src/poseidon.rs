@@ -42,6 +42,21 @@ impl<SC: SpongeConstants, H: Hashable> Poseidon<SC, H> {
     }
 }
 
+impl<SC: SpongeConstants, H: Hashable> Poseidon<SC, H>
+where
+    H::D: DomainParameter,
+{
+    /// Hash a message of field elements of arbitrary length, with the domain
+    /// separation set by the most recent initialization
+    pub fn hash_fields(&mut self, input: &[Fp]) -> Fp {
+        self.reset();
+        self.sponge.absorb(input);
+        let output = self.digest();
+        self.reset();
+        output
+    }
+}
+
 /// Poseidon hasher type with legacy plonk sponge constants
 pub type PoseidonHasherLegacy<H> = Poseidon<PlonkSpongeConstantsLegacy, H>;
 
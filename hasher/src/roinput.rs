@@ -1,16 +1,46 @@
 //! Random oracle input structures and algorithms
 //!
 //! Definition of random oracle input structure and
-//! methods for serializing into bytes and field elements
+//! methods for serializing into bytes and field elements,
+//! parsing them back from bytes and representing them as JSON
+
+use std::{fmt, str::FromStr};
 
 use bitvec::{prelude::*, view::AsBits};
 
 use ark_ff::PrimeField;
 use mina_curves::pasta::{Fp, Fq};
 use o1_utils::FieldHelpers;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
 
 use super::Hashable;
 
+/// Random oracle input errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ROInputError {
+    /// Byte length not matching the number of fields and bits
+    #[error("expected {expected} bytes, found {found}")]
+    Length {
+        /// Number of bytes of the serialized input
+        expected: usize,
+        /// Number of bytes given
+        found: usize,
+    },
+    /// Non-zero bits after the last bit of the input
+    #[error("the padding bits are not zero")]
+    Padding,
+    /// Field element not in canonical form
+    #[error("the field element at position {0} is not canonical")]
+    Field(usize),
+    /// Character other than `0` or `1` in a bit string
+    #[error("invalid bit {0:?}")]
+    Bit(char),
+}
+
+/// Random oracle input result type
+pub type Result<T> = std::result::Result<T, ROInputError>;
+
 /// Random oracle input structure
 ///
 /// The random oracle input encapsulates the serialization format and methods using during hashing.
@@ -54,7 +84,18 @@ use super::Hashable;
 /// Additionally, during signing all members of the random oracle input get serialized
 /// together in two different ways: both as *bytes* and as a vector of *field elements*.
 /// The random oracle input automates and encapsulates this complexity.
-#[derive(Default, Debug, Clone, PartialEq, Eq)]
+///
+/// A random oracle input (de)serializes with serde as its canonical JSON representation,
+/// where field elements are decimal strings and bits are a string of `0`s and `1`s, in
+/// the order they were appended
+///
+/// ```json
+/// { "fields": ["1", "2"], "bits": "1011" }
+/// ```
+///
+/// Its [`Display`](fmt::Display) implementation is a pretty-printer meant for debugging.
+#[derive(Default, Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(into = "ROInputJson", try_from = "ROInputJson")]
 pub struct ROInput {
     fields: Vec<Fp>,
     bits: BitVec<u8>,
@@ -173,10 +214,137 @@ impl ROInput {
 
         fields
     }
+
+    /// Base field elements of the random oracle input
+    pub fn fields(&self) -> &[Fp] {
+        &self.fields
+    }
+
+    /// Bits of the random oracle input, in the order they were appended
+    pub fn bits(&self) -> impl ExactSizeIterator<Item = bool> + '_ {
+        self.bits.iter().by_vals()
+    }
+
+    /// Deserialize random oracle input from bytes, the inverse of [`ROInput::to_bytes`]
+    ///
+    /// As the bytes do not delimit the field elements from the bits, the number of
+    /// field elements `fields_len` and of bits `bits_len` must be given.
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` is not the serialization of `fields_len` field elements
+    /// followed by `bits_len` bits.
+    pub fn from_bytes(bytes: &[u8], fields_len: usize, bits_len: usize) -> Result<Self> {
+        let field_size = Fp::MODULUS_BIT_SIZE as usize;
+        let len = fields_len * field_size + bits_len;
+        let expected = (len + 7) / 8;
+        if bytes.len() != expected {
+            return Err(ROInputError::Length {
+                expected,
+                found: bytes.len(),
+            });
+        }
+
+        let bits = bytes.as_bits::<Lsb0>();
+        if bits[len..].any() {
+            return Err(ROInputError::Padding);
+        }
+
+        let fields = bits[..fields_len * field_size]
+            .chunks(field_size)
+            .enumerate()
+            .map(|(i, chunk)| {
+                let mut bv = BitVec::<u8>::new();
+                bv.extend_from_bitslice(chunk);
+                bv.resize((field_size + 7) / 8 * 8, false);
+                Fp::from_bytes(&bv.into_vec()).map_err(|_| ROInputError::Field(i))
+            })
+            .collect::<Result<Vec<Fp>>>()?;
+
+        Ok(ROInput {
+            fields,
+            bits: bits[fields_len * field_size..len].to_bitvec(),
+        })
+    }
+}
+
+impl fmt::Display for ROInput {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "ROInput {{")?;
+        writeln!(f, "  fields ({}):", self.fields.len())?;
+        for (i, field) in self.fields.iter().enumerate() {
+            writeln!(f, "    {i}: {}", field.into_bigint())?;
+        }
+        write!(f, "  bits ({}):", self.bits.len())?;
+        // one line of 8 bytes at a time, prefixed by the offset of its first bit
+        for (i, line) in self.bits.chunks(64).enumerate() {
+            write!(f, "\n    {:>5}:", 64 * i)?;
+            for byte in line.chunks(8) {
+                write!(f, " ")?;
+                for bit in byte {
+                    write!(f, "{}", u8::from(*bit))?;
+                }
+            }
+        }
+        write!(f, "\n}}")
+    }
+}
+
+/// Canonical JSON representation of a random oracle input
+#[derive(Serialize, Deserialize)]
+struct ROInputJson {
+    fields: Vec<String>,
+    bits: String,
+}
+
+impl From<ROInput> for ROInputJson {
+    fn from(roi: ROInput) -> Self {
+        ROInputJson {
+            fields: roi
+                .fields
+                .iter()
+                .map(|f| f.into_bigint().to_string())
+                .collect(),
+            bits: roi.bits().map(|b| if b { '1' } else { '0' }).collect(),
+        }
+    }
+}
+
+impl TryFrom<ROInputJson> for ROInput {
+    type Error = ROInputError;
+
+    fn try_from(json: ROInputJson) -> Result<Self> {
+        let fields = json
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(i, decimal)| {
+                // Fp::from_str reduces modulo p and accepts leading zeros
+                Fp::from_str(decimal)
+                    .ok()
+                    .filter(|f| f.into_bigint().to_string() == *decimal)
+                    .ok_or(ROInputError::Field(i))
+            })
+            .collect::<Result<Vec<Fp>>>()?;
+
+        let bits = json
+            .bits
+            .chars()
+            .map(|c| match c {
+                '0' => Ok(false),
+                '1' => Ok(true),
+                c => Err(ROInputError::Bit(c)),
+            })
+            .collect::<Result<BitVec<u8>>>()?;
+
+        Ok(ROInput { fields, bits })
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use ark_ff::One;
+
     use crate::Hashable;
 
     use super::*;
@@ -908,4 +1076,116 @@ mod tests {
         };
         assert_ne!(b1.to_roinput(), b2.to_roinput());
     }
+
+    fn example_roinput() -> ROInput {
+        ROInput::new()
+            .append_field(Fp::from(42u32))
+            .append_field(-Fp::one())
+            .append_bool(true)
+            .append_u32(5)
+            .append_bool(false)
+            .append_scalar(Fq::from(3u32))
+    }
+
+    #[test]
+    fn from_bytes() {
+        for roi in [
+            ROInput::new(),
+            ROInput::new().append_bool(true),
+            ROInput::new().append_field(Fp::from(7u32)),
+            example_roinput(),
+        ] {
+            let bytes = roi.to_bytes();
+            let parsed = ROInput::from_bytes(&bytes, roi.fields().len(), roi.bits().len())
+                .expect("failed to parse roinput");
+            assert_eq!(parsed, roi);
+            assert_eq!(parsed.to_bytes(), bytes);
+        }
+
+        // 2 fields and 289 bits fit in 2 * 255 + 289 = 799 bits
+        let bytes = example_roinput().to_bytes();
+        assert_eq!(bytes.len(), 100);
+        assert_eq!(
+            ROInput::from_bytes(&bytes, 2, 297),
+            Err(ROInputError::Length {
+                expected: 101,
+                found: 100
+            })
+        );
+        assert_eq!(
+            ROInput::from_bytes(&[0b11], 0, 1),
+            Err(ROInputError::Padding)
+        );
+
+        // Splitting the bits differently gives another input
+        let parsed = ROInput::from_bytes(&bytes, 1, 544).expect("failed to parse roinput");
+        assert_eq!(parsed.fields(), &[Fp::from(42u32)]);
+        assert_eq!(parsed.to_bytes(), bytes);
+
+        // The all-ones 255 bits are larger than the modulus
+        assert_eq!(
+            ROInput::from_bytes(&[0xff; 32], 1, 1),
+            Err(ROInputError::Field(0))
+        );
+    }
+
+    #[test]
+    fn json() {
+        let roi = ROInput::new()
+            .append_field(Fp::from(1u32))
+            .append_field(Fp::from(2u32))
+            .append_bool(true)
+            .append_bool(false)
+            .append_bool(true)
+            .append_bool(true);
+        let json = serde_json::to_string(&roi).expect("failed to serialize roinput");
+        assert_eq!(json, r#"{"fields":["1","2"],"bits":"1011"}"#);
+        assert_eq!(
+            serde_json::from_str::<ROInput>(&json).expect("failed to deserialize roinput"),
+            roi
+        );
+
+        let roi = example_roinput();
+        let json = serde_json::to_string(&roi).expect("failed to serialize roinput");
+        assert_eq!(
+            serde_json::from_str::<ROInput>(&json).expect("failed to deserialize roinput"),
+            roi
+        );
+
+        // The modulus, leading zeros and non-decimal strings are not canonical
+        let modulus = Fp::MODULUS.to_string();
+        for field in [modulus.as_str(), "01", "0x1", ""] {
+            let json = format!(r#"{{"fields":["{field}"],"bits":""}}"#);
+            let err = serde_json::from_str::<ROInput>(&json).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "the field element at position 0 is not canonical"
+            );
+        }
+        let err = serde_json::from_str::<ROInput>(r#"{"fields":[],"bits":"0120"}"#).unwrap_err();
+        assert_eq!(err.to_string(), "invalid bit '2'");
+    }
+
+    #[test]
+    fn display() {
+        let roi = ROInput::new()
+            .append_field(Fp::from(42u32))
+            .append_bool(true)
+            .append_u64(u64::MAX)
+            .append_bool(false);
+        assert_eq!(
+            roi.to_string(),
+            "ROInput {
+  fields (1):
+    0: 42
+  bits (66):
+        0: 11111111 11111111 11111111 11111111 11111111 11111111 11111111 11111111
+       64: 10
+}"
+        );
+        assert_eq!(
+            ROInput::new().to_string(),
+            "ROInput {\n  fields (0):\n  bits (0):\n}"
+        );
+    }
 }
@@ -1,4 +1,7 @@
-use mina_hasher::{create_kimchi, create_legacy, Fp, Hashable, Hasher, ROInput};
+use mina_hasher::{
+    create_kimchi, create_legacy, poseidon::Poseidon, Fp, Hashable, Hasher, ROInput,
+};
+use mina_poseidon::constants::SpongeConstants;
 use o1_utils::FieldHelpers;
 use serde::Deserialize;
 use std::{fs::File, path::PathBuf};
@@ -57,6 +60,34 @@ fn test_vectors(test_vector_file: &str, hasher: &mut dyn Hasher<TestVector>) {
     }
 }
 
+fn test_vectors_fields(
+    test_vector_file: &str,
+    hasher: &mut Poseidon<impl SpongeConstants, TestVector>,
+) {
+    // read test vectors from given file
+    let mut path = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    path.push("../poseidon/tests/test_vectors");
+    path.push(test_vector_file);
+
+    let file = File::open(&path).expect("couldn't open test vector file");
+    let test_vectors: TestVectors =
+        serde_json::from_reader(file).expect("couldn't deserialize test vector file");
+
+    // execute test vectors
+    for test_vector in test_vectors.test_vectors {
+        let input: Vec<Fp> = test_vector
+            .input
+            .iter()
+            .map(|input| Fp::from_hex(input).expect("failed to deserialize field element"))
+            .collect();
+        let expected_output =
+            Fp::from_hex(&test_vector.output).expect("failed to deserialize field element");
+
+        // hash the message & check against expect output
+        assert_eq!(hasher.hash_fields(&input), expected_output);
+    }
+}
+
 //
 // Tests
 //
@@ -73,6 +104,14 @@ fn hasher_test_vectors_kimchi() {
     test_vectors("kimchi.json", &mut hasher);
 }
 
+#[test]
+fn hasher_test_vectors_fields() {
+    let mut hasher = create_legacy::<TestVector>(());
+    test_vectors_fields("legacy.json", &mut hasher);
+    let mut hasher = create_kimchi::<TestVector>(());
+    test_vectors_fields("kimchi.json", &mut hasher);
+}
+
 #[test]
 fn interfaces() {
     #[derive(Clone)]
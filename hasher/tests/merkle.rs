@@ -0,0 +1,172 @@
+use ark_ff::{One, Zero};
+use mina_hasher::{
+    create_kimchi,
+    merkle::{
+        MerkleHasherKimchi, MerkleHasherLegacy, MerkleTreeError, MerkleTreeNode, MAX_DEPTH,
+        MINA_LEDGER_DEPTH,
+    },
+    Fp, Hasher, MerkleHasher, MerkleTree, SparseMerkleTree,
+};
+
+fn leaves(n: u64) -> Vec<Fp> {
+    (1..=n).map(Fp::from).collect()
+}
+
+#[test]
+fn merge_domain_separation() {
+    let mut hasher = MerkleHasherKimchi::kimchi();
+    let (left, right) = (Fp::from(1u32), Fp::from(2u32));
+
+    for height in [0, 1, 34] {
+        let mut node_hasher = create_kimchi::<MerkleTreeNode>(height);
+        assert_eq!(
+            hasher.merge(height, left, right),
+            node_hasher.hash(&MerkleTreeNode { left, right })
+        );
+        assert_eq!(
+            hasher.merge(height, left, right),
+            node_hasher.hash_fields(&[left, right])
+        );
+    }
+    assert_ne!(hasher.merge(0, left, right), hasher.merge(1, left, right));
+    assert_ne!(hasher.merge(0, left, right), hasher.merge(0, right, left));
+    assert_ne!(
+        hasher.merge(0, left, right),
+        MerkleHasherLegacy::legacy().merge(0, left, right)
+    );
+}
+
+#[test]
+fn empty_tree() {
+    let empty_leaf = Fp::from(42u32);
+    let mut hasher = MerkleHasherKimchi::kimchi();
+    let empty1 = hasher.merge(0, empty_leaf, empty_leaf);
+    let empty2 = hasher.merge(1, empty1, empty1);
+
+    let tree = MerkleTree::new(MerkleHasherKimchi::kimchi(), 2, empty_leaf).unwrap();
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), empty2);
+
+    let tree = SparseMerkleTree::new(MerkleHasherKimchi::kimchi(), 2, empty_leaf).unwrap();
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), empty2);
+
+    let tree = MerkleTree::new(MerkleHasherKimchi::kimchi(), 0, empty_leaf).unwrap();
+    assert_eq!(tree.root(), empty_leaf);
+}
+
+#[test]
+fn append_only_tree() {
+    let mut tree = MerkleTree::new(MerkleHasherLegacy::legacy(), 3, Fp::zero()).unwrap();
+    let mut hasher = MerkleHasherLegacy::legacy();
+
+    // Root of [1, 2, 3, 0, ..., 0], computed by hand
+    tree.extend(leaves(3)).unwrap();
+    assert_eq!(tree.len(), 3);
+    assert_eq!(tree.leaves(), leaves(3));
+    let zero = Fp::zero();
+    let n12 = hasher.merge(0, 1u32.into(), 2u32.into());
+    let n30 = hasher.merge(0, 3u32.into(), zero);
+    let n00 = hasher.merge(0, zero, zero);
+    let left = hasher.merge(1, n12, n30);
+    let right = hasher.merge(1, n00, n00);
+    let expected = hasher.merge(2, left, right);
+    assert_eq!(tree.root(), expected);
+
+    assert_eq!(tree.append(Fp::from(4u32)), Ok(3));
+    tree.extend(leaves(4)).unwrap();
+    assert_eq!(tree.append(Fp::one()), Err(MerkleTreeError::Full));
+    assert_eq!(tree.len(), 8);
+
+    // Inclusion proofs
+    let root = tree.root();
+    for (index, leaf) in tree.leaves().iter().copied().enumerate() {
+        let path = tree.witness(index as u64).unwrap();
+        assert_eq!(path.depth(), 3);
+        assert!(path.verify(&mut hasher, leaf, root));
+        assert!(!path.verify(&mut hasher, leaf + Fp::one(), root));
+    }
+    assert_eq!(tree.witness(8), Err(MerkleTreeError::Index(8)));
+}
+
+#[test]
+fn sparse_tree_matches_append_only_tree() {
+    let depth = 10;
+    let mut tree = MerkleTree::new(MerkleHasherKimchi::kimchi(), depth, Fp::zero()).unwrap();
+    let mut sparse =
+        SparseMerkleTree::new(MerkleHasherKimchi::kimchi(), depth, Fp::zero()).unwrap();
+
+    // Insert in reverse order
+    for (index, leaf) in leaves(37).into_iter().enumerate().rev() {
+        assert_eq!(sparse.insert(index as u64, leaf), Ok(None));
+    }
+    tree.extend(leaves(37)).unwrap();
+    assert_eq!(sparse.len(), 37);
+    assert_eq!(sparse.root(), tree.root());
+
+    let mut hasher = MerkleHasherKimchi::kimchi();
+    for index in [0, 1, 17, 36, 37, 1023] {
+        let path = sparse.witness(index).unwrap();
+        assert_eq!(path, tree.witness(index).unwrap());
+        let leaf = sparse.get(index).unwrap_or_else(Fp::zero);
+        assert!(path.verify(&mut hasher, leaf, tree.root()));
+    }
+}
+
+#[test]
+fn sparse_tree_updates() {
+    let empty_leaf = Fp::from(7u32);
+    let mut tree =
+        SparseMerkleTree::new(MerkleHasherKimchi::kimchi(), MINA_LEDGER_DEPTH, empty_leaf).unwrap();
+    let empty_root = tree.root();
+    let mut hasher = MerkleHasherKimchi::kimchi();
+
+    let index = (1 << MINA_LEDGER_DEPTH) - 1;
+    assert_eq!(tree.insert(index, Fp::one()), Ok(None));
+    assert_eq!(tree.insert(3, Fp::from(3u32)), Ok(None));
+    assert_eq!(tree.insert(3, Fp::from(5u32)), Ok(Some(Fp::from(3u32))));
+    assert_eq!(
+        tree.leaves().collect::<Vec<_>>(),
+        vec![(3, Fp::from(5u32)), (index, Fp::one())]
+    );
+    let root = tree.root();
+    assert_ne!(root, empty_root);
+
+    // Inclusion proof
+    let path = tree.witness(index).unwrap();
+    assert!(path.verify(&mut hasher, Fp::one(), root));
+
+    // Exclusion proof
+    let path = tree.witness(4).unwrap();
+    assert_eq!(tree.get(4), None);
+    assert!(path.verify(&mut hasher, empty_leaf, root));
+    assert!(!tree
+        .witness(3)
+        .unwrap()
+        .verify(&mut hasher, empty_leaf, root));
+
+    // Removing all the leaves gives back the empty tree
+    assert_eq!(tree.remove(3), Ok(Some(Fp::from(5u32))));
+    assert_eq!(tree.remove(3), Ok(None));
+    assert_eq!(tree.remove(index), Ok(Some(Fp::one())));
+    assert!(tree.is_empty());
+    assert_eq!(tree.root(), empty_root);
+
+    assert_eq!(
+        tree.insert(1 << MINA_LEDGER_DEPTH, Fp::one()),
+        Err(MerkleTreeError::Index(1 << MINA_LEDGER_DEPTH))
+    );
+}
+
+#[test]
+fn invalid_depth() {
+    assert!(matches!(
+        MerkleTree::new(MerkleHasherKimchi::kimchi(), MAX_DEPTH + 1, Fp::zero()),
+        Err(MerkleTreeError::Depth(64))
+    ));
+    assert!(matches!(
+        SparseMerkleTree::new(MerkleHasherKimchi::kimchi(), MAX_DEPTH + 1, Fp::zero()),
+        Err(MerkleTreeError::Depth(64))
+    ));
+    assert!(SparseMerkleTree::new(MerkleHasherKimchi::kimchi(), MAX_DEPTH, Fp::zero()).is_ok());
+}
@@ -38,5 +38,40 @@ pub fn bench_proof_verification(c: &mut Criterion) {
     }
 }
 
-criterion_group!(benches, bench_proof_creation, bench_proof_verification);
+pub fn bench_prepared_proof_verification(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Prepared proof verification");
+    group.sample_size(10).sampling_mode(SamplingMode::Flat); // for slow benchmarks
+
+    // The opening proofs of a batch are checked together, with a single MSM
+    // over the SRS, which makes the per-proof work that a prepared verifier
+    // index saves visible.
+    let batch_size = 32;
+    let ctx = BenchmarkCtx::new(10);
+    let batch = vec![ctx.create_proof(); batch_size];
+    let prepared_index = ctx.prepare();
+
+    group.bench_function(
+        format!(
+            "batch verification of {batch_size} proofs (SRS size 2^{{{}}}, {} gates)",
+            ctx.srs_size(),
+            ctx.num_gates
+        ),
+        |b| b.iter(|| ctx.batch_verification(black_box(&batch))),
+    );
+    group.bench_function(
+        format!(
+            "batch verification of {batch_size} proofs, with a prepared verifier index (SRS size 2^{{{}}}, {} gates)",
+            ctx.srs_size(),
+            ctx.num_gates
+        ),
+        |b| b.iter(|| ctx.prepared_batch_verification(&prepared_index, black_box(&batch))),
+    );
+}
+
+criterion_group!(
+    benches,
+    bench_proof_creation,
+    bench_proof_verification,
+    bench_prepared_proof_verification
+);
 criterion_main!(benches);
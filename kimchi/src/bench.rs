@@ -17,8 +17,8 @@ use crate::{
     },
     proof::ProverProof,
     prover_index::{testing::new_index_for_test, ProverIndex},
-    verifier::{batch_verify, Context},
-    verifier_index::VerifierIndex,
+    verifier::{batch_verify, batch_verify_prepared, Context, PreparedContext},
+    verifier_index::{PreparedVerifierIndex, VerifierIndex},
 };
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
@@ -115,4 +115,32 @@ impl BenchmarkCtx {
         )
         .unwrap();
     }
+
+    /// Prepares the verifier index, for [`BenchmarkCtx::prepared_batch_verification`]
+    pub fn prepare(&self) -> PreparedVerifierIndex<'_, Vesta, OpeningProof<Vesta>, BaseSponge> {
+        PreparedVerifierIndex::new(&self.verifier_index)
+    }
+
+    /// Same as [`BenchmarkCtx::batch_verification`], with the verifier index
+    /// prepared by [`BenchmarkCtx::prepare`]
+    #[allow(clippy::type_complexity)]
+    pub fn prepared_batch_verification(
+        &self,
+        prepared_index: &PreparedVerifierIndex<'_, Vesta, OpeningProof<Vesta>, BaseSponge>,
+        batch: &[(ProverProof<Vesta, OpeningProof<Vesta>>, Vec<Fp>)],
+    ) {
+        let batch: Vec<_> = batch
+            .iter()
+            .map(|(proof, public)| PreparedContext {
+                prepared_index,
+                proof,
+                public_input: public,
+            })
+            .collect();
+        batch_verify_prepared::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &self.group_map,
+            &batch,
+        )
+        .unwrap();
+    }
 }
@@ -0,0 +1,62 @@
+//! Print the statistics of a circuit, given as a constraint system serialized
+//! with MessagePack (e.g. with `rmp_serde::to_vec(&cs)`).
+//!
+//! Usage: `circuit_stats <path> [--pallas] [--max-poly-size <size>]`
+//!
+//! The circuit is over the scalar field of Vesta, or of Pallas with `--pallas`,
+//! and the maximal size of polynomial section defaults to the size of its domain.
+
+use std::{env, fs::File, io::BufReader, process};
+
+use ark_poly::EvaluationDomain;
+use kimchi::{
+    circuits::{constraints::ConstraintSystem, stats::CircuitStats},
+    curve::KimchiCurve,
+};
+use mina_curves::pasta::{Pallas, Vesta};
+
+const USAGE: &str = "usage: circuit_stats <path> [--pallas] [--max-poly-size <size>]";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("{msg}");
+    eprintln!("{USAGE}");
+    process::exit(1)
+}
+
+fn stats<G: KimchiCurve>(path: &str, max_poly_size: Option<usize>) -> CircuitStats {
+    let file = File::open(path).unwrap_or_else(|e| fail(&format!("cannot open {path}: {e}")));
+    let cs: ConstraintSystem<G::ScalarField> = rmp_serde::from_read(BufReader::new(file))
+        .unwrap_or_else(|e| fail(&format!("cannot read the constraint system: {e}")));
+    let max_poly_size = max_poly_size.unwrap_or_else(|| cs.domain.d1.size());
+    CircuitStats::new::<G>(&cs, max_poly_size)
+}
+
+fn main() {
+    let mut path = None;
+    let mut pallas = false;
+    let mut max_poly_size = None;
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--pallas" => pallas = true,
+            "--max-poly-size" => {
+                let size = args
+                    .next()
+                    .and_then(|size| size.parse().ok())
+                    .unwrap_or_else(|| fail("--max-poly-size expects a number"));
+                max_poly_size = Some(size);
+            }
+            _ if path.is_none() && !arg.starts_with("--") => path = Some(arg),
+            _ => fail(&format!("unexpected argument {arg}")),
+        }
+    }
+    let path = path.unwrap_or_else(|| fail("missing path to the constraint system"));
+
+    let stats = if pallas {
+        stats::<Pallas>(&path, max_poly_size)
+    } else {
+        stats::<Vesta>(&path, max_poly_size)
+    };
+    println!("{stats}");
+}
@@ -82,6 +82,61 @@ impl Default for FeatureFlags {
     }
 }
 
+impl FeatureFlags {
+    /// The names of the optional features, in the order of [`FeatureFlags::bits`]
+    pub const NAMES: [&'static str; 12] = [
+        "range_check0",
+        "range_check1",
+        "foreign_field_add",
+        "foreign_field_mul",
+        "xor",
+        "rot",
+        "xor_lookup",
+        "lookup",
+        "range_check_lookup",
+        "foreign_field_mul_lookup",
+        "joint_lookup",
+        "runtime_tables",
+    ];
+
+    /// Whether each feature of [`FeatureFlags::NAMES`] is enabled
+    fn to_array(self) -> [bool; 12] {
+        let lookup = self.lookup_features;
+        [
+            self.range_check0,
+            self.range_check1,
+            self.foreign_field_add,
+            self.foreign_field_mul,
+            self.xor,
+            self.rot,
+            lookup.patterns.xor,
+            lookup.patterns.lookup,
+            lookup.patterns.range_check,
+            lookup.patterns.foreign_field_mul,
+            lookup.joint_lookup_used,
+            lookup.uses_runtime_tables,
+        ]
+    }
+
+    /// The names of the enabled features
+    pub fn enabled(&self) -> Vec<&'static str> {
+        self.to_array()
+            .into_iter()
+            .zip(Self::NAMES)
+            .filter_map(|(enabled, name)| enabled.then_some(name))
+            .collect()
+    }
+
+    /// The enabled features as a bit mask, where the bit `i` stands for the
+    /// feature `FeatureFlags::NAMES[i]`
+    pub fn bits(&self) -> u32 {
+        self.to_array()
+            .into_iter()
+            .enumerate()
+            .fold(0, |bits, (i, enabled)| bits | (u32::from(enabled) << i))
+    }
+}
+
 /// The polynomials representing evaluated columns, in coefficient form.
 #[serde_as]
 #[derive(Clone, Serialize, Deserialize, Debug)]
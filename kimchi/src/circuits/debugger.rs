@@ -0,0 +1,368 @@
+//! This module implements a witness debugger.
+//!
+//! [`ProverIndex::verify`] stops at the first unsatisfied constraint. For large
+//! circuits, it is often more useful to see every failure at once: the
+//! [`WitnessReport`] evaluates the constraints of all the gates, the copy
+//! constraints and the lookups, and lists everything the witness does not
+//! satisfy.
+
+use crate::{
+    circuits::{
+        constraints::ConstraintSystem,
+        gate::{CurrOrNext, GateType},
+        lookup::{
+            constraints::lookup_gate,
+            lookups::{LocalPosition, LookupTableID},
+            runtime_tables::RuntimeTable,
+        },
+        polynomial::COLUMNS,
+        wires::{Wire, PERMUTS},
+    },
+    curve::KimchiCurve,
+    prover_index::ProverIndex,
+};
+use ark_ff::PrimeField;
+use ark_poly::EvaluationDomain;
+use poly_commitment::OpenProof;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_with::serde_as;
+use std::{array, collections::HashSet, fmt};
+
+/// A constraint of the circuit that is not satisfied by the witness
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ConstraintFailure<F: PrimeField> {
+    /// A constraint of the gate at `row` does not evaluate to zero
+    Gate {
+        row: usize,
+        typ: GateType,
+        /// Index of the constraint within the gate, starting from 1
+        constraint: usize,
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+        evaluation: F,
+        /// The wire values of the row
+        #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+        curr: Vec<F>,
+        /// The wire values of the next row
+        #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+        next: Vec<F>,
+    },
+    /// Two cells wired together hold different values
+    CopyConstraint {
+        typ: GateType,
+        src: Wire,
+        dst: Wire,
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+        src_value: F,
+        #[serde_as(as = "o1_utils::serialization::SerdeAs")]
+        dst_value: F,
+    },
+    /// A cell is wired to a column that is not part of the permutation
+    WireColumn { typ: GateType, src: Wire, dst: Wire },
+    /// A public input gate does not toggle its left wire
+    IncorrectPublic { row: usize },
+    /// A lookup of `row` is not in the lookup tables
+    Lookup {
+        row: usize,
+        /// The gate performing the lookup
+        typ: Option<GateType>,
+        /// The table looked up, when it is not given by the witness
+        table_id: Option<i32>,
+        #[serde_as(as = "Vec<o1_utils::serialization::SerdeAs>")]
+        entry: Vec<F>,
+    },
+}
+
+impl<F: PrimeField> ConstraintFailure<F> {
+    /// The row at which the failure occurs
+    pub fn row(&self) -> usize {
+        match self {
+            ConstraintFailure::Gate { row, .. }
+            | ConstraintFailure::IncorrectPublic { row }
+            | ConstraintFailure::Lookup { row, .. } => *row,
+            ConstraintFailure::CopyConstraint { src, .. }
+            | ConstraintFailure::WireColumn { src, .. } => src.row,
+        }
+    }
+}
+
+/// Formats field elements as decimal integers
+fn fmt_values<F: PrimeField>(values: &[F]) -> String {
+    let values: Vec<_> = values.iter().map(|v| v.into_bigint().to_string()).collect();
+    format!("[{}]", values.join(", "))
+}
+
+impl<F: PrimeField> fmt::Display for ConstraintFailure<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConstraintFailure::Gate {
+                row,
+                typ,
+                constraint,
+                evaluation,
+                curr,
+                next,
+            } => write!(
+                f,
+                "row {row}: constraint {constraint} of the {typ:?} gate evaluates to {} (curr = {}, next = {})",
+                evaluation.into_bigint(),
+                fmt_values(curr),
+                fmt_values(next),
+            ),
+            ConstraintFailure::CopyConstraint {
+                typ,
+                src,
+                dst,
+                src_value,
+                dst_value,
+            } => write!(
+                f,
+                "row {}: {typ:?} copy constraint {},{} -> {},{} is not satisfied ({} != {})",
+                src.row,
+                src.row,
+                src.col,
+                dst.row,
+                dst.col,
+                src_value.into_bigint(),
+                dst_value.into_bigint(),
+            ),
+            ConstraintFailure::WireColumn { typ, src, dst } => write!(
+                f,
+                "row {}: {typ:?} wire {},{} -> {},{} can only be connected to the first {PERMUTS} columns",
+                src.row, src.row, src.col, dst.row, dst.col,
+            ),
+            ConstraintFailure::IncorrectPublic { row } => {
+                write!(f, "row {row}: incorrect public input gate")
+            }
+            ConstraintFailure::Lookup {
+                row,
+                typ,
+                table_id,
+                entry,
+            } => {
+                write!(f, "row {row}: lookup of {}", fmt_values(entry))?;
+                if let Some(typ) = typ {
+                    write!(f, " by the {typ:?} gate")?;
+                }
+                if let Some(table_id) = table_id {
+                    write!(f, " in table {table_id}")?;
+                }
+                write!(f, " is not in the lookup tables")
+            }
+        }
+    }
+}
+
+/// All the constraints of a circuit that a witness does not satisfy
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(bound = "ConstraintFailure<F>: Serialize + DeserializeOwned")]
+pub struct WitnessReport<F: PrimeField> {
+    /// The failures, ordered by row
+    pub failures: Vec<ConstraintFailure<F>>,
+}
+
+impl<F: PrimeField> WitnessReport<F> {
+    /// Evaluate every constraint of the constraint system `cs` on `witness`.
+    ///
+    /// The witness is padded with zeros up to the size of the domain, as done by the
+    /// prover. The `runtime_tables` are only used by circuits with runtime lookup tables.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the witness has more rows than the domain.
+    pub fn create<G: KimchiCurve<ScalarField = F>>(
+        cs: &ConstraintSystem<F>,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+        runtime_tables: &[RuntimeTable<F>],
+    ) -> Self {
+        // pad the witness
+        let n = cs.domain.d1.size();
+        assert!(
+            witness[0].len() <= n,
+            "the witness is larger than the domain"
+        );
+        let witness: [Vec<F>; COLUMNS] = array::from_fn(|i| {
+            let mut w = witness[i].to_vec();
+            w.resize(n, F::zero());
+            w
+        });
+
+        let mut failures = vec![];
+        for (row, gate) in cs.gates.iter().enumerate() {
+            let mut row_failures = vec![];
+
+            // copy constraints
+            for col in 0..PERMUTS {
+                let src = Wire { row, col };
+                let dst = gate.wires[col];
+                if dst.col >= PERMUTS {
+                    row_failures.push(ConstraintFailure::WireColumn {
+                        typ: gate.typ,
+                        src,
+                        dst,
+                    });
+                } else if witness[col][row] != witness[dst.col][dst.row] {
+                    row_failures.push(ConstraintFailure::CopyConstraint {
+                        typ: gate.typ,
+                        src,
+                        dst,
+                        src_value: witness[col][row],
+                        dst_value: witness[dst.col][dst.row],
+                    });
+                }
+            }
+
+            // for public gates, only the left wire is toggled
+            if row < cs.public && gate.coeffs.first() != Some(&F::one()) {
+                row_failures.push(ConstraintFailure::IncorrectPublic { row });
+            }
+
+            // gate constraints
+            let evaluations = gate
+                .constraint_evaluations::<G>(row, &witness, cs, public)
+                .expect("the witness is padded to the size of the domain");
+            for (i, evaluation) in evaluations.into_iter().enumerate() {
+                if !evaluation.is_zero() {
+                    row_failures.push(ConstraintFailure::Gate {
+                        row,
+                        typ: gate.typ,
+                        constraint: i + 1,
+                        evaluation,
+                        curr: witness.iter().map(|w| w[row]).collect(),
+                        next: witness
+                            .iter()
+                            .map(|w| w.get(row + 1).copied().unwrap_or_else(F::zero))
+                            .collect(),
+                    });
+                }
+            }
+
+            failures.push(row_failures);
+        }
+
+        // lookups are attributed to the row they are performed at
+        failures.resize(n, vec![]);
+        for (row, failure) in lookup_failures(cs, &witness, runtime_tables) {
+            failures[row].push(failure);
+        }
+
+        WitnessReport {
+            failures: failures.into_iter().flatten().collect(),
+        }
+    }
+
+    /// Whether the witness satisfies all the constraints
+    pub fn is_ok(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// The rows with at least one failure, in increasing order
+    pub fn failing_rows(&self) -> Vec<usize> {
+        let mut rows: Vec<_> = self.failures.iter().map(ConstraintFailure::row).collect();
+        rows.dedup();
+        rows
+    }
+}
+
+impl<F: PrimeField> fmt::Display for WitnessReport<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_ok() {
+            return write!(f, "all constraints are satisfied");
+        }
+        write!(f, "{} unsatisfied constraint(s):", self.failures.len())?;
+        for failure in &self.failures {
+            write!(f, "\n  {failure}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Check every lookup of the circuit against the lookup tables, and return the lookups
+/// that are not in any table along with their rows
+fn lookup_failures<F: PrimeField>(
+    cs: &ConstraintSystem<F>,
+    witness: &[Vec<F>; COLUMNS],
+    runtime_tables: &[RuntimeTable<F>],
+) -> Vec<(usize, ConstraintFailure<F>)> {
+    let lcs = match &cs.lookup_constraint_system {
+        Some(lcs) => lcs,
+        None => return vec![],
+    };
+
+    // We do not look up values in the last `zk_rows` rows, nor in the row before
+    let lookup_rows = cs.domain.d1.size() - cs.zk_rows as usize - 1;
+
+    // Rebuild the concatenated table from the evaluations of its columns
+    let mut table: Vec<Vec<F>> = (0..lookup_rows)
+        .map(|row| lcs.lookup_table8.iter().map(|c| c.evals[8 * row]).collect())
+        .collect();
+    let table_ids: Vec<F> = (0..lookup_rows)
+        .map(|row| {
+            lcs.table_ids8
+                .as_ref()
+                .map_or_else(F::zero, |ids| ids.evals[8 * row])
+        })
+        .collect();
+
+    // The runtime tables contribute to the second column of the table
+    if let Some(mut offset) = lcs.runtime_table_offset {
+        for rt in runtime_tables {
+            for (entry, value) in table.iter_mut().skip(offset).zip(&rt.data) {
+                entry[1] += value;
+            }
+            offset += rt.data.len();
+        }
+    }
+
+    let width = lcs.lookup_table8.len();
+    let table: HashSet<(F, Vec<F>)> = table_ids.into_iter().zip(table).collect();
+
+    let mut failures = vec![];
+    let by_row = lcs.configuration.lookup_info.by_row(&cs.gates);
+    for (row, lookups) in by_row.iter().enumerate().take(lookup_rows) {
+        let eval = |pos: LocalPosition| -> F {
+            let row = match pos.row {
+                CurrOrNext::Curr => row,
+                CurrOrNext::Next => row + 1,
+            };
+            witness[pos.column][row]
+        };
+        for lookup in lookups {
+            let value = lookup.reduce(&eval);
+            let mut padded = value.entry.clone();
+            padded.resize(width.max(padded.len()), F::zero());
+            if !table.contains(&(value.table_id, padded)) {
+                let table_id = match lookup.table_id {
+                    LookupTableID::Constant(table_id) => Some(table_id),
+                    LookupTableID::WitnessColumn(_) => None,
+                };
+                failures.push((
+                    row,
+                    ConstraintFailure::Lookup {
+                        row,
+                        typ: lookup_gate(&cs.gates, row).map(|gate| gate.typ),
+                        table_id,
+                        entry: value.entry,
+                    },
+                ));
+            }
+        }
+    }
+    failures
+}
+
+impl<F: PrimeField, G: KimchiCurve<ScalarField = F>, OpeningProof: OpenProof<G>>
+    ProverIndex<G, OpeningProof>
+{
+    /// Evaluate every constraint of the circuit on the witness and report all the
+    /// failures, see [`WitnessReport::create`].
+    pub fn witness_report(
+        &self,
+        witness: &[Vec<F>; COLUMNS],
+        public: &[F],
+        runtime_tables: &[RuntimeTable<F>],
+    ) -> WitnessReport<F> {
+        WitnessReport::create::<G>(&self.cs, witness, public, runtime_tables)
+    }
+}
@@ -6,8 +6,8 @@ use crate::{
         berkeley_columns::BerkeleyChallenges,
         constraints::ConstraintSystem,
         polynomials::{
-            complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul, keccak,
-            poseidon, range_check, rot, turshi, varbasemul, xor,
+            complete_add, endomul_scalar, endosclmul, foreign_field_add, foreign_field_mul,
+            generic, keccak, poseidon, range_check, rot, turshi, varbasemul, xor,
         },
         wires::*,
     },
@@ -226,32 +226,8 @@ impl<F: PrimeField> CircuitGate<F> {
         row: usize,
         witness: &[Vec<F>; COLUMNS],
         cs: &ConstraintSystem<F>,
-        _public: &[F],
+        public: &[F],
     ) -> CircuitGateResult<()> {
-        // Grab the relevant part of the witness
-        let argument_witness = self.argument_witness(row, witness)?;
-        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
-        // are one because this function is not running the prover.
-        let constants = expr::Constants {
-            endo_coefficient: cs.endo,
-            mds: &G::sponge_params().mds,
-            zk_rows: cs.zk_rows,
-        };
-        //TODO : use generic challenges, since we do not need those here
-        let challenges = BerkeleyChallenges {
-            alpha: F::one(),
-            beta: F::one(),
-            gamma: F::one(),
-            joint_combiner: F::one(),
-        };
-        // Create the argument environment for the constraints over field elements
-        let env = ArgumentEnv::<F, F>::create(
-            argument_witness,
-            self.coeffs.clone(),
-            constants,
-            challenges,
-        );
-
         // Check the wiring (i.e. copy constraints) for this gate
         // Note: Gates can operated on row Curr or Curr and Next.
         //       It could be nice for gates to know this and then
@@ -274,16 +250,70 @@ impl<F: PrimeField> CircuitGate<F> {
             }
         }
 
+        // Perform witness verification on each constraint for this gate
+        let results = self.constraint_evaluations::<G>(row, witness, cs, public)?;
+
+        // Check for failed constraints
+        for (i, result) in results.iter().enumerate() {
+            if !result.is_zero() {
+                // Pinpoint failed constraint
+                return Err(CircuitGateError::Constraint(self.typ, i + 1));
+            }
+        }
+
+        // TODO: implement generic plookup witness verification
+
+        Ok(())
+    }
+
+    /// Evaluate the constraints of this gate on the witness at `row`, in the order
+    /// in which the gate defines them. A satisfied constraint evaluates to zero.
+    ///
+    /// Lookups are not part of the gate constraints, so no evaluation is returned
+    /// for the lookup gate.
+    pub fn constraint_evaluations<G: KimchiCurve<ScalarField = F>>(
+        &self,
+        row: usize,
+        witness: &[Vec<F>; COLUMNS],
+        cs: &ConstraintSystem<F>,
+        public: &[F],
+    ) -> CircuitGateResult<Vec<F>> {
+        // Grab the relevant part of the witness
+        let argument_witness = self.argument_witness(row, witness)?;
+        // Set up the constants.  Note that alpha, beta, gamma and joint_combiner
+        // are one because this function is not running the prover.
+        let constants = expr::Constants {
+            endo_coefficient: cs.endo,
+            mds: &G::sponge_params().mds,
+            zk_rows: cs.zk_rows,
+        };
+        //TODO : use generic challenges, since we do not need those here
+        let challenges = BerkeleyChallenges {
+            alpha: F::one(),
+            beta: F::one(),
+            gamma: F::one(),
+            joint_combiner: F::one(),
+        };
+        // Missing coefficients are zero, as in the coefficient columns of the index
+        let mut coeffs = self.coeffs.clone();
+        if coeffs.len() < COLUMNS {
+            coeffs.resize(COLUMNS, F::zero());
+        }
+        // Create the argument environment for the constraints over field elements
+        let env = ArgumentEnv::<F, F>::create(argument_witness, coeffs, constants, challenges);
+
         let mut cache = expr::Cache::default();
 
-        // Perform witness verification on each constraint for this gate
-        let results = match self.typ {
+        // Evaluate each constraint for this gate
+        let evaluations = match self.typ {
             GateType::Zero => {
                 vec![]
             }
             GateType::Generic => {
-                // TODO: implement the verification for the generic gate
-                vec![]
+                let mut evaluations = generic::Generic::constraint_checks(&env, &mut cache);
+                // The public input is only added to the first generic gate of the row
+                evaluations[0] -= public.get(row).copied().unwrap_or_else(F::zero);
+                evaluations
             }
             GateType::Poseidon => poseidon::Poseidon::constraint_checks(&env, &mut cache),
             GateType::CompleteAdd => complete_add::CompleteAdd::constraint_checks(&env, &mut cache),
@@ -327,17 +357,7 @@ impl<F: PrimeField> CircuitGate<F> {
             }
         };
 
-        // Check for failed constraints
-        for (i, result) in results.iter().enumerate() {
-            if !result.is_zero() {
-                // Pinpoint failed constraint
-                return Err(CircuitGateError::Constraint(self.typ, i + 1));
-            }
-        }
-
-        // TODO: implement generic plookup witness verification
-
-        Ok(())
+        Ok(evaluations)
     }
 
     // Return the part of the witness relevant to this gate at the given row offset
@@ -5,10 +5,11 @@ use crate::{
         gate::{CircuitGate, CurrOrNext},
         lookup::lookups::{
             JointLookup, JointLookupSpec, JointLookupValue, LocalPosition, LookupInfo,
+            LookupPattern, LookupTableID,
         },
         wires::COLUMNS,
     },
-    error::ProverError,
+    error::{ErrorContext, ProverError},
 };
 use ark_ff::{FftField, One, PrimeField, Zero};
 use ark_poly::{EvaluationDomain, Evaluations, Radix2EvaluationDomain as D};
@@ -24,6 +25,41 @@ use super::runtime_tables;
 /// Number of constraints produced by the argument.
 pub const CONSTRAINTS: u32 = 7;
 
+/// The gate performing the lookups of `row`: either the gate of this row, or the gate of
+/// the previous row if it performs its lookups on the next row
+pub(crate) fn lookup_gate<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    row: usize,
+) -> Option<&CircuitGate<F>> {
+    gates
+        .get(row)
+        .filter(|gate| LookupPattern::from_gate(gate.typ, Curr).is_some())
+        .or_else(|| row.checked_sub(1).and_then(|prev| gates.get(prev)))
+}
+
+/// Context of a failed lookup at `row`: the gate using the lookup, the table and the entry
+/// looked up
+fn lookup_error_context<F: PrimeField>(
+    gates: &[CircuitGate<F>],
+    row: usize,
+    joint_lookup: &JointLookupSpec<F>,
+    eval: impl Fn(LocalPosition) -> F,
+) -> ErrorContext {
+    let entry: Vec<F> = joint_lookup
+        .entry
+        .iter()
+        .map(|e| e.evaluate(&eval))
+        .collect();
+    let mut context = ErrorContext::row(row).values(&entry);
+    if let Some(gate) = lookup_gate(gates, row) {
+        context = context.gate_type(gate.typ);
+    }
+    if let LookupTableID::Constant(table_id) = joint_lookup.table_id {
+        context = context.table_id(table_id);
+    }
+    context
+}
+
 /// Pad with zeroes and then add 3 random elements in the last two
 /// rows for zero knowledge.
 ///
@@ -138,7 +174,14 @@ pub fn sorted<F: PrimeField>(
             let joint_lookup_evaluation =
                 joint_lookup.evaluate(&joint_combiner, &table_id_combiner, &eval);
             match counts.get_mut(&joint_lookup_evaluation) {
-                None => return Err(ProverError::ValueNotInTable(i)),
+                None => {
+                    return Err(ProverError::ValueNotInTable(lookup_error_context(
+                        gates,
+                        i,
+                        joint_lookup,
+                        eval,
+                    )))
+                }
                 Some(count) => *count += 1,
             }
         }
@@ -4,6 +4,7 @@ pub mod macros;
 pub mod argument;
 pub mod berkeley_columns;
 pub mod constraints;
+pub mod debugger;
 pub mod domain_constant_evaluation;
 pub mod domains;
 pub mod expr;
@@ -13,5 +14,6 @@ pub mod polynomial;
 pub mod polynomials;
 pub mod scalars;
 mod serialization_helper;
+pub mod stats;
 pub mod wires;
 pub mod witness;
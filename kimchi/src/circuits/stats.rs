@@ -0,0 +1,285 @@
+//! This module implements a summary of what a circuit costs.
+//!
+//! The [`CircuitStats`] of a [`ConstraintSystem`] report the rows used by each
+//! gate type, the domain and its unused rows, the optional features, the lookup
+//! tables and the expected size of a proof, so that the growth of a circuit can
+//! be tracked from one version to the next.
+
+use crate::{
+    circuits::{
+        constraints::{ConstraintSystem, FeatureFlags},
+        gate::GateType,
+        lookup::runtime_tables::RuntimeTableSpec,
+        wires::{COLUMNS, PERMUTS},
+    },
+    curve::KimchiCurve,
+    prover_index::ProverIndex,
+};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use ark_poly::EvaluationDomain;
+use ark_serialize::CanonicalSerialize;
+use o1_utils::math;
+use poly_commitment::ipa::OpeningProof;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+/// The number of selectors evaluated in every proof
+/// (generic, poseidon, complete add, var base mul, endo mul and endo mul scalar)
+const MANDATORY_SELECTORS: usize = 6;
+
+/// The number of chunks of the quotient polynomial, per chunk of the other polynomials
+const QUOTIENT_CHUNKS: usize = 7;
+
+/// The size of a fixed lookup table
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LookupTableStats {
+    /// The table ID
+    pub id: i32,
+    /// The number of distinct entries of the table
+    pub len: usize,
+}
+
+/// A summary of what a circuit costs
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CircuitStats {
+    /// Number of rows of the circuit, without the zero gates padding it to the domain size
+    pub rows: usize,
+    /// Number of rows of each gate type
+    pub gates: BTreeMap<GateType, usize>,
+    /// Number of public inputs
+    pub public: usize,
+    /// Number of previous evaluation challenges, for recursive proving
+    pub prev_challenges: usize,
+    /// Size of the evaluation domain
+    pub domain_size: usize,
+    /// Number of zero-knowledge rows
+    pub zk_rows: u64,
+    /// Number of rows of the domain used neither by the circuit nor for zero-knowledge
+    pub slack: usize,
+    /// Maximal size of polynomial section
+    pub max_poly_size: usize,
+    /// Number of chunks of the committed polynomials
+    pub num_chunks: usize,
+    /// Flags for optional features
+    pub feature_flags: FeatureFlags,
+    /// Fixed lookup tables, including the ones used by the gates
+    /// (the table with ID 0 always contains the zero entry of the dummy lookups)
+    pub lookup_tables: Vec<LookupTableStats>,
+    /// Runtime lookup tables
+    pub runtime_tables: Vec<RuntimeTableSpec>,
+    /// Number of group elements of a proof
+    pub proof_points: usize,
+    /// Number of field elements of a proof
+    pub proof_scalars: usize,
+    /// Expected size of a proof in bytes, as its group and field elements in compressed form
+    pub proof_size: usize,
+}
+
+impl CircuitStats {
+    /// Compute the statistics of the circuit `cs`, committed with an SRS of size
+    /// `max_poly_size` and proven with the inner product argument of [`OpeningProof`]
+    pub fn new<G: KimchiCurve>(
+        cs: &ConstraintSystem<G::ScalarField>,
+        max_poly_size: usize,
+    ) -> Self {
+        let domain_size = cs.domain.d1.size();
+        let num_chunks = if domain_size < max_poly_size {
+            1
+        } else {
+            domain_size / max_poly_size
+        };
+
+        // the circuit is padded with zero gates up to the domain size
+        let rows = cs
+            .gates
+            .iter()
+            .rposition(|gate| gate.typ != GateType::Zero)
+            .map_or(0, |row| row + 1);
+        let mut gates = BTreeMap::new();
+        for gate in &cs.gates[..rows] {
+            *gates.entry(gate.typ).or_insert(0) += 1;
+        }
+        let slack = domain_size.saturating_sub(rows + cs.zk_rows as usize);
+
+        let (lookup_tables, runtime_tables) = lookup_tables(cs);
+
+        // the commitments to the witness, the permutation and the quotient
+        let mut commitments = COLUMNS + 1 + QUOTIENT_CHUNKS;
+        // the evaluations of the public input, the witness, the permutation,
+        // the sigmas but the last one, the coefficients and the selectors
+        let mut evaluations = 1 + COLUMNS + 1 + (PERMUTS - 1) + COLUMNS + MANDATORY_SELECTORS;
+        let flags = cs.feature_flags;
+        evaluations += [
+            flags.range_check0,
+            flags.range_check1,
+            flags.foreign_field_add,
+            flags.foreign_field_mul,
+            flags.xor,
+            flags.rot,
+        ]
+        .into_iter()
+        .filter(|flag| *flag)
+        .count();
+        if let Some(lcs) = &cs.lookup_constraint_system {
+            // the sorted polynomials and the aggregation
+            let sorted = lcs.configuration.lookup_info.max_per_row + 1;
+            commitments += sorted + 1;
+            // which are evaluated along with the table and the lookup selectors
+            let selectors = &lcs.lookup_selectors;
+            evaluations += sorted
+                + 2
+                + [
+                    selectors.xor.is_some(),
+                    selectors.lookup.is_some(),
+                    selectors.range_check.is_some(),
+                    selectors.ffmul.is_some(),
+                ]
+                .into_iter()
+                .filter(|selector| *selector)
+                .count();
+            // the runtime table and its selector
+            if lcs.runtime_tables.is_some() {
+                commitments += 1;
+                evaluations += 2;
+            }
+        }
+
+        // every polynomial is chunked, and evaluated at zeta and zeta * omega
+        let rounds = math::ceil_log2(max_poly_size);
+        let mut proof_points = commitments * num_chunks;
+        let mut proof_scalars = 2 * evaluations * num_chunks + 1;
+        // the previous challenges, with their commitments
+        proof_points += cs.prev_challenges;
+        proof_scalars += cs.prev_challenges * rounds;
+        // the opening proof, made of its rounds, delta and sg, z1 and z2
+        proof_points += 2 * rounds + 2;
+        proof_scalars += 2;
+
+        let point_size = G::zero().compressed_size();
+        let scalar_size = G::ScalarField::zero().compressed_size();
+        let proof_size = proof_points * point_size + proof_scalars * scalar_size;
+
+        CircuitStats {
+            rows,
+            gates,
+            public: cs.public,
+            prev_challenges: cs.prev_challenges,
+            domain_size,
+            zk_rows: cs.zk_rows,
+            slack,
+            max_poly_size,
+            num_chunks,
+            feature_flags: cs.feature_flags,
+            lookup_tables,
+            runtime_tables,
+            proof_points,
+            proof_scalars,
+            proof_size,
+        }
+    }
+}
+
+impl<G: KimchiCurve> ProverIndex<G, OpeningProof<G>>
+where
+    G::BaseField: PrimeField,
+{
+    /// Compute the statistics of the circuit of this index
+    pub fn stats(&self) -> CircuitStats {
+        CircuitStats::new::<G>(&self.cs, self.max_poly_size)
+    }
+}
+
+/// The fixed lookup tables of the circuit, read from the concatenated table,
+/// and its runtime tables
+fn lookup_tables<F: PrimeField>(
+    cs: &ConstraintSystem<F>,
+) -> (Vec<LookupTableStats>, Vec<RuntimeTableSpec>) {
+    let lcs = match &cs.lookup_constraint_system {
+        Some(lcs) => lcs,
+        None => return (vec![], vec![]),
+    };
+
+    // The fixed tables come first, and are followed by the runtime tables if any
+    let lookup_rows = cs.domain.d1.size() - cs.zk_rows as usize - 1;
+    let fixed_rows = lcs.runtime_table_offset.unwrap_or(lookup_rows);
+
+    let mut entries = HashSet::new();
+    let mut lens = BTreeMap::new();
+    for row in 0..fixed_rows {
+        let id = lcs
+            .table_ids8
+            .as_ref()
+            .map_or_else(F::zero, |ids| ids.evals[8 * row]);
+        let entry: Vec<F> = lcs.lookup_table8.iter().map(|c| c.evals[8 * row]).collect();
+        if entries.insert((id, entry)) {
+            *lens.entry(field_to_i32(id)).or_insert(0) += 1;
+        }
+    }
+    let fixed = lens
+        .into_iter()
+        .map(|(id, len)| LookupTableStats { id, len })
+        .collect();
+
+    (fixed, lcs.runtime_tables.clone().unwrap_or_default())
+}
+
+/// The table ID encoded by a field element, see [`o1_utils::field_helpers::i32_to_field`]
+fn field_to_i32<F: PrimeField>(x: F) -> i32 {
+    let small = |x: F| {
+        let x = x.into_bigint();
+        (x.num_bits() < 32).then(|| x.as_ref()[0] as i32)
+    };
+    small(x)
+        .or_else(|| small(-x).map(|id| -id))
+        .expect("table IDs are 32-bit integers")
+}
+
+impl fmt::Display for CircuitStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "rows: {}", self.rows)?;
+        for (typ, count) in &self.gates {
+            writeln!(f, "  {typ:?}: {count}")?;
+        }
+        writeln!(f, "public inputs: {}", self.public)?;
+        writeln!(f, "previous challenges: {}", self.prev_challenges)?;
+        writeln!(
+            f,
+            "domain size: {} ({} zk rows, {} rows of slack)",
+            self.domain_size, self.zk_rows, self.slack
+        )?;
+        writeln!(
+            f,
+            "chunks: {} (max poly size {})",
+            self.num_chunks, self.max_poly_size
+        )?;
+
+        let features = self.feature_flags.enabled();
+        if features.is_empty() {
+            writeln!(f, "features: none")?;
+        } else {
+            writeln!(f, "features: {}", features.join(", "))?;
+        }
+
+        if !self.lookup_tables.is_empty() {
+            writeln!(f, "lookup tables:")?;
+            for table in &self.lookup_tables {
+                writeln!(f, "  {}: {} entries", table.id, table.len)?;
+            }
+        }
+        if !self.runtime_tables.is_empty() {
+            writeln!(f, "runtime tables:")?;
+            for table in &self.runtime_tables {
+                writeln!(f, "  {}: {} entries", table.id, table.len)?;
+            }
+        }
+
+        write!(
+            f,
+            "proof size: {} bytes ({} group elements, {} field elements)",
+            self.proof_size, self.proof_points, self.proof_scalars
+        )
+    }
+}
@@ -0,0 +1,525 @@
+//! This module implements stable, versioned encodings of a [`ProverProof`]
+//! and of a [`VerifierIndex`], in binary and in JSON.
+//!
+//! ## Binary encoding
+//!
+//! The binary encoding is a header followed by the content, the object
+//! serialized with MessagePack. The integers of the header are little-endian.
+//!
+//! | length | field                                                       |
+//! | ------ | ----------------------------------------------------------- |
+//! | 4      | the magic bytes `KIMC`                                      |
+//! | 2      | the version of the format, currently [`FORMAT_VERSION`]     |
+//! | 1      | the [`Kind`] of the object, `0` for a proof and `1` for a verifier index |
+//! | 1      | the length `n` of the name of the curve                     |
+//! | n      | the name of the curve, as given by [`KimchiCurve::NAME`]    |
+//! | 4      | the features of the object, as given by [`FeatureFlags::bits`] |
+//!
+//! The structs of the content are encoded as maps from the names of their
+//! fields to their values, so that the encoding does not depend on the order
+//! of the fields.
+//!
+//! An encoding starting without the magic bytes is read as the version `0` of
+//! the format: the object alone, serialized with MessagePack with its structs
+//! as arrays of their fields, as written by `rmp_serde::to_vec` (or by
+//! [`VerifierIndex::to_file`] in the releases preceding the versioned format).
+//!
+//! ## JSON encoding
+//!
+//! The JSON encoding is an object with the fields of the header, the features
+//! being given by their names (see [`FeatureFlags::NAMES`]), and the content:
+//!
+//! ```text
+//! {
+//!   "kind": "proof",
+//!   "version": 1,
+//!   "curve": "vesta",
+//!   "features": ["xor", "xor_lookup"],
+//!   "content": { "commitments": ..., "proof": ..., ... }
+//! }
+//! ```
+//!
+//! ## Features
+//!
+//! The features of a verifier index are the [`FeatureFlags`] of its circuit.
+//! The features of a proof are the ones whose selectors are evaluated in the
+//! proof, which leaves out `joint_lookup`.
+
+use crate::{
+    circuits::constraints::FeatureFlags, curve::KimchiCurve, error::EncodingError,
+    linearization::expr_linearization, proof::ProverProof, verifier_index::VerifierIndex,
+};
+use poly_commitment::OpenProof;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::{
+    io::{BufRead, Write},
+    sync::Arc,
+};
+
+/// The magic bytes starting a binary encoding
+pub const MAGIC: [u8; 4] = *b"KIMC";
+
+/// The latest version of the format
+pub const FORMAT_VERSION: u16 = 1;
+
+/// The version of the format of the encodings without header
+pub const LEGACY_VERSION: u16 = 0;
+
+/// The result of encoding or decoding an object
+pub type Result<T> = std::result::Result<T, EncodingError>;
+
+/// The kinds of encoded objects
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Kind {
+    /// A [`ProverProof`]
+    Proof = 0,
+    /// A [`VerifierIndex`]
+    VerifierIndex = 1,
+}
+
+impl Kind {
+    /// The name of the kind, used by the JSON encoding
+    pub fn name(self) -> &'static str {
+        match self {
+            Kind::Proof => "proof",
+            Kind::VerifierIndex => "verifier_index",
+        }
+    }
+}
+
+/// The header of an encoding, describing its content
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Header {
+    /// The kind of the encoded object
+    pub kind: Kind,
+    /// The version of the format
+    pub version: u16,
+    /// The name of the curve of the object
+    pub curve: String,
+    /// The features of the object, as given by [`FeatureFlags::bits`]
+    pub features: u32,
+}
+
+impl Header {
+    /// The header of the latest version of the format, for an object of kind
+    /// `kind` over the curve `G` using the features `flags`
+    pub fn new<G: KimchiCurve>(kind: Kind, flags: &FeatureFlags) -> Self {
+        Header {
+            kind,
+            version: FORMAT_VERSION,
+            curve: G::NAME.to_string(),
+            features: flags.bits(),
+        }
+    }
+
+    /// Write the header in binary
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the name of the curve is longer than 255 bytes, or
+    /// if `writer` fails.
+    pub fn write<W: Write>(&self, mut writer: W) -> Result<()> {
+        let curve_len = u8::try_from(self.curve.len()).map_err(|_| {
+            EncodingError::MalformedHeader("the name of the curve is too long".to_string())
+        })?;
+        writer.write_all(&MAGIC).map_err(io_error)?;
+        writer
+            .write_all(&self.version.to_le_bytes())
+            .map_err(io_error)?;
+        writer
+            .write_all(&[self.kind as u8, curve_len])
+            .map_err(io_error)?;
+        writer.write_all(self.curve.as_bytes()).map_err(io_error)?;
+        writer
+            .write_all(&self.features.to_le_bytes())
+            .map_err(io_error)
+    }
+
+    /// Read the header of a binary encoding, or `None` if the encoding has no
+    /// header and is thus of the version [`LEGACY_VERSION`] of the format
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the header is malformed, or if `reader` fails.
+    pub fn read<R: BufRead>(mut reader: R) -> Result<Option<Self>> {
+        if !reader.fill_buf().map_err(io_error)?.starts_with(&MAGIC) {
+            return Ok(None);
+        }
+        reader.consume(MAGIC.len());
+
+        let mut fixed = [0u8; 4];
+        reader.read_exact(&mut fixed).map_err(io_error)?;
+        let version = u16::from_le_bytes([fixed[0], fixed[1]]);
+        let kind = match fixed[2] {
+            0 => Kind::Proof,
+            1 => Kind::VerifierIndex,
+            kind => {
+                return Err(EncodingError::MalformedHeader(format!(
+                    "unknown kind {kind}"
+                )))
+            }
+        };
+        let mut curve = vec![0u8; usize::from(fixed[3])];
+        reader.read_exact(&mut curve).map_err(io_error)?;
+        let curve = String::from_utf8(curve).map_err(|_| {
+            EncodingError::MalformedHeader("the name of the curve is not UTF-8".to_string())
+        })?;
+        let mut features = [0u8; 4];
+        reader.read_exact(&mut features).map_err(io_error)?;
+
+        Ok(Some(Header {
+            kind,
+            version,
+            curve,
+            features: u32::from_le_bytes(features),
+        }))
+    }
+
+    /// Check that the header describes an object of kind `kind` over the curve
+    /// `G`, in a version of the format this release can read
+    fn check<G: KimchiCurve>(&self, kind: Kind) -> Result<()> {
+        if self.kind != kind {
+            return Err(EncodingError::WrongKind {
+                expected: kind.name(),
+                found: self.kind.name().to_string(),
+            });
+        }
+        if self.version == LEGACY_VERSION || self.version > FORMAT_VERSION {
+            return Err(EncodingError::UnsupportedVersion(self.version));
+        }
+        if self.curve != G::NAME {
+            return Err(EncodingError::WrongCurve {
+                expected: G::NAME,
+                found: self.curve.clone(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Check that the object has the features of the header
+    fn check_features(&self, flags: &FeatureFlags) -> Result<()> {
+        if self.features != flags.bits() {
+            return Err(EncodingError::FeatureMismatch {
+                header: self.features,
+                content: flags.bits(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// The JSON encoding of an object
+#[derive(Serialize, Deserialize)]
+struct JsonEncoding<T> {
+    kind: String,
+    version: u16,
+    curve: String,
+    features: Vec<String>,
+    content: T,
+}
+
+impl<T> JsonEncoding<T> {
+    /// The header of the encoding
+    fn header(&self) -> Result<Header> {
+        let kind = match self.kind.as_str() {
+            "proof" => Kind::Proof,
+            "verifier_index" => Kind::VerifierIndex,
+            kind => {
+                return Err(EncodingError::MalformedHeader(format!(
+                    "unknown kind {kind}"
+                )))
+            }
+        };
+        let features = self.features.iter().try_fold(0, |bits, feature| {
+            FeatureFlags::NAMES
+                .iter()
+                .position(|name| name == feature)
+                .map(|i| bits | (1 << i))
+                .ok_or_else(|| EncodingError::UnknownFeature(feature.clone()))
+        })?;
+        Ok(Header {
+            kind,
+            version: self.version,
+            curve: self.curve.clone(),
+            features,
+        })
+    }
+}
+
+fn io_error(e: std::io::Error) -> EncodingError {
+    EncodingError::Io(e.to_string())
+}
+
+fn msgpack_error<E: ToString>(e: E) -> EncodingError {
+    EncodingError::MessagePack(e.to_string())
+}
+
+fn json_error(e: serde_json::Error) -> EncodingError {
+    EncodingError::Json(e.to_string())
+}
+
+/// Write the binary encoding of `value`, with the header `header`
+fn write_binary<W: Write, T: Serialize>(mut writer: W, header: &Header, value: &T) -> Result<()> {
+    header.write(&mut writer)?;
+    rmp_serde::encode::write_named(&mut writer, value).map_err(msgpack_error)
+}
+
+/// Read a binary encoding of an object of kind `kind` over the curve `G`,
+/// returning its header (`None` for the legacy format) and the object
+fn read_binary<G: KimchiCurve, R: BufRead, T: DeserializeOwned>(
+    mut reader: R,
+    kind: Kind,
+) -> Result<(Option<Header>, T)> {
+    let header = Header::read(&mut reader)?;
+    if let Some(header) = &header {
+        header.check::<G>(kind)?;
+    }
+    let value = rmp_serde::from_read(reader).map_err(msgpack_error)?;
+    Ok((header, value))
+}
+
+/// Encode `value` in JSON, with the header `header`
+fn to_json<T: Serialize>(header: &Header, value: &T) -> Result<String> {
+    let flags = FeatureFlags::NAMES
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| header.features & (1 << i) != 0)
+        .map(|(_, name)| name.to_string())
+        .collect();
+    serde_json::to_string(&JsonEncoding {
+        kind: header.kind.name().to_string(),
+        version: header.version,
+        curve: header.curve.clone(),
+        features: flags,
+        content: value,
+    })
+    .map_err(json_error)
+}
+
+/// Decode a JSON encoding of an object of kind `kind` over the curve `G`,
+/// returning its header and the object
+fn from_json<G: KimchiCurve, T: DeserializeOwned>(json: &str, kind: Kind) -> Result<(Header, T)> {
+    let encoding: JsonEncoding<serde_json::Value> =
+        serde_json::from_str(json).map_err(json_error)?;
+    let header = encoding.header()?;
+    header.check::<G>(kind)?;
+    let value = serde_json::from_value(encoding.content).map_err(json_error)?;
+    Ok((header, value))
+}
+
+impl<G: KimchiCurve, OpeningProof: OpenProof<G> + Serialize + DeserializeOwned>
+    ProverProof<G, OpeningProof>
+{
+    /// The features whose selectors are evaluated in the proof
+    pub fn feature_flags(&self) -> FeatureFlags {
+        let evals = &self.evals;
+        let mut flags = FeatureFlags {
+            range_check0: evals.range_check0_selector.is_some(),
+            range_check1: evals.range_check1_selector.is_some(),
+            foreign_field_add: evals.foreign_field_add_selector.is_some(),
+            foreign_field_mul: evals.foreign_field_mul_selector.is_some(),
+            xor: evals.xor_selector.is_some(),
+            rot: evals.rot_selector.is_some(),
+            ..FeatureFlags::default()
+        };
+        let lookup = &mut flags.lookup_features;
+        lookup.patterns.xor = evals.xor_lookup_selector.is_some();
+        lookup.patterns.lookup = evals.lookup_gate_lookup_selector.is_some();
+        lookup.patterns.range_check = evals.range_check_lookup_selector.is_some();
+        lookup.patterns.foreign_field_mul = evals.foreign_field_mul_lookup_selector.is_some();
+        lookup.uses_runtime_tables = evals.runtime_lookup_table_selector.is_some();
+        flags
+    }
+
+    /// The header of the encodings of the proof
+    fn header(&self) -> Header {
+        Header::new::<G>(Kind::Proof, &self.feature_flags())
+    }
+
+    /// Write the binary encoding of the proof
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof cannot be serialized, or if `writer` fails.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<()> {
+        write_binary(writer, &self.header(), self)
+    }
+
+    /// Read a proof from its binary encoding, in any version of the format
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a proof over `G`, if
+    /// its version is not supported, or if `reader` fails.
+    pub fn read_from<R: BufRead>(reader: R) -> Result<Self> {
+        let (header, proof): (_, Self) = read_binary::<G, _, _>(reader, Kind::Proof)?;
+        if let Some(header) = header {
+            header.check_features(&proof.feature_flags())?;
+        }
+        Ok(proof)
+    }
+
+    /// The binary encoding of the proof
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof cannot be serialized.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decode a proof from its binary encoding, see [`ProverProof::read_from`]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a proof over `G`, or
+    /// if its version is not supported.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Self::read_from(bytes)
+    }
+
+    /// The JSON encoding of the proof
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the proof cannot be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        to_json(&self.header(), self)
+    }
+
+    /// Decode a proof from its JSON encoding
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a proof over `G`, or
+    /// if its version is not supported.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let (header, proof): (_, Self) = from_json::<G, _>(json, Kind::Proof)?;
+        header.check_features(&proof.feature_flags())?;
+        Ok(proof)
+    }
+}
+
+impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
+where
+    OpeningProof::SRS: Default,
+{
+    /// The features of the circuit of the verifier index
+    pub fn feature_flags(&self) -> FeatureFlags {
+        FeatureFlags {
+            range_check0: self.range_check0_comm.is_some(),
+            range_check1: self.range_check1_comm.is_some(),
+            foreign_field_add: self.foreign_field_add_comm.is_some(),
+            foreign_field_mul: self.foreign_field_mul_comm.is_some(),
+            xor: self.xor_comm.is_some(),
+            rot: self.rot_comm.is_some(),
+            lookup_features: self
+                .lookup_index
+                .as_ref()
+                .map_or(FeatureFlags::default().lookup_features, |lookup| {
+                    lookup.lookup_info.features
+                }),
+        }
+    }
+
+    /// The header of the encodings of the verifier index
+    fn header(&self) -> Header {
+        Header::new::<G>(Kind::VerifierIndex, &self.feature_flags())
+    }
+
+    /// Fill in the fields of a decoded verifier index that are not encoded:
+    /// the SRS and the endoscalar coefficient, which are given, and the
+    /// linearization, which is computed from the features of the circuit
+    fn complete(mut self, srs: Arc<OpeningProof::SRS>, endo: G::ScalarField) -> Self {
+        let (linearization, powers_of_alpha) =
+            expr_linearization(Some(&self.feature_flags()), true);
+        self.srs = srs;
+        self.endo = endo;
+        self.linearization = linearization;
+        self.powers_of_alpha = powers_of_alpha;
+        self
+    }
+
+    /// Write the binary encoding of the verifier index, without its SRS
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the verifier index cannot be serialized, or if
+    /// `writer` fails.
+    pub fn write_to<W: Write>(&self, writer: W) -> Result<()> {
+        write_binary(writer, &self.header(), self)
+    }
+
+    /// Read a verifier index from its binary encoding, in any version of the
+    /// format, given its SRS and its endoscalar coefficient
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a verifier index over
+    /// `G`, if its version is not supported, or if `reader` fails.
+    pub fn read_from<R: BufRead>(
+        reader: R,
+        srs: Arc<OpeningProof::SRS>,
+        endo: G::ScalarField,
+    ) -> Result<Self> {
+        let (header, index): (_, Self) = read_binary::<G, _, _>(reader, Kind::VerifierIndex)?;
+        if let Some(header) = header {
+            header.check_features(&index.feature_flags())?;
+        }
+        Ok(index.complete(srs, endo))
+    }
+
+    /// The binary encoding of the verifier index, without its SRS
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the verifier index cannot be serialized.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let mut bytes = vec![];
+        self.write_to(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Decode a verifier index from its binary encoding, see
+    /// [`VerifierIndex::read_from`]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a verifier index over
+    /// `G`, or if its version is not supported.
+    pub fn from_bytes(
+        bytes: &[u8],
+        srs: Arc<OpeningProof::SRS>,
+        endo: G::ScalarField,
+    ) -> Result<Self> {
+        Self::read_from(bytes, srs, endo)
+    }
+
+    /// The JSON encoding of the verifier index, without its SRS
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the verifier index cannot be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        to_json(&self.header(), self)
+    }
+
+    /// Decode a verifier index from its JSON encoding, given its SRS and its
+    /// endoscalar coefficient
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the encoding is not the one of a verifier index over
+    /// `G`, or if its version is not supported.
+    pub fn from_json(
+        json: &str,
+        srs: Arc<OpeningProof::SRS>,
+        endo: G::ScalarField,
+    ) -> Result<Self> {
+        let (header, index): (_, Self) = from_json::<G, _>(json, Kind::VerifierIndex)?;
+        header.check_features(&index.feature_flags())?;
+        Ok(index.complete(srs, endo))
+    }
+}
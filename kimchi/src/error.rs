@@ -1,12 +1,98 @@
 //! This module implements the [`ProverError`] type.
 
-use crate::circuits::lookup::index::LookupError; // not sure about hierarchy
+use crate::circuits::{
+    constraints::GateError,
+    gate::{CircuitGate, GateType},
+    lookup::index::LookupError, // not sure about hierarchy
+    wires::{Wire, COLUMNS},
+};
+use ark_ff::PrimeField;
 use poly_commitment::error::CommitmentError;
+use std::fmt;
 use thiserror::Error;
 
+/// Location and value of the witness data that caused a [`ProverError`].
+/// Only the known fields are set, and displayed.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ErrorContext {
+    /// Type of the gate
+    pub gate_type: Option<GateType>,
+    /// Row of the gate or witness cell
+    pub row: Option<usize>,
+    /// Column of the witness cell
+    pub column: Option<usize>,
+    /// Id of the lookup table
+    pub table_id: Option<i32>,
+    /// Offending value, in decimal
+    pub value: Option<String>,
+}
+
+impl ErrorContext {
+    /// Creates the context of the gate or witness cell at `row`
+    pub fn row(row: usize) -> Self {
+        Self {
+            row: Some(row),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the type of the gate
+    pub fn gate_type(mut self, gate_type: GateType) -> Self {
+        self.gate_type = Some(gate_type);
+        self
+    }
+
+    /// Sets the column of the witness cell
+    pub fn column(mut self, column: usize) -> Self {
+        self.column = Some(column);
+        self
+    }
+
+    /// Sets the id of the lookup table
+    pub fn table_id(mut self, table_id: i32) -> Self {
+        self.table_id = Some(table_id);
+        self
+    }
+
+    /// Sets the offending value
+    pub fn value<F: PrimeField>(mut self, value: F) -> Self {
+        self.value = Some(value.into_bigint().to_string());
+        self
+    }
+
+    /// Sets the offending value to a tuple of field elements, e.g. a lookup entry
+    pub fn values<F: PrimeField>(mut self, values: &[F]) -> Self {
+        let values: Vec<_> = values.iter().map(|v| v.into_bigint().to_string()).collect();
+        self.value = Some(format!("({})", values.join(", ")));
+        self
+    }
+}
+
+impl fmt::Display for ErrorContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut fields = vec![];
+        if let Some(gate_type) = self.gate_type {
+            fields.push(format!("gate={gate_type:?}"));
+        }
+        if let Some(row) = self.row {
+            fields.push(format!("row={row}"));
+        }
+        if let Some(column) = self.column {
+            fields.push(format!("column={column}"));
+        }
+        if let Some(table_id) = self.table_id {
+            fields.push(format!("table_id={table_id}"));
+        }
+        if let Some(value) = &self.value {
+            fields.push(format!("value={value}"));
+        }
+        write!(f, "{}", fields.join(", "))
+    }
+}
+
 /// Errors that can arise when creating a proof
 // TODO(mimoo): move this out of oracle
-#[derive(Error, Debug, Clone, Copy)]
+#[derive(Error, Debug, Clone)]
 pub enum ProverError {
     #[error("the circuit is too large")]
     NoRoomForZkInWitness,
@@ -25,8 +111,14 @@ pub enum ProverError {
     #[error("the permutation was not constructed correctly: {0}")]
     Permutation(&'static str),
 
-    #[error("the lookup failed to find a match in the table: row={0}")]
-    ValueNotInTable(usize),
+    #[error("the lookup failed to find a match in the table: {0}")]
+    ValueNotInTable(ErrorContext),
+
+    #[error("the witness does not satisfy the circuit ({context}): {reason}")]
+    InvalidWitness {
+        context: ErrorContext,
+        reason: String,
+    },
 
     #[error("the runtime tables provided did not match the index's configuration")]
     RuntimeTablesInconsistent,
@@ -35,6 +127,49 @@ pub enum ProverError {
     WrongBlinders(CommitmentError),
 }
 
+impl ProverError {
+    /// Creates an [`ProverError::InvalidWitness`] error from the error `err` returned when
+    /// verifying `witness` against the circuit `gates`
+    pub fn invalid_witness<F: PrimeField>(
+        err: GateError,
+        gates: &[CircuitGate<F>],
+        witness: &[Vec<F>; COLUMNS],
+    ) -> Self {
+        // The witness is padded with zeros up to the domain size when verified
+        let cell = |wire: Wire| {
+            witness[wire.col]
+                .get(wire.row)
+                .copied()
+                .unwrap_or_else(F::zero)
+        };
+        let gate_context = |row: usize| {
+            let context = ErrorContext::row(row);
+            match gates.get(row) {
+                Some(gate) => context.gate_type(gate.typ),
+                None => context,
+            }
+        };
+
+        let (context, reason) = match err {
+            GateError::DisconnectedWires(src, dst) => (
+                gate_context(src.row).column(src.col).value(cell(src)),
+                format!(
+                    "copy constraint to row={}, column={} is not satisfied (value={})",
+                    dst.row,
+                    dst.col,
+                    cell(dst).into_bigint()
+                ),
+            ),
+            GateError::IncorrectPublic(row) => (
+                gate_context(row),
+                "public input gate is not correctly set up".to_string(),
+            ),
+            GateError::Custom { row, err } => (gate_context(row), err),
+        };
+        ProverError::InvalidWitness { context, reason }
+    }
+}
+
 /// Errors that can arise when verifying a proof
 #[derive(Error, Debug, Clone, Copy)]
 pub enum VerifyError {
@@ -112,3 +247,52 @@ pub enum VerifierIndexError {
     #[error("srs has already been set")]
     SRSHasBeenSet,
 }
+
+/// Errors that can arise when encoding or decoding a proof or a verifier index
+#[derive(Error, Debug, Clone)]
+pub enum EncodingError {
+    #[error("malformed header: {0}")]
+    MalformedHeader(String),
+    #[error("the encoding is for a {found}, not a {expected}")]
+    WrongKind {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("the format version {0} is not supported")]
+    UnsupportedVersion(u16),
+    #[error("the encoding is for the curve {found}, not {expected}")]
+    WrongCurve {
+        expected: &'static str,
+        found: String,
+    },
+    #[error("the features of the header ({header:#x}) do not match the ones of the content ({content:#x})")]
+    FeatureMismatch { header: u32, content: u32 },
+    #[error("unknown feature {0}")]
+    UnknownFeature(String),
+    #[error("i/o error: {0}")]
+    Io(String),
+    #[error("MessagePack error: {0}")]
+    MessagePack(String),
+    #[error("JSON error: {0}")]
+    Json(String),
+}
+
+/// Errors that can arise when encoding, decoding or verifying a proof of the
+/// EVM profile
+#[derive(Error, Debug, Clone)]
+pub enum EvmError {
+    #[error("the feature {0} is not supported by the EVM profile")]
+    UnsupportedFeature(&'static str),
+    #[error("recursion is not supported by the EVM profile")]
+    Recursion,
+    #[error("chunked polynomials are not supported by the EVM profile")]
+    Chunking,
+    #[error("the calldata is of an unexpected size (expected {0}, got {1})")]
+    IncorrectCalldataLength(usize, usize),
+    #[error("the word at offset {0} of the calldata is not a field element")]
+    InvalidFieldElement(usize),
+    #[error("the point at offset {0} of the calldata is not on the curve")]
+    InvalidPoint(usize),
+    #[error("the proof failed to verify: {0}")]
+    Verify(VerifyError),
+}
@@ -0,0 +1,327 @@
+//! This module implements the layout of the calldata of the EVM verifier: the
+//! public input of a proof, followed by the proof itself.
+//!
+//! The calldata is a sequence of 32-byte words. A field element is one word,
+//! its canonical representative in big-endian, and a point is two words, its
+//! coordinates, the point at infinity being `(0, 0)`. These are the encodings
+//! of the BN254 precompiles of the EVM.
+//!
+//! | content                                | words                      |
+//! |----------------------------------------|----------------------------|
+//! | the public input                       | `public`                   |
+//! | the commitments to the witness columns | `2 * COLUMNS`              |
+//! | the commitment to the permutation      | `2`                        |
+//! | the chunks of the quotient commitment  | `2 * QUOTIENT_CHUNKS`      |
+//! | `ft(ζω)`                               | `1`                        |
+//! | the evaluations at `ζ` and `ζω`        | `2 * EVALUATIONS`          |
+//! | the quotient of the opening            | `2`                        |
+//! | the blinding factor of the opening     | `1`                        |
+//!
+//! The evaluations of each polynomial are in the order in which the Fr-sponge
+//! absorbs them: the permutation, the selectors of the generic, Poseidon,
+//! complete addition, variable base multiplication, endomorphism
+//! multiplication and endomorphism scalar gates, the witness columns, the
+//! coefficients and the permutation columns. Their section of the calldata can
+//! then be hashed as is. The evaluations of the public input polynomial are not
+//! part of the calldata, the verifier computes them from the public input.
+
+use super::{Curve, EvmError, Fr, OpeningProof, Proof, Result};
+use crate::{
+    circuits::wires::{COLUMNS, PERMUTS},
+    plonk_sponge::evaluations_to_absorb,
+    proof::{PointEvaluations, ProofEvaluations, ProverCommitments},
+};
+use ark_bn254::Fq;
+use ark_ec::AffineRepr;
+use ark_ff::{BigInt, BigInteger, PrimeField, Zero};
+use poly_commitment::commitment::PolyComm;
+use std::array;
+
+/// The size in bytes of a word of the calldata
+pub const WORD_SIZE: usize = 32;
+
+/// The number of chunks of the commitment to the quotient polynomial
+pub const QUOTIENT_CHUNKS: usize = 7;
+
+/// The number of polynomials evaluated in the calldata
+pub const EVALUATIONS: usize = 7 + 2 * COLUMNS + PERMUTS - 1;
+
+/// The number of words of the calldata of a proof, besides its public input
+const PROOF_WORDS: usize = 2 * COLUMNS + 2 + 2 * QUOTIENT_CHUNKS + 1 + 2 * EVALUATIONS + 2 + 1;
+
+/// A proof of the EVM profile with its public input, as laid out in the
+/// calldata
+#[derive(Clone, Debug, PartialEq)]
+pub struct Calldata {
+    /// The public input of the proof
+    pub public_input: Vec<Fr>,
+    /// The commitments to the witness columns
+    pub w_comm: [Curve; COLUMNS],
+    /// The commitment to the permutation polynomial
+    pub z_comm: Curve,
+    /// The chunks of the commitment to the quotient polynomial
+    pub t_comm: [Curve; QUOTIENT_CHUNKS],
+    /// The evaluation of `ft` at `ζω`
+    pub ft_eval1: Fr,
+    /// The evaluations of the polynomials, without the ones of the public
+    /// input
+    pub evals: ProofEvaluations<PointEvaluations<Fr>>,
+    /// The quotient of the KZG opening
+    pub quotient: Curve,
+    /// The blinding factor of the KZG opening
+    pub blinding: Fr,
+}
+
+impl Calldata {
+    /// The size in bytes of the calldata of a proof with `public` public
+    /// inputs
+    pub fn size(public: usize) -> usize {
+        WORD_SIZE * (public + PROOF_WORDS)
+    }
+
+    /// Lay out `proof` and its public input `public_input`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `proof` is not a proof of the profile, see
+    /// [`super::check_index`].
+    pub fn new(proof: &Proof, public_input: &[Fr]) -> Result<Self> {
+        if let Some(feature) = proof.feature_flags().enabled().first() {
+            return Err(EvmError::UnsupportedFeature(feature));
+        }
+        if proof.commitments.lookup.is_some() || proof.evals.lookup_aggregation.is_some() {
+            return Err(EvmError::UnsupportedFeature("lookup"));
+        }
+        if !proof.prev_challenges.is_empty() {
+            return Err(EvmError::Recursion);
+        }
+
+        let commitments = &proof.commitments;
+        let single = |comm: &PolyComm<Curve>| match comm.chunks[..] {
+            [chunk] => Ok(chunk),
+            _ => Err(EvmError::Chunking),
+        };
+        let mut w_comm = [Curve::zero(); COLUMNS];
+        for (w, comm) in w_comm.iter_mut().zip(&commitments.w_comm) {
+            *w = single(comm)?;
+        }
+        let t_comm = commitments
+            .t_comm
+            .chunks
+            .clone()
+            .try_into()
+            .map_err(|_| EvmError::Chunking)?;
+
+        let not_chunked = |evals: &Vec<Fr>| evals.len() == 1;
+        if !evaluations_to_absorb(&proof.evals)
+            .into_iter()
+            .all(|evals| not_chunked(&evals.zeta) && not_chunked(&evals.zeta_omega))
+        {
+            return Err(EvmError::Chunking);
+        }
+        let mut evals = proof.evals.map_ref(&|evals| PointEvaluations {
+            zeta: evals.zeta[0],
+            zeta_omega: evals.zeta_omega[0],
+        });
+        evals.public = None;
+
+        Ok(Calldata {
+            public_input: public_input.to_vec(),
+            w_comm,
+            z_comm: single(&commitments.z_comm)?,
+            t_comm,
+            ft_eval1: proof.ft_eval1,
+            evals,
+            quotient: proof.proof.quotient,
+            blinding: proof.proof.blinding,
+        })
+    }
+
+    /// The proof and its public input, to verify with [`crate::verifier`]
+    pub fn into_proof(self) -> (Proof, Vec<Fr>) {
+        let proof = Proof {
+            commitments: ProverCommitments {
+                w_comm: self.w_comm.map(|w| PolyComm::new(vec![w])),
+                z_comm: PolyComm::new(vec![self.z_comm]),
+                t_comm: PolyComm::new(self.t_comm.to_vec()),
+                lookup: None,
+            },
+            proof: OpeningProof {
+                quotient: self.quotient,
+                blinding: self.blinding,
+            },
+            evals: self.evals.map(&|evals| PointEvaluations {
+                zeta: vec![evals.zeta],
+                zeta_omega: vec![evals.zeta_omega],
+            }),
+            ft_eval1: self.ft_eval1,
+            prev_challenges: vec![],
+        };
+        (proof, self.public_input)
+    }
+
+    /// The evaluations of the calldata, in their order in the calldata
+    pub fn evaluations(&self) -> Vec<&PointEvaluations<Fr>> {
+        evaluations_to_absorb(&self.evals)
+    }
+
+    /// The encoding of the calldata
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::size(self.public_input.len()));
+        for x in &self.public_input {
+            write_field(&mut bytes, x);
+        }
+        for g in self.w_comm.iter().chain([&self.z_comm]).chain(&self.t_comm) {
+            write_point(&mut bytes, g);
+        }
+        write_field(&mut bytes, &self.ft_eval1);
+        for evals in self.evaluations() {
+            write_field(&mut bytes, &evals.zeta);
+            write_field(&mut bytes, &evals.zeta_omega);
+        }
+        write_point(&mut bytes, &self.quotient);
+        write_field(&mut bytes, &self.blinding);
+        bytes
+    }
+
+    /// Decode the calldata `bytes` of a proof with `public` public inputs
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` is not of the size of the calldata, or if one
+    /// of its words is not a field element or one of its points is not on the
+    /// curve.
+    pub fn from_bytes(bytes: &[u8], public: usize) -> Result<Self> {
+        if bytes.len() != Self::size(public) {
+            return Err(EvmError::IncorrectCalldataLength(
+                Self::size(public),
+                bytes.len(),
+            ));
+        }
+        let mut reader = Reader { bytes, offset: 0 };
+
+        let public_input = (0..public).map(|_| reader.field()).collect::<Result<_>>()?;
+        let mut w_comm = [Curve::zero(); COLUMNS];
+        for w in &mut w_comm {
+            *w = reader.point()?;
+        }
+        let z_comm = reader.point()?;
+        let mut t_comm = [Curve::zero(); QUOTIENT_CHUNKS];
+        for t in &mut t_comm {
+            *t = reader.point()?;
+        }
+        let ft_eval1 = reader.field()?;
+
+        // in the order of `evaluations_to_absorb`
+        let mut evals = (0..EVALUATIONS)
+            .map(|_| {
+                Ok(PointEvaluations {
+                    zeta: reader.field()?,
+                    zeta_omega: reader.field()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?
+            .into_iter();
+        let mut next = || evals.next().expect("the number of evaluations is fixed");
+        let z = next();
+        let generic_selector = next();
+        let poseidon_selector = next();
+        let complete_add_selector = next();
+        let mul_selector = next();
+        let emul_selector = next();
+        let endomul_scalar_selector = next();
+        let w = array::from_fn(|_| next());
+        let coefficients = array::from_fn(|_| next());
+        let s = array::from_fn(|_| next());
+        let evals = ProofEvaluations {
+            public: None,
+            w,
+            z,
+            s,
+            coefficients,
+            generic_selector,
+            poseidon_selector,
+            complete_add_selector,
+            mul_selector,
+            emul_selector,
+            endomul_scalar_selector,
+            range_check0_selector: None,
+            range_check1_selector: None,
+            foreign_field_add_selector: None,
+            foreign_field_mul_selector: None,
+            xor_selector: None,
+            rot_selector: None,
+            lookup_aggregation: None,
+            lookup_table: None,
+            lookup_sorted: array::from_fn(|_| None),
+            runtime_lookup_table: None,
+            runtime_lookup_table_selector: None,
+            xor_lookup_selector: None,
+            lookup_gate_lookup_selector: None,
+            range_check_lookup_selector: None,
+            foreign_field_mul_lookup_selector: None,
+        };
+
+        Ok(Calldata {
+            public_input,
+            w_comm,
+            z_comm,
+            t_comm,
+            ft_eval1,
+            evals,
+            quotient: reader.point()?,
+            blinding: reader.field()?,
+        })
+    }
+}
+
+fn write_field<F: PrimeField>(bytes: &mut Vec<u8>, x: &F) {
+    bytes.extend(x.into_bigint().to_bytes_be());
+}
+
+fn write_point(bytes: &mut Vec<u8>, g: &Curve) {
+    if g.infinity {
+        write_field(bytes, &Fq::zero());
+        write_field(bytes, &Fq::zero());
+    } else {
+        write_field(bytes, &g.x);
+        write_field(bytes, &g.y);
+    }
+}
+
+/// Reads the words of a calldata of the right size
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl Reader<'_> {
+    /// Read a field element, rejecting the non-canonical representatives
+    fn field<F: PrimeField<BigInt = BigInt<4>>>(&mut self) -> Result<F> {
+        let word = &self.bytes[self.offset..self.offset + WORD_SIZE];
+        let limbs = array::from_fn(|i| {
+            let end = WORD_SIZE - 8 * i;
+            u64::from_be_bytes(word[end - 8..end].try_into().unwrap())
+        });
+        let x =
+            F::from_bigint(BigInt::new(limbs)).ok_or(EvmError::InvalidFieldElement(self.offset));
+        self.offset += WORD_SIZE;
+        x
+    }
+
+    /// Read a point, checking that it is in the group
+    fn point(&mut self) -> Result<Curve> {
+        let offset = self.offset;
+        let x: Fq = self.field()?;
+        let y: Fq = self.field()?;
+        if x.is_zero() && y.is_zero() {
+            return Ok(Curve::zero());
+        }
+        let g = Curve::new_unchecked(x, y);
+        if g.is_on_curve() && g.is_in_correct_subgroup_assuming_on_curve() {
+            Ok(g)
+        } else {
+            Err(EvmError::InvalidPoint(offset))
+        }
+    }
+}
@@ -0,0 +1,74 @@
+//! This module implements the profile of kimchi for the proofs verified by a
+//! smart contract on the EVM:
+//!
+//! - the proofs are over BN254, the curve of the precompiles of the EVM,
+//! - the polynomial commitment scheme is KZG, checked with a single pairing,
+//! - the Fiat-Shamir transform uses Keccak-256, see [`crate::keccak_sponge`].
+//!
+//! The input of the contract is the [`calldata::Calldata`] of a proof, and
+//! [`verifier::verify`] is a reference implementation of the contract.
+//!
+//! To keep the layout of the calldata fixed, the profile only supports the
+//! circuits without lookups and optional gates, without recursion, and whose
+//! domain fits in the SRS (i.e. whose polynomials are not chunked).
+
+pub mod calldata;
+pub mod verifier;
+
+use crate::{
+    error::EvmError,
+    keccak_sponge::{KeccakFqSponge, KeccakFrSponge},
+    proof::ProverProof,
+    verifier_index::VerifierIndex,
+};
+use ark_poly::EvaluationDomain;
+use poly_commitment::kzg::KZGProof;
+
+/// The curve of the profile
+pub type Curve = ark_bn254::G1Affine;
+
+/// The scalar field of [`Curve`]
+pub type Fr = ark_bn254::Fr;
+
+/// The pairing of the KZG openings
+pub type Pairing = ark_bn254::Bn254;
+
+/// The opening proof of the profile
+pub type OpeningProof = KZGProof<Pairing>;
+
+/// The Fq-sponge of the profile
+pub type BaseSponge = KeccakFqSponge<ark_bn254::g1::Config>;
+
+/// The Fr-sponge of the profile
+pub type ScalarSponge = KeccakFrSponge<Fr>;
+
+/// A proof of the profile
+pub type Proof = ProverProof<Curve, OpeningProof>;
+
+/// A verifier index of the profile
+pub type Index = VerifierIndex<Curve, OpeningProof>;
+
+/// The result of an operation of the profile
+pub type Result<T> = std::result::Result<T, EvmError>;
+
+/// Check that the circuit of `index` is supported by the profile
+///
+/// # Errors
+///
+/// Will give error if the circuit uses lookups or optional gates, if it
+/// verifies previous proofs, or if its domain is larger than the SRS.
+pub fn check_index(index: &Index) -> Result<()> {
+    if let Some(feature) = index.feature_flags().enabled().first() {
+        return Err(EvmError::UnsupportedFeature(feature));
+    }
+    if index.lookup_index.is_some() {
+        return Err(EvmError::UnsupportedFeature("lookup"));
+    }
+    if index.prev_challenges > 0 {
+        return Err(EvmError::Recursion);
+    }
+    if index.domain.size() > index.max_poly_size {
+        return Err(EvmError::Chunking);
+    }
+    Ok(())
+}
@@ -0,0 +1,265 @@
+//! This module implements a reference verifier of the EVM profile.
+//!
+//! It follows step by step what the verifier contract does: it decodes the
+//! calldata, runs the Keccak-256 transcript, evaluates the public input
+//! polynomial, computes `ft(ζ)` and the commitment to `ft`, and batches all
+//! the openings into a single pairing check. The verifier index only provides
+//! the values that the contract embeds as constants: its digest, its
+//! commitments, the shifts of the permutation, the domain, the linearization
+//! of the constraints and the points of the SRS that are used.
+
+use super::{
+    calldata::Calldata, check_index, BaseSponge, Curve, Fr, Index, Pairing, Result, ScalarSponge,
+};
+use crate::{
+    circuits::{
+        argument::ArgumentType,
+        berkeley_columns::{BerkeleyChallenges, Column},
+        constraints::ConstraintSystem,
+        expr::{Constants, PolishToken},
+        gate::GateType,
+        polynomials::permutation,
+        wires::{COLUMNS, PERMUTS},
+    },
+    curve::KimchiCurve,
+    error::{EvmError, VerifyError},
+    plonk_sponge::FrSponge,
+    proof::PointEvaluations,
+};
+use ark_ec::{pairing::Pairing as _, AffineRepr, CurveGroup};
+use ark_ff::{batch_inversion, Field, One, Zero};
+use ark_poly::EvaluationDomain;
+use mina_poseidon::{sponge::ScalarChallenge, FqSponge};
+use poly_commitment::SRS as _;
+
+/// The columns of the evaluations of the calldata, in their order
+fn columns() -> impl Iterator<Item = Column> {
+    [
+        Column::Z,
+        Column::Index(GateType::Generic),
+        Column::Index(GateType::Poseidon),
+        Column::Index(GateType::CompleteAdd),
+        Column::Index(GateType::VarBaseMul),
+        Column::Index(GateType::EndoMul),
+        Column::Index(GateType::EndoMulScalar),
+    ]
+    .into_iter()
+    .chain((0..COLUMNS).map(Column::Witness))
+    .chain((0..COLUMNS).map(Column::Coefficient))
+    .chain((0..PERMUTS - 1).map(Column::Permutation))
+}
+
+/// Verify the proof of the calldata `calldata` against `index`, as the verifier
+/// contract does
+///
+/// # Errors
+///
+/// Will give error if the circuit of `index` is not supported by the profile,
+/// if `calldata` cannot be decoded, or if the proof is invalid.
+pub fn verify(index: &Index, calldata: &[u8]) -> Result<()> {
+    check_index(index)?;
+    let calldata = Calldata::from_bytes(calldata, index.public)?;
+
+    let srs = index.srs();
+    let domain = index.domain;
+    let n = domain.size;
+    let (_, endo_r) = Curve::endos();
+
+    let commitment = |col: Column| {
+        let comm = match col {
+            Column::Witness(i) => return Ok(calldata.w_comm[i]),
+            Column::Z => return Ok(calldata.z_comm),
+            Column::Coefficient(i) => &index.coefficients_comm[i],
+            Column::Permutation(i) => &index.sigma_comm[i],
+            Column::Index(GateType::Generic) => &index.generic_comm,
+            Column::Index(GateType::Poseidon) => &index.psm_comm,
+            Column::Index(GateType::CompleteAdd) => &index.complete_add_comm,
+            Column::Index(GateType::VarBaseMul) => &index.mul_comm,
+            Column::Index(GateType::EndoMul) => &index.emul_comm,
+            Column::Index(GateType::EndoMulScalar) => &index.endomul_scalar_comm,
+            _ => return Err(EvmError::Verify(VerifyError::MissingCommitment(col))),
+        };
+        Ok(comm.get_first_chunk())
+    };
+
+    //~ 1. Commit to the negated public input polynomial, masked by the blinding
+    //~    commitment.
+    let lagrange_basis = srs.get_lagrange_basis(domain);
+    let public_comm = calldata
+        .public_input
+        .iter()
+        .zip(lagrange_basis)
+        .fold(srs.blinding_commitment().into_group(), |acc, (x, l)| {
+            acc - l.get_first_chunk() * x
+        })
+        .into_affine();
+
+    //~ 1. Run the Fq-sponge over the digest of the verifier index and the
+    //~    commitments of the proof, to get $\beta$, $\gamma$, $\alpha$ and $\zeta$.
+    let mut fq_sponge = BaseSponge::new(Curve::other_curve_sponge_params());
+    fq_sponge.absorb_fq(&[index.digest::<BaseSponge>()]);
+    fq_sponge.absorb_g(&[public_comm]);
+    fq_sponge.absorb_g(&calldata.w_comm);
+    let beta = fq_sponge.challenge();
+    let gamma = fq_sponge.challenge();
+    fq_sponge.absorb_g(&[calldata.z_comm]);
+    let alpha = ScalarChallenge(fq_sponge.challenge()).to_field(endo_r);
+    fq_sponge.absorb_g(&calldata.t_comm);
+    let zeta = ScalarChallenge(fq_sponge.challenge()).to_field(endo_r);
+
+    //~ 1. Evaluate the negated public input polynomial at $\zeta$ and
+    //~    $\zeta\omega$, with the barycentric formula.
+    let zeta_omega = zeta * domain.group_gen;
+    let zeta_n = zeta.pow([n]);
+    let public_evals = [zeta, zeta_omega].map(|x| {
+        let mut denominators: Vec<_> = domain
+            .elements()
+            .take(calldata.public_input.len())
+            .map(|w| x - w)
+            .collect();
+        batch_inversion(&mut denominators);
+        let sum: Fr = calldata
+            .public_input
+            .iter()
+            .zip(domain.elements())
+            .zip(denominators)
+            .map(|((p, w), d)| -*p * w * d)
+            .sum();
+        sum * (x.pow([n]) - Fr::one()) * domain.size_inv
+    });
+
+    //~ 1. Run the Fr-sponge over the digest of the Fq-sponge and the
+    //~    evaluations, to get $v$. There are no previous challenges, whose
+    //~    digest is the one of an empty sponge. The evalscale $u$ is not used
+    //~    by KZG.
+    let mut fr_sponge = ScalarSponge::new(Curve::sponge_params());
+    fr_sponge.absorb(&fq_sponge.digest());
+    fr_sponge.absorb(&ScalarSponge::new(Curve::sponge_params()).digest());
+    fr_sponge.absorb(&calldata.ft_eval1);
+    fr_sponge.absorb_multiple(&public_evals);
+    for evals in calldata.evaluations() {
+        fr_sponge.absorb(&evals.zeta);
+        fr_sponge.absorb(&evals.zeta_omega);
+    }
+    let v = fr_sponge.challenge().to_field(endo_r);
+
+    let mut evals = calldata.evals.clone();
+    evals.public = Some(PointEvaluations {
+        zeta: public_evals[0],
+        zeta_omega: public_evals[1],
+    });
+    let (w, z, s) = (&evals.w, &evals.z, &evals.s);
+
+    //~ 1. Compute $ft(\zeta)$, from the permutation argument and the constant
+    //~    term of the linearization.
+    let mut all_alphas = index.powers_of_alpha.clone();
+    all_alphas.instantiate(alpha);
+    let alphas: Vec<_> = all_alphas
+        .get_alphas(ArgumentType::Permutation, permutation::CONSTRAINTS)
+        .collect();
+    let zkp: Fr = (1..=index.zk_rows)
+        .map(|i| zeta - domain.group_gen.pow([n - i]))
+        .product();
+    let zk_w = domain.group_gen.pow([n - index.zk_rows]);
+    let constants = Constants {
+        endo_coefficient: index.endo,
+        mds: &Curve::sponge_params().mds,
+        zk_rows: index.zk_rows,
+    };
+    let challenges = BerkeleyChallenges {
+        alpha,
+        beta,
+        gamma,
+        joint_combiner: Fr::zero(),
+    };
+    let evaluate = |tokens: &[_]| {
+        PolishToken::evaluate(tokens, domain, zeta, &evals, &constants, &challenges)
+            .expect("the linearization of the profile only uses the evaluations of the calldata")
+    };
+
+    let ft_eval0 = {
+        let init = (w[PERMUTS - 1].zeta + gamma) * z.zeta_omega * alphas[0] * zkp;
+        let mut ft_eval0 = w
+            .iter()
+            .zip(s)
+            .map(|(w, s)| beta * s.zeta + w.zeta + gamma)
+            .fold(init, |acc, x| acc * x);
+        ft_eval0 -= public_evals[0];
+        ft_eval0 -= w
+            .iter()
+            .zip(&index.shift)
+            .map(|(w, shift)| gamma + beta * zeta * shift + w.zeta)
+            .fold(alphas[0] * zkp * z.zeta, |acc, x| acc * x);
+        let numerator = (zeta_n - Fr::one())
+            * (alphas[1] * (zeta - zk_w) + alphas[2] * (zeta - Fr::one()))
+            * (Fr::one() - z.zeta);
+        let denominator = (zeta - zk_w) * (zeta - Fr::one());
+        ft_eval0 += numerator * denominator.inverse().expect("negligible probability");
+        ft_eval0 - evaluate(&index.linearization.constant_term)
+    };
+
+    //~ 1. Compute the commitment to $ft$, from the linearization and the chunks
+    //~    of the quotient.
+    let ft_comm = {
+        let perm_scalar =
+            ConstraintSystem::perm_scalars(&evals, beta, gamma, alphas.iter().copied(), zkp);
+        let mut f_comm = index.sigma_comm[PERMUTS - 1].get_first_chunk() * perm_scalar;
+        for (col, tokens) in &index.linearization.index_terms {
+            f_comm += commitment(*col)? * evaluate(tokens);
+        }
+        let zeta_to_srs_len = zeta.pow([index.max_poly_size as u64]);
+        let t_comm = calldata
+            .t_comm
+            .iter()
+            .rev()
+            .fold(<Curve as AffineRepr>::Group::zero(), |acc, t| {
+                acc * zeta_to_srs_len + t
+            });
+        f_comm - t_comm * (zeta_n - Fr::one())
+    };
+
+    //~ 1. Combine the commitments and the evaluations of the opening with the
+    //~    powers of $v$: the public input, $ft$ and the columns of the calldata.
+    let mut openings = vec![
+        (public_comm.into_group(), public_evals),
+        (ft_comm, [ft_eval0, calldata.ft_eval1]),
+    ];
+    for (col, evals) in columns().zip(calldata.evaluations()) {
+        openings.push((
+            commitment(col)?.into_group(),
+            [evals.zeta, evals.zeta_omega],
+        ));
+    }
+    let mut combined_comm = <Curve as AffineRepr>::Group::zero();
+    let mut combined_evals = [Fr::zero(); 2];
+    let mut v_i = Fr::one();
+    for (comm, evals) in openings {
+        combined_comm += comm * v_i;
+        combined_evals[0] += evals[0] * v_i;
+        combined_evals[1] += evals[1] * v_i;
+        v_i *= v;
+    }
+
+    //~ 1. Check the KZG opening at $\zeta$ and $\zeta\omega$ with a pairing:
+    //~    $e(C - [I(x)]_1 - b [h]_1, [1]_2) = e(q, [(x - \zeta)(x - \zeta\omega)]_2)$,
+    //~    where $I$ is the line through the combined evaluations.
+    let g1 = &srs.full_srs.g;
+    let g2 = &srs.verifier_srs.g;
+    let slope = (combined_evals[1] - combined_evals[0]) / (zeta_omega - zeta);
+    let intercept = combined_evals[0] - slope * zeta;
+    let numerator =
+        combined_comm - g1[0] * intercept - g1[1] * slope - srs.full_srs.h * calldata.blinding;
+    let divisor = g2[0] * (zeta * zeta_omega) - g2[1] * (zeta + zeta_omega) + g2[2];
+    let pairing = Pairing::multi_pairing(
+        [
+            numerator.into_affine(),
+            (-calldata.quotient.into_group()).into_affine(),
+        ],
+        [g2[0], divisor.into_affine()],
+    );
+    if pairing.is_zero() {
+        Ok(())
+    } else {
+        Err(EvmError::Verify(VerifyError::OpenProof))
+    }
+}
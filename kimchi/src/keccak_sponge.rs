@@ -0,0 +1,169 @@
+//! This module implements the [`FqSponge`] and [`FrSponge`] of the
+//! Fiat-Shamir transform with Keccak-256, instead of Poseidon.
+//!
+//! Keccak-256 is the hash function that the EVM computes natively, which makes
+//! the challenges cheap to recompute by a verifier running in a smart
+//! contract (see [`crate::evm`]). The transcript is a chain of hashes:
+//!
+//! - the sponges absorb field elements as 32-byte big-endian words, and
+//!   points as their two coordinates, the point at infinity being `(0, 0)`,
+//! - a squeeze hashes the previous state (32 zero bytes initially) followed by
+//!   the words absorbed since the previous squeeze, and the hash becomes the
+//!   new state,
+//! - a field element squeezed out of the sponge is the hash, read as a
+//!   big-endian integer, reduced modulo the field size,
+//! - a challenge is made of the 128 lowest bits of the hash, like the
+//!   challenges of the Poseidon sponges.
+//!
+//! In Solidity, a squeeze is then
+//! `state = keccak256(abi.encodePacked(state, words))`.
+
+use crate::{
+    plonk_sponge::{evaluations_to_absorb, FrSponge},
+    proof::{PointEvaluations, ProofEvaluations},
+};
+use ark_ec::short_weierstrass::{Affine, SWCurveConfig};
+use ark_ff::{BigInteger, PrimeField, Zero};
+use mina_poseidon::{
+    poseidon::ArithmeticSpongeParams,
+    sponge::{ScalarChallenge, CHALLENGE_LENGTH_IN_LIMBS},
+    FqSponge,
+};
+use std::marker::PhantomData;
+use tiny_keccak::{Hasher, Keccak};
+
+/// The size in bytes of a Keccak-256 hash
+const HASH_SIZE: usize = 32;
+
+/// The size in bytes of a challenge
+const CHALLENGE_SIZE: usize = 8 * CHALLENGE_LENGTH_IN_LIMBS;
+
+/// The chain of Keccak-256 hashes shared by the sponges
+#[derive(Clone, Debug, Default)]
+struct KeccakState {
+    /// The last hash squeezed out of the sponge
+    state: [u8; HASH_SIZE],
+    /// The words absorbed since the last squeeze
+    pending: Vec<u8>,
+}
+
+impl KeccakState {
+    fn absorb<F: PrimeField>(&mut self, x: &F) {
+        self.pending.extend(x.into_bigint().to_bytes_be());
+    }
+
+    fn squeeze(&mut self) -> [u8; HASH_SIZE] {
+        let mut hasher = Keccak::v256();
+        hasher.update(&self.state);
+        hasher.update(&self.pending);
+        hasher.finalize(&mut self.state);
+        self.pending.clear();
+        self.state
+    }
+
+    fn squeeze_field<F: PrimeField>(&mut self) -> F {
+        F::from_be_bytes_mod_order(&self.squeeze())
+    }
+
+    fn squeeze_challenge<F: PrimeField>(&mut self) -> F {
+        F::from_be_bytes_mod_order(&self.squeeze()[HASH_SIZE - CHALLENGE_SIZE..])
+    }
+}
+
+/// A [`FqSponge`] for the curve `P`, based on Keccak-256
+#[derive(Clone, Debug)]
+pub struct KeccakFqSponge<P: SWCurveConfig> {
+    state: KeccakState,
+    _curve: PhantomData<P>,
+}
+
+impl<P: SWCurveConfig> FqSponge<P::BaseField, Affine<P>, P::ScalarField> for KeccakFqSponge<P>
+where
+    P::BaseField: PrimeField,
+{
+    /// Creates a new sponge. The parameters of Poseidon are ignored.
+    fn new(_params: &'static ArithmeticSpongeParams<P::BaseField>) -> Self {
+        KeccakFqSponge {
+            state: KeccakState::default(),
+            _curve: PhantomData,
+        }
+    }
+
+    fn absorb_fq(&mut self, x: &[P::BaseField]) {
+        x.iter().for_each(|x| self.state.absorb(x));
+    }
+
+    fn absorb_g(&mut self, g: &[Affine<P>]) {
+        for g in g {
+            if g.infinity {
+                // absorb a fake point (0, 0)
+                self.state.absorb(&P::BaseField::zero());
+                self.state.absorb(&P::BaseField::zero());
+            } else {
+                self.state.absorb(&g.x);
+                self.state.absorb(&g.y);
+            }
+        }
+    }
+
+    fn absorb_fr(&mut self, x: &[P::ScalarField]) {
+        // a scalar is a word too, there is no need to convert it to the base field
+        x.iter().for_each(|x| self.state.absorb(x));
+    }
+
+    fn challenge_fq(&mut self) -> P::BaseField {
+        self.state.squeeze_field()
+    }
+
+    fn challenge(&mut self) -> P::ScalarField {
+        self.state.squeeze_challenge()
+    }
+
+    fn digest_fq(mut self) -> P::BaseField {
+        self.state.squeeze_field()
+    }
+
+    fn digest(mut self) -> P::ScalarField {
+        self.state.squeeze_field()
+    }
+}
+
+/// A [`FrSponge`] for the field `Fr`, based on Keccak-256
+#[derive(Clone, Debug)]
+pub struct KeccakFrSponge<Fr> {
+    state: KeccakState,
+    _field: PhantomData<Fr>,
+}
+
+impl<Fr: PrimeField> FrSponge<Fr> for KeccakFrSponge<Fr> {
+    /// Creates a new sponge. The parameters of Poseidon are ignored.
+    fn new(_params: &'static ArithmeticSpongeParams<Fr>) -> Self {
+        KeccakFrSponge {
+            state: KeccakState::default(),
+            _field: PhantomData,
+        }
+    }
+
+    fn absorb(&mut self, x: &Fr) {
+        self.state.absorb(x);
+    }
+
+    fn absorb_multiple(&mut self, x: &[Fr]) {
+        x.iter().for_each(|x| self.state.absorb(x));
+    }
+
+    fn challenge(&mut self) -> ScalarChallenge<Fr> {
+        ScalarChallenge(self.state.squeeze_challenge())
+    }
+
+    fn digest(mut self) -> Fr {
+        self.state.squeeze_field()
+    }
+
+    fn absorb_evaluations(&mut self, e: &ProofEvaluations<PointEvaluations<Vec<Fr>>>) {
+        evaluations_to_absorb(e).into_iter().for_each(|p| {
+            self.absorb_multiple(&p.zeta);
+            self.absorb_multiple(&p.zeta_omega);
+        })
+    }
+}
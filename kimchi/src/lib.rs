@@ -14,7 +14,11 @@ pub mod alphas;
 pub mod bench;
 pub mod circuits;
 pub mod curve;
+pub mod encoding;
 pub mod error;
+#[cfg(feature = "bn254")]
+pub mod evm;
+pub mod keccak_sponge;
 pub mod lagrange_basis_evaluations;
 pub mod linearization;
 pub mod oracles;
@@ -199,7 +199,9 @@ where
         // Catch mistakes before proof generation.
         if cfg!(debug_assertions) && !index.cs.disable_gates_checks {
             let public = witness[0][0..index.cs.public].to_vec();
-            index.verify(&witness, &public).expect("incorrect witness");
+            index
+                .verify(&witness, &public)
+                .map_err(|err| ProverError::invalid_witness(err, &index.cs.gates, &witness))?;
         }
 
         //~ 1. Ensure we have room in the witness for the zero-knowledge rows.
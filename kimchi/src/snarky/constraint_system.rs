@@ -233,9 +233,78 @@ pub struct EcEndoscaleInput<Var> {
     pub n_acc: Var,
 }
 
+/** One `ForeignFieldAdd` row of a chain of foreign field additions,
+`left_input + sign * right_input = field_overflow * foreign_field_modulus + result`,
+where the result is the left input of the next row. */
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct ForeignFieldAddRound<Var, Field> {
+    pub left_input: Vec<Var>,
+    pub right_input: Vec<Var>,
+    pub field_overflow: Var,
+    pub carry: Var,
+    pub sign: Field,
+}
+
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct ForeignFieldAddInput<Var, Field> {
+    pub rounds: Vec<ForeignFieldAddRound<Var, Field>>,
+    pub result: Vec<Var>,
+    pub foreign_field_modulus: Vec<Field>,
+}
+
+/** The `ForeignFieldMul` and `Zero` rows of a foreign field multiplication,
+`left_input * right_input = quotient * foreign_field_modulus + remainder`.
+The limbs of `carry1` are in little-endian order. */
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct ForeignFieldMulInput<Var, Field> {
+    pub left_input: Vec<Var>,
+    pub right_input: Vec<Var>,
+    pub quotient: Vec<Var>,
+    pub quotient_hi_bound: Var,
+    pub remainder01: Var,
+    pub remainder2: Var,
+    pub product1_lo: Var,
+    pub product1_hi_0: Var,
+    pub product1_hi_1: Var,
+    pub carry0: Var,
+    pub carry1: Vec<Var>,
+    pub foreign_field_modulus2: Field,
+    pub neg_foreign_field_modulus: Vec<Field>,
+}
+
+/** The `Rot64` row rotating a 64-bit word to the left by `log2(two_to_rot)` bits,
+followed by the two `RangeCheck0` rows constraining `shifted` and `excess` to 64 bits. */
+#[derive(Debug)]
+#[cfg_attr(
+    feature = "ocaml_types",
+    derive(ocaml::IntoValue, ocaml::FromValue, ocaml_gen::Struct)
+)]
+pub struct Rot64Input<Var, Field> {
+    //[Var; 15]
+    pub rotation: Vec<Var>,
+    //[[Var; 15]; 2]
+    pub range_checks: Vec<Vec<Var>>,
+    pub two_to_rot: Field,
+}
+
 /** A PLONK constraint (or gate) can be [`Basic`](KimchiConstraint::Basic), [`Poseidon`](KimchiConstraint::Poseidon),
  * [`EcAddComplete`](KimchiConstraint::EcAddComplete), [`EcScale`](KimchiConstraint::EcScale),
- * [`EcEndoscale`](KimchiConstraint::EcEndoscale), or [`EcEndoscalar`](KimchiConstraint::EcEndoscalar). */
+ * [`EcEndoscale`](KimchiConstraint::EcEndoscale), [`EcEndoscalar`](KimchiConstraint::EcEndoscalar),
+ * [`RangeCheck`](KimchiConstraint::RangeCheck), [`ForeignFieldAdd`](KimchiConstraint::ForeignFieldAdd),
+ * [`RangeCheck0`](KimchiConstraint::RangeCheck0), [`ForeignFieldMul`](KimchiConstraint::ForeignFieldMul),
+ * [`Xor`](KimchiConstraint::Xor), or [`Rot64`](KimchiConstraint::Rot64). */
 #[derive(Debug)]
 #[cfg_attr(
     feature = "ocaml_types",
@@ -251,6 +320,13 @@ pub enum KimchiConstraint<Var, Field> {
     EcEndoscalar(Vec<EndoscaleScalarRound<Var>>),
     //[[Var; 15]; 4]
     RangeCheck(Vec<Vec<Var>>),
+    //[Var; 15], with the two 12-bit copies at zero for a 64-bit check
+    RangeCheck0(Vec<Var>),
+    ForeignFieldAdd(ForeignFieldAddInput<Var, Field>),
+    ForeignFieldMul(ForeignFieldMulInput<Var, Field>),
+    //[[Var; 15]; n] followed by the [Var; 3] high parts, which are zero
+    Xor(Vec<Vec<Var>>),
+    Rot64(Rot64Input<Var, Field>),
 }
 
 /* TODO: This is a Unique_id in OCaml. */
@@ -968,7 +1044,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
                 if s == Field::one() {
                     x
                 } else {
-                    let sx = self.create_internal(Some(s), vec![(s, x)]);
+                    let sx = self.create_internal(None, vec![(s, x)]);
                     // s * x - sx = 0
                     self.add_generic_constraint(
                         labels,
@@ -990,7 +1066,7 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
             (s, ConstantOrVar::Constant) => match self.cached_constants.get(&s) {
                 Some(x) => *x,
                 None => {
-                    let x = self.create_internal(None, vec![]);
+                    let x = self.create_internal(Some(s), vec![]);
                     self.add_generic_constraint(
                         labels,
                         loc,
@@ -1683,11 +1759,154 @@ impl<Field: PrimeField> SnarkyConstraintSystem<Field> {
                 self.add_row(labels, loc, r2, GateType::RangeCheck1, vec![]);
                 self.add_row(labels, loc, r3, GateType::Zero, vec![]);
             }
+            KimchiConstraint::RangeCheck0(row) => {
+                let vars = row
+                    .into_iter()
+                    .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                    .collect_vec();
+                self.add_row(
+                    labels,
+                    loc,
+                    vars,
+                    GateType::RangeCheck0,
+                    vec![Field::zero()],
+                );
+            }
+            KimchiConstraint::ForeignFieldAdd(ForeignFieldAddInput {
+                rounds,
+                result,
+                foreign_field_modulus,
+            }) => {
+                for ForeignFieldAddRound {
+                    left_input,
+                    right_input,
+                    field_overflow,
+                    carry,
+                    sign,
+                } in rounds
+                {
+                    // 0   1   2   3   4   5   6         7
+                    // a0  a1  a2  b0  b1  b2  overflow  carry
+                    let vars = left_input
+                        .into_iter()
+                        .chain(right_input)
+                        .chain([field_overflow, carry])
+                        .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                        .collect_vec();
+                    let mut coeffs = foreign_field_modulus.clone();
+                    coeffs.push(sign);
+                    self.add_row(labels, loc, vars, GateType::ForeignFieldAdd, coeffs);
+                }
+
+                // 0   1   2
+                // r0  r1  r2
+                let vars = result
+                    .into_iter()
+                    .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                    .collect_vec();
+                self.add_row(labels, loc, vars, GateType::Zero, vec![]);
+            }
+            KimchiConstraint::ForeignFieldMul(ForeignFieldMulInput {
+                left_input,
+                right_input,
+                quotient,
+                quotient_hi_bound,
+                remainder01,
+                remainder2,
+                product1_lo,
+                product1_hi_0,
+                product1_hi_1,
+                carry0,
+                carry1,
+                foreign_field_modulus2,
+                neg_foreign_field_modulus,
+            }) => {
+                let mut reduce = |v| Some(self.reduce_to_var(labels, loc, v));
+                let carry1: Vec<_> = carry1.into_iter().map(&mut reduce).collect();
+                let carry0 = reduce(carry0);
+
+                // 0   1   2   3   4   5   6    7      8       9       10      11      12      13      14
+                // a0  a1  a2  b0  b1  b2  p10  v1_0   v1_12   v1_24   v1_36   v1_84   v1_86   v1_88   v1_90
+                let curr_row = left_input
+                    .into_iter()
+                    .chain(right_input)
+                    .chain([product1_lo])
+                    .map(&mut reduce)
+                    .chain(carry1[0..4].iter().copied())
+                    .chain(carry1[7..11].iter().copied())
+                    .collect_vec();
+
+                // 0    1   2   3   4   5    6     7     8      9      10     11
+                // r01  r2  q0  q1  q2  q'2  p110  p111  v1_48  v1_60  v1_72  v0
+                let next_row = [remainder01, remainder2]
+                    .into_iter()
+                    .chain(quotient)
+                    .chain([quotient_hi_bound, product1_hi_0, product1_hi_1])
+                    .map(&mut reduce)
+                    .chain(carry1[4..7].iter().copied())
+                    .chain([carry0])
+                    .collect_vec();
+
+                let mut coeffs = vec![foreign_field_modulus2];
+                coeffs.extend(neg_foreign_field_modulus);
+                self.add_row(labels, loc, curr_row, GateType::ForeignFieldMul, coeffs);
+                self.add_row(labels, loc, next_row, GateType::Zero, vec![]);
+            }
+            KimchiConstraint::Xor(rows) => {
+                let num_xors = rows.len() - 1;
+                for (i, row) in rows.into_iter().enumerate() {
+                    // 0    1    2    3..7          7..11         11..15
+                    // in1  in2  out  in1 nybbles  in2 nybbles  out nybbles
+                    let vars = row
+                        .into_iter()
+                        .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                        .collect_vec();
+                    let typ = if i < num_xors {
+                        GateType::Xor16
+                    } else {
+                        GateType::Zero
+                    };
+                    self.add_row(labels, loc, vars, typ, vec![]);
+                }
+            }
+            KimchiConstraint::Rot64(Rot64Input {
+                rotation,
+                range_checks,
+                two_to_rot,
+            }) => {
+                // 0     1        2       3..7          7..15
+                // word  rotated  excess  bound limbs  bound crumbs
+                let vars = rotation
+                    .into_iter()
+                    .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                    .collect_vec();
+                self.add_row(labels, loc, vars, GateType::Rot64, vec![two_to_rot]);
+
+                // 0                  1  2  3..7    7..15
+                // shifted or excess  0  0  limbs  crumbs
+                for row in range_checks {
+                    let vars = row
+                        .into_iter()
+                        .map(|v| Some(self.reduce_to_var(labels, loc, v)))
+                        .collect_vec();
+                    self.add_row(
+                        labels,
+                        loc,
+                        vars,
+                        GateType::RangeCheck0,
+                        vec![Field::zero()],
+                    );
+                }
+            }
         }
     }
     pub(crate) fn sponge_params(&self) -> mina_poseidon::poseidon::ArithmeticSpongeParams<Field> {
         self.constants.poseidon.clone()
     }
+
+    pub(crate) fn constants(&self) -> &Constants<Field> {
+        &self.constants
+    }
 }
 
 enum ConstantOrVar {
@@ -1809,7 +2028,12 @@ where
             | KimchiConstraint::EcScale { .. }
             | KimchiConstraint::EcEndoscale { .. }
             | KimchiConstraint::EcEndoscalar { .. }
-            | KimchiConstraint::RangeCheck { .. } => (),
+            | KimchiConstraint::RangeCheck { .. }
+            | KimchiConstraint::RangeCheck0 { .. }
+            | KimchiConstraint::ForeignFieldAdd { .. }
+            | KimchiConstraint::ForeignFieldMul { .. }
+            | KimchiConstraint::Xor { .. }
+            | KimchiConstraint::Rot64 { .. } => (),
         };
         Ok(())
     }
@@ -1859,6 +2083,29 @@ mod tests {
         assert_eq!(gates[0].wires[1].col, 0);
     }
 
+    #[test]
+    fn test_reduce_to_var_scaled_and_constant() {
+        let mut state = setup(0);
+
+        let labels = &vec![];
+        let loc = &Cow::Borrowed("");
+
+        // 3 * x and the constant 5 are each reduced to a new variable,
+        // which is constrained by a generic gate
+        let x = FieldVar::Var(0);
+        state.reduce_to_var(labels, loc, x.scale(Fp::from(3)));
+        state.reduce_to_var(labels, loc, FieldVar::Constant(Fp::from(5)));
+
+        let witness = state.compute_witness(|_| Fp::from(7));
+        let gates = state.finalize_and_get_gates();
+        assert!(gates.iter().any(|gate| gate.typ == GateType::Generic));
+        for (row, gate) in gates.iter().enumerate() {
+            if gate.typ == GateType::Generic {
+                gate.verify_generic(row, &witness, &[]).unwrap();
+            }
+        }
+    }
+
     #[test]
     fn test_permutation_public() {
         let mut state = setup(1);
@@ -272,6 +272,7 @@ where
                 Ok(FieldVar::Var(terms[0].1))
             }
             (Some(c), terms) if terms.is_empty() => Ok(FieldVar::Constant(c)),
+            (None, terms) if terms.is_empty() => Ok(FieldVar::zero()),
             _ => {
                 let y: FieldVar<F> = state.compute(loc.clone(), |env| env.read_var(self))?;
                 // this call will reduce [self]
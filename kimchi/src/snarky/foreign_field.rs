@@ -0,0 +1,571 @@
+//! The [ForeignFieldVar] type is a snarky type that represents an element of a foreign field,
+//! that is, a field whose modulus is different from the one of the circuit
+//! (for example, the base field of secp256k1 or BN254).
+//!
+//! An element is represented by three limbs of 88 bits each (in little-endian order),
+//! and is always kept in its canonical form (smaller than the modulus).
+//! The operations are backed by the `ForeignFieldAdd` and `ForeignFieldMul` gates,
+//! and the limbs are constrained with multi-range-checks.
+//!
+//! The modulus is given as a type implementing the [ForeignField] trait,
+//! so that elements of different fields cannot be mixed up.
+
+use std::{array, borrow::Cow, fmt::Debug, marker::PhantomData};
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use num_integer::Integer;
+use o1_utils::foreign_field::ForeignFieldHelpers;
+
+use crate::{
+    circuits::polynomials::{
+        foreign_field_add::{self, witness::FFOps},
+        foreign_field_common::{
+            BigUintForeignFieldHelpers, FieldArrayCompose, KimchiForeignElement,
+        },
+        foreign_field_mul,
+    },
+    snarky::{
+        constraint_system::{
+            ForeignFieldAddInput, ForeignFieldAddRound, ForeignFieldMulInput, KimchiConstraint,
+        },
+        cvar::FieldVar,
+        errors::SnarkyResult,
+        range_checks::range_check,
+        runner::{Constraint, RunState},
+        snarky_type::SnarkyType,
+    },
+};
+
+/// A foreign field, defined by its modulus.
+///
+/// The modulus must not exceed the maximum foreign field modulus supported by the gates
+/// (about `2^259` for the Pasta fields).
+pub trait ForeignField: Debug {
+    /// The modulus of the foreign field.
+    fn modulus() -> BigUint;
+}
+
+macro_rules! foreign_field {
+    ($name:ident, $doc:literal, $hex:literal) => {
+        #[doc = $doc]
+        #[derive(Debug, Clone, Copy)]
+        pub struct $name;
+
+        impl ForeignField for $name {
+            fn modulus() -> BigUint {
+                BigUint::parse_bytes($hex, 16).unwrap()
+            }
+        }
+    };
+}
+
+foreign_field!(
+    Secp256k1Base,
+    "The base field of secp256k1.",
+    b"fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f"
+);
+foreign_field!(
+    Secp256k1Scalar,
+    "The scalar field of secp256k1.",
+    b"fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141"
+);
+foreign_field!(
+    Bn254Base,
+    "The base field of BN254.",
+    b"30644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47"
+);
+foreign_field!(
+    Bn254Scalar,
+    "The scalar field of BN254.",
+    b"30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001"
+);
+foreign_field!(
+    Ed25519Base,
+    "The base field of Ed25519, of modulus `2^255 - 19`.",
+    b"7fffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffed"
+);
+foreign_field!(
+    Ed25519Scalar,
+    "The scalar field of Ed25519.",
+    b"1000000000000000000000000000000014def9dea2f79cd65812631a5cf5d3ed"
+);
+
+/// An element of the foreign field `M`, as three limbs of 88 bits.
+#[derive(Debug, Clone)]
+pub struct ForeignFieldVar<F, M>
+where
+    F: PrimeField,
+{
+    limbs: [FieldVar<F>; 3],
+    foreign_field: PhantomData<M>,
+}
+
+impl<F, M> SnarkyType<F> for ForeignFieldVar<F, M>
+where
+    F: PrimeField,
+    M: ForeignField,
+{
+    type Auxiliary = ();
+
+    type OutOfCircuit = BigUint;
+
+    const SIZE_IN_FIELD_ELEMENTS: usize = 3;
+
+    fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+        (self.limbs.to_vec(), ())
+    }
+
+    fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+        assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        Self::from_limbs(cvars.try_into().unwrap())
+    }
+
+    fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        self.range_check(cs, loc.clone())?;
+        self.assert_less_than_modulus(cs, loc)
+    }
+
+    fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+    fn value_to_field_elements(value: &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+        assert!(
+            *value < M::modulus(),
+            "foreign field element is not smaller than the modulus"
+        );
+        (value.to_field_limbs::<F>().to_vec(), ())
+    }
+
+    fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
+        assert_eq!(fields.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        [fields[0], fields[1], fields[2]].compose()
+    }
+}
+
+impl<F, M> ForeignFieldVar<F, M>
+where
+    F: PrimeField,
+    M: ForeignField,
+{
+    fn from_limbs(limbs: [FieldVar<F>; 3]) -> Self {
+        Self {
+            limbs,
+            foreign_field: PhantomData,
+        }
+    }
+
+    /// Creates a constant, reduced modulo the modulus of the foreign field.
+    pub fn constant(value: &BigUint) -> Self {
+        let value = value % M::modulus();
+        Self::from_limbs(value.to_field_limbs::<F>().map(FieldVar::constant))
+    }
+
+    /// Returns the limbs of the element, in little-endian order.
+    pub fn limbs(&self) -> &[FieldVar<F>; 3] {
+        &self.limbs
+    }
+
+    /// Returns the value of the element if it is a constant.
+    pub fn to_constant(&self) -> Option<BigUint> {
+        let mut limbs = [F::zero(); 3];
+        for (limb, var) in limbs.iter_mut().zip(&self.limbs) {
+            match var {
+                FieldVar::Constant(c) => *limb = *c,
+                _ => return None,
+            }
+        }
+        Some(limbs.compose())
+    }
+
+    /// Constrains the limbs of the element to be 88 bits each.
+    pub fn range_check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        if self.to_constant().is_some() {
+            return Ok(());
+        }
+        let [x0, x1, x2] = self.limbs.clone();
+        range_check(cs, loc, x0, x1, x2)
+    }
+
+    /// Constrains the element to be smaller than the modulus of the foreign field,
+    /// assuming that its limbs are range checked.
+    ///
+    /// This is done by checking that `x + 2^264 - modulus` fits in 264 bits.
+    pub fn assert_less_than_modulus(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<()> {
+        if let Some(x) = self.to_constant() {
+            assert!(x < M::modulus(), "constant is not smaller than the modulus");
+            return Ok(());
+        }
+
+        let limbs = self.limbs.clone();
+        let [carry, bound0, bound1, bound2]: [FieldVar<F>; 4] =
+            cs.compute_unsafe(loc.clone(), move |env| {
+                let limbs = limbs.clone().map(|limb| env.read_var(&limb));
+                let mut witness = array::from_fn(|_| vec![]);
+                foreign_field_add::witness::extend_witness_bound_addition(
+                    &mut witness,
+                    &limbs,
+                    &M::modulus().to_field_limbs(),
+                );
+                [witness[7][0], witness[0][1], witness[1][1], witness[2][1]]
+            })?;
+
+        let round = Self::bound_round(self.limbs.clone(), carry);
+        Self::add_chain(cs, loc.clone(), vec![round], [&bound0, &bound1, &bound2])?;
+        range_check(cs, loc, bound0, bound1, bound2)
+    }
+
+    /// Adds `other` to the element.
+    pub fn add(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        self.add_or_sub(cs, loc, other, FFOps::Add)
+    }
+
+    /// Subtracts `other` from the element.
+    pub fn sub(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        self.add_or_sub(cs, loc, other, FFOps::Sub)
+    }
+
+    fn add_or_sub(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+        opcode: FFOps,
+    ) -> SnarkyResult<Self> {
+        let modulus = M::modulus();
+        if let (Some(x), Some(y)) = (self.to_constant(), other.to_constant()) {
+            let res = match opcode {
+                FFOps::Add => x + y,
+                FFOps::Sub => x + &modulus - y,
+            };
+            return Ok(Self::constant(&res));
+        }
+
+        // the first row holds the operation,
+        // the second one holds the result and checks that it is smaller than the modulus
+        let left = self.limbs.clone();
+        let right = other.limbs.clone();
+        let [field_overflow, carry, res0, res1, res2, bound_carry, bound0, bound1, bound2]: [FieldVar<F>; 9] =
+            cs.compute_unsafe(loc.clone(), move |env| {
+                let left = left.clone().map(|limb| env.read_var(&limb)).compose();
+                let right = right.clone().map(|limb| env.read_var(&limb)).compose();
+                let witness = foreign_field_add::witness::create_chain::<F>(
+                    &vec![left, right],
+                    &[opcode],
+                    M::modulus(),
+                );
+                [
+                    witness[6][0],
+                    witness[7][0],
+                    witness[0][1],
+                    witness[1][1],
+                    witness[2][1],
+                    witness[7][1],
+                    witness[0][2],
+                    witness[1][2],
+                    witness[2][2],
+                ]
+            })?;
+
+        let result = [res0, res1, res2];
+        let rounds = vec![
+            ForeignFieldAddRound {
+                left_input: self.limbs.to_vec(),
+                right_input: other.limbs.to_vec(),
+                field_overflow,
+                carry,
+                sign: opcode.sign(),
+            },
+            Self::bound_round(result.clone(), bound_carry),
+        ];
+        Self::add_chain(cs, loc.clone(), rounds, [&bound0, &bound1, &bound2])?;
+
+        let [r0, r1, r2] = result.clone();
+        range_check(cs, loc.clone(), r0, r1, r2)?;
+        range_check(cs, loc, bound0, bound1, bound2)?;
+
+        Ok(Self::from_limbs(result))
+    }
+
+    /// Multiplies the element by `other`.
+    pub fn mul(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        let modulus = M::modulus();
+        if let (Some(x), Some(y)) = (self.to_constant(), other.to_constant()) {
+            return Ok(Self::constant(&(x * y)));
+        }
+
+        let left = self.limbs.clone();
+        let right = other.limbs.clone();
+        let cells: [FieldVar<F>; 22] = cs.compute_unsafe(loc.clone(), move |env| {
+            let left = left.clone().map(|limb| env.read_var(&limb)).compose();
+            let right = right.clone().map(|limb| env.read_var(&limb)).compose();
+            let remainder = (&left * &right).mod_floor(&M::modulus());
+            let (witness, _) =
+                foreign_field_mul::witness::create::<F>(&left, &right, &M::modulus());
+
+            // the cells of the multiplication rows that are not copies of the inputs,
+            // except for the compact remainder, which is given in 3 limbs
+            let curr = (6..15).map(|col| witness[col][0]);
+            let next = (2..12).map(|col| witness[col][1]);
+            let mut cells = curr.chain(next).chain(remainder.to_field_limbs::<F>());
+            array::from_fn(|_| cells.next().unwrap())
+        })?;
+        let [product1_lo, carry1_0, carry1_12, carry1_24, carry1_36, carry1_84, carry1_86, carry1_88, carry1_90, quotient0, quotient1, quotient2, quotient_hi_bound, product1_hi_0, product1_hi_1, carry1_48, carry1_60, carry1_72, carry0, remainder0, remainder1, remainder2] =
+            cells;
+
+        let neg_modulus = modulus.negate().to_field_limbs::<F>();
+        let input = ForeignFieldMulInput {
+            left_input: self.limbs.to_vec(),
+            right_input: other.limbs.to_vec(),
+            quotient: vec![quotient0.clone(), quotient1.clone(), quotient2.clone()],
+            quotient_hi_bound: quotient_hi_bound.clone(),
+            remainder01: &remainder0 + &remainder1.scale(KimchiForeignElement::<F>::two_to_limb()),
+            remainder2: remainder2.clone(),
+            product1_lo: product1_lo.clone(),
+            product1_hi_0: product1_hi_0.clone(),
+            product1_hi_1,
+            carry0,
+            carry1: vec![
+                carry1_0, carry1_12, carry1_24, carry1_36, carry1_48, carry1_60, carry1_72,
+                carry1_84, carry1_86, carry1_88, carry1_90,
+            ],
+            foreign_field_modulus2: modulus.to_field_limbs::<F>()[2],
+            neg_foreign_field_modulus: neg_modulus.to_vec(),
+        };
+        cs.add_constraint(
+            Constraint::KimchiConstraint(KimchiConstraint::ForeignFieldMul(input)),
+            Some("foreign field multiplication".into()),
+            loc.clone(),
+        )?;
+
+        // external checks of the multiplication gate
+        range_check(cs, loc.clone(), quotient0, quotient1, quotient2)?;
+        range_check(
+            cs,
+            loc.clone(),
+            quotient_hi_bound,
+            product1_lo,
+            product1_hi_0,
+        )?;
+
+        let remainder = Self::from_limbs([remainder0, remainder1, remainder2]);
+        remainder.range_check(cs, loc.clone())?;
+        remainder.assert_less_than_modulus(cs, loc)?;
+
+        Ok(remainder)
+    }
+
+    /// Constrains the element to be equal to `other`.
+    pub fn assert_equals(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<()> {
+        for (x, y) in self.limbs.iter().zip(&other.limbs) {
+            x.assert_equals(cs, loc.clone(), y)?;
+        }
+        Ok(())
+    }
+
+    /// The row of the bound check `x + 2^264 = 1 * modulus + bound`.
+    fn bound_round(
+        limbs: [FieldVar<F>; 3],
+        carry: FieldVar<F>,
+    ) -> ForeignFieldAddRound<FieldVar<F>, F> {
+        ForeignFieldAddRound {
+            left_input: limbs.to_vec(),
+            right_input: vec![
+                FieldVar::zero(),
+                FieldVar::zero(),
+                FieldVar::constant(KimchiForeignElement::<F>::two_to_limb()),
+            ],
+            field_overflow: FieldVar::constant(F::one()),
+            carry,
+            sign: F::one(),
+        }
+    }
+
+    fn add_chain(
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        rounds: Vec<ForeignFieldAddRound<FieldVar<F>, F>>,
+        result: [&FieldVar<F>; 3],
+    ) -> SnarkyResult<()> {
+        let input = ForeignFieldAddInput {
+            rounds,
+            result: result.map(Clone::clone).to_vec(),
+            foreign_field_modulus: M::modulus().to_field_limbs::<F>().to_vec(),
+        };
+        cs.add_constraint(
+            Constraint::KimchiConstraint(KimchiConstraint::ForeignFieldAdd(input)),
+            Some("foreign field addition".into()),
+            loc,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use num_bigint::RandBigInt;
+    use poly_commitment::ipa::OpeningProof;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// Computes `2 * (a * b + c - a)` and checks that `a * b` is `ab`
+    struct TestCircuit<M>(PhantomData<M>);
+
+    struct Priv {
+        a: BigUint,
+        b: BigUint,
+        ab: BigUint,
+    }
+
+    impl<M: ForeignField> SnarkyCircuit for TestCircuit<M> {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Priv;
+        type PublicInput = ForeignFieldVar<Fp, M>;
+        type PublicOutput = ForeignFieldVar<Fp, M>;
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            c: Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            c.check(sys, loc!())?;
+            let a: ForeignFieldVar<Fp, M> = sys.compute(loc!(), |_| private.unwrap().a.clone())?;
+            let b: ForeignFieldVar<Fp, M> = sys.compute(loc!(), |_| private.unwrap().b.clone())?;
+            let ab: ForeignFieldVar<Fp, M> =
+                sys.compute(loc!(), |_| private.unwrap().ab.clone())?;
+
+            let res = a.mul(sys, loc!(), &b)?;
+            res.assert_equals(sys, loc!(), &ab)?;
+            let res = res.add(sys, loc!(), &c)?;
+            let res = res.sub(sys, loc!(), &a)?;
+
+            let two = ForeignFieldVar::constant(&BigUint::from(2u32));
+            res.mul(sys, loc!(), &two)
+        }
+    }
+
+    fn test_foreign_field<M: ForeignField>() {
+        let modulus = M::modulus();
+        let (mut prover_index, verifier_index) =
+            TestCircuit::<M>(PhantomData).compile_to_indexes().unwrap();
+
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let max = &modulus - 1u32;
+        let inputs = [
+            (
+                rng.gen_biguint_below(&modulus),
+                rng.gen_biguint_below(&modulus),
+                rng.gen_biguint_below(&modulus),
+            ),
+            (max.clone(), max.clone(), max.clone()),
+            (BigUint::from(0u32), max, BigUint::from(1u32)),
+        ];
+
+        for (a, b, c) in inputs {
+            let ab = (&a * &b) % &modulus;
+            let expected = (&ab + &c + &modulus - &a) * 2u32 % &modulus;
+
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>(c.clone(), Priv { a, b, ab }, true)
+                .unwrap();
+            assert_eq!(*public_output, expected);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, c, expected);
+        }
+    }
+
+    #[test]
+    fn snarky_foreign_field_secp256k1() {
+        test_foreign_field::<Secp256k1Base>();
+    }
+
+    #[test]
+    fn snarky_foreign_field_bn254() {
+        test_foreign_field::<Bn254Base>();
+    }
+
+    #[test]
+    fn snarky_foreign_field_ed25519() {
+        test_foreign_field::<Ed25519Base>();
+    }
+
+    #[test]
+    fn snarky_foreign_field_wrong_product() {
+        let (mut prover_index, _) = TestCircuit::<Secp256k1Scalar>(PhantomData)
+            .compile_to_indexes()
+            .unwrap();
+
+        let private_input = Priv {
+            a: BigUint::from(3u32),
+            b: BigUint::from(5u32),
+            ab: BigUint::from(16u32),
+        };
+        let res = prover_index.prove::<BaseSponge, ScalarSponge>(
+            BigUint::from(0u32),
+            private_input,
+            true,
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn moduli() {
+        assert_eq!(
+            Secp256k1Base::modulus(),
+            BigUint::from_bytes_be(&secp256k1::constants::FIELD_SIZE)
+        );
+        assert_eq!(
+            Secp256k1Scalar::modulus(),
+            BigUint::from_bytes_be(&secp256k1::constants::CURVE_ORDER)
+        );
+        assert_eq!(
+            Bn254Base::modulus().to_string(),
+            "21888242871839275222246405745257275088696311157297823662689037894645226208583"
+        );
+        assert_eq!(
+            Bn254Scalar::modulus().to_string(),
+            "21888242871839275222246405745257275088548364400416034343698204186575808495617"
+        );
+        assert_eq!(Ed25519Base::modulus(), BigUint::from(2u32).pow(255) - 19u32);
+        assert_eq!(
+            Ed25519Scalar::modulus(),
+            BigUint::from(2u32).pow(252)
+                + "27742317777372353535851937790883648493"
+                    .parse::<BigUint>()
+                    .unwrap()
+        );
+    }
+}
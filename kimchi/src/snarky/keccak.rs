@@ -0,0 +1,311 @@
+//! The Keccak-256 and SHA3-256 hash functions.
+//!
+//! The message is given as a list of bytes, each of them being range checked,
+//! and the digest is returned as 32 range-checked bytes.
+//!
+//! The Keccak permutation works on 25 lanes of 64 bits, using the bitwise operations
+//! of [UInt64Var].
+//!
+//! Note that the `KeccakRound` and `KeccakSponge` gates cannot be used here,
+//! as their 1965 witness columns do not fit in the 15 columns of a kimchi circuit:
+//! the permutation is built on the `Xor16`, `Rot64` and generic gates instead.
+
+use std::{array, borrow::Cow};
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+
+use crate::{
+    circuits::polynomials::keccak::{
+        constants::{DIM, RATE_IN_BYTES},
+        OFF, RC,
+    },
+    snarky::{
+        cvar::FieldVar,
+        errors::SnarkyResult,
+        runner::RunState,
+        snarky_type::SnarkyType,
+        uint::{check_bits, UInt64Var},
+    },
+};
+
+/// The number of bytes of a digest.
+pub const DIGEST_LENGTH: usize = 32;
+
+/// The first padding byte of Keccak-256 (as used by Ethereum).
+const KECCAK_PAD: u8 = 0x01;
+
+/// The first padding byte of SHA3-256, which includes the domain separation bits.
+const SHA3_PAD: u8 = 0x06;
+
+/// Hashes `bytes` with Keccak-256, the variant of Keccak used by Ethereum.
+pub fn keccak256<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    bytes: &[FieldVar<F>],
+) -> SnarkyResult<[FieldVar<F>; DIGEST_LENGTH]> {
+    hash(runner, loc, bytes, KECCAK_PAD)
+}
+
+/// Hashes `bytes` with SHA3-256, as standardized in FIPS 202.
+pub fn sha3_256<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    bytes: &[FieldVar<F>],
+) -> SnarkyResult<[FieldVar<F>; DIGEST_LENGTH]> {
+    hash(runner, loc, bytes, SHA3_PAD)
+}
+
+/// The sponge with a rate of 1088 bits and a capacity of 512 bits,
+/// the message being padded with `pad`, zeros, and `0x80`.
+fn hash<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    bytes: &[FieldVar<F>],
+    pad: u8,
+) -> SnarkyResult<[FieldVar<F>; DIGEST_LENGTH]> {
+    for byte in bytes {
+        check_byte(runner, loc.clone(), byte)?;
+    }
+
+    // the padding always adds at least one byte
+    let padded_len = (bytes.len() / RATE_IN_BYTES + 1) * RATE_IN_BYTES;
+    let mut padded = bytes.to_vec();
+    padded.resize(padded_len, FieldVar::zero());
+    padded[bytes.len()] = &padded[bytes.len()] + FieldVar::constant(F::from(pad));
+    padded[padded_len - 1] = &padded[padded_len - 1] + FieldVar::constant(F::from(0x80u8));
+
+    // absorb
+    let mut state: [UInt64Var<F>; DIM * DIM] = array::from_fn(|_| UInt64Var::constant(0));
+    for block in padded.chunks(RATE_IN_BYTES) {
+        for (lane, bytes) in state.iter_mut().zip(block.chunks(8)) {
+            let bytes = UInt64Var::from_cvars_unsafe(vec![bytes_to_lane(bytes)], ());
+            *lane = lane.xor(runner, loc.clone(), &bytes)?;
+        }
+        permutation(runner, loc.clone(), &mut state)?;
+    }
+
+    // squeeze
+    let mut digest = Vec::with_capacity(DIGEST_LENGTH);
+    for lane in &state[..DIGEST_LENGTH / 8] {
+        digest.extend(lane_to_bytes(runner, loc.clone(), lane)?);
+    }
+    Ok(digest.try_into().unwrap())
+}
+
+/// The Keccak-f\[1600\] permutation, where the lane `(x, y)` is at index `x + 5 * y`.
+fn permutation<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    state: &mut [UInt64Var<F>; DIM * DIM],
+) -> SnarkyResult<()> {
+    for rc in RC {
+        // theta
+        let mut parities = Vec::with_capacity(DIM);
+        for x in 0..DIM {
+            let mut parity = state[x].clone();
+            for y in 1..DIM {
+                parity = parity.xor(runner, loc.clone(), &state[x + DIM * y])?;
+            }
+            parities.push(parity);
+        }
+        for x in 0..DIM {
+            let rotated = parities[(x + 1) % DIM].rotl(runner, loc.clone(), 1)?;
+            let d = parities[(x + DIM - 1) % DIM].xor(runner, loc.clone(), &rotated)?;
+            for y in 0..DIM {
+                state[x + DIM * y] = state[x + DIM * y].xor(runner, loc.clone(), &d)?;
+            }
+        }
+
+        // rho and pi
+        let mut b = state.clone();
+        for x in 0..DIM {
+            for y in 0..DIM {
+                b[y + DIM * ((2 * x + 3 * y) % DIM)] =
+                    state[x + DIM * y].rotl(runner, loc.clone(), OFF[y][x] as u32)?;
+            }
+        }
+
+        // chi
+        for x in 0..DIM {
+            for y in 0..DIM {
+                let not = b[(x + 1) % DIM + DIM * y].not();
+                let and = not.and(runner, loc.clone(), &b[(x + 2) % DIM + DIM * y])?;
+                state[x + DIM * y] = b[x + DIM * y].xor(runner, loc.clone(), &and)?;
+            }
+        }
+
+        // iota
+        state[0] = state[0].xor(runner, loc.clone(), &UInt64Var::constant(rc))?;
+    }
+
+    Ok(())
+}
+
+/// Checks that `byte` fits in 8 bits.
+fn check_byte<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    byte: &FieldVar<F>,
+) -> SnarkyResult<()> {
+    if let FieldVar::Constant(x) = byte {
+        assert!(
+            x.to_biguint() < BigUint::from(256u32),
+            "constant is not a byte"
+        );
+        return Ok(());
+    }
+    check_bits(runner, loc, byte, 8)
+}
+
+/// The little-endian composition of up to 8 bytes.
+fn bytes_to_lane<F: PrimeField>(bytes: &[FieldVar<F>]) -> FieldVar<F> {
+    let terms: Vec<_> = bytes
+        .iter()
+        .enumerate()
+        .map(|(i, byte)| (F::from(1u64 << (8 * i)), byte.clone()))
+        .collect();
+    FieldVar::linear_combination(&terms)
+}
+
+/// The little-endian decomposition of a lane into range-checked bytes.
+fn lane_to_bytes<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    lane: &UInt64Var<F>,
+) -> SnarkyResult<[FieldVar<F>; 8]> {
+    if let Some(x) = lane.to_constant() {
+        return Ok(x
+            .to_le_bytes()
+            .map(|byte| FieldVar::constant(F::from(byte))));
+    }
+
+    let lane = lane.value();
+    let lane_ = lane.clone();
+    let bytes: [FieldVar<F>; 8] = runner.compute(loc.clone(), move |env| {
+        let lane = env.read_var(&lane_).to_biguint();
+        let lane = lane.iter_u64_digits().next().unwrap_or(0);
+        lane.to_le_bytes().map(F::from)
+    })?;
+    for byte in &bytes {
+        check_byte(runner, loc.clone(), byte)?;
+    }
+    bytes_to_lane(&bytes).assert_equals(runner, loc, lane)?;
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+    use rand::Rng;
+    use tiny_keccak::{Hasher, Keccak, Sha3};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// Hashes a public message of `N` bytes with Keccak-256 or SHA3-256
+    struct TestCircuit<const N: usize> {
+        sha3: bool,
+    }
+
+    impl<const N: usize> SnarkyCircuit for TestCircuit<N> {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = [FieldVar<Fp>; N];
+        type PublicOutput = [FieldVar<Fp>; DIGEST_LENGTH];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            message: Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            if self.sha3 {
+                sys.sha3_256(loc!(), &message)
+            } else {
+                sys.keccak256(loc!(), &message)
+            }
+        }
+    }
+
+    fn expected_digest(sha3: bool, message: &[u8]) -> [u8; DIGEST_LENGTH] {
+        fn digest(mut hasher: impl Hasher, message: &[u8]) -> [u8; DIGEST_LENGTH] {
+            let mut digest = [0; DIGEST_LENGTH];
+            hasher.update(message);
+            hasher.finalize(&mut digest);
+            digest
+        }
+        if sha3 {
+            digest(Sha3::v256(), message)
+        } else {
+            digest(Keccak::v256(), message)
+        }
+    }
+
+    fn test_hash<const N: usize>(sha3: bool, messages: &[[u8; N]]) {
+        let (mut prover_index, verifier_index) =
+            TestCircuit::<N> { sha3 }.compile_to_indexes().unwrap();
+
+        for message in messages {
+            let public_input = message.map(Fp::from);
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>(public_input, (), true)
+                .unwrap();
+
+            let expected = expected_digest(sha3, message).map(Fp::from);
+            assert_eq!(*public_output, expected);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, public_input, expected);
+        }
+    }
+
+    #[test]
+    fn snarky_keccak256() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_hash::<32>(false, &[rng.gen(), [0; 32], [0xff; 32]]);
+    }
+
+    #[test]
+    fn snarky_sha3_256() {
+        // the padding is a single byte for messages of 135 bytes
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_hash::<135>(true, &[array::from_fn(|_| rng.gen())]);
+    }
+
+    #[test]
+    fn snarky_keccak256_two_blocks() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        test_hash::<RATE_IN_BYTES>(false, &[array::from_fn(|_| rng.gen())]);
+    }
+
+    #[test]
+    fn snarky_keccak256_constant() {
+        let mut sys = RunState::<Fp>::new::<Vesta>(0, 0, true);
+        let message = b"hello world".map(|b| FieldVar::constant(Fp::from(b)));
+        let digest = keccak256(&mut sys, loc!(), &message).unwrap();
+        let expected = expected_digest(false, b"hello world");
+        for (byte, expected) in digest.iter().zip(expected) {
+            assert!(matches!(byte, FieldVar::Constant(b) if *b == Fp::from(expected)));
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid RangeCheck0 constraint")]
+    fn snarky_keccak256_invalid_byte() {
+        let (mut prover_index, _) = TestCircuit::<1> { sha3: false }
+            .compile_to_indexes()
+            .unwrap();
+        let _ = prover_index.prove::<BaseSponge, ScalarSponge>([Fp::from(256u32)], (), true);
+    }
+}
@@ -15,10 +15,15 @@ pub mod constraint_system;
 pub mod cvar;
 pub mod errors;
 pub mod folding;
+pub mod foreign_field;
+pub mod keccak;
+pub mod point;
 pub mod poseidon;
 pub(crate) mod range_checks;
 pub mod runner;
+pub mod schnorr;
 pub mod snarky_type;
+pub mod uint;
 pub mod union_find;
 
 #[cfg(test)]
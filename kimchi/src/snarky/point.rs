@@ -0,0 +1,609 @@
+//! The [PointVar] type is a snarky type that represents an affine point
+//! of the curve whose base field is the field of the circuit
+//! (Pallas for circuits over the scalar field of Vesta, and vice versa).
+//!
+//! The point at infinity cannot be represented,
+//! so that an operation whose result is the point at infinity cannot be proven.
+//! Additions are backed by the `CompleteAdd` gate, scalar multiplications by the `VarBaseMul` gate,
+//! and scalar multiplications using the endomorphism of the curve by the `EndoMul` gate.
+
+use std::{array, borrow::Cow};
+
+use ark_ff::PrimeField;
+
+use crate::{
+    circuits::{
+        polynomials::{endosclmul, varbasemul},
+        wires::COLUMNS,
+    },
+    snarky::{
+        boolean::Boolean,
+        constraint_system::{
+            EcAddCompleteInput, EcEndoscaleInput, EndoscaleRound, KimchiConstraint, ScaleRound,
+        },
+        cvar::FieldVar,
+        errors::SnarkyResult,
+        runner::{Constraint, RunState, WitnessGeneration},
+        snarky_type::SnarkyType,
+    },
+};
+
+/// The number of bits handled by a `VarBaseMul` gate.
+const VARBASEMUL_BITS: usize = 5;
+
+/// The number of bits handled by an `EndoMul` gate.
+const ENDOMUL_BITS: usize = 4;
+
+/// The maximum number of bits of a scalar given to [PointVar::scale].
+pub const MAX_SCALAR_BITS: usize = 256;
+
+/// An affine point, as its two coordinates.
+#[derive(Debug, Clone)]
+pub struct PointVar<F>
+where
+    F: PrimeField,
+{
+    x: FieldVar<F>,
+    y: FieldVar<F>,
+}
+
+impl<F> SnarkyType<F> for PointVar<F>
+where
+    F: PrimeField,
+{
+    type Auxiliary = ();
+
+    type OutOfCircuit = (F, F);
+
+    const SIZE_IN_FIELD_ELEMENTS: usize = 2;
+
+    fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+        (vec![self.x.clone(), self.y.clone()], ())
+    }
+
+    fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+        assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        Self::new_unsafe(cvars[0].clone(), cvars[1].clone())
+    }
+
+    /// Constrains the point to be on the curve `y^2 = x^3 + b`.
+    fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        let b = curve_b(cs);
+        if let Some((x, y)) = self.to_constant() {
+            assert_eq!(
+                y.square(),
+                x.square() * x + b,
+                "constant is not on the curve"
+            );
+            return Ok(());
+        }
+
+        let x_squared = self.x.mul(&self.x, Some("x^2".into()), loc.clone(), cs)?;
+        let y_squared = self.y.mul(&self.y, Some("y^2".into()), loc.clone(), cs)?;
+        cs.assert_r1cs(
+            Some("on curve".into()),
+            loc,
+            x_squared,
+            self.x.clone(),
+            &y_squared - &FieldVar::constant(b),
+        )
+    }
+
+    fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+    fn value_to_field_elements(value: &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+        (vec![value.0, value.1], ())
+    }
+
+    fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
+        assert_eq!(fields.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        (fields[0], fields[1])
+    }
+}
+
+impl<F> PointVar<F>
+where
+    F: PrimeField,
+{
+    fn new_unsafe(x: FieldVar<F>, y: FieldVar<F>) -> Self {
+        Self { x, y }
+    }
+
+    /// Creates a constant point, which is not checked to be on the curve.
+    pub fn constant((x, y): (F, F)) -> Self {
+        Self::new_unsafe(FieldVar::constant(x), FieldVar::constant(y))
+    }
+
+    /// The generator of the curve.
+    pub fn generator(cs: &RunState<F>) -> Self {
+        Self::constant(cs.constants().base)
+    }
+
+    /// Returns the x-coordinate of the point.
+    pub fn x(&self) -> &FieldVar<F> {
+        &self.x
+    }
+
+    /// Returns the y-coordinate of the point.
+    pub fn y(&self) -> &FieldVar<F> {
+        &self.y
+    }
+
+    /// Returns the coordinates of the point if it is a constant.
+    pub fn to_constant(&self) -> Option<(F, F)> {
+        match (&self.x, &self.y) {
+            (FieldVar::Constant(x), FieldVar::Constant(y)) => Some((*x, *y)),
+            _ => None,
+        }
+    }
+
+    /// Seals the coordinates of the point, so that they are not reduced at each use.
+    fn seal(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<Self> {
+        let x = self.x.seal(cs, loc.clone())?;
+        let y = self.y.seal(cs, loc)?;
+        Ok(Self::new_unsafe(x, y))
+    }
+
+    /// Returns the opposite of the point.
+    pub fn neg(&self) -> Self {
+        Self::new_unsafe(self.x.clone(), self.y.scale(-F::one()))
+    }
+
+    /// Adds `other` to the point, which can be the point itself.
+    pub fn add(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        if let (Some(p1), Some(p2)) = (self.to_constant(), other.to_constant()) {
+            let [x3, y3, inf, ..] = complete_add(p1, p2);
+            assert!(inf.is_zero(), "the sum is the point at infinity");
+            return Ok(Self::constant((x3, y3)));
+        }
+
+        let (p1, p2) = (self.clone(), other.clone());
+        let [x3, y3, same_x, slope, inf_z, x21_inv]: [FieldVar<F>; 6] =
+            cs.compute(loc.clone(), move |env| {
+                let [x3, y3, _inf, same_x, slope, inf_z, x21_inv] =
+                    complete_add(p1.read(env), p2.read(env));
+                [x3, y3, same_x, slope, inf_z, x21_inv]
+            })?;
+
+        // the result is constrained to not be the point at infinity
+        let input = EcAddCompleteInput {
+            p1: (self.x.clone(), self.y.clone()),
+            p2: (other.x.clone(), other.y.clone()),
+            p3: (x3.clone(), y3.clone()),
+            inf: FieldVar::zero(),
+            same_x,
+            slope,
+            inf_z,
+            x21_inv,
+        };
+        cs.add_constraint(
+            Constraint::KimchiConstraint(KimchiConstraint::EcAddComplete(input)),
+            Some("ec add".into()),
+            loc,
+        )?;
+
+        Ok(Self::new_unsafe(x3, y3))
+    }
+
+    /// Doubles the point.
+    pub fn double(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<Self> {
+        self.add(cs, loc, self)
+    }
+
+    /// Multiplies the point by the scalar of (at most [MAX_SCALAR_BITS]) little-endian `bits`.
+    ///
+    /// The scalar must not be a multiple of the order of the point.
+    pub fn scale(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: &[Boolean<F>],
+    ) -> SnarkyResult<Self> {
+        assert!(
+            !bits.is_empty() && bits.len() <= MAX_SCALAR_BITS,
+            "a scalar has between 1 and {MAX_SCALAR_BITS} bits"
+        );
+        let point = self.seal(cs, loc.clone())?;
+
+        // with scalar = 2 * k + b0 and k of n bits,
+        // the `VarBaseMul` gates compute [2 * k + 2^n + 1] point,
+        // from which [2^n + 1 - b0] point is subtracted
+        let (b0, high) = bits.split_first().unwrap();
+        let n = VARBASEMUL_BITS * (1 + high.len().saturating_sub(1) / VARBASEMUL_BITS);
+        let mut high_bits = high.to_vec();
+        high_bits.resize(n, Boolean::false_());
+        high_bits.reverse();
+        let shifted = point.scale_shifted(cs, loc.clone(), &high_bits)?;
+
+        // the correction of a constant point is computed outside of the circuit
+        let (correction, correction_minus_point) = match point.to_constant() {
+            Some(base) => {
+                let mut power = base;
+                for _ in 0..n {
+                    let [x, y, ..] = complete_add(power, power);
+                    power = (x, y);
+                }
+                let [x, y, ..] = complete_add(power, base);
+                (Self::constant((x, y)), Self::constant(power))
+            }
+            None => {
+                let zeros = vec![Boolean::false_(); n];
+                let correction = point.scale_shifted(cs, loc.clone(), &zeros)?;
+                let correction_minus_point = correction.add(cs, loc.clone(), &point.neg())?;
+                (correction, correction_minus_point)
+            }
+        };
+        let correction = correction.select(cs, loc.clone(), b0, &correction_minus_point)?;
+
+        shifted.add(cs, loc, &correction.neg())
+    }
+
+    /// Multiplies the fixed point `base` by the scalar of little-endian `bits`.
+    ///
+    /// This is [PointVar::scale] on the constant point, whose correction is computed
+    /// outside of the circuit, which takes about half the rows of a variable point.
+    pub fn scale_fixed(
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        base: (F, F),
+        bits: &[Boolean<F>],
+    ) -> SnarkyResult<Self> {
+        Self::constant(base).scale(cs, loc, bits)
+    }
+
+    /// Returns `other` if `bit` is set, or the point otherwise.
+    fn select(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bit: &Boolean<F>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        let bit = bit.to_field_var();
+        let x = &self.x + &bit.mul(&(&other.x - &self.x), None, loc.clone(), cs)?;
+        let y = &self.y + &bit.mul(&(&other.y - &self.y), None, loc, cs)?;
+        Ok(Self::new_unsafe(x, y))
+    }
+
+    /// Computes `[2 * k + 2^n + 1] self` with the `n` big-endian `bits` of `k`,
+    /// `n` being a multiple of 5.
+    fn scale_shifted(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: &[Boolean<F>],
+    ) -> SnarkyResult<Self> {
+        if let (Some(base), Some(bits)) = (self.to_constant(), to_constants(bits)) {
+            let mut witness = array::from_fn(|_| vec![F::zero(); 2 * bits.len()]);
+            let acc0 = double(base);
+            let res = varbasemul::witness(&mut witness, 0, base, &bits, acc0);
+            return Ok(Self::constant(res.acc));
+        }
+
+        let mut acc = self.double(cs, loc.clone())?.seal(cs, loc.clone())?;
+        let mut n = FieldVar::zero();
+        let mut rounds = Vec::with_capacity(bits.len() / VARBASEMUL_BITS);
+        for chunk in bits.chunks(VARBASEMUL_BITS) {
+            let (base, acc_, n_) = (self.clone(), acc.clone(), n.clone());
+            let chunk_: Vec<_> = chunk.iter().map(Boolean::to_field_var).collect();
+            let cells: [FieldVar<F>; 16] = cs.compute(loc.clone(), move |env| {
+                let bits: Vec<_> = chunk_.iter().map(|b| env.read_var(b).is_one()).collect();
+                let mut witness: [_; COLUMNS] = array::from_fn(|_| vec![F::zero(); 2]);
+                let res =
+                    varbasemul::witness(&mut witness, 0, base.read(env), &bits, acc_.read(env));
+
+                // x1, y1, ..., x4, y4 on the first row, then x5, y5, s0, ..., s4 on the second one
+                let n_next = env.read_var(&n_) * F::from(32u64) + res.n;
+                let mut cells = (7..COLUMNS)
+                    .map(|col| witness[col][0])
+                    .chain([0, 1, 7, 8, 9, 10, 11].map(|col| witness[col][1]))
+                    .chain([n_next]);
+                array::from_fn(|_| cells.next().unwrap())
+            })?;
+
+            let mut accs = vec![(acc.x.clone(), acc.y.clone())];
+            accs.extend(cells[..10].chunks(2).map(|c| (c[0].clone(), c[1].clone())));
+            let round = ScaleRound {
+                accs,
+                bits: chunk.iter().map(Boolean::to_field_var).collect(),
+                ss: cells[10..15].to_vec(),
+                base: (self.x.clone(), self.y.clone()),
+                n_prev: n,
+                n_next: cells[15].clone(),
+            };
+            acc = Self::new_unsafe(cells[8].clone(), cells[9].clone());
+            n = cells[15].clone();
+            rounds.push(round);
+        }
+
+        cs.add_constraint(
+            Constraint::KimchiConstraint(KimchiConstraint::EcScale(rounds)),
+            Some("ec scale".into()),
+            loc,
+        )?;
+
+        Ok(acc)
+    }
+
+    /// Multiplies the point by the scalar encoded with the endomorphism of the curve
+    /// by the little-endian `bits`, whose number is a multiple of 4.
+    ///
+    /// This is the scalar obtained with `ScalarChallenge::to_field`
+    /// from the challenge made of the bits, as used by the recursion verifier.
+    pub fn endo_scale(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: &[Boolean<F>],
+    ) -> SnarkyResult<Self> {
+        assert!(
+            !bits.is_empty() && bits.len() % ENDOMUL_BITS == 0,
+            "the number of bits must be a non-zero multiple of {ENDOMUL_BITS}"
+        );
+        let endo = cs.constants().endo;
+        let bits: Vec<_> = bits.iter().rev().cloned().collect();
+
+        // the accumulator starts at 2 * (endo(point) + point)
+        let point = self.seal(cs, loc.clone())?;
+        let endo_point = Self::new_unsafe(point.x.scale(endo), point.y.clone());
+        let acc = endo_point.add(cs, loc.clone(), &point)?;
+        let mut acc = acc.double(cs, loc.clone())?;
+
+        if let (Some(base), Some(bits)) = (point.to_constant(), to_constants(&bits)) {
+            let rows = bits.len() / ENDOMUL_BITS;
+            let mut witness = array::from_fn(|_| vec![F::zero(); rows + 1]);
+            let acc0 = acc.to_constant().unwrap();
+            let res = endosclmul::gen_witness(&mut witness, 0, endo, base, &bits, acc0);
+            return Ok(Self::constant(res.acc));
+        }
+
+        let mut n = FieldVar::zero();
+        let mut rounds = Vec::with_capacity(bits.len() / ENDOMUL_BITS);
+        for chunk in bits.chunks(ENDOMUL_BITS) {
+            let (base, acc_, n_) = (point.clone(), acc.clone(), n.clone());
+            let chunk_: Vec<_> = chunk.iter().map(Boolean::to_field_var).collect();
+            let [xr, yr, s1, s3, xs, ys, n_next]: [FieldVar<F>; 7] =
+                cs.compute(loc.clone(), move |env| {
+                    let bits: Vec<_> = chunk_.iter().map(|b| env.read_var(b).is_one()).collect();
+                    let mut witness: [_; COLUMNS] = array::from_fn(|_| vec![F::zero(); 2]);
+                    let res = endosclmul::gen_witness(
+                        &mut witness,
+                        0,
+                        endo,
+                        base.read(env),
+                        &bits,
+                        acc_.read(env),
+                    );
+                    let n_next = env.read_var(&n_) * F::from(16u64) + res.n;
+                    let [xr, yr, s1, s3] = [7, 8, 9, 10].map(|col| witness[col][0]);
+                    [xr, yr, s1, s3, res.acc.0, res.acc.1, n_next]
+                })?;
+
+            let [b1, b2, b3, b4] = array::from_fn(|i| chunk[i].to_field_var());
+            rounds.push(EndoscaleRound {
+                xt: point.x.clone(),
+                yt: point.y.clone(),
+                xp: acc.x.clone(),
+                yp: acc.y.clone(),
+                n_acc: n,
+                xr,
+                yr,
+                s1,
+                s3,
+                b1,
+                b2,
+                b3,
+                b4,
+            });
+            acc = Self::new_unsafe(xs, ys);
+            n = n_next;
+        }
+
+        let input = EcEndoscaleInput {
+            state: rounds,
+            xs: acc.x.clone(),
+            ys: acc.y.clone(),
+            n_acc: n,
+        };
+        cs.add_constraint(
+            Constraint::KimchiConstraint(KimchiConstraint::EcEndoscale(input)),
+            Some("ec endoscale".into()),
+            loc,
+        )?;
+
+        Ok(acc)
+    }
+
+    /// Reads the coordinates of the point.
+    fn read(&self, env: &dyn WitnessGeneration<F>) -> (F, F) {
+        (env.read_var(&self.x), env.read_var(&self.y))
+    }
+}
+
+/// The coefficient `b` of the curve `y^2 = x^3 + b`,
+/// as all the curves used with kimchi have a zero coefficient `a`.
+fn curve_b<F: PrimeField>(cs: &RunState<F>) -> F {
+    let (x, y) = cs.constants().base;
+    y.square() - x.square() * x
+}
+
+/// Returns the values of `bits` if they are all constants.
+fn to_constants<F: PrimeField>(bits: &[Boolean<F>]) -> Option<Vec<bool>> {
+    bits.iter().map(Boolean::to_constant).collect()
+}
+
+/// The cells `[x3, y3, inf, same_x, slope, inf_z, x21_inv]` of a `CompleteAdd` row.
+fn complete_add<F: PrimeField>((x1, y1): (F, F), (x2, y2): (F, F)) -> [F; 7] {
+    let same_x = x1 == x2;
+    let inf = same_x && y1 != y2;
+    let x21_inv = (x2 - x1).inverse().unwrap_or_else(F::zero);
+    let slope = if same_x {
+        let x1_squared = x1.square();
+        (x1_squared.double() + x1_squared) * y1.double().inverse().unwrap_or_else(F::zero)
+    } else {
+        (y2 - y1) * x21_inv
+    };
+    let x3 = slope.square() - x1 - x2;
+    let y3 = slope * (x1 - x3) - y1;
+    let inf_z = if same_x {
+        (y2 - y1).inverse().unwrap_or_else(F::zero)
+    } else {
+        F::zero()
+    };
+    [x3, y3, F::from(inf), F::from(same_x), slope, inf_z, x21_inv]
+}
+
+/// The double of a point that is not the point at infinity.
+fn double<F: PrimeField>(point: (F, F)) -> (F, F) {
+    let [x, y, ..] = complete_add(point, point);
+    (x, y)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use ark_ec::{AffineRepr, CurveGroup};
+    use ark_ff::{BigInteger, UniformRand};
+    use mina_curves::pasta::{Fp, Fq, Pallas, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge, ScalarChallenge},
+    };
+    use poly_commitment::ipa::{endos, OpeningProof};
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// The number of bits of a scalar of Pallas
+    const SCALAR_BITS: usize = 255;
+
+    /// The number of bits of a scalar challenge
+    const CHALLENGE_BITS: usize = 128;
+
+    /// Computes `p + q`, `2 p`, `p - q`, `[s] p`, `[s] g` and the endo-scaling of `p` by `c`
+    struct TestCircuit;
+
+    struct Priv {
+        s: [bool; SCALAR_BITS],
+        c: [bool; CHALLENGE_BITS],
+    }
+
+    impl SnarkyCircuit for TestCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = Priv;
+        type PublicInput = (PointVar<Fp>, PointVar<Fp>);
+        type PublicOutput = [PointVar<Fp>; 6];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            (p, q): Self::PublicInput,
+            private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            p.check(sys, loc!())?;
+            q.check(sys, loc!())?;
+            let s: [Boolean<Fp>; SCALAR_BITS] = sys.compute(loc!(), |_| private.unwrap().s)?;
+            let c: [Boolean<Fp>; CHALLENGE_BITS] = sys.compute(loc!(), |_| private.unwrap().c)?;
+
+            let generator = sys.constants().base;
+            Ok([
+                p.add(sys, loc!(), &q)?,
+                p.double(sys, loc!())?,
+                p.add(sys, loc!(), &q.neg())?,
+                p.scale(sys, loc!(), &s)?,
+                PointVar::scale_fixed(sys, loc!(), generator, &s)?,
+                p.endo_scale(sys, loc!(), &c)?,
+            ])
+        }
+    }
+
+    fn coordinates(point: Pallas) -> (Fp, Fp) {
+        let (x, y) = point.xy().unwrap();
+        (*x, *y)
+    }
+
+    fn bits<const N: usize>(x: impl PrimeField) -> [bool; N] {
+        let bits = x.into_bigint().to_bits_le();
+        array::from_fn(|i| bits[i])
+    }
+
+    #[test]
+    fn snarky_point() {
+        let (mut prover_index, verifier_index) = TestCircuit.compile_to_indexes().unwrap();
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let (_, endo_r) = endos::<Pallas>();
+
+        let challenge = Fq::from(u128::rand(&mut rng));
+        let scalars = [Fq::rand(&mut rng), Fq::from(1u32), -Fq::from(1u32)];
+        for s in scalars {
+            let p = (Pallas::generator() * Fq::rand(&mut rng)).into_affine();
+            let q = (Pallas::generator() * Fq::rand(&mut rng)).into_affine();
+            let public_input = (coordinates(p), coordinates(q));
+            let private_input = Priv {
+                s: bits(s),
+                c: bits(challenge),
+            };
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>(public_input, private_input, true)
+                .unwrap();
+
+            let endo_scalar = ScalarChallenge(challenge).to_field(&endo_r);
+            let expected = [
+                p + q,
+                p + p,
+                p - q,
+                p * s,
+                Pallas::generator() * s,
+                p * endo_scalar,
+            ]
+            .map(|point| coordinates(point.into_affine()));
+            assert_eq!(*public_output, expected);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, public_input, expected);
+        }
+    }
+
+    #[test]
+    fn snarky_point_constant() {
+        let mut sys = RunState::<Fp>::new::<Vesta>(0, 0, true);
+        let p = (Pallas::generator() * Fq::from(7u32)).into_affine();
+        let s = Fq::from(12345u32);
+        let s_bits = bits::<20>(s).map(|b| {
+            if b {
+                Boolean::true_()
+            } else {
+                Boolean::false_()
+            }
+        });
+
+        let point = PointVar::constant(coordinates(p));
+        let res = point.scale(&mut sys, loc!(), &s_bits).unwrap();
+        assert_eq!(res.to_constant(), Some(coordinates((p * s).into_affine())));
+        let res = point.double(&mut sys, loc!()).unwrap();
+        assert_eq!(res.to_constant(), Some(coordinates((p + p).into_affine())));
+    }
+
+    #[test]
+    fn snarky_point_not_on_curve() {
+        let (mut prover_index, _) = TestCircuit.compile_to_indexes().unwrap();
+        let private_input = Priv {
+            s: [true; SCALAR_BITS],
+            c: [true; CHALLENGE_BITS],
+        };
+        let g = coordinates(Pallas::generator());
+        let res = prover_index.prove::<BaseSponge, ScalarSponge>(
+            ((Fp::from(1u32), Fp::from(1u32)), g),
+            private_input,
+            true,
+        );
+        assert!(res.is_err());
+    }
+}
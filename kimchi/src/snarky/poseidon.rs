@@ -23,10 +23,23 @@ pub fn poseidon<F: PrimeField>(
     loc: Cow<'static, str>,
     preimage: (FieldVar<F>, FieldVar<F>),
 ) -> (FieldVar<F>, FieldVar<F>) {
-    let initial_state = [preimage.0, preimage.1, FieldVar::zero()];
-    let (constraint, hash) = {
+    let [a, b, _] = permutation(runner, loc, [preimage.0, preimage.1, FieldVar::zero()]);
+    (a, b)
+}
+
+/// The Poseidon permutation of the whole state of the sponge.
+pub fn permutation<F: PrimeField>(
+    runner: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    initial_state: [FieldVar<F>; SPONGE_WIDTH],
+) -> [FieldVar<F>; SPONGE_WIDTH] {
+    let (constraint, last) = {
         let params = runner.poseidon_params();
+        // the successor of the last state is not computed, as there is no such round
         let mut iter = successors((initial_state, 0_usize).into(), |(prev, i)| {
+            if *i == ROUNDS_PER_HASH {
+                return None;
+            }
             //this case may justify moving to Cow
             let state = round(runner, loc.clone(), prev, *i, &params);
             Some((state, i + 1))
@@ -46,22 +59,18 @@ pub fn poseidon<F: PrimeField>(
             })
             .collect_vec();
         let last = iter.next().unwrap();
-        let hash = {
-            let [a, b, _] = last.clone();
-            (a, b)
-        };
         let constraint = Constraint::KimchiConstraint(KimchiConstraint::Poseidon2(PoseidonInput {
             states: states.into_iter().map(|s| s.to_vec()).collect(),
             last: last.to_vec(),
         }));
-        (constraint, hash)
+        (constraint, last)
     };
 
     runner
         .add_constraint(constraint, Some("Poseidon".into()), loc)
         .expect("compiler bug");
 
-    hash
+    last
 }
 
 fn round<F: PrimeField>(
@@ -161,9 +170,8 @@ where
         sys: &mut RunState<F>,
         loc: Cow<'static, str>,
     ) -> (FieldVar<F>, FieldVar<F>) {
-        let left = self.state[0].clone();
-        let right = self.state[1].clone();
-        sys.poseidon(loc, (left, right))
+        self.state = permutation(sys, loc, self.state.clone());
+        (self.state[0].clone(), self.state[1].clone())
     }
 
     /// Squeeze.
@@ -201,3 +209,108 @@ where
 {
     fn absorb(&self, duplex: &mut DuplexState<F>, sys: &mut RunState<F>);
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        loc,
+        snarky::{api::SnarkyCircuit, errors::SnarkyResult},
+    };
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        pasta::fp_kimchi,
+        permutation::poseidon_block_cipher,
+        poseidon::{ArithmeticSponge, Sponge},
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// Hashes two public field elements
+    struct PoseidonCircuit;
+
+    impl SnarkyCircuit for PoseidonCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = [FieldVar<Fp>; 2];
+        type PublicOutput = [FieldVar<Fp>; 2];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            [a, b]: Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let (a, b) = sys.poseidon(loc!(), (a, b));
+            Ok([a, b])
+        }
+    }
+
+    /// Absorbs three public field elements and squeezes three field elements
+    struct DuplexCircuit;
+
+    impl SnarkyCircuit for DuplexCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = [FieldVar<Fp>; 3];
+        type PublicOutput = [FieldVar<Fp>; 3];
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            inputs: Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let mut duplex = DuplexState::new();
+            duplex.absorb(sys, loc!(), &inputs);
+            Ok([
+                duplex.squeeze(sys, loc!()),
+                duplex.squeeze(sys, loc!()),
+                duplex.squeeze(sys, loc!()),
+            ])
+        }
+    }
+
+    #[test]
+    fn snarky_poseidon_permutation() {
+        let (a, b) = (Fp::from(1729), Fp::from(42));
+        let mut expected = vec![a, b, Fp::from(0)];
+        poseidon_block_cipher::<Fp, PlonkSpongeConstantsKimchi>(
+            fp_kimchi::static_params(),
+            &mut expected,
+        );
+
+        let (mut prover_index, verifier_index) = PoseidonCircuit.compile_to_indexes().unwrap();
+        let (proof, public_output) = prover_index
+            .prove::<BaseSponge, ScalarSponge>([a, b], (), true)
+            .unwrap();
+        assert_eq!(public_output.to_vec(), expected[..2]);
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, [a, b], *public_output);
+    }
+
+    #[test]
+    fn snarky_duplex_matches_sponge() {
+        let inputs = [Fp::from(1), Fp::from(2), Fp::from(3)];
+        let mut sponge =
+            ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+        sponge.absorb(&inputs);
+        let expected = [sponge.squeeze(), sponge.squeeze(), sponge.squeeze()];
+
+        let (mut prover_index, verifier_index) = DuplexCircuit.compile_to_indexes().unwrap();
+        let (proof, public_output) = prover_index
+            .prove::<BaseSponge, ScalarSponge>(inputs, (), true)
+            .unwrap();
+        assert_eq!(*public_output, expected);
+
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, inputs, *public_output);
+    }
+}
@@ -8,6 +8,7 @@ use super::{
     errors::{
         RealSnarkyError, SnarkyCompilationError, SnarkyError, SnarkyResult, SnarkyRuntimeResult,
     },
+    keccak::{self, DIGEST_LENGTH},
     poseidon::poseidon,
     range_checks::range_check,
 };
@@ -658,6 +659,10 @@ where
         self.system.as_ref().map(|sys| sys.sponge_params()).unwrap()
     }
 
+    pub(crate) fn constants(&self) -> &Constants<F> {
+        self.system.as_ref().map(|sys| sys.constants()).unwrap()
+    }
+
     pub fn poseidon(
         &mut self,
         loc: Cow<'static, str>,
@@ -675,4 +680,22 @@ where
     ) -> SnarkyResult<()> {
         range_check(self, loc, v0, v1, v2)
     }
+
+    /// Hashes `bytes` with Keccak-256, returning the 32 bytes of the digest.
+    pub fn keccak256(
+        &mut self,
+        loc: Cow<'static, str>,
+        bytes: &[FieldVar<F>],
+    ) -> SnarkyResult<[FieldVar<F>; DIGEST_LENGTH]> {
+        keccak::keccak256(self, loc, bytes)
+    }
+
+    /// Hashes `bytes` with SHA3-256, returning the 32 bytes of the digest.
+    pub fn sha3_256(
+        &mut self,
+        loc: Cow<'static, str>,
+        bytes: &[FieldVar<F>],
+    ) -> SnarkyResult<[FieldVar<F>; DIGEST_LENGTH]> {
+        keccak::sha3_256(self, loc, bytes)
+    }
 }
@@ -0,0 +1,363 @@
+//! The verification of Mina's Schnorr signatures, as done by `mina-signer` with its kimchi hasher.
+//!
+//! A signature `(rx, s)` of a message by a public key `P` is valid if
+//! `s * G - e * P` has `rx` as x-coordinate and an even y-coordinate,
+//! the challenge `e` being the Poseidon hash of the message, `P` and `rx`
+//! with the sponge initialized by the domain string of the network.
+//!
+//! The message is given as its random oracle input (`ROInput` in `mina-hasher`),
+//! that is its field elements, and its bits packed into field elements as `ROInput::to_fields` does.
+//! As the signatures are over the curve whose base field is the field of the circuit,
+//! this gadget is meant for circuits over the scalar field of Vesta.
+
+use std::borrow::Cow;
+
+use ark_ff::{BigInteger, PrimeField};
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+
+use crate::snarky::{
+    boolean::Boolean, cvar::FieldVar, errors::SnarkyResult, point::PointVar, poseidon::DuplexState,
+    runner::RunState, snarky_type::SnarkyType,
+};
+
+/// The number of bits of the scalar `s` of a signature.
+pub const SCALAR_BITS: usize = 255;
+
+/// The maximum length of a domain string.
+const MAX_DOMAIN_STRING_LEN: usize = 20;
+
+/// A signature, with the scalar `s` given as its little-endian bits
+/// (which are not checked to be the canonical representation of a scalar).
+#[derive(Debug, Clone)]
+pub struct SignatureVar<F>
+where
+    F: PrimeField,
+{
+    rx: FieldVar<F>,
+    s: [Boolean<F>; SCALAR_BITS],
+}
+
+impl<F> SnarkyType<F> for SignatureVar<F>
+where
+    F: PrimeField,
+{
+    type Auxiliary = ();
+
+    /// The field `rx` and the integer `s`.
+    type OutOfCircuit = (F, BigUint);
+
+    const SIZE_IN_FIELD_ELEMENTS: usize = 1 + SCALAR_BITS;
+
+    fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+        let mut cvars = vec![self.rx.clone()];
+        cvars.extend(self.s.iter().map(Boolean::to_field_var));
+        (cvars, ())
+    }
+
+    fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+        assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        let (rx, s) = cvars.split_first().unwrap();
+        Self {
+            rx: rx.clone(),
+            s: std::array::from_fn(|i| Boolean::create_unsafe(s[i].clone())),
+        }
+    }
+
+    fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        self.s.check(cs, loc)
+    }
+
+    fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+    fn value_to_field_elements((rx, s): &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+        assert!(s.bits() <= SCALAR_BITS as u64, "s has too many bits");
+        let mut fields = vec![*rx];
+        fields.extend((0..SCALAR_BITS).map(|i| F::from(s.bit(i as u64))));
+        (fields, ())
+    }
+
+    fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
+        assert_eq!(fields.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        let mut s = BigUint::default();
+        for (i, bit) in fields[1..].iter().enumerate() {
+            s.set_bit(i as u64, bit.is_one());
+        }
+        (fields[0], s)
+    }
+}
+
+impl<F> SignatureVar<F>
+where
+    F: PrimeField,
+{
+    /// Returns the x-coordinate of the nonce point of the signature.
+    pub fn rx(&self) -> &FieldVar<F> {
+        &self.rx
+    }
+
+    /// Returns the little-endian bits of the scalar of the signature.
+    pub fn s(&self) -> &[Boolean<F>] {
+        &self.s
+    }
+}
+
+/// Checks that `signature` is a valid signature by `public_key`
+/// of the message made of the `fields` and `packed_bits` of its random oracle input,
+/// with `domain` the domain string of the network (e.g. `"CodaSignature"` on testnets).
+pub fn verify<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    domain: &str,
+    public_key: &PointVar<F>,
+    signature: &SignatureVar<F>,
+    fields: &[FieldVar<F>],
+    packed_bits: &[FieldVar<F>],
+) -> SnarkyResult<()> {
+    // the challenge, as computed by the kimchi hasher of `mina-hasher`
+    let mut sponge = DuplexState::new();
+    let prefix = FieldVar::constant(domain_prefix_to_field(domain));
+    sponge.absorb(cs, loc.clone(), &[prefix]);
+    sponge.squeeze(cs, loc.clone());
+    sponge.absorb(cs, loc.clone(), fields);
+    sponge.absorb(
+        cs,
+        loc.clone(),
+        &[
+            public_key.x().clone(),
+            public_key.y().clone(),
+            signature.rx.clone(),
+        ],
+    );
+    sponge.absorb(cs, loc.clone(), packed_bits);
+    let e = sponge.squeeze(cs, loc.clone());
+
+    // as the base field is smaller than the scalar field,
+    // the challenge is the same integer in the scalar field
+    let e = unpack(cs, loc.clone(), &e)?;
+    let s_g = PointVar::generator(cs).scale(cs, loc.clone(), &signature.s)?;
+    let e_p = public_key.scale(cs, loc.clone(), &e)?;
+    let r = s_g.add(cs, loc.clone(), &e_p.neg())?;
+
+    r.x().assert_equals(cs, loc.clone(), &signature.rx)?;
+    let y = unpack(cs, loc.clone(), r.y())?;
+    y[0].to_field_var()
+        .assert_equals(cs, loc, &FieldVar::zero())
+}
+
+/// The domain string, padded with `*` to [MAX_DOMAIN_STRING_LEN] bytes, as a field element.
+fn domain_prefix_to_field<F: PrimeField>(domain: &str) -> F {
+    assert!(
+        domain.len() <= MAX_DOMAIN_STRING_LEN,
+        "a domain string has at most {MAX_DOMAIN_STRING_LEN} bytes"
+    );
+    let mut bytes = format!("{domain:*<MAX_DOMAIN_STRING_LEN$}").into_bytes();
+    bytes.resize(F::size_in_bytes(), 0);
+    F::from_bytes(&bytes).expect("invalid domain bytes")
+}
+
+/// The canonical decomposition of `x` into its little-endian bits,
+/// which are checked to be the bits of an integer smaller than the modulus.
+fn unpack<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    x: &FieldVar<F>,
+) -> SnarkyResult<Vec<Boolean<F>>> {
+    let num_bits = F::MODULUS_BIT_SIZE as usize;
+    if let FieldVar::Constant(x) = x {
+        let x = x.into_bigint();
+        let bits = (0..num_bits)
+            .map(|i| {
+                if x.get_bit(i) {
+                    Boolean::true_()
+                } else {
+                    Boolean::false_()
+                }
+            })
+            .collect();
+        return Ok(bits);
+    }
+
+    let mut bits = Vec::with_capacity(num_bits);
+    for i in 0..num_bits {
+        let bit: Boolean<F> = cs.compute(loc.clone(), |env| {
+            env.read_var(x).to_biguint().bit(i as u64)
+        })?;
+        bits.push(bit);
+    }
+    let terms: Vec<_> = bits
+        .iter()
+        .enumerate()
+        .map(|(i, bit)| (F::from(2u64).pow([i as u64]), bit.to_field_var()))
+        .collect();
+    FieldVar::linear_combination(&terms).assert_equals(cs, loc.clone(), x)?;
+
+    // from the most significant bit, `equal` is set while the bits are those of the modulus,
+    // so that a bit must be unset while `equal` is set and the one of the modulus is unset
+    let mut equal = FieldVar::constant(F::one());
+    for (i, bit) in bits.iter().enumerate().rev() {
+        let bit = bit.to_field_var();
+        if F::MODULUS.get_bit(i) {
+            equal = equal.mul(&bit, Some("unpack".into()), loc.clone(), cs)?;
+        } else {
+            cs.assert_r1cs(
+                Some("unpack".into()),
+                loc.clone(),
+                equal.clone(),
+                bit,
+                FieldVar::zero(),
+            )?;
+        }
+    }
+    equal.assert_equals(cs, loc, &FieldVar::zero())?;
+
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use ark_ec::AffineRepr;
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_hasher::Hashable;
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use mina_signer::{Keypair, NetworkId, Payment, PubKey, Signature, Signer};
+    use poly_commitment::ipa::OpeningProof;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    /// The number of fields and of packed bits of the random oracle input of a payment.
+    const FIELDS: usize = 3;
+    const PACKED_BITS: usize = 3;
+
+    /// Verifies a signature of a public payment
+    struct TestCircuit {
+        network_id: NetworkId,
+    }
+
+    impl SnarkyCircuit for TestCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = (
+            (PointVar<Fp>, SignatureVar<Fp>),
+            ([FieldVar<Fp>; FIELDS], [FieldVar<Fp>; PACKED_BITS]),
+        );
+        type PublicOutput = ();
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            ((public_key, signature), (fields, packed_bits)): Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            let domain = self.network_id.clone().into_domain_string();
+            verify(
+                sys,
+                loc!(),
+                &domain,
+                &public_key,
+                &signature,
+                &fields,
+                &packed_bits,
+            )
+        }
+    }
+
+    type PublicInput =
+        <<TestCircuit as SnarkyCircuit>::PublicInput as SnarkyType<Fp>>::OutOfCircuit;
+
+    fn public_input(public_key: &PubKey, signature: &Signature, payment: &Payment) -> PublicInput {
+        let (x, y) = public_key.point().xy().unwrap();
+        let signature = (signature.rx, signature.s.into_bigint().into());
+
+        let roinput = payment.to_roinput();
+        let fields: [Fp; FIELDS] = roinput.fields().try_into().unwrap();
+        let packed_bits: [Fp; PACKED_BITS] = roinput.to_fields()[FIELDS..].try_into().unwrap();
+
+        (((*x, *y), signature), (fields, packed_bits))
+    }
+
+    fn test_signatures(network_id: NetworkId) {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let kp = Keypair::rand(&mut rng).unwrap();
+        let receiver = Keypair::rand(&mut rng).unwrap();
+        let payment = Payment::new(
+            kp.public.into_compressed(),
+            receiver.public.into_compressed(),
+            1729000000000,
+            2000000000,
+            16,
+        );
+        let other_payment = Payment::new(
+            kp.public.into_compressed(),
+            receiver.public.into_compressed(),
+            1729000000001,
+            2000000000,
+            16,
+        );
+
+        let mut ctx = mina_signer::create_kimchi::<Payment>(network_id.clone());
+        let signature = ctx.sign(&kp, &payment);
+        assert!(ctx.verify(&signature, &kp.public, &payment));
+        assert!(!ctx.verify(&signature, &kp.public, &other_payment));
+        assert!(!ctx.verify(&signature, &receiver.public, &payment));
+
+        let (mut prover_index, verifier_index) =
+            TestCircuit { network_id }.compile_to_indexes().unwrap();
+
+        let public_input = public_input(&kp.public, &signature, &payment);
+        let (proof, _) = prover_index
+            .prove::<BaseSponge, ScalarSponge>(public_input.clone(), (), true)
+            .unwrap();
+        verifier_index.verify::<BaseSponge, ScalarSponge>(proof, public_input, ());
+
+        for public_input in [
+            self::public_input(&kp.public, &signature, &other_payment),
+            self::public_input(&receiver.public, &signature, &payment),
+        ] {
+            let res = prover_index.prove::<BaseSponge, ScalarSponge>(public_input, (), true);
+            assert!(res.is_err());
+        }
+    }
+
+    #[test]
+    fn snarky_schnorr_testnet() {
+        test_signatures(NetworkId::TESTNET);
+    }
+
+    #[test]
+    fn snarky_schnorr_mainnet() {
+        test_signatures(NetworkId::MAINNET);
+    }
+
+    #[test]
+    fn snarky_schnorr_wrong_network() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let kp = Keypair::rand(&mut rng).unwrap();
+        let payment = Payment::new(
+            kp.public.into_compressed(),
+            kp.public.into_compressed(),
+            1,
+            1,
+            0,
+        );
+        let signature =
+            mina_signer::create_kimchi::<Payment>(NetworkId::TESTNET).sign(&kp, &payment);
+
+        let (mut prover_index, _) = TestCircuit {
+            network_id: NetworkId::MAINNET,
+        }
+        .compile_to_indexes()
+        .unwrap();
+        let public_input = public_input(&kp.public, &signature, &payment);
+        let res = prover_index.prove::<BaseSponge, ScalarSponge>(public_input, (), true);
+        assert!(res.is_err());
+    }
+}
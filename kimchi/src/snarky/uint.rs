@@ -0,0 +1,701 @@
+//! The [UIntVar] type is a snarky type that represents an unsigned integer
+//! of 32 bits ([UInt32Var]) or 64 bits ([UInt64Var]).
+//!
+//! The size of an integer is constrained with `RangeCheck0` gates,
+//! each XOR with a chain of `Xor16` gates, and each 64-bit rotation with a `Rot64` gate.
+//! The other operations are derived from linear combinations
+//! and from decompositions of the integers into range-checked parts.
+
+use std::{array, borrow::Cow, fmt::Debug, marker::PhantomData};
+
+use ark_ff::PrimeField;
+use num_bigint::BigUint;
+use o1_utils::FieldHelpers;
+
+use crate::{
+    circuits::wires::COLUMNS,
+    snarky::{
+        boolean::Boolean,
+        constraint_system::{KimchiConstraint, Rot64Input},
+        cvar::FieldVar,
+        errors::SnarkyResult,
+        runner::{Constraint, RunState},
+        snarky_type::SnarkyType,
+    },
+};
+
+/// An unsigned integer type that can be represented in a circuit.
+pub trait UInt: Copy + Debug + Into<u64> + TryFrom<u64> {
+    /// The number of bits of the integer, at most 64.
+    const BITS: usize;
+}
+
+impl UInt for u32 {
+    const BITS: usize = 32;
+}
+
+impl UInt for u64 {
+    const BITS: usize = 64;
+}
+
+/// An unsigned integer of type `T`, held in a single field variable.
+#[derive(Debug, Clone)]
+pub struct UIntVar<F, T>
+where
+    F: PrimeField,
+{
+    value: FieldVar<F>,
+    uint: PhantomData<T>,
+}
+
+/// A 32-bit unsigned integer.
+pub type UInt32Var<F> = UIntVar<F, u32>;
+
+/// A 64-bit unsigned integer.
+pub type UInt64Var<F> = UIntVar<F, u64>;
+
+impl<F, T> SnarkyType<F> for UIntVar<F, T>
+where
+    F: PrimeField,
+    T: UInt,
+{
+    type Auxiliary = ();
+
+    type OutOfCircuit = T;
+
+    const SIZE_IN_FIELD_ELEMENTS: usize = 1;
+
+    fn to_cvars(&self) -> (Vec<FieldVar<F>>, Self::Auxiliary) {
+        (vec![self.value.clone()], ())
+    }
+
+    fn from_cvars_unsafe(cvars: Vec<FieldVar<F>>, _aux: Self::Auxiliary) -> Self {
+        assert_eq!(cvars.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        Self::from_field_unsafe(cvars[0].clone())
+    }
+
+    fn check(&self, cs: &mut RunState<F>, loc: Cow<'static, str>) -> SnarkyResult<()> {
+        check_bits(cs, loc, &self.value, T::BITS)
+    }
+
+    fn constraint_system_auxiliary() -> Self::Auxiliary {}
+
+    fn value_to_field_elements(value: &Self::OutOfCircuit) -> (Vec<F>, Self::Auxiliary) {
+        (vec![F::from((*value).into())], ())
+    }
+
+    fn value_of_field_elements(fields: Vec<F>, _aux: Self::Auxiliary) -> Self::OutOfCircuit {
+        assert_eq!(fields.len(), Self::SIZE_IN_FIELD_ELEMENTS);
+        let value = fields[0].to_biguint();
+        u64::try_from(&value)
+            .ok()
+            .and_then(|value| T::try_from(value).ok())
+            .unwrap_or_else(|| panic!("{value} does not fit in {} bits", T::BITS))
+    }
+}
+
+impl<F, T> UIntVar<F, T>
+where
+    F: PrimeField,
+    T: UInt,
+{
+    fn from_field_unsafe(value: FieldVar<F>) -> Self {
+        Self {
+            value,
+            uint: PhantomData,
+        }
+    }
+
+    /// Creates a constant.
+    pub fn constant(value: T) -> Self {
+        Self::from_field_unsafe(FieldVar::constant(F::from(value.into())))
+    }
+
+    /// Returns the field variable holding the integer.
+    pub fn value(&self) -> &FieldVar<F> {
+        &self.value
+    }
+
+    /// Returns the value of the integer if it is a constant.
+    pub fn to_constant(&self) -> Option<T> {
+        match &self.value {
+            FieldVar::Constant(c) => Some(Self::value_of_field_elements(vec![*c], ())),
+            _ => None,
+        }
+    }
+
+    /// The largest integer, `2^BITS - 1`.
+    fn max() -> F {
+        F::from(u64::MAX >> (64 - T::BITS))
+    }
+
+    /// Returns the bitwise XOR of the integer and `other`.
+    pub fn xor(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        let out = xor(cs, loc, &self.value, &other.value, T::BITS)?;
+        Ok(Self::from_field_unsafe(out))
+    }
+
+    /// Returns the bitwise AND of the integer and `other`,
+    /// using that `a + b = (a xor b) + 2 * (a and b)`.
+    pub fn and(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        let xor = self.xor(cs, loc.clone(), other)?;
+        let inv_two = F::from(2u64).inverse().unwrap();
+        let and = (&(&self.value + &other.value) - &xor.value).scale(inv_two);
+        Ok(Self::from_field_unsafe(and.seal(cs, loc)?))
+    }
+
+    /// Returns the bitwise NOT of the integer, that is, `2^BITS - 1 - x`.
+    pub fn not(&self) -> Self {
+        Self::from_field_unsafe(&FieldVar::constant(Self::max()) - &self.value)
+    }
+
+    /// Rotates the integer by `bits` bits to the left.
+    pub fn rotl(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: u32,
+    ) -> SnarkyResult<Self> {
+        let bits = bits as usize % T::BITS;
+        if bits == 0 {
+            return Ok(self.clone());
+        }
+        if T::BITS == 64 {
+            let rotated = rot64(cs, loc, &self.value, bits as u32)?;
+            return Ok(Self::from_field_unsafe(rotated));
+        }
+
+        // with x = high * 2^(BITS - bits) + low, the rotation is low * 2^bits + high
+        let (high, low) = self.split_at(cs, loc.clone(), T::BITS - bits)?;
+        let rotated = &low.scale(F::from(2u64).pow([bits as u64])) + &high;
+        Ok(Self::from_field_unsafe(rotated.seal(cs, loc)?))
+    }
+
+    /// Shifts the integer by `bits` bits to the right.
+    pub fn shr(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: u32,
+    ) -> SnarkyResult<Self> {
+        let bits = bits as usize;
+        if bits == 0 {
+            return Ok(self.clone());
+        }
+        if bits >= T::BITS {
+            return Ok(Self::from_field_unsafe(FieldVar::zero()));
+        }
+
+        let (high, _) = self.split_at(cs, loc, bits)?;
+        Ok(Self::from_field_unsafe(high))
+    }
+
+    /// Splits the integer into `high * 2^bits + low`, with `low` fitting in `bits` bits
+    /// and `high` fitting in the `BITS - bits` remaining bits.
+    fn split_at(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: usize,
+    ) -> SnarkyResult<(FieldVar<F>, FieldVar<F>)> {
+        if let Some(x) = self.to_constant() {
+            let x: u64 = x.into();
+            let low = x & (u64::MAX >> (64 - bits));
+            return Ok((
+                FieldVar::constant(F::from(x >> bits)),
+                FieldVar::constant(F::from(low)),
+            ));
+        }
+
+        let value = self.value.clone();
+        let (high, low): (FieldVar<F>, FieldVar<F>) = cs.compute(loc.clone(), move |env| {
+            let x = env.read_var(&value).to_biguint();
+            let low = x.iter_u64_digits().next().unwrap_or(0) & (u64::MAX >> (64 - bits));
+            (F::from(x >> bits), F::from(low))
+        })?;
+        check_bits(cs, loc.clone(), &high, T::BITS - bits)?;
+        check_bits(cs, loc.clone(), &low, bits)?;
+
+        let two_to_bits = F::from(2u64).pow([bits as u64]);
+        (&high.scale(two_to_bits) + &low).assert_equals(cs, loc, &self.value)?;
+
+        Ok((high, low))
+    }
+
+    /// Adds `other` to the integer, returning the sum modulo `2^BITS`
+    /// and whether the addition overflowed.
+    pub fn overflowing_add(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<(Self, Boolean<F>)> {
+        Self::split_carry(cs, loc, &self.value + &other.value)
+    }
+
+    /// Adds `other` to the integer, returning the sum modulo `2^BITS`.
+    pub fn wrapping_add(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Self> {
+        Ok(self.overflowing_add(cs, loc, other)?.0)
+    }
+
+    /// Returns whether the integer is smaller than `other`,
+    /// using that `x + 2^BITS - other` overflows if and only if `x >= other`.
+    pub fn lt(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Boolean<F>> {
+        let two_to_bits = FieldVar::constant(Self::max() + F::one());
+        let (_, carry) = Self::split_carry(cs, loc, &(&self.value + &two_to_bits) - &other.value)?;
+        Ok(carry.not())
+    }
+
+    /// Returns whether the integer is smaller than or equal to `other`.
+    pub fn le(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        other: &Self,
+    ) -> SnarkyResult<Boolean<F>> {
+        Ok(other.lt(cs, loc, self)?.not())
+    }
+
+    /// Splits `x`, which must fit in `BITS + 1` bits, into `low + carry * 2^BITS`.
+    fn split_carry(
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        x: FieldVar<F>,
+    ) -> SnarkyResult<(Self, Boolean<F>)> {
+        if let FieldVar::Constant(c) = x {
+            let c = c.to_biguint();
+            let low = c.iter_u64_digits().next().unwrap_or(0) & (u64::MAX >> (64 - T::BITS));
+            let carry = if c.bit(T::BITS as u64) {
+                Boolean::true_()
+            } else {
+                Boolean::false_()
+            };
+            return Ok((
+                Self::from_field_unsafe(FieldVar::constant(F::from(low))),
+                carry,
+            ));
+        }
+
+        // the low part is range checked and the carry is boolean
+        let x_ = x.clone();
+        let (low, carry): (Self, Boolean<F>) = cs.compute(loc.clone(), move |env| {
+            let x = env.read_var(&x_).to_biguint();
+            let low = x.iter_u64_digits().next().unwrap_or(0) & (u64::MAX >> (64 - T::BITS));
+            let low = T::try_from(low).ok().unwrap();
+            (low, x.bit(T::BITS as u64))
+        })?;
+
+        let two_to_bits = Self::max() + F::one();
+        (&low.value + &carry.to_field_var().scale(two_to_bits)).assert_equals(cs, loc, &x)?;
+
+        Ok((low, carry))
+    }
+
+    /// Decomposes the integer into its `BITS` bits, in little-endian order.
+    pub fn to_bits(
+        &self,
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+    ) -> SnarkyResult<Vec<Boolean<F>>> {
+        if let Some(x) = self.to_constant() {
+            let x: u64 = x.into();
+            let bits = (0..T::BITS)
+                .map(|i| {
+                    if (x >> i) & 1 == 1 {
+                        Boolean::true_()
+                    } else {
+                        Boolean::false_()
+                    }
+                })
+                .collect();
+            return Ok(bits);
+        }
+
+        let mut bits = Vec::with_capacity(T::BITS);
+        for i in 0..T::BITS {
+            let bit: Boolean<F> = cs.compute(loc.clone(), |env| {
+                env.read_var(&self.value).to_biguint().bit(i as u64)
+            })?;
+            bits.push(bit);
+        }
+        compose(&bits).assert_equals(cs, loc, &self.value)?;
+
+        Ok(bits)
+    }
+
+    /// Composes an integer from at most `BITS` bits, in little-endian order.
+    pub fn from_bits(
+        cs: &mut RunState<F>,
+        loc: Cow<'static, str>,
+        bits: &[Boolean<F>],
+    ) -> SnarkyResult<Self> {
+        assert!(
+            bits.len() <= T::BITS,
+            "{} bits do not fit in {} bits",
+            bits.len(),
+            T::BITS
+        );
+        Ok(Self::from_field_unsafe(compose(bits).seal(cs, loc)?))
+    }
+}
+
+/// The little-endian composition of `bits`.
+fn compose<F: PrimeField>(bits: &[Boolean<F>]) -> FieldVar<F> {
+    let terms: Vec<_> = bits
+        .iter()
+        .enumerate()
+        .map(|(i, bit)| (F::from(2u64).pow([i as u64]), bit.to_field_var()))
+        .collect();
+    FieldVar::linear_combination(&terms)
+}
+
+/// Checks that `x` fits in `bits` bits, with `bits` at most 64,
+/// by checking that both `x` and `x * 2^(64 - bits)` fit in 64 bits.
+pub(crate) fn check_bits<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    x: &FieldVar<F>,
+    bits: usize,
+) -> SnarkyResult<()> {
+    if let FieldVar::Constant(c) = x {
+        assert!(
+            c.to_biguint().bits() <= bits as u64,
+            "constant does not fit in {bits} bits"
+        );
+        return Ok(());
+    }
+
+    check_64_bits(cs, loc.clone(), x)?;
+    if bits < 64 {
+        let shifted = x.scale(F::from(2u64).pow([64 - bits as u64]));
+        check_64_bits(cs, loc, &shifted)?;
+    }
+    Ok(())
+}
+
+/// Checks that `x` fits in 64 bits with a single `RangeCheck0` row.
+fn check_64_bits<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    x: &FieldVar<F>,
+) -> SnarkyResult<()> {
+    let x_ = x.clone();
+    let limbs: [FieldVar<F>; 12] = cs.compute(loc.clone(), move |env| {
+        let x = env.read_var(&x_).to_biguint();
+        let mut limbs = limbs64(&x);
+        array::from_fn(|_| limbs.next().unwrap())
+    })?;
+
+    let row = [x.clone(), FieldVar::zero(), FieldVar::zero()]
+        .into_iter()
+        .chain(limbs)
+        .collect();
+    cs.add_constraint(
+        Constraint::KimchiConstraint(KimchiConstraint::RangeCheck0(row)),
+        Some("range check 64".into()),
+        loc,
+    )
+}
+
+/// Returns the XOR of `left` and `right`, and checks that they fit in `bits` bits,
+/// with `bits` a multiple of 16.
+fn xor<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    left: &FieldVar<F>,
+    right: &FieldVar<F>,
+    bits: usize,
+) -> SnarkyResult<FieldVar<F>> {
+    if let (FieldVar::Constant(x), FieldVar::Constant(y)) = (left, right) {
+        let out = x.to_biguint() ^ y.to_biguint();
+        return Ok(FieldVar::constant(F::from(out)));
+    }
+
+    // each `Xor16` row checks 16 bits of the inputs and of the output,
+    // the rest of them being carried to the next row
+    let num_xors = bits / 16;
+    let mut rows = Vec::with_capacity(num_xors + 1);
+    let mut out = None;
+    for i in 0..num_xors {
+        let (left_, right_) = (left.clone(), right.clone());
+        let row: [FieldVar<F>; COLUMNS] = cs.compute(loc.clone(), move |env| {
+            let left = env.read_var(&left_).to_biguint();
+            let right = env.read_var(&right_).to_biguint();
+            xor_row(&left, &right, i)
+        })?;
+        let mut row = row.to_vec();
+        if i == 0 {
+            row[0] = left.clone();
+            row[1] = right.clone();
+            out = Some(row[2].clone());
+        }
+        rows.push(row);
+    }
+    rows.push(vec![FieldVar::zero(); 3]);
+
+    cs.add_constraint(
+        Constraint::KimchiConstraint(KimchiConstraint::Xor(rows)),
+        Some("xor".into()),
+        loc,
+    )?;
+
+    Ok(out.unwrap())
+}
+
+/// The `i`-th `Xor16` row of the XOR of `left` and `right`.
+fn xor_row<F: PrimeField>(left: &BigUint, right: &BigUint, i: usize) -> [F; COLUMNS] {
+    let out = left ^ right;
+    let words = [left, right, &out].map(|word| word >> (16 * i));
+    array::from_fn(|col| match col {
+        0..=2 => F::from(words[col].clone()),
+        _ => F::from(bits(&words[(col - 3) / 4], 4 * ((col - 3) % 4), 4)),
+    })
+}
+
+/// Rotates the 64-bit `word` by `rot` bits to the left, with `rot` smaller than 64.
+fn rot64<F: PrimeField>(
+    cs: &mut RunState<F>,
+    loc: Cow<'static, str>,
+    word: &FieldVar<F>,
+    rot: u32,
+) -> SnarkyResult<FieldVar<F>> {
+    if rot == 0 {
+        return Ok(word.clone());
+    }
+    if let FieldVar::Constant(x) = word {
+        let x = bits(&x.to_biguint(), 0, 64);
+        return Ok(FieldVar::constant(F::from(x.rotate_left(rot))));
+    }
+
+    // word * 2^rot = excess * 2^64 + shifted
+    // rotated = shifted + excess
+    let word_ = word.clone();
+    let cells: [FieldVar<F>; 39] = cs.compute(loc.clone(), move |env| {
+        let word = env.read_var(&word_).to_biguint();
+        let two_to_64 = BigUint::from(1u8) << 64;
+        let shifted: BigUint = (&word << rot) % &two_to_64;
+        let excess = &word >> (64 - rot);
+        let rotated = &shifted + &excess;
+        let bound = &excess + two_to_64 - (BigUint::from(1u8) << rot);
+
+        let mut cells = [rotated, excess.clone(), shifted.clone()]
+            .into_iter()
+            .map(F::from)
+            .chain(limbs64(&bound))
+            .chain(limbs64(&shifted))
+            .chain(limbs64(&excess));
+        array::from_fn(|_| cells.next().unwrap())
+    })?;
+
+    let rotated = cells[0].clone();
+    let (excess, shifted) = (&cells[1], &cells[2]);
+    let (bound_limbs, range_check_limbs) = cells[3..].split_at(12);
+    let rotation = [word.clone(), rotated.clone(), excess.clone()]
+        .into_iter()
+        .chain(bound_limbs.iter().cloned())
+        .collect();
+    let range_checks = [shifted, excess]
+        .into_iter()
+        .zip(range_check_limbs.chunks(12))
+        .map(|(value, limbs)| {
+            [value.clone(), FieldVar::zero(), FieldVar::zero()]
+                .into_iter()
+                .chain(limbs.iter().cloned())
+                .collect()
+        })
+        .collect();
+
+    let input = Rot64Input {
+        rotation,
+        range_checks,
+        two_to_rot: F::from(2u64).pow([rot as u64]),
+    };
+    cs.add_constraint(
+        Constraint::KimchiConstraint(KimchiConstraint::Rot64(input)),
+        Some("rot64".into()),
+        loc,
+    )?;
+
+    Ok(rotated)
+}
+
+/// The four 12-bit limbs and eight 2-bit crumbs of a 64-bit value, most significant first,
+/// as laid out in the `Rot64` and `RangeCheck0` rows.
+fn limbs64<F: PrimeField>(x: &BigUint) -> impl Iterator<Item = F> + '_ {
+    let limbs = (0..4).map(|i| (52 - 12 * i, 12));
+    let crumbs = (0..8).map(|i| (14 - 2 * i, 2));
+    limbs
+        .chain(crumbs)
+        .map(|(start, len)| F::from(bits(x, start, len)))
+}
+
+/// The `len` bits of `x` starting at `start`.
+fn bits(x: &BigUint, start: usize, len: usize) -> u64 {
+    let x = (x >> start).iter_u64_digits().next().unwrap_or(0);
+    x & (u64::MAX >> (64 - len))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{loc, snarky::api::SnarkyCircuit};
+    use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+    use mina_poseidon::{
+        constants::PlonkSpongeConstantsKimchi,
+        sponge::{DefaultFqSponge, DefaultFrSponge},
+    };
+    use poly_commitment::ipa::OpeningProof;
+    use rand::Rng;
+
+    type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+    type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
+    const ROT: u32 = 7;
+    const SHR: u32 = 5;
+
+    /// Computes `a xor b`, `a and b`, `not a`, `rotl(a, ROT)`, `a >> SHR`,
+    /// `a + b` and the bits of `a`, then whether the addition overflowed,
+    /// `a < b` and `a <= b`
+    struct TestCircuit<T>(PhantomData<T>);
+
+    impl<T: UInt> SnarkyCircuit for TestCircuit<T> {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = (UIntVar<Fp, T>, UIntVar<Fp, T>);
+        type PublicOutput = ([UIntVar<Fp, T>; 7], [Boolean<Fp>; 3]);
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            (a, b): Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            a.check(sys, loc!())?;
+            b.check(sys, loc!())?;
+
+            let (sum, overflow) = a.overflowing_add(sys, loc!(), &b)?;
+            let bits = a.to_bits(sys, loc!())?;
+            let uints = [
+                a.xor(sys, loc!(), &b)?,
+                a.and(sys, loc!(), &b)?,
+                a.not(),
+                a.rotl(sys, loc!(), ROT)?,
+                a.shr(sys, loc!(), SHR)?,
+                sum,
+                UIntVar::from_bits(sys, loc!(), &bits)?,
+            ];
+            let booleans = [overflow, a.lt(sys, loc!(), &b)?, a.le(sys, loc!(), &b)?];
+            Ok((uints, booleans))
+        }
+    }
+
+    fn expected<T: UInt>(a: T, b: T) -> ([T; 7], [bool; 3]) {
+        let (a, b): (u64, u64) = (a.into(), b.into());
+        let max = u64::MAX >> (64 - T::BITS);
+        let rotl = ((a << ROT) | (a >> (T::BITS as u32 - ROT))) & max;
+        let sum = a as u128 + b as u128;
+        let uints = [a ^ b, a & b, !a & max, rotl, a >> SHR, sum as u64 & max, a];
+        let booleans = [sum > max as u128, a < b, a <= b];
+        (uints.map(|x| T::try_from(x).ok().unwrap()), booleans)
+    }
+
+    fn test_uint<T>(inputs: &[(T, T)])
+    where
+        T: UInt + PartialEq,
+    {
+        let (mut prover_index, verifier_index) =
+            TestCircuit::<T>(PhantomData).compile_to_indexes().unwrap();
+
+        for &(a, b) in inputs {
+            let (proof, public_output) = prover_index
+                .prove::<BaseSponge, ScalarSponge>((a, b), (), true)
+                .unwrap();
+
+            let expected = expected(a, b);
+            assert_eq!(*public_output, expected);
+
+            verifier_index.verify::<BaseSponge, ScalarSponge>(proof, (a, b), expected);
+        }
+    }
+
+    #[test]
+    fn snarky_uint32() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let x = rng.gen();
+        test_uint::<u32>(&[(rng.gen(), rng.gen()), (u32::MAX, 1), (x, x), (0, u32::MAX)]);
+    }
+
+    #[test]
+    fn snarky_uint64() {
+        let mut rng = o1_utils::tests::make_test_rng(None);
+        let x = rng.gen();
+        test_uint::<u64>(&[(rng.gen(), rng.gen()), (u64::MAX, 1), (x, x), (0, u64::MAX)]);
+    }
+
+    #[test]
+    fn snarky_uint_constant() {
+        let mut sys = RunState::<Fp>::new::<Vesta>(0, 0, true);
+        let (a, b) = (0xdead_beef_u32, 0x1234_5678_u32);
+        let (x, y) = (UInt32Var::constant(a), UInt32Var::constant(b));
+
+        let (sum, overflow) = x.overflowing_add(&mut sys, loc!(), &y).unwrap();
+        assert_eq!(sum.to_constant(), Some(a.wrapping_add(b)));
+        assert_eq!(overflow.to_constant(), Some(false));
+        let and = x.and(&mut sys, loc!(), &y).unwrap();
+        assert_eq!(and.to_constant(), Some(a & b));
+        let rotl = x.rotl(&mut sys, loc!(), 12).unwrap();
+        assert_eq!(rotl.to_constant(), Some(a.rotate_left(12)));
+        let lt = x.lt(&mut sys, loc!(), &y).unwrap();
+        assert_eq!(lt.to_constant(), Some(false));
+    }
+
+    /// Checks that the public input is a 32-bit integer
+    struct RangeCircuit;
+
+    impl SnarkyCircuit for RangeCircuit {
+        type Curve = Vesta;
+        type Proof = OpeningProof<Self::Curve>;
+
+        type PrivateInput = ();
+        type PublicInput = FieldVar<Fp>;
+        type PublicOutput = ();
+
+        fn circuit(
+            &self,
+            sys: &mut RunState<Fp>,
+            x: Self::PublicInput,
+            _private: Option<&Self::PrivateInput>,
+        ) -> SnarkyResult<Self::PublicOutput> {
+            UInt32Var::from_cvars_unsafe(vec![x], ()).check(sys, loc!())
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid RangeCheck0 constraint")]
+    fn snarky_uint32_out_of_range() {
+        let (mut prover_index, _) = RangeCircuit.compile_to_indexes().unwrap();
+        let _ = prover_index.prove::<BaseSponge, ScalarSponge>(Fp::from(1u64 << 32), (), true);
+    }
+}
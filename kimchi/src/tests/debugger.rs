@@ -0,0 +1,240 @@
+use super::framework::TestFramework;
+use crate::circuits::{
+    debugger::ConstraintFailure,
+    gate::{CircuitGate, Connect, GateType},
+    lookup::{
+        runtime_tables::{RuntimeTable, RuntimeTableCfg},
+        tables::LookupTable,
+    },
+    polynomials::generic::testing::{create_circuit, fill_in_witness},
+    wires::{Wire, COLUMNS},
+};
+use ark_ff::{One, Zero};
+use mina_curves::pasta::{Fp, Vesta};
+use std::array;
+
+#[test]
+fn witness_report_valid_witness() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .public_inputs(public.clone())
+        .setup();
+    let report = runner.prover_index().witness_report(&witness, &public, &[]);
+    assert!(report.is_ok());
+    assert_eq!(report.to_string(), "all constraints are satisfied");
+
+    // A different public input fails the first generic gate of every public row
+    let wrong_public = vec![Fp::from(4u8); 5];
+    let report = runner
+        .prover_index()
+        .witness_report(&witness, &wrong_public, &[]);
+    assert_eq!(report.failing_rows(), vec![0, 1, 2, 3, 4]);
+    for failure in &report.failures {
+        assert!(matches!(
+            failure,
+            ConstraintFailure::Gate {
+                typ: GateType::Generic,
+                constraint: 1,
+                evaluation,
+                ..
+            } if *evaluation == -Fp::one()
+        ));
+    }
+}
+
+#[test]
+fn witness_report_lists_all_failing_gates() {
+    let gates = create_circuit(0, 0);
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    // addition output of row 1
+    witness[2][1] = Fp::zero();
+    // multiplication output of row 3
+    witness[5][3] += Fp::one();
+    // second constant of row 12
+    witness[3][12] = 6u32.into();
+
+    let runner = TestFramework::<Vesta>::default().gates(gates).setup();
+    let report = runner.prover_index().witness_report(&witness, &[], &[]);
+
+    assert_eq!(report.failing_rows(), vec![1, 3, 12]);
+    assert_eq!(report.failures.len(), 3);
+
+    let mut curr = vec![Fp::zero(); COLUMNS];
+    curr[0] = 11u32.into();
+    curr[1] = 23u32.into();
+    curr[3] = 11u32.into();
+    curr[4] = 23u32.into();
+    curr[5] = 506u32.into();
+    let mut next = curr.clone();
+    next[2] = 80u32.into();
+    assert_eq!(
+        report.failures[0],
+        ConstraintFailure::Gate {
+            row: 1,
+            typ: GateType::Generic,
+            constraint: 1,
+            evaluation: 80u32.into(),
+            curr,
+            next,
+        }
+    );
+    assert!(matches!(
+        report.failures[1],
+        ConstraintFailure::Gate { row: 3, constraint: 2, evaluation, .. } if evaluation == -Fp::one()
+    ));
+    assert!(matches!(
+        report.failures[2],
+        ConstraintFailure::Gate { row: 12, constraint: 2, evaluation, .. } if evaluation == Fp::one()
+    ));
+    assert!(report
+        .to_string()
+        .starts_with("3 unsatisfied constraint(s):\n  row 1: constraint 1 of the Generic gate evaluates to 80 (curr = [11, 23, 0, 11, 23, 506, 0,"));
+}
+
+#[test]
+fn witness_report_copy_constraints() {
+    let mut gates = create_circuit(0, 0);
+    gates.connect_cell_pair((1, 0), (2, 0));
+
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    witness[0][2] = 12u32.into();
+
+    let runner = TestFramework::<Vesta>::default().gates(gates).setup();
+    let report = runner.prover_index().witness_report(&witness, &[], &[]);
+
+    assert_eq!(
+        report.failures,
+        vec![
+            ConstraintFailure::CopyConstraint {
+                typ: GateType::Generic,
+                src: Wire { row: 1, col: 0 },
+                dst: Wire { row: 2, col: 0 },
+                src_value: 11u32.into(),
+                dst_value: 12u32.into(),
+            },
+            ConstraintFailure::CopyConstraint {
+                typ: GateType::Generic,
+                src: Wire { row: 2, col: 0 },
+                dst: Wire { row: 1, col: 0 },
+                src_value: 12u32.into(),
+                dst_value: 11u32.into(),
+            },
+            ConstraintFailure::Gate {
+                row: 2,
+                typ: GateType::Generic,
+                constraint: 1,
+                evaluation: Fp::one(),
+                curr: witness.iter().map(|w| w[2]).collect(),
+                next: witness.iter().map(|w| w[3]).collect(),
+            },
+        ]
+    );
+    assert_eq!(
+        report.failures[0].to_string(),
+        "row 1: Generic copy constraint 1,0 -> 2,0 is not satisfied (11 != 12)"
+    );
+}
+
+#[test]
+fn witness_report_lookups() {
+    let num_lookups = 20;
+    let lookup_tables = vec![LookupTable {
+        id: 0,
+        data: vec![
+            (0u64..4).map(Into::into).collect(),
+            (0u64..4).map(|i| (10 * i).into()).collect(),
+        ],
+    }];
+
+    let gates = (0..num_lookups)
+        .map(|i| CircuitGate::new(GateType::Lookup, Wire::for_row(i), vec![]))
+        .collect();
+
+    // Look up (1, 10), (2, 20) and (3, 30) in table 0 on every row
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_lookups]);
+    for i in 1..=3u64 {
+        witness[2 * i as usize - 1] = vec![i.into(); num_lookups];
+        witness[2 * i as usize] = vec![(10 * i).into(); num_lookups];
+    }
+    witness[4][5] = 21u64.into();
+    witness[5][9] = 4u64.into();
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .lookup_tables(lookup_tables)
+        .setup();
+    let report = runner.prover_index().witness_report(&witness, &[], &[]);
+
+    assert_eq!(
+        report.failures,
+        vec![
+            ConstraintFailure::Lookup {
+                row: 5,
+                typ: Some(GateType::Lookup),
+                table_id: None,
+                entry: vec![2u64.into(), 21u64.into()],
+            },
+            ConstraintFailure::Lookup {
+                row: 9,
+                typ: Some(GateType::Lookup),
+                table_id: None,
+                entry: vec![4u64.into(), 30u64.into()],
+            },
+        ]
+    );
+    assert_eq!(
+        report.failures[0].to_string(),
+        "row 5: lookup of [2, 21] by the Lookup gate is not in the lookup tables"
+    );
+}
+
+#[test]
+fn witness_report_runtime_tables() {
+    let cfg = RuntimeTableCfg {
+        id: 1,
+        first_column: [8u32, 9, 7].into_iter().map(Into::into).collect(),
+    };
+    let data: Vec<Fp> = [0u32, 2, 4].into_iter().map(Into::into).collect();
+    let runtime_table = RuntimeTable { id: cfg.id(), data };
+
+    let num_lookups = 20;
+    let gates = (0..num_lookups)
+        .map(|i| CircuitGate::new(GateType::Lookup, Wire::for_row(i), vec![]))
+        .collect();
+
+    // Look up (8, 0), (9, 2) and (7, 4) in the runtime table on every row
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_lookups]);
+    witness[0] = vec![1u32.into(); num_lookups];
+    for (i, (key, value)) in [(8u32, 0u32), (9, 2), (7, 4)].into_iter().enumerate() {
+        witness[2 * i + 1] = vec![key.into(); num_lookups];
+        witness[2 * i + 2] = vec![value.into(); num_lookups];
+    }
+    witness[4][3] = 3u32.into();
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .runtime_tables_setup(vec![cfg])
+        .setup();
+    let report = runner
+        .prover_index()
+        .witness_report(&witness, &[], &[runtime_table]);
+
+    assert_eq!(
+        report.failures,
+        vec![ConstraintFailure::Lookup {
+            row: 3,
+            typ: Some(GateType::Lookup),
+            table_id: None,
+            entry: vec![9u32.into(), 3u32.into()],
+        }]
+    );
+}
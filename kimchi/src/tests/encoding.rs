@@ -0,0 +1,223 @@
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    curve::KimchiCurve,
+    encoding::{Header, Kind, FORMAT_VERSION, MAGIC},
+    error::EncodingError,
+    proof::ProverProof,
+    prover_index::{testing::new_index_for_test, ProverIndex},
+    verifier::verify,
+    verifier_index::VerifierIndex,
+};
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Pallas, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
+use std::{array, fs, path::Path};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+type Proof = ProverProof<Vesta, OpeningProof<Vesta>>;
+type Index = VerifierIndex<Vesta, OpeningProof<Vesta>>;
+
+/// The proofs and verifier indexes of the circuit of [`circuit`]. The `v0`
+/// ones are the MessagePack encodings without header, written with the code
+/// preceding the versioned format; the other ones are written by
+/// [`generate_encoding_fixtures`]
+const FIXTURES: &str = "resources/tests/encoding";
+const PROOF_V0: &[u8] = include_bytes!("../../resources/tests/encoding/proof_v0.msgpack");
+const PROOF_V1: &[u8] = include_bytes!("../../resources/tests/encoding/proof_v1.bin");
+const PROOF_V1_JSON: &str = include_str!("../../resources/tests/encoding/proof_v1.json");
+const INDEX_V0: &[u8] = include_bytes!("../../resources/tests/encoding/verifier_index_v0.msgpack");
+const INDEX_V1: &[u8] = include_bytes!("../../resources/tests/encoding/verifier_index_v1.bin");
+const INDEX_V1_JSON: &str = include_str!("../../resources/tests/encoding/verifier_index_v1.json");
+
+/// The circuit of the fixtures, with its public input
+fn circuit() -> (ProverIndex<Vesta, OpeningProof<Vesta>>, Vec<Fp>) {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+    (new_index_for_test(gates, public.len()), public)
+}
+
+fn prove(index: &ProverIndex<Vesta, OpeningProof<Vesta>>, public: &[Fp]) -> Proof {
+    let rows = create_circuit::<Fp>(0, public.len()).len();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); rows]);
+    fill_in_witness(0, &mut witness, public);
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap()
+}
+
+fn check_proof(index: &Index, proof: &Proof, public: &[Fp]) {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    verify::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map, index, proof, public,
+    )
+    .unwrap();
+}
+
+/// Decode the verifier index `bytes` for the circuit of `index`
+fn decode_index(
+    index: &ProverIndex<Vesta, OpeningProof<Vesta>>,
+    bytes: &[u8],
+) -> Result<Index, EncodingError> {
+    Index::from_bytes(bytes, index.srs.clone(), *Vesta::other_curve_endo())
+}
+
+#[test]
+fn test_proof_encoding() {
+    let (index, public) = circuit();
+    let proof = prove(&index, &public);
+
+    let bytes = proof.to_bytes().unwrap();
+    assert!(bytes.starts_with(&MAGIC));
+    let header = Header::read(&bytes[..]).unwrap().unwrap();
+    assert_eq!(
+        header,
+        Header {
+            kind: Kind::Proof,
+            version: FORMAT_VERSION,
+            curve: "vesta".to_string(),
+            features: 0,
+        }
+    );
+    assert_eq!(Proof::from_bytes(&bytes).unwrap(), proof);
+
+    let json = proof.to_json().unwrap();
+    assert!(json.starts_with(r#"{"kind":"proof","version":1,"curve":"vesta","features":[]"#));
+    assert_eq!(Proof::from_json(&json).unwrap(), proof);
+
+    // the encoding without header of the previous releases
+    let legacy = rmp_serde::to_vec(&proof).unwrap();
+    assert_eq!(Proof::from_bytes(&legacy).unwrap(), proof);
+}
+
+#[test]
+fn test_verifier_index_encoding() {
+    let (index, public) = circuit();
+    let proof = prove(&index, &public);
+    let verifier_index = index.verifier_index();
+    let digest = verifier_index.digest::<BaseSponge>();
+
+    let bytes = verifier_index.to_bytes().unwrap();
+    let decoded = decode_index(&index, &bytes).unwrap();
+    assert_eq!(decoded.digest::<BaseSponge>(), digest);
+    check_proof(&decoded, &proof, &public);
+
+    let json = verifier_index.to_json().unwrap();
+    let decoded = Index::from_json(&json, index.srs.clone(), *Vesta::other_curve_endo()).unwrap();
+    assert_eq!(decoded.digest::<BaseSponge>(), digest);
+    check_proof(&decoded, &proof, &public);
+
+    let legacy = rmp_serde::to_vec(&verifier_index).unwrap();
+    let decoded = decode_index(&index, &legacy).unwrap();
+    assert_eq!(decoded.digest::<BaseSponge>(), digest);
+    check_proof(&decoded, &proof, &public);
+}
+
+#[test]
+fn test_encoding_errors() {
+    let (index, public) = circuit();
+    let proof = prove(&index, &public);
+    let bytes = proof.to_bytes().unwrap();
+
+    assert!(matches!(
+        decode_index(&index, &bytes),
+        Err(EncodingError::WrongKind { .. })
+    ));
+    assert!(matches!(
+        ProverProof::<Pallas, OpeningProof<Pallas>>::from_bytes(&bytes),
+        Err(EncodingError::WrongCurve { .. })
+    ));
+
+    // the version follows the magic bytes
+    let mut future = bytes.clone();
+    future[MAGIC.len()] = 2;
+    assert!(matches!(
+        Proof::from_bytes(&future),
+        Err(EncodingError::UnsupportedVersion(2))
+    ));
+
+    // the features end the header of a proof over vesta
+    let mut features = bytes;
+    features[MAGIC.len() + 4 + "vesta".len()] = 1;
+    assert!(matches!(
+        Proof::from_bytes(&features),
+        Err(EncodingError::FeatureMismatch {
+            header: 1,
+            content: 0
+        })
+    ));
+}
+
+/// Check that the proofs and verifier indexes written by the previous
+/// versions of the format can still be decoded, and verified
+#[test]
+fn test_encoding_fixtures() {
+    let (index, public) = circuit();
+    let digest = index.verifier_index().digest::<BaseSponge>();
+
+    let indexes = [
+        decode_index(&index, INDEX_V0).unwrap(),
+        decode_index(&index, INDEX_V1).unwrap(),
+        Index::from_json(INDEX_V1_JSON, index.srs.clone(), *Vesta::other_curve_endo()).unwrap(),
+    ];
+    let proofs = [
+        Proof::from_bytes(PROOF_V0).unwrap(),
+        Proof::from_bytes(PROOF_V1).unwrap(),
+        Proof::from_json(PROOF_V1_JSON).unwrap(),
+    ];
+    for verifier_index in &indexes {
+        assert_eq!(verifier_index.digest::<BaseSponge>(), digest);
+        for proof in &proofs {
+            check_proof(verifier_index, proof, &public);
+        }
+    }
+}
+
+/// Write the fixtures of the latest version of the format. Run it once for
+/// each new version of the format, and keep the fixtures of the previous
+/// versions.
+#[test]
+#[ignore]
+fn generate_encoding_fixtures() {
+    let (index, public) = circuit();
+    let proof = prove(&index, &public);
+    let verifier_index = index.verifier_index();
+
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join(FIXTURES);
+    fs::create_dir_all(&dir).unwrap();
+    let write = |name: String, bytes: &[u8]| fs::write(dir.join(name), bytes).unwrap();
+
+    write(
+        format!("proof_v{FORMAT_VERSION}.bin"),
+        &proof.to_bytes().unwrap(),
+    );
+    write(
+        format!("proof_v{FORMAT_VERSION}.json"),
+        proof.to_json().unwrap().as_bytes(),
+    );
+    write(
+        format!("verifier_index_v{FORMAT_VERSION}.bin"),
+        &verifier_index.to_bytes().unwrap(),
+    );
+    write(
+        format!("verifier_index_v{FORMAT_VERSION}.json"),
+        verifier_index.to_json().unwrap().as_bytes(),
+    );
+}
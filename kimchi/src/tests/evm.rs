@@ -0,0 +1,199 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        gate::CircuitGate,
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    error::{EvmError, VerifyError},
+    evm::{
+        calldata::{Calldata, WORD_SIZE},
+        check_index, verifier, BaseSponge, Curve, Fr, Index, OpeningProof, Proof, ScalarSponge,
+    },
+    plonk_sponge::FrSponge,
+    proof::RecursionChallenge,
+    prover_index::ProverIndex,
+    verifier::verify,
+};
+use ark_bn254::Fq;
+use ark_ec::AffineRepr;
+use ark_ff::Zero;
+use groupmap::GroupMap;
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+    FqSponge,
+};
+use poly_commitment::{commitment::CommitmentCurve, kzg::PairingSRS, PolyComm, SRS as _};
+use std::array;
+
+type TestRunner = super::framework::TestRunner<Curve, OpeningProof>;
+
+/// The circuit of the tests, with its public input
+fn circuit() -> (TestRunner, Vec<Fr>) {
+    let public = vec![Fr::from(3u8); 5];
+    let runner = setup(
+        TestFramework::default()
+            .gates(create_circuit(0, public.len()))
+            .public_inputs(public.clone()),
+    );
+    (runner, public)
+}
+
+fn setup(framework: TestFramework<Curve, OpeningProof>) -> TestRunner {
+    framework.setup_with_custom_srs(|d1, srs_size| {
+        let srs = PairingSRS::create(srs_size);
+        srs.full_srs.get_lagrange_basis(d1);
+        srs
+    })
+}
+
+fn prove<EFqSponge, EFrSponge>(index: &ProverIndex<Curve, OpeningProof>, public: &[Fr]) -> Proof
+where
+    EFqSponge: Clone + FqSponge<Fq, Curve, Fr>,
+    EFrSponge: FrSponge<Fr>,
+{
+    let rows = create_circuit::<Fr>(0, public.len()).len();
+    let mut witness: [Vec<Fr>; COLUMNS] = array::from_fn(|_| vec![Fr::zero(); rows]);
+    fill_in_witness(0, &mut witness, public);
+
+    let group_map = <Curve as CommitmentCurve>::Map::setup();
+    Proof::create::<EFqSponge, EFrSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap()
+}
+
+fn check_proof(index: &Index, proof: &Proof, public: &[Fr]) {
+    let group_map = <Curve as CommitmentCurve>::Map::setup();
+    verify::<Curve, BaseSponge, ScalarSponge, OpeningProof>(&group_map, index, proof, public)
+        .unwrap();
+}
+
+#[test]
+fn test_evm_proof() {
+    let (runner, public) = circuit();
+    let index = runner.prover_index();
+    let verifier_index = index.verifier_index();
+    check_index(&verifier_index).unwrap();
+
+    let proof = prove::<BaseSponge, ScalarSponge>(index, &public);
+    check_proof(&verifier_index, &proof, &public);
+
+    let calldata = Calldata::new(&proof, &public).unwrap();
+    let bytes = calldata.to_bytes();
+    assert_eq!(bytes.len(), Calldata::size(public.len()));
+    verifier::verify(&verifier_index, &bytes).unwrap();
+
+    // the proof of the calldata is checked by the verifier of kimchi as well
+    let decoded = Calldata::from_bytes(&bytes, public.len()).unwrap();
+    assert_eq!(decoded, calldata);
+    let (decoded, decoded_public) = decoded.into_proof();
+    assert_eq!(decoded_public, public);
+    check_proof(&verifier_index, &decoded, &public);
+}
+
+#[test]
+fn test_evm_invalid_calldata() {
+    let (runner, public) = circuit();
+    let index = runner.prover_index();
+    let verifier_index = index.verifier_index();
+    let proof = prove::<BaseSponge, ScalarSponge>(index, &public);
+    let bytes = Calldata::new(&proof, &public).unwrap().to_bytes();
+    let check = |bytes: &[u8]| verifier::verify(&verifier_index, bytes);
+
+    // another public input
+    let mut other_public = bytes.clone();
+    other_public[WORD_SIZE - 1] ^= 1;
+    assert!(matches!(
+        check(&other_public),
+        Err(EvmError::Verify(VerifyError::OpenProof))
+    ));
+
+    // another ft(ζω), which follows the commitments
+    let ft_eval1 = WORD_SIZE * (public.len() + 2 * (COLUMNS + 1 + 7));
+    let mut other_eval = bytes.clone();
+    other_eval[ft_eval1 + WORD_SIZE - 1] ^= 1;
+    assert!(matches!(
+        check(&other_eval),
+        Err(EvmError::Verify(VerifyError::OpenProof))
+    ));
+
+    assert!(matches!(
+        check(&bytes[1..]),
+        Err(EvmError::IncorrectCalldataLength(expected, found)) if expected == found + 1
+    ));
+
+    let mut not_field = bytes.clone();
+    not_field[..WORD_SIZE].fill(0xff);
+    assert!(matches!(
+        check(&not_field),
+        Err(EvmError::InvalidFieldElement(0))
+    ));
+
+    // the coordinate y of the first commitment
+    let point = WORD_SIZE * public.len();
+    let mut not_point = bytes;
+    not_point[point + 2 * WORD_SIZE - 1] ^= 1;
+    assert!(matches!(
+        check(&not_point),
+        Err(EvmError::InvalidPoint(offset)) if offset == point
+    ));
+
+    // a proof with the Fiat-Shamir transform of Poseidon
+    type SpongeParams = PlonkSpongeConstantsKimchi;
+    let poseidon = prove::<
+        DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>,
+        DefaultFrSponge<Fr, SpongeParams>,
+    >(index, &public);
+    let bytes = Calldata::new(&poseidon, &public).unwrap().to_bytes();
+    assert!(matches!(
+        check(&bytes),
+        Err(EvmError::Verify(VerifyError::OpenProof))
+    ));
+}
+
+#[test]
+fn test_evm_unsupported() {
+    let (runner, public) = circuit();
+    let proof = prove::<BaseSponge, ScalarSponge>(runner.prover_index(), &public);
+
+    let mut chunked = proof.clone();
+    chunked.commitments.z_comm = PolyComm::new(vec![Curve::generator(); 2]);
+    assert!(matches!(
+        Calldata::new(&chunked, &public),
+        Err(EvmError::Chunking)
+    ));
+
+    let mut recursive = proof;
+    recursive.prev_challenges = vec![RecursionChallenge::new(
+        vec![],
+        PolyComm::new(vec![Curve::generator()]),
+    )];
+    assert!(matches!(
+        Calldata::new(&recursive, &public),
+        Err(EvmError::Recursion)
+    ));
+
+    let mut gates = vec![];
+    CircuitGate::<Fr>::extend_xor_gadget(&mut gates, 64);
+    let runner = setup(TestFramework::default().gates(gates));
+    assert!(matches!(
+        check_index(&runner.prover_index().verifier_index()),
+        Err(EvmError::UnsupportedFeature("xor"))
+    ));
+
+    let runner = setup(
+        TestFramework::default()
+            .gates(create_circuit(0, 0))
+            .num_prev_challenges(1),
+    );
+    assert!(matches!(
+        check_index(&runner.prover_index().verifier_index()),
+        Err(EvmError::Recursion)
+    ));
+}
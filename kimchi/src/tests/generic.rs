@@ -1,15 +1,27 @@
 use super::framework::TestFramework;
-use crate::circuits::{
-    polynomials::generic::testing::{create_circuit, fill_in_witness},
-    wires::COLUMNS,
+use crate::{
+    circuits::{
+        gate::{CircuitGate, GateType},
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::{Wire, COLUMNS},
+    },
+    error::{ErrorContext, ProverError, VerifyError},
+    proof::ProverProof,
+    prover_index::testing::new_index_for_test,
+    verifier::{
+        batch_verify_each, batch_verify_prepared, batch_verify_stream, verify_prepared, Context,
+        PreparedContext,
+    },
+    verifier_index::PreparedVerifierIndex,
 };
-use ark_ff::Zero;
+use ark_ff::{One, Zero};
+use groupmap::GroupMap;
 use mina_curves::pasta::{Fp, Vesta, VestaParameters};
 use mina_poseidon::{
     constants::PlonkSpongeConstantsKimchi,
     sponge::{DefaultFqSponge, DefaultFrSponge},
 };
-use poly_commitment::SRS;
+use poly_commitment::{commitment::CommitmentCurve, ipa::OpeningProof};
 use std::array;
 
 type SpongeParams = PlonkSpongeConstantsKimchi;
@@ -90,9 +102,148 @@ fn test_generic_gate_pub_empty() {
         .unwrap();
 }
 
+#[test]
+fn test_generic_gate_prepared_verifier_index() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    // create witness
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let prepared_index = PreparedVerifierIndex::<_, _, BaseSponge>::new(&verifier_index);
+    assert_eq!(
+        prepared_index.digest(),
+        verifier_index.digest::<BaseSponge>()
+    );
+
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proofs: Vec<_> = (0..2)
+        .map(|_| {
+            ProverProof::create::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                witness.clone(),
+                &[],
+                &index,
+                &mut rand::rngs::OsRng,
+            )
+            .unwrap()
+        })
+        .collect();
+
+    // verify the proofs one at a time, and in a batch
+    for proof in &proofs {
+        verify_prepared::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &prepared_index,
+            proof,
+            &public,
+        )
+        .unwrap();
+    }
+    let batch: Vec<_> = proofs
+        .iter()
+        .map(|proof| PreparedContext {
+            prepared_index: &prepared_index,
+            proof,
+            public_input: &public,
+        })
+        .collect();
+    batch_verify_prepared::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+        &group_map, &batch,
+    )
+    .unwrap();
+
+    // the proofs are not valid for another public input
+    let other_public = vec![Fp::from(4u8); 5];
+    assert!(
+        verify_prepared::<Vesta, BaseSponge, ScalarSponge, OpeningProof<Vesta>>(
+            &group_map,
+            &prepared_index,
+            &proofs[0],
+            &other_public,
+        )
+        .is_err()
+    );
+}
+
+#[test]
+fn test_generic_gate_batch_verify_each() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    // create witness
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    let index = new_index_for_test::<Vesta>(gates, public.len());
+    let verifier_index = index.verifier_index();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let mut proofs: Vec<_> = (0..5)
+        .map(|_| {
+            let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+                &group_map,
+                witness.clone(),
+                &[],
+                &index,
+                &mut rand::rngs::OsRng,
+            )
+            .unwrap();
+            (proof, public.clone())
+        })
+        .collect();
+
+    // the second proof is checked against another public input, which only
+    // shows in the check of the opening proofs, and the fourth one is malformed
+    proofs[1].1 = vec![Fp::from(4u8); 5];
+    proofs[3].0.evals.z.zeta.clear();
+
+    let check = |results: &[Result<(), VerifyError>]| {
+        assert_eq!(results.len(), proofs.len());
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(VerifyError::OpenProof)));
+        assert!(results[2].is_ok());
+        assert!(matches!(
+            results[3],
+            Err(VerifyError::IncorrectEvaluationsLength(1, 0, _))
+        ));
+        assert!(results[4].is_ok());
+    };
+
+    let batch: Vec<_> = proofs
+        .iter()
+        .map(|(proof, public_input)| Context {
+            verifier_index: &verifier_index,
+            proof,
+            public_input,
+        })
+        .collect();
+    check(&batch_verify_each::<
+        Vesta,
+        BaseSponge,
+        ScalarSponge,
+        OpeningProof<Vesta>,
+    >(&group_map, &batch));
+
+    // verify the proofs as a stream, by batches of 2 proofs
+    let prepared_index = PreparedVerifierIndex::<_, _, BaseSponge>::new(&verifier_index);
+    let results: Vec<_> = batch_verify_stream::<_, _, ScalarSponge, _, _>(
+        &group_map,
+        &prepared_index,
+        proofs.clone(),
+        2,
+    )
+    .collect();
+    check(&results);
+}
+
 #[cfg(feature = "bn254")]
 #[test]
 fn test_generic_gate_kzg() {
+    use poly_commitment::SRS;
+
     type Fp = ark_bn254::Fr;
     type SpongeParams = PlonkSpongeConstantsKimchi;
     type BaseSponge = DefaultFqSponge<ark_bn254::g1::Config, SpongeParams>;
@@ -121,3 +272,61 @@ fn test_generic_gate_kzg() {
     .prove_and_verify::<BaseSponge, ScalarSponge>()
     .unwrap();
 }
+
+/// Try to create a proof with an invalid `witness`, bypassing the gates checks of the test
+/// framework to exercise the ones of the prover
+fn prove_invalid_witness(
+    gates: Vec<CircuitGate<Fp>>,
+    witness: [Vec<Fp>; COLUMNS],
+) -> Result<(), ProverError> {
+    let runner = TestFramework::<Vesta>::default().gates(gates).setup();
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    ProverProof::create_recursive::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        &[],
+        runner.prover_index(),
+        vec![],
+        None,
+        &mut rand::rngs::OsRng,
+    )
+    .map(|_| ())
+}
+
+#[test]
+fn test_generic_gate_invalid_witness() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let gates = create_circuit(0, 0);
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+    witness[2][3] += Fp::one();
+
+    match prove_invalid_witness(gates, witness) {
+        Err(ProverError::InvalidWitness { context, .. }) => {
+            assert_eq!(context, ErrorContext::row(3).gate_type(GateType::Generic))
+        }
+        res => panic!("unexpected result: {res:?}"),
+    }
+}
+
+#[test]
+fn test_generic_gate_disconnected_wires() {
+    if !cfg!(debug_assertions) {
+        return;
+    }
+
+    let mut gates = create_circuit(0, 0);
+    gates[3].wires[2] = Wire { row: 4, col: 5 };
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &[]);
+
+    let err = prove_invalid_witness(gates, witness).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "the witness does not satisfy the circuit (gate=Generic, row=3, column=2, value=80): \
+         copy constraint to row=4, column=5 is not satisfied (value=506)"
+    );
+}
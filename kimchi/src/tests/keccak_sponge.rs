@@ -0,0 +1,70 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        polynomials::generic::testing::{create_circuit, fill_in_witness},
+        wires::COLUMNS,
+    },
+    curve::KimchiCurve,
+    keccak_sponge::{KeccakFqSponge, KeccakFrSponge},
+    plonk_sponge::FrSponge,
+};
+use ark_ec::AffineRepr;
+use ark_ff::{BigInteger, PrimeField, Zero};
+use mina_curves::pasta::{Fp, Fq, Vesta, VestaParameters};
+use mina_poseidon::FqSponge;
+use std::array;
+
+type BaseSponge = KeccakFqSponge<VestaParameters>;
+type ScalarSponge = KeccakFrSponge<Fp>;
+
+#[test]
+fn test_keccak_sponge() {
+    // a squeeze of a new sponge is keccak256(abi.encode(uint256(0)))
+    let hash =
+        hex::decode("290decd9548b62a8d60345a988386fc84ba6bc95484008f6362f93160ef3e563").unwrap();
+    assert_eq!(
+        BaseSponge::new(Vesta::other_curve_sponge_params()).digest_fq(),
+        Fq::from_be_bytes_mod_order(&hash)
+    );
+    assert_eq!(
+        ScalarSponge::new(Vesta::sponge_params()).digest(),
+        Fp::from_be_bytes_mod_order(&hash)
+    );
+
+    // a point is absorbed as its coordinates, the point at infinity as (0, 0)
+    let g = Vesta::generator();
+    let mut points = BaseSponge::new(Vesta::other_curve_sponge_params());
+    points.absorb_g(&[g, Vesta::zero()]);
+    let mut coordinates = BaseSponge::new(Vesta::other_curve_sponge_params());
+    coordinates.absorb_fq(&[g.x, g.y, Fq::zero(), Fq::zero()]);
+    let challenge = points.challenge();
+    assert_eq!(challenge, coordinates.challenge());
+    assert!(challenge.into_bigint().num_bits() <= 128);
+
+    // the two sponges absorb a scalar as the same word
+    let mut fq_sponge = BaseSponge::new(Vesta::other_curve_sponge_params());
+    fq_sponge.absorb_fr(&[Fp::from(42u8)]);
+    let mut fr_sponge = ScalarSponge::new(Vesta::sponge_params());
+    fr_sponge.absorb(&Fp::from(42u8));
+    assert_eq!(fq_sponge.challenge(), fr_sponge.challenge().0);
+}
+
+#[test]
+fn test_keccak_sponge_proof() {
+    let public = vec![Fp::from(3u8); 5];
+    let gates = create_circuit(0, public.len());
+
+    // create witness
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    fill_in_witness(0, &mut witness, &public);
+
+    // create and verify proof based on the witness, with the Fiat-Shamir
+    // transform of Keccak-256
+    TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .public_inputs(public)
+        .setup()
+        .prove_and_verify::<BaseSponge, ScalarSponge>()
+        .unwrap();
+}
@@ -331,7 +331,10 @@ fn test_negative_test_runtime_table_value_not_in_table() {
 
     // The whole witness is wrong, but we check the first row is incorrect as it
     // is the error returned by the prover call.
-    assert_eq!(err, "the lookup failed to find a match in the table: row=0");
+    assert_eq!(
+        err,
+        "the lookup failed to find a match in the table: gate=Lookup, row=0, value=(42, 42)"
+    );
 }
 
 #[test]
@@ -729,3 +732,39 @@ fn test_dummy_zero_entry_is_counted_while_computing_domain_size() {
     // As the dummy entry has been added, we reached the next power of two
     assert!(domain_size == (1 << (power_of_2 + 1)));
 }
+
+#[test]
+fn lookup_gate_reports_failing_lookup() {
+    let num_lookups = 20;
+    let lookup_tables = vec![LookupTable {
+        id: 0,
+        data: vec![
+            (0u64..4).map(Into::into).collect(),
+            (0u64..4).map(|i| (10 * i).into()).collect(),
+        ],
+    }];
+
+    let gates = (0..num_lookups)
+        .map(|i| CircuitGate::new(GateType::Lookup, Wire::for_row(i), vec![]))
+        .collect();
+
+    // Look up (1, 10), (2, 20) and (3, 30) in table 0 on every row
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_lookups]);
+    for i in 1..=3u64 {
+        witness[2 * i as usize - 1] = vec![i.into(); num_lookups];
+        witness[2 * i as usize] = vec![(10 * i).into(); num_lookups];
+    }
+    witness[4][5] = 21u64.into();
+
+    let err = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .lookup_tables(lookup_tables)
+        .setup()
+        .prove::<BaseSponge, ScalarSponge>()
+        .unwrap_err();
+    assert_eq!(
+        err,
+        "the lookup failed to find a match in the table: gate=Lookup, row=5, value=(2, 21)"
+    );
+}
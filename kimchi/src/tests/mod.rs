@@ -1,14 +1,19 @@
 // IMPROVEME: move all tests in top-level directory tests
 mod and;
 mod chunked;
+mod debugger;
 mod ec;
+mod encoding;
 mod endomul;
 mod endomul_scalar;
+#[cfg(feature = "bn254")]
+mod evm;
 mod foreign_field_add;
 mod foreign_field_mul;
 mod framework;
 mod generic;
 mod keccak;
+mod keccak_sponge;
 mod lookup;
 mod not;
 mod poseidon;
@@ -16,5 +21,6 @@ mod range_check;
 mod recursion;
 mod rot;
 mod serde;
+mod stats;
 mod varbasemul;
 mod xor;
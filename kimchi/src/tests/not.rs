@@ -437,14 +437,14 @@ fn test_bad_not_xor() {
     witness[9][1] = PallasField::zero();
     witness[10][1] = PallasField::zero();
 
-    assert_eq!(
-        TestFramework::<Vesta>::default()
-            .gates(cs.gates)
-            .witness(witness)
-            .setup()
-            .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>(),
-        Err(String::from(
-            "the lookup failed to find a match in the table: row=1"
-        ))
-    );
+    // The inputs are random, so only the location of the failed lookup is known
+    let err = TestFramework::<Vesta>::default()
+        .gates(cs.gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap_err();
+    assert!(err.starts_with(
+        "the lookup failed to find a match in the table: gate=Xor16, row=1, table_id=0, value="
+    ));
 }
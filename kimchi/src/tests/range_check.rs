@@ -583,7 +583,7 @@ fn verify_range_check0_v0_test_lookups() {
                 .witness(witness)
                 .prove_and_verify::<BaseSponge, ScalarSponge>(),
             Err(String::from(
-                "the lookup failed to find a match in the table: row=0"
+                "the lookup failed to find a match in the table: gate=RangeCheck0, row=0, table_id=1, value=(8191)"
             ))
         );
     }
@@ -636,7 +636,7 @@ fn verify_range_check0_v1_test_lookups() {
                 .witness(witness)
                 .prove_and_verify::<BaseSponge, ScalarSponge>(),
             Err(String::from(
-                "the lookup failed to find a match in the table: row=1"
+                "the lookup failed to find a match in the table: gate=RangeCheck0, row=1, table_id=1, value=(8191)"
             ))
         );
     }
@@ -986,7 +986,7 @@ fn verify_range_check1_test_curr_row_lookups() {
                 .witness(witness.clone())
                 .prove_and_verify::<BaseSponge, ScalarSponge>(),
             Err(String::from(
-                "the lookup failed to find a match in the table: row=2"
+                "the lookup failed to find a match in the table: gate=RangeCheck1, row=2, table_id=1, value=(8191)"
             ))
         );
     }
@@ -1040,7 +1040,7 @@ fn verify_range_check1_test_next_row_lookups() {
                     .witness(witness.clone())
                     .prove_and_verify::<BaseSponge, ScalarSponge>(),
                 Err(String::from(
-                    "the lookup failed to find a match in the table: row=3"
+                    "the lookup failed to find a match in the table: gate=RangeCheck1, row=3, table_id=1, value=(8191)"
                 ))
             );
         }
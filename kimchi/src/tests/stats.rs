@@ -0,0 +1,227 @@
+use super::framework::TestFramework;
+use crate::{
+    circuits::{
+        gate::{CircuitGate, GateType},
+        lookup::{
+            runtime_tables::{RuntimeTable, RuntimeTableCfg},
+            tables::XOR_TABLE_ID,
+        },
+        polynomial::COLUMNS,
+        polynomials::{generic::GenericGateSpec, xor},
+        stats::{CircuitStats, LookupTableStats},
+        wires::Wire,
+    },
+    proof::ProverProof,
+    prover_index::ProverIndex,
+};
+use ark_ec::AffineRepr;
+use ark_ff::{One, UniformRand, Zero};
+use ark_serialize::CanonicalSerialize;
+use groupmap::GroupMap;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{
+    commitment::CommitmentCurve,
+    ipa::{OpeningProof, SRS},
+    PolyComm, SRS as _,
+};
+use std::{array, cell::Cell, collections::BTreeMap};
+
+type SpongeParams = PlonkSpongeConstantsKimchi;
+type BaseSponge = DefaultFqSponge<VestaParameters, SpongeParams>;
+type ScalarSponge = DefaultFrSponge<Fp, SpongeParams>;
+
+/// The numbers of group and field elements of `proof`
+fn proof_elements(proof: &ProverProof<Vesta, OpeningProof<Vesta>>) -> (usize, usize) {
+    let comms = &proof.commitments;
+    let mut points = comms.w_comm.iter().map(PolyComm::len).sum::<usize>()
+        + comms.z_comm.len()
+        + comms.t_comm.len();
+    if let Some(lookup) = &comms.lookup {
+        points += lookup.sorted.iter().map(PolyComm::len).sum::<usize>()
+            + lookup.aggreg.len()
+            + lookup.runtime.as_ref().map_or(0, PolyComm::len);
+    }
+    points += proof
+        .prev_challenges
+        .iter()
+        .map(|chal| chal.comm.len())
+        .sum::<usize>();
+    points += 2 * proof.proof.lr.len() + 2;
+
+    // ft_eval1, z1 and z2
+    let scalars = Cell::new(3);
+    proof.evals.map_ref(&|evals| {
+        scalars.set(scalars.get() + evals.zeta.len() + evals.zeta_omega.len());
+    });
+    let scalars = scalars.get()
+        + proof
+            .prev_challenges
+            .iter()
+            .map(|chal| chal.chals.len())
+            .sum::<usize>();
+
+    (points, scalars)
+}
+
+/// Create a proof and check that its size is the one of the statistics of the circuit
+fn check_proof_size(
+    index: &ProverIndex<Vesta, OpeningProof<Vesta>>,
+    witness: [Vec<Fp>; COLUMNS],
+    runtime_tables: &[RuntimeTable<Fp>],
+) -> CircuitStats {
+    let group_map = <Vesta as CommitmentCurve>::Map::setup();
+    let proof = ProverProof::create::<BaseSponge, ScalarSponge, _>(
+        &group_map,
+        witness,
+        runtime_tables,
+        index,
+        &mut rand::rngs::OsRng,
+    )
+    .unwrap();
+
+    let stats = index.stats();
+    assert_eq!(
+        (stats.proof_points, stats.proof_scalars),
+        proof_elements(&proof)
+    );
+    assert_eq!(
+        stats.proof_size,
+        stats.proof_points * Vesta::zero().compressed_size()
+            + stats.proof_scalars * Fp::zero().compressed_size()
+    );
+    stats
+}
+
+#[test]
+fn test_generic_circuit_stats() {
+    let public = 3;
+    let num_gates = 200;
+    let rng = &mut rand::rngs::OsRng;
+
+    let mut gates: Vec<_> = (0..public)
+        .map(|row| {
+            CircuitGate::create_generic_gadget(Wire::for_row(row), GenericGateSpec::Pub, None)
+        })
+        .collect();
+    gates.extend((public..num_gates).map(|row| {
+        CircuitGate::create_generic_gadget(
+            Wire::for_row(row),
+            GenericGateSpec::Add {
+                left_coeff: None,
+                right_coeff: None,
+                output_coeff: None,
+            },
+            None,
+        )
+    }));
+
+    // the public inputs, followed by additions of random values
+    let public_inputs: Vec<Fp> = (0..public as u64).map(Fp::from).collect();
+    let left: Vec<Fp> = (public..num_gates).map(|_| Fp::rand(rng)).collect();
+    let right: Vec<Fp> = (public..num_gates).map(|_| Fp::rand(rng)).collect();
+    let output: Vec<Fp> = left.iter().zip(&right).map(|(l, r)| *l + r).collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); num_gates]);
+    witness[0] = [public_inputs.clone(), left].concat();
+    witness[1][public..].copy_from_slice(&right);
+    witness[2][public..].copy_from_slice(&output);
+
+    // the circuit does not fit in a single chunk of 128 rows
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .public_inputs(public_inputs)
+        .override_srs_size(128)
+        .setup_with_custom_srs(|d1, srs_size| {
+            let srs = SRS::<Vesta>::create(srs_size);
+            srs.get_lagrange_basis(d1);
+            srs
+        });
+    let stats = check_proof_size(runner.prover_index(), witness, &[]);
+
+    assert_eq!(stats.rows, num_gates);
+    assert_eq!(
+        stats.gates,
+        BTreeMap::from([(GateType::Generic, num_gates)])
+    );
+    assert_eq!(stats.public, public);
+    assert_eq!(stats.domain_size, 256);
+    assert_eq!(stats.max_poly_size, 128);
+    assert_eq!(stats.num_chunks, 2);
+    assert_eq!(
+        stats.slack,
+        stats.domain_size - stats.rows - stats.zk_rows as usize
+    );
+    assert!(stats.lookup_tables.is_empty());
+    assert!(stats.runtime_tables.is_empty());
+    assert!(stats.to_string().contains("features: none"));
+}
+
+#[test]
+fn test_xor_circuit_stats() {
+    let mut gates = vec![];
+    CircuitGate::<Fp>::extend_xor_gadget(&mut gates, 64);
+    let witness = xor::create_xor_witness(Fp::from(0x1234u64), Fp::from(u64::MAX), 64);
+
+    let runner = TestFramework::<Vesta>::default().gates(gates).setup();
+    let stats = check_proof_size(runner.prover_index(), witness, &[]);
+
+    // four Xor16 rows and the generic row checking that the final row is zero
+    assert_eq!(stats.rows, 5);
+    assert_eq!(
+        stats.gates,
+        BTreeMap::from([(GateType::Generic, 1), (GateType::Xor16, 4)])
+    );
+    assert!(stats.feature_flags.xor);
+    assert!(stats.feature_flags.lookup_features.patterns.xor);
+    assert_eq!(
+        stats.lookup_tables,
+        vec![LookupTableStats {
+            id: XOR_TABLE_ID,
+            len: 256
+        }]
+    );
+    assert!(stats.to_string().contains("features: xor, xor_lookup"));
+}
+
+#[test]
+fn test_runtime_table_circuit_stats() {
+    let first_column: Vec<Fp> = [8u32, 9, 8, 7, 1].into_iter().map(Into::into).collect();
+    let data: Vec<Fp> = [0u32, 2, 3, 4, 5].into_iter().map(Into::into).collect();
+    let cfg = RuntimeTableCfg {
+        id: 1,
+        first_column: first_column.clone(),
+    };
+    let runtime_table = RuntimeTable {
+        id: 1,
+        data: data.clone(),
+    };
+
+    // lookups of the three first entries of the runtime table
+    let gates: Vec<_> = (0..2)
+        .map(|row| CircuitGate::new(GateType::Lookup, Wire::for_row(row), vec![]))
+        .collect();
+    let mut witness: [Vec<Fp>; COLUMNS] = array::from_fn(|_| vec![Fp::zero(); gates.len()]);
+    witness[0] = vec![Fp::one(); gates.len()];
+    for (i, (key, value)) in first_column.iter().zip(&data).take(3).enumerate() {
+        witness[1 + 2 * i] = vec![*key; gates.len()];
+        witness[2 + 2 * i] = vec![*value; gates.len()];
+    }
+
+    let runner = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .runtime_tables_setup(vec![cfg])
+        .setup();
+    let stats = check_proof_size(runner.prover_index(), witness, &[runtime_table]);
+
+    assert_eq!(stats.gates, BTreeMap::from([(GateType::Lookup, 2)]));
+    assert!(stats.feature_flags.lookup_features.uses_runtime_tables);
+    assert!(stats.lookup_tables.is_empty());
+    assert_eq!(stats.runtime_tables.len(), 1);
+    assert_eq!(
+        (stats.runtime_tables[0].id, stats.runtime_tables[0].len),
+        (1, 5)
+    );
+}
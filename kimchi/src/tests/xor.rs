@@ -334,16 +334,16 @@ fn test_bad_xor() {
         witness[COLUMNS - i][0] = PallasField::zero();
     }
 
-    assert_eq!(
-        TestFramework::<Vesta>::default()
-            .gates(gates)
-            .witness(witness)
-            .setup()
-            .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>(),
-        Err(String::from(
-            "the lookup failed to find a match in the table: row=0"
-        ))
-    );
+    // The inputs are random, so only the location of the failed lookup is known
+    let err = TestFramework::<Vesta>::default()
+        .gates(gates)
+        .witness(witness)
+        .setup()
+        .prove_and_verify::<VestaBaseSponge, VestaScalarSponge>()
+        .unwrap_err();
+    assert!(err.starts_with(
+        "the lookup failed to find a match in the table: gate=Xor16, row=0, table_id=0, value="
+    ));
 }
 
 #[test]
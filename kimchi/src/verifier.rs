@@ -17,7 +17,7 @@ use crate::{
     oracles::OraclesResult,
     plonk_sponge::FrSponge,
     proof::{PointEvaluations, ProofEvaluations, ProverProof, RecursionChallenge},
-    verifier_index::VerifierIndex,
+    verifier_index::{PreparedVerifierIndex, VerifierIndex},
 };
 use ark_ec::AffineRepr;
 use ark_ff::{Field, One, PrimeField, Zero};
@@ -31,6 +31,7 @@ use poly_commitment::{
     OpenProof, SRS as _,
 };
 use rand::thread_rng;
+use rayon::prelude::*;
 
 /// The result of a proof verification.
 pub type Result<T> = std::result::Result<T, VerifyError>;
@@ -95,6 +96,18 @@ impl<'a, G: KimchiCurve, OpeningProof: OpenProof<G>> Context<'a, G, OpeningProof
     }
 }
 
+/// A proof to verify against a [`PreparedVerifierIndex`]
+pub struct PreparedContext<'a, G: KimchiCurve, OpeningProof: OpenProof<G>, EFqSponge> {
+    /// The [PreparedVerifierIndex] associated to the proof
+    pub prepared_index: &'a PreparedVerifierIndex<'a, G, OpeningProof, EFqSponge>,
+
+    /// The proof to verify
+    pub proof: &'a ProverProof<G, OpeningProof>,
+
+    /// The public input used in the creation of the proof
+    pub public_input: &'a [G::ScalarField],
+}
+
 impl<G: KimchiCurve, OpeningProof: OpenProof<G>> ProverProof<G, OpeningProof>
 where
     G::BaseField: PrimeField,
@@ -116,6 +129,26 @@ where
         index: &VerifierIndex<G, OpeningProof>,
         public_comm: &PolyComm<G>,
         public_input: Option<&[G::ScalarField]>,
+    ) -> Result<OraclesResult<G, EFqSponge>> {
+        self.oracles_with_digest::<EFqSponge, EFrSponge>(
+            index,
+            index.digest::<EFqSponge>(),
+            public_comm,
+            public_input,
+        )
+    }
+
+    /// Runs the random oracle argument as [`ProverProof::oracles`], given the
+    /// digest of the verifier index `index_digest`
+    fn oracles_with_digest<
+        EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+        EFrSponge: FrSponge<G::ScalarField>,
+    >(
+        &self,
+        index: &VerifierIndex<G, OpeningProof>,
+        index_digest: G::BaseField,
+        public_comm: &PolyComm<G>,
+        public_input: Option<&[G::ScalarField]>,
     ) -> Result<OraclesResult<G, EFqSponge>> {
         //~
         //~ #### Fiat-Shamir argument
@@ -142,8 +175,7 @@ where
         let mut fq_sponge = EFqSponge::new(G::other_curve_sponge_params());
 
         //~ 1. Absorb the digest of the VerifierIndex.
-        let verifier_index_digest = index.digest::<EFqSponge>();
-        fq_sponge.absorb_fq(&[verifier_index_digest]);
+        fq_sponge.absorb_fq(&[index_digest]);
 
         //~ 1. Absorb the commitments of the previous challenges with the Fq-sponge.
         for RecursionChallenge { comm, .. } in &self.prev_challenges {
@@ -761,7 +793,7 @@ where
 }
 
 fn to_batch<'a, G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
-    verifier_index: &VerifierIndex<G, OpeningProof>,
+    prepared_index: &PreparedVerifierIndex<G, OpeningProof, EFqSponge>,
     proof: &'a ProverProof<G, OpeningProof>,
     public_input: &'a [<G as AffineRepr>::ScalarField],
 ) -> Result<BatchEvaluationProof<'a, G, EFqSponge, OpeningProof>>
@@ -779,6 +811,7 @@ where
     //~ Essentially, this steps verifies that $f(\zeta) = t(\zeta) * Z_H(\zeta)$.
     //~
 
+    let verifier_index = prepared_index.index;
     let zk_rows = verifier_index.zk_rows;
 
     if proof.prev_challenges.len() != verifier_index.prev_challenges {
@@ -794,15 +827,7 @@ where
     }
 
     //~ 1. Check the length of evaluations inside the proof.
-    let chunk_size = {
-        let d1_size = verifier_index.domain.size();
-        if d1_size < verifier_index.max_poly_size {
-            1
-        } else {
-            d1_size / verifier_index.max_poly_size
-        }
-    };
-    check_proof_evals_len(proof, chunk_size)?;
+    check_proof_evals_len(proof, prepared_index.num_chunks)?;
 
     //~ 1. Commit to the negated public input polynomial.
     let public_comm = {
@@ -811,12 +836,9 @@ where
                 verifier_index.public,
             ));
         }
-        let lgr_comm = verifier_index
-            .srs()
-            .get_lagrange_basis(verifier_index.domain);
-        let com: Vec<_> = lgr_comm.iter().take(verifier_index.public).collect();
+        let com: Vec<_> = prepared_index.public_lagrange_comms.iter().collect();
         if public_input.is_empty() {
-            PolyComm::new(vec![verifier_index.srs().blinding_commitment(); chunk_size])
+            prepared_index.empty_public_comm.clone()
         } else {
             let elm: Vec<_> = public_input.iter().map(|s| -*s).collect();
             let public_comm = PolyComm::<G>::multi_scalar_mul(&com, &elm);
@@ -843,7 +865,12 @@ where
         ft_eval0,
         combined_inner_product,
         ..
-    } = proof.oracles::<EFqSponge, EFrSponge>(verifier_index, &public_comm, Some(public_input))?;
+    } = proof.oracles_with_digest::<EFqSponge, EFrSponge>(
+        verifier_index,
+        prepared_index.digest,
+        &public_comm,
+        Some(public_input),
+    )?;
 
     //~ 1. Combine the chunked polynomials' evaluations
     //~    (TODO: most likely only the quotient polynomial is chunked)
@@ -1188,6 +1215,31 @@ where
     batch_verify::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
 }
 
+/// Verify a proof [`ProverProof`] using a [`PreparedVerifierIndex`] and a `group_map`.
+///
+/// # Errors
+///
+/// Will give error if `proof` is not verified as valid.
+pub fn verify_prepared<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    group_map: &G::Map,
+    prepared_index: &PreparedVerifierIndex<G, OpeningProof, EFqSponge>,
+    proof: &ProverProof<G, OpeningProof>,
+    public_input: &[G::ScalarField],
+) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let proofs = vec![PreparedContext {
+        prepared_index,
+        proof,
+        public_input,
+    }];
+    batch_verify_prepared::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
+}
+
 /// This function verifies the batch of zk-proofs
 ///     proofs: vector of Plonk proofs
 ///     RETURN: verification status
@@ -1199,6 +1251,37 @@ pub fn batch_verify<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
     group_map: &G::Map,
     proofs: &[Context<G, OpeningProof>],
 ) -> Result<()>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let prepared_indexes: Vec<PreparedVerifierIndex<G, OpeningProof, EFqSponge>> = proofs
+        .iter()
+        .map(|context| PreparedVerifierIndex::new(context.verifier_index))
+        .collect();
+    let proofs: Vec<_> = proofs
+        .iter()
+        .zip(&prepared_indexes)
+        .map(|(context, prepared_index)| PreparedContext {
+            prepared_index,
+            proof: context.proof,
+            public_input: context.public_input,
+        })
+        .collect();
+    batch_verify_prepared::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
+}
+
+/// This function verifies the batch of zk-proofs, each against a [`PreparedVerifierIndex`]
+///
+/// # Errors
+///
+/// Will give error if `srs` of `proof` is invalid or `verify` process fails.
+pub fn batch_verify_prepared<G, EFqSponge, EFrSponge, OpeningProof: OpenProof<G>>(
+    group_map: &G::Map,
+    proofs: &[PreparedContext<G, OpeningProof, EFqSponge>],
+) -> Result<()>
 where
     G: KimchiCurve,
     G::BaseField: PrimeField,
@@ -1219,23 +1302,23 @@ where
 
     //~ 1. Ensure that all the proof's verifier index have a URS of the same length. (TODO: do they have to be the same URS though? should we check for that?)
     // TODO: Account for the different SRS lengths
-    let srs = proofs[0].verifier_index.srs();
-    for &Context { verifier_index, .. } in proofs {
-        if verifier_index.srs().max_poly_size() != srs.max_poly_size() {
+    let srs = proofs[0].prepared_index.index.srs();
+    for context in proofs {
+        if context.prepared_index.index.srs().max_poly_size() != srs.max_poly_size() {
             return Err(VerifyError::DifferentSRS);
         }
     }
 
     //~ 1. Validate each proof separately following the [partial verification](#partial-verification) steps.
     let mut batch = vec![];
-    for &Context {
-        verifier_index,
+    for &PreparedContext {
+        prepared_index,
         proof,
         public_input,
     } in proofs
     {
         batch.push(to_batch::<G, EFqSponge, EFrSponge, OpeningProof>(
-            verifier_index,
+            prepared_index,
             proof,
             public_input,
         )?);
@@ -1248,3 +1331,202 @@ where
         Err(VerifyError::OpenProof)
     }
 }
+
+/// Verifies the batch of zk-proofs as [`batch_verify`] does, but returns the
+/// result of the verification of each proof, in the order of `proofs`.
+///
+/// The proofs are partially verified in parallel, and their opening proofs are
+/// then checked together; if this check fails, the batch is bisected to find
+/// the invalid opening proofs.
+pub fn batch_verify_each<G, EFqSponge, EFrSponge, OpeningProof>(
+    group_map: &G::Map,
+    proofs: &[Context<G, OpeningProof>],
+) -> Vec<Result<()>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    G::Map: Sync,
+    OpeningProof: OpenProof<G> + Send + Sync,
+    OpeningProof::SRS: Send + Sync,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField> + Send + Sync,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    let prepared_indexes: Vec<PreparedVerifierIndex<G, OpeningProof, EFqSponge>> = proofs
+        .iter()
+        .map(|context| PreparedVerifierIndex::new(context.verifier_index))
+        .collect();
+    let proofs: Vec<_> = proofs
+        .iter()
+        .zip(&prepared_indexes)
+        .map(|(context, prepared_index)| PreparedContext {
+            prepared_index,
+            proof: context.proof,
+            public_input: context.public_input,
+        })
+        .collect();
+    batch_verify_each_prepared::<G, EFqSponge, EFrSponge, OpeningProof>(group_map, &proofs)
+}
+
+/// Verifies the batch of zk-proofs as [`batch_verify_prepared`] does, but
+/// returns the result of the verification of each proof, see [`batch_verify_each`]
+pub fn batch_verify_each_prepared<G, EFqSponge, EFrSponge, OpeningProof>(
+    group_map: &G::Map,
+    proofs: &[PreparedContext<G, OpeningProof, EFqSponge>],
+) -> Vec<Result<()>>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    G::Map: Sync,
+    OpeningProof: OpenProof<G> + Send + Sync,
+    OpeningProof::SRS: Send + Sync,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField> + Send + Sync,
+    EFrSponge: FrSponge<G::ScalarField>,
+{
+    if proofs.is_empty() {
+        return vec![];
+    }
+
+    // the opening proofs are checked against the SRS of the first proof
+    let srs = proofs[0].prepared_index.index.srs();
+
+    // partially verify each proof
+    let partial: Vec<Result<_>> = proofs
+        .par_iter()
+        .map(|context| {
+            if context.prepared_index.index.srs().max_poly_size() != srs.max_poly_size() {
+                return Err(VerifyError::DifferentSRS);
+            }
+            to_batch::<G, EFqSponge, EFrSponge, OpeningProof>(
+                context.prepared_index,
+                context.proof,
+                context.public_input,
+            )
+        })
+        .collect();
+
+    // check the opening proofs of the partially verified proofs
+    let (indices, batch): (Vec<_>, Vec<_>) = partial
+        .iter()
+        .enumerate()
+        .filter_map(|(i, res)| res.as_ref().ok().map(|batch| (i, batch)))
+        .unzip();
+    let valid = check_openings(srs.as_ref(), group_map, &batch);
+
+    let mut results: Vec<Result<()>> = partial.into_iter().map(|res| res.map(|_| ())).collect();
+    for (i, valid) in indices.into_iter().zip(valid) {
+        if !valid {
+            results[i] = Err(VerifyError::OpenProof);
+        }
+    }
+    results
+}
+
+/// Checks the opening proofs of `batch` together, and returns whether each of
+/// them is valid, bisecting the batch when the check fails
+fn check_openings<G, EFqSponge, OpeningProof>(
+    srs: &OpeningProof::SRS,
+    group_map: &G::Map,
+    batch: &[&BatchEvaluationProof<G, EFqSponge, OpeningProof>],
+) -> Vec<bool>
+where
+    G: KimchiCurve,
+    G::Map: Sync,
+    OpeningProof: OpenProof<G> + Sync,
+    OpeningProof::SRS: Sync,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField> + Sync,
+{
+    if batch.is_empty() {
+        return vec![];
+    }
+
+    // the check updates the sponges, so that it is done on copies of the batch
+    let mut copy: Vec<_> = batch
+        .iter()
+        .map(|proof| BatchEvaluationProof {
+            sponge: proof.sponge.clone(),
+            evaluations: proof
+                .evaluations
+                .iter()
+                .map(|evaluation| Evaluation {
+                    commitment: evaluation.commitment.clone(),
+                    evaluations: evaluation.evaluations.clone(),
+                })
+                .collect(),
+            evaluation_points: proof.evaluation_points.clone(),
+            polyscale: proof.polyscale,
+            evalscale: proof.evalscale,
+            opening: proof.opening,
+            combined_inner_product: proof.combined_inner_product,
+        })
+        .collect();
+    if OpeningProof::verify(srs, group_map, &mut copy, &mut thread_rng()) {
+        return vec![true; batch.len()];
+    }
+    if batch.len() == 1 {
+        return vec![false];
+    }
+
+    let (left, right) = batch.split_at(batch.len() / 2);
+    let (mut left, right) = rayon::join(
+        || check_openings(srs, group_map, left),
+        || check_openings(srs, group_map, right),
+    );
+    left.extend(right);
+    left
+}
+
+/// Verifies a stream of zk-proofs against a [`PreparedVerifierIndex`], by
+/// batches of at most `batch_size` proofs, so that at most `batch_size` proofs
+/// are held in memory at once.
+///
+/// Returns the result of the verification of each proof, in the order of
+/// `proofs`, as [`batch_verify_each`] does.
+///
+/// # Panics
+///
+/// Will panic if `batch_size` is zero.
+pub fn batch_verify_stream<'a, G, EFqSponge, EFrSponge, OpeningProof, I>(
+    group_map: &'a G::Map,
+    prepared_index: &'a PreparedVerifierIndex<'a, G, OpeningProof, EFqSponge>,
+    proofs: I,
+    batch_size: usize,
+) -> impl Iterator<Item = Result<()>> + 'a
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    G::Map: Sync,
+    OpeningProof: OpenProof<G> + Send + Sync + 'a,
+    OpeningProof::SRS: Send + Sync,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField> + Send + Sync,
+    EFrSponge: FrSponge<G::ScalarField>,
+    I: IntoIterator<Item = (ProverProof<G, OpeningProof>, Vec<G::ScalarField>)>,
+    I::IntoIter: 'a,
+{
+    assert!(
+        batch_size > 0,
+        "the batches must contain at least one proof"
+    );
+
+    let mut proofs = proofs.into_iter();
+    std::iter::from_fn(move || {
+        let batch: Vec<_> = proofs.by_ref().take(batch_size).collect();
+        if batch.is_empty() {
+            return None;
+        }
+        let contexts: Vec<_> = batch
+            .iter()
+            .map(|(proof, public_input)| PreparedContext {
+                prepared_index,
+                proof,
+                public_input,
+            })
+            .collect();
+        Some(batch_verify_each_prepared::<
+            G,
+            EFqSponge,
+            EFrSponge,
+            OpeningProof,
+        >(group_map, &contexts))
+    })
+    .flatten()
+}
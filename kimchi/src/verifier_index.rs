@@ -14,7 +14,7 @@ use crate::{
     prover_index::ProverIndex,
 };
 use ark_ff::{One, PrimeField};
-use ark_poly::{univariate::DensePolynomial, Radix2EvaluationDomain as D};
+use ark_poly::{univariate::DensePolynomial, EvaluationDomain, Radix2EvaluationDomain as D};
 use mina_poseidon::FqSponge;
 use once_cell::sync::OnceCell;
 use poly_commitment::{
@@ -27,6 +27,7 @@ use std::{
     array,
     fs::{File, OpenOptions},
     io::{BufReader, BufWriter, Seek, SeekFrom::Start},
+    marker::PhantomData,
     path::Path,
     sync::Arc,
 };
@@ -337,6 +338,7 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
     }
 
     /// Deserializes a [`VerifierIndex`] from a file, given a pointer to an SRS and an optional offset in the file.
+    /// The file can be in any version of the format of [`crate::encoding`].
     ///
     /// # Errors
     ///
@@ -360,27 +362,20 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
             reader.seek(Start(offset)).map_err(|e| e.to_string())?;
         }
 
-        // deserialize
-        let mut verifier_index = Self::deserialize(&mut rmp_serde::Deserializer::new(reader))
-            .map_err(|e| e.to_string())?;
-
-        // fill in the rest
-        verifier_index.srs = srs;
-        verifier_index.endo = endo;
-
-        Ok(verifier_index)
+        // deserialize, and fill in the rest
+        Self::read_from(reader, srs, endo).map_err(|e| e.to_string())
     }
 
-    /// Writes a [`VerifierIndex`] to a file, potentially appending it to the already-existing content (if append is set to true)
+    /// Writes a [`VerifierIndex`] to a file, in the binary format of [`crate::encoding`],
+    /// potentially appending it to the already-existing content (if append is set to true)
     // TODO: append should be a bool, not an option
     /// # Errors
     ///
     /// Will give error if it fails to open a file or writes to the file.
-    ///
-    /// # Panics
-    ///
-    /// Will panic if `path` is invalid or `file serialization` has issue.
-    pub fn to_file(&self, path: &Path, append: Option<bool>) -> Result<(), String> {
+    pub fn to_file(&self, path: &Path, append: Option<bool>) -> Result<(), String>
+    where
+        OpeningProof::SRS: Default,
+    {
         let append = append.unwrap_or(true);
         let file = OpenOptions::new()
             .append(append)
@@ -389,8 +384,7 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
 
         let writer = BufWriter::new(file);
 
-        self.serialize(&mut rmp_serde::Serializer::new(writer))
-            .map_err(|e| e.to_string())
+        self.write_to(writer).map_err(|e| e.to_string())
     }
 
     /// Compute the digest of the [`VerifierIndex`], which can be used for the Fiat-Shamir
@@ -523,3 +517,67 @@ impl<G: KimchiCurve, OpeningProof: OpenProof<G>> VerifierIndex<G, OpeningProof>
         fq_sponge.digest_fq()
     }
 }
+
+/// A [`VerifierIndex`] along with the values that verifying a proof derives
+/// from the index alone, computed once to verify many proofs against the same
+/// index (see [`crate::verifier::verify_prepared`]).
+///
+/// The digest of the index depends on the sponge `EFqSponge` verifying the proofs.
+pub struct PreparedVerifierIndex<'a, G: KimchiCurve, OpeningProof: OpenProof<G>, EFqSponge> {
+    /// The verifier index
+    pub(crate) index: &'a VerifierIndex<G, OpeningProof>,
+    /// The digest of the verifier index, absorbed first by the Fq-sponge
+    pub(crate) digest: G::BaseField,
+    /// The number of chunks of the polynomials of the circuit
+    pub(crate) num_chunks: usize,
+    /// The commitments to the Lagrange basis of the public input
+    pub(crate) public_lagrange_comms: Vec<PolyComm<G>>,
+    /// The commitment to an empty public input
+    pub(crate) empty_public_comm: PolyComm<G>,
+    _sponge: PhantomData<EFqSponge>,
+}
+
+impl<'a, G, OpeningProof, EFqSponge> PreparedVerifierIndex<'a, G, OpeningProof, EFqSponge>
+where
+    G: KimchiCurve,
+    G::BaseField: PrimeField,
+    OpeningProof: OpenProof<G>,
+    EFqSponge: Clone + FqSponge<G::BaseField, G, G::ScalarField>,
+{
+    /// Prepare the verification of proofs against `index`
+    pub fn new(index: &'a VerifierIndex<G, OpeningProof>) -> Self {
+        let domain_size = index.domain.size();
+        let num_chunks = if domain_size < index.max_poly_size {
+            1
+        } else {
+            domain_size / index.max_poly_size
+        };
+
+        let srs = index.srs();
+        let public_lagrange_comms = srs.get_lagrange_basis(index.domain)[..index.public].to_vec();
+        let empty_public_comm = PolyComm::new(vec![srs.blinding_commitment(); num_chunks]);
+
+        // initialize the values the index computes lazily
+        index.permutation_vanishing_polynomial_m();
+        index.w();
+
+        PreparedVerifierIndex {
+            index,
+            digest: index.digest::<EFqSponge>(),
+            num_chunks,
+            public_lagrange_comms,
+            empty_public_comm,
+            _sponge: PhantomData,
+        }
+    }
+
+    /// The verifier index
+    pub fn index(&self) -> &'a VerifierIndex<G, OpeningProof> {
+        self.index
+    }
+
+    /// The digest of the verifier index
+    pub fn digest(&self) -> G::BaseField {
+        self.digest
+    }
+}
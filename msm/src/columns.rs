@@ -2,9 +2,10 @@ use std::collections::HashMap;
 
 use folding::expressions::FoldingColumnTrait;
 use kimchi::circuits::expr::{CacheId, FormattedOutput};
+use serde::{Deserialize, Serialize};
 
 /// Describe a generic indexed variable X_{i}.
-#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Hash, Serialize, Deserialize)]
 pub enum Column {
     /// Columns related to the relation encoded in the circuit
     Relation(usize),
@@ -378,16 +378,23 @@ mod tests {
 
     #[test]
     fn test_meta_deserialize_from_file() {
-        let path = "meta_test.json";
-        let mut output =
-            File::create(path).unwrap_or_else(|_| panic!("Could not create file {path}"));
+        // Written in the temporary directory, not to leave it in the repository
+        let path = std::env::temp_dir().join(format!("meta_test_{}.json", std::process::id()));
+        let path = path.as_path();
+        let mut output = File::create(path)
+            .unwrap_or_else(|_| panic!("Could not create file {}", path.display()));
         write!(output, "{}", META_SAMPLE)
-            .unwrap_or_else(|_| panic!("Could not write to file {path}"));
+            .unwrap_or_else(|_| panic!("Could not write to file {}", path.display()));
 
-        let input = File::open(path).unwrap_or_else(|_| panic!("Could not open file {path}"));
+        let input =
+            File::open(path).unwrap_or_else(|_| panic!("Could not open file {}", path.display()));
         let buffered = BufReader::new(input);
-        let read: Meta = serde_json::from_reader(buffered)
-            .unwrap_or_else(|_| panic!("Failed to deserialize metadata from file {path}"));
+        let read: Meta = serde_json::from_reader(buffered).unwrap_or_else(|_| {
+            panic!(
+                "Failed to deserialize metadata from file {}",
+                path.display()
+            )
+        });
 
         let expected = Meta {
             symbols: vec![
@@ -9,7 +9,6 @@ pub fn main_cli() -> clap::Command {
         .arg(arg!(--input <FILE> "initial state file").default_value("state.json"))
         .arg(arg!(--output <FILE> "output state file").default_value("out.json"))
         .arg(arg!(--meta <FILE> "metadata file").default_value("meta.json"))
-        // The CLI arguments below this line are ignored at this point
         .arg(
             Arg::new("proof-at")
                 .short('p')
@@ -24,6 +23,7 @@ pub fn main_cli() -> clap::Command {
                 .value_name("FORMAT")
                 .default_value("proof-%d.json"),
         )
+        // Ignored at this point
         .arg(
             Arg::new("snapshot-fmt")
                 .long("snapshot-fmt")
@@ -44,6 +44,7 @@ pub fn main_cli() -> clap::Command {
                 .default_value("never")
                 .value_parser(step_frequency_parser),
         )
+        // Ignored at this point
         .arg(
             Arg::new("pprof-cpu")
                 .long("pprof.cpu")
@@ -110,3 +111,114 @@ pub fn read_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
         host,
     }
 }
+
+/// The command line interface of the RISC-V 32IM edition of the o1vm, which
+/// runs an ELF program instead of a Cannon state
+pub fn riscv_cli() -> clap::Command {
+    let app_name = "riscv_o1vm";
+    clap::Command::new(app_name)
+        .version("0.1")
+        .about("o1vm - run a RISC-V 32IM program")
+        .arg(arg!(<ELF> "RISC-V 32IM program to run").value_parser(value_parser!(String)))
+        .arg(
+            Arg::new("arg")
+                .long("arg")
+                .value_name("ARG")
+                .action(ArgAction::Append)
+                .help("argument given to the program, after its name"),
+        )
+        .arg(
+            Arg::new("env")
+                .long("env")
+                .value_name("VAR=VALUE")
+                .action(ArgAction::Append)
+                .help("environment variable given to the program"),
+        )
+        .arg(
+            arg!(--output <FILE> "output file of the final registers and exit code, - for stdout")
+                .default_value("out.json")
+                .long_help(
+                    "output file of the final registers and exit code, - for stdout. \
+                     The standard error is used instead if the program writes to the \
+                     standard output.",
+                ),
+        )
+        .arg(
+            Arg::new("trace")
+                .long("trace")
+                .action(ArgAction::SetTrue)
+                .help("print each executed instruction on stderr"),
+        )
+        .arg(
+            Arg::new("symbols")
+                .long("symbols")
+                .action(ArgAction::SetTrue)
+                .help("print the symbol of each executed instruction in the trace"),
+        )
+        .arg(
+            Arg::new("snapshot-fmt")
+                .long("snapshot-fmt")
+                .value_name("FORMAT")
+                .default_value("state-%d.json"),
+        )
+        .arg(
+            Arg::new("stop-at")
+                .long("stop-at")
+                .value_name("FREQ")
+                .default_value("never")
+                .value_parser(step_frequency_parser),
+        )
+        .arg(
+            Arg::new("info-at")
+                .long("info-at")
+                .value_name("FREQ")
+                .default_value("never")
+                .value_parser(step_frequency_parser),
+        )
+        .arg(
+            Arg::new("snapshot-state-at")
+                .long("snapshot-state-at")
+                .value_name("FREQ")
+                .default_value("never")
+                .value_parser(step_frequency_parser),
+        )
+        .arg(
+            arg!(host: [HOST] "host program specification <host program> [host program arguments]")
+                .num_args(1..)
+                .last(true)
+                .value_parser(value_parser!(String)),
+        )
+}
+
+/// Read the configuration of the RISC-V edition from the arguments of
+/// [riscv_cli]. The input state file is the ELF program, and the output state
+/// file the file of the final registers.
+pub fn read_riscv_configuration(cli: &clap::ArgMatches) -> VmConfiguration {
+    let elf_file = cli.get_one::<String>("ELF").unwrap();
+    let output_file = cli.get_one::<String>("output").unwrap();
+
+    let info_at = cli.get_one::<StepFrequency>("info-at").unwrap();
+    let stop_at = cli.get_one::<StepFrequency>("stop-at").unwrap();
+    let snapshot_state_at = cli.get_one::<StepFrequency>("snapshot-state-at").unwrap();
+    let snapshot_fmt = cli.get_one::<String>("snapshot-fmt").unwrap();
+
+    let host = cli.get_many::<String>("host").map(|mut vals| HostProgram {
+        name: vals.next().unwrap().to_string(),
+        arguments: vals.map(|x| x.to_string()).collect(),
+    });
+
+    VmConfiguration {
+        input_state_file: elf_file.to_string(),
+        output_state_file: output_file.to_string(),
+        // The symbols are read from the ELF program
+        metadata_file: elf_file.to_string(),
+        proof_at: StepFrequency::Never,
+        stop_at: stop_at.clone(),
+        snapshot_state_at: snapshot_state_at.clone(),
+        info_at: info_at.clone(),
+        proof_fmt: String::new(),
+        snapshot_fmt: snapshot_fmt.to_string(),
+        pprof_cpu: false,
+        host,
+    }
+}
@@ -1,145 +1,220 @@
-use crate::cannon::{Page, State, PAGE_SIZE};
-use elf::{endian::LittleEndian, section::SectionHeader, ElfBytes};
+use crate::{
+    cannon::{Meta, Page, Symbol, PAGE_ADDRESS_MASK, PAGE_SIZE},
+    interpreters::riscv32im::state::{Permissions, Segment, State},
+};
+use elf::{
+    abi::{EM_RISCV, PF_R, PF_W, PF_X, PT_LOAD, STT_FUNC, STT_OBJECT},
+    endian::LittleEndian,
+    file::Class,
+    ElfBytes,
+};
 use log::debug;
-use std::{collections::HashMap, path::Path};
+use std::{collections::BTreeMap, path::Path};
+
+/// The address right above the initial stack of the program. The stack grows
+/// downwards, and its pages are allocated when they are accessed.
+pub const STACK_TOP: u32 = 0x8000_0000;
+
+/// The address of the first memory mapping of the `mmap` syscall. The segments
+/// of the program, and the data added by `brk`, must be below this address.
+pub const MMAP_START: u32 = 0x4000_0000;
+
+/// The index of the stack pointer in the general purpose registers
+const REGISTER_SP: usize = 2;
+
+// The types of the entries of the auxiliary vector given on the stack
+const AT_NULL: u32 = 0;
+const AT_PAGESZ: u32 = 6;
+const AT_ENTRY: u32 = 9;
+
+/// The memory of a program being loaded, as pages indexed by their number
+#[derive(Default)]
+struct Memory(BTreeMap<u32, Vec<u8>>);
+
+impl Memory {
+    fn write(&mut self, addr: u32, bytes: &[u8]) {
+        for (i, byte) in bytes.iter().enumerate() {
+            let addr = addr + i as u32;
+            let page = self
+                .0
+                .entry(addr / PAGE_SIZE)
+                .or_insert_with(|| vec![0; PAGE_SIZE as usize]);
+            page[(addr % PAGE_SIZE) as usize] = *byte;
+        }
+    }
+
+    fn into_pages(self) -> Vec<Page> {
+        self.0
+            .into_iter()
+            .map(|(index, data)| Page { index, data })
+            .collect()
+    }
+}
 
 /// Parse an ELF file and return the parsed data as a structure that is expected
-/// by the o1vm RISC-V 32 bits edition.
-// FIXME: parametrize by an architecture. We should return a state depending on the
-// architecture. In the meantime, we can have parse_riscv32i and parse_mips.
-// FIXME: for now, we return a State structure, either for RISC-V 32i or MIPS.
-// We should return a structure specifically built for the o1vm, and not tight
-// to Cannon. It will be done in a future PR to avoid breaking the current code
-// and have a huge diff.
+/// by the o1vm RISC-V 32 bits edition. The program is given its path as its
+/// only argument, and no environment variables. See [load_riscv32].
 pub fn parse_riscv32(path: &Path) -> Result<State, String> {
+    let program = path.to_string_lossy();
+    let (state, _meta) = load_riscv32(path, &[&program], &[])?;
+    Ok(state)
+}
+
+/// Load the RISC-V 32 bits program of the ELF file `path`, with the arguments
+/// `args` and the environment variables `envs`, and return its initial state
+/// and the symbols of its functions and objects.
+///
+/// All the `PT_LOAD` segments are mapped in memory with their permissions, the
+/// bytes of a segment after the data of the file (e.g. `.bss`) being zeroed.
+/// The stack is set up below [STACK_TOP] as the Linux kernel does: the stack
+/// pointer points to `argc`, followed by `argv`, `envp` and the auxiliary
+/// vector, each terminated by a null entry, and by the strings they point to.
+pub fn load_riscv32(path: &Path, args: &[&str], envs: &[&str]) -> Result<(State, Meta), String> {
     debug!("Start parsing the ELF file to load a RISC-V 32i compatible state");
-    let file_data = std::fs::read(path).expect("Could not read file.");
-    let slice = file_data.as_slice();
-    let file = ElfBytes::<LittleEndian>::minimal_parse(slice).expect("Open ELF file failed.");
-
-    // Checking it is RISC-V
-    assert_eq!(file.ehdr.e_machine, 243);
-
-    let (shdrs_opt, strtab_opt) = file
-        .section_headers_with_strtab()
-        .expect("shdrs offsets should be valid");
-    let (shdrs, strtab) = (
-        shdrs_opt.expect("Should have shdrs"),
-        strtab_opt.expect("Should have strtab"),
-    );
-
-    // Parse the shdrs and collect them into a map keyed on their zero-copied name
-    let sections_by_name: HashMap<&str, SectionHeader> = shdrs
-        .iter()
-        .map(|shdr| {
-            (
-                strtab
-                    .get(shdr.sh_name as usize)
-                    .expect("Failed to get section name"),
-                shdr,
-            )
-        })
-        .collect();
-
-    debug!("Loading the text section, which contains the executable code.");
-    // Getting the executable code.
-    let text_section = sections_by_name
-        .get(".text")
-        .expect("Should have .text section");
-
-    let (text_section_data, _) = file
-        .section_data(text_section)
-        .expect("Failed to read data from .text section");
-
-    let code_section_starting_address = text_section.sh_addr as usize;
-    let code_section_size = text_section.sh_size as usize;
-    let code_section_end_address = code_section_starting_address + code_section_size;
-    debug!(
-        "The executable code starts at address {}, has size {} bytes, and ends at address {}.",
-        code_section_starting_address, code_section_size, code_section_end_address
-    );
-
-    // Building the memory pages
-    let mut memory: Vec<Page> = vec![];
-    let page_size_usize: usize = PAGE_SIZE.try_into().unwrap();
-    // Padding to get the right number of pages. We suppose that the memory
-    // index starts at 0.
-    let start_page_address: usize =
-        (code_section_starting_address / page_size_usize) * page_size_usize;
-    let end_page_address =
-        (code_section_end_address / (page_size_usize - 1)) * page_size_usize + page_size_usize;
-
-    let first_page_index = start_page_address / page_size_usize;
-    let last_page_index = (end_page_address - 1) / page_size_usize;
-    let mut data_offset = 0;
-    (first_page_index..=last_page_index).for_each(|page_index| {
-        let mut data = vec![0; page_size_usize];
-        // Special case of only one page
-        if first_page_index == last_page_index {
-            let data_length = code_section_end_address - code_section_starting_address;
-            let page_offset = code_section_starting_address - start_page_address;
-            data[page_offset..page_offset + data_length]
-                .copy_from_slice(&text_section_data[0..data_length]);
-            data_offset += data_length;
-        } else {
-            let data_length = if page_index == last_page_index {
-                code_section_end_address - (page_index * page_size_usize)
-            } else {
-                page_size_usize
-            };
-            let page_offset = if page_index == first_page_index {
-                code_section_starting_address - start_page_address
-            } else {
-                0
-            };
-            data[page_offset..]
-                .copy_from_slice(&text_section_data[data_offset..data_offset + data_length]);
-            data_offset += data_length;
+    let file_data =
+        std::fs::read(path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    let file = ElfBytes::<LittleEndian>::minimal_parse(file_data.as_slice())
+        .map_err(|e| format!("Invalid ELF file: {e}"))?;
+
+    if file.ehdr.e_machine != EM_RISCV {
+        return Err(format!(
+            "The ELF file is not a RISC-V program (machine {})",
+            file.ehdr.e_machine
+        ));
+    }
+    if file.ehdr.class != Class::ELF32 {
+        return Err("The ELF file is not a 32 bits program".to_string());
+    }
+
+    // Entry point of the program
+    let entry = file.ehdr.e_entry as u32;
+    if entry == 0 {
+        // The documentation of the ELF library says that it means the ELF
+        // doesn't have an entry point.
+        return Err("The ELF file has no entry point".to_string());
+    }
+
+    let mut memory = Memory::default();
+    let mut segments = vec![];
+    let program_headers = file
+        .segments()
+        .ok_or("The ELF file has no program headers")?;
+    for phdr in program_headers.iter().filter(|phdr| phdr.p_type == PT_LOAD) {
+        let start = phdr.p_vaddr as u32;
+        let (file_size, size) = (phdr.p_filesz as u32, phdr.p_memsz as u32);
+        if file_size > size || start as u64 + size as u64 > MMAP_START as u64 {
+            return Err(format!(
+                "Invalid segment at address {start:#x} of size {size:#x}"
+            ));
         }
-        let page = Page {
-            index: page_index as u32,
-            data,
+        let data = file
+            .segment_data(&phdr)
+            .map_err(|e| format!("Failed to read the segment at {start:#x}: {e}"))?;
+        let permissions = Permissions {
+            read: phdr.p_flags & PF_R != 0,
+            write: phdr.p_flags & PF_W != 0,
+            execute: phdr.p_flags & PF_X != 0,
         };
-        memory.push(page);
-    });
+        debug!(
+            "Loading the segment at address {start:#x}, of size {size:#x} ({file_size:#x} \
+             bytes in the file), with permissions {permissions}"
+        );
+        memory.write(start, &data[..file_size as usize]);
+        // The bytes that are not in the file, e.g. `.bss`, are zeroed, even if
+        // another segment shares the page.
+        memory.write(start + file_size, &vec![0; (size - file_size) as usize]);
+        segments.push(Segment {
+            start,
+            size,
+            permissions,
+        });
+    }
+    segments.sort_by_key(|segment| segment.start);
 
-    // FIXME: add data section into memory for static data saved in the binary
+    // The data added by `brk` starts at the page following the segments.
+    let program_break = segments
+        .iter()
+        .map(|segment| (segment.start + segment.size + PAGE_ADDRESS_MASK) & !PAGE_ADDRESS_MASK)
+        .max()
+        .unwrap_or(0);
 
-    // FIXME: we're lucky that RISCV32i and MIPS have the same number of
-    let registers: [u32; 32] = [0; 32];
+    let stack_pointer = {
+        // The strings of the arguments and environment variables are at the
+        // top of the stack, followed by the pointers to them.
+        let mut strings_start = STACK_TOP;
+        let mut pointers = |strings: &[&str]| -> Vec<u32> {
+            strings
+                .iter()
+                .map(|s| {
+                    strings_start -= s.len() as u32 + 1;
+                    memory.write(strings_start, s.as_bytes());
+                    memory.write(strings_start + s.len() as u32, &[0]);
+                    strings_start
+                })
+                .collect()
+        };
+        let argv = pointers(args);
+        let envp = pointers(envs);
 
-    // FIXME: it is only because we share the same structure for the state.
-    let preimage_key: [u8; 32] = [0; 32];
-    // FIXME: it is only because we share the same structure for the state.
-    let preimage_offset = 0;
+        let mut words = vec![args.len() as u32];
+        words.extend(argv);
+        words.push(0);
+        words.extend(envp);
+        words.push(0);
+        words.extend([AT_PAGESZ, PAGE_SIZE, AT_ENTRY, entry, AT_NULL, 0]);
 
-    // Entry point of the program
-    let pc: u32 = file.ehdr.e_entry as u32;
-    assert!(pc != 0, "Entry point is 0. The documentation of the ELF library says that it means the ELF doesn't have an entry point. This is not supported.");
-    let next_pc: u32 = pc + 4u32;
+        // The stack pointer is aligned on 16 bytes, as required by the ABI.
+        let stack_pointer = (strings_start - 4 * words.len() as u32) & !0xf;
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_le_bytes()).collect();
+        memory.write(stack_pointer, &bytes);
+        stack_pointer
+    };
+    debug!("The stack pointer starts at address {stack_pointer:#x}");
+
+    let mut registers = [0; 32];
+    registers[REGISTER_SP] = stack_pointer;
+
+    // The symbols of the functions and objects, to report where the program
+    // is running
+    let mut symbols = vec![];
+    if let Some((symbol_table, string_table)) = file
+        .symbol_table()
+        .map_err(|e| format!("Failed to read the symbol table: {e}"))?
+    {
+        for symbol in symbol_table.iter() {
+            let symbol_type = symbol.st_symtype();
+            if symbol.st_size == 0 || (symbol_type != STT_FUNC && symbol_type != STT_OBJECT) {
+                continue;
+            }
+            let name = string_table
+                .get(symbol.st_name as usize)
+                .map_err(|e| format!("Failed to read the name of a symbol: {e}"))?;
+            symbols.push(Symbol {
+                name: name.to_string(),
+                start: symbol.st_value as u32,
+                size: symbol.st_size as usize,
+            });
+        }
+    }
+    symbols.sort_by_key(|symbol| symbol.start);
 
     let state = State {
-        memory,
-        // FIXME: only because Cannon related
-        preimage_key,
-        // FIXME: only because Cannon related
-        preimage_offset,
-        pc,
-        next_pc,
-        // FIXME: only because Cannon related
-        lo: 0,
-        // FIXME: only because Cannon related
-        hi: 0,
-        heap: 0,
+        memory: memory.into_pages(),
+        segments,
+        entry,
+        pc: entry,
+        next_pc: entry + 4,
+        registers,
+        program_break,
+        heap: MMAP_START,
+        preimage_key: [0; 32],
+        preimage_offset: 0,
+        preimage: None,
+        last_hint: None,
         exit: 0,
         exited: false,
         step: 0,
-        registers,
-        // FIXME: only because Cannon related
-        last_hint: None,
-        // FIXME: only because Cannon related
-        preimage: None,
     };
 
-    Ok(state)
+    Ok((state, Meta { symbols }))
 }
@@ -1179,7 +1179,7 @@ impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
         opcode
     }
 
-    fn should_trigger_at(&self, at: &StepFrequency) -> bool {
+    pub fn should_trigger_at(&self, at: &StepFrequency) -> bool {
         let m: u64 = self.normalized_instruction_counter();
         match at {
             StepFrequency::Never => false,
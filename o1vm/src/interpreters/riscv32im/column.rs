@@ -4,7 +4,7 @@ use super::{
         Instruction::{self, IType, MType, RType, SBType, SType, SyscallType, UJType, UType},
         RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction, UJInstruction,
     },
-    INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
+    INSTRUCTION_SET_SIZE, SCRATCH_SIZE, SCRATCH_SIZE_WITHOUT_KECCAK,
 };
 use kimchi::circuits::{
     berkeley_columns::BerkeleyChallengeTerm,
@@ -12,6 +12,29 @@ use kimchi::circuits::{
 };
 use strum::EnumCount;
 
+/// The number of hashes performed so far in the block
+pub(crate) const RISCV_HASH_COUNTER_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK;
+/// The number of bytes of the preimage that have been read so far in this hash
+pub(crate) const RISCV_BYTE_COUNTER_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 1;
+/// A flag indicating whether the preimage has been read fully or not
+pub(crate) const RISCV_END_OF_PREIMAGE_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 2;
+/// The number of preimage bytes processed in this step
+pub(crate) const RISCV_NUM_BYTES_READ_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 3;
+/// The at most 4-byte chunk of the preimage that has been read in this step.
+/// Contains a field element of at most 4 bytes.
+pub(crate) const RISCV_PREIMAGE_CHUNK_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 4;
+/// The at most 4-bytes of the preimage that are currently being processed
+/// Consists of 4 field elements of at most 1 byte each.
+pub(crate) const RISCV_PREIMAGE_BYTES_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 5;
+/// The at most 4-bytes of the length that are currently being processed
+pub(crate) const RISCV_LENGTH_BYTES_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 5 + 4;
+/// Flags indicating whether at least N bytes have been processed in this step
+pub(crate) const RISCV_HAS_N_BYTES_OFF: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 5 + 4 + 4;
+/// The maximum size of a chunk (4 bytes)
+pub(crate) const RISCV_CHUNK_BYTES_LEN: usize = 4;
+/// The location of the preimage key as a field element of 248bits
+pub(crate) const RISCV_PREIMAGE_KEY: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 5 + 4 + 4 + 4;
+
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub enum Column {
     ScratchState(usize),
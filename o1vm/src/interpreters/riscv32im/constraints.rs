@@ -1,17 +1,23 @@
 use super::{
-    column::{Column, E},
+    column::{
+        Column, E, RISCV_BYTE_COUNTER_OFF, RISCV_CHUNK_BYTES_LEN, RISCV_END_OF_PREIMAGE_OFF,
+        RISCV_HASH_COUNTER_OFF, RISCV_HAS_N_BYTES_OFF, RISCV_LENGTH_BYTES_OFF,
+        RISCV_NUM_BYTES_READ_OFF, RISCV_PREIMAGE_BYTES_OFF, RISCV_PREIMAGE_CHUNK_OFF,
+        RISCV_PREIMAGE_KEY,
+    },
     interpreter::{Instruction, InterpreterEnv},
     INSTRUCTION_SET_SIZE,
 };
 use crate::{
     interpreters::riscv32im::{constraints::ConstantTerm::Literal, SCRATCH_SIZE},
-    lookups::Lookup,
+    lookups::{Lookup, LookupTableIDs},
 };
 use ark_ff::{Field, One};
 use kimchi::circuits::{
     expr::{ConstantTerm, Expr, ExprInner, Operations, Variable},
     gate::CurrOrNext,
 };
+use std::array;
 
 pub struct Env<F: Field> {
     pub scratch_state_idx: usize,
@@ -426,6 +432,205 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     fn report_exit(&mut self, _exit_code: &Self::Variable) {}
 
+    fn request_preimage_write(
+        &mut self,
+        _addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // How many hashes have been performed so far in the circuit
+        let hash_counter = self.variable(Self::Position::ScratchState(RISCV_HASH_COUNTER_OFF));
+
+        // How many bytes have been read from the preimage so far
+        let byte_counter = self.variable(Self::Position::ScratchState(RISCV_BYTE_COUNTER_OFF));
+
+        // Whether this is the last step of the preimage or not (boolean)
+        let end_of_preimage =
+            self.variable(Self::Position::ScratchState(RISCV_END_OF_PREIMAGE_OFF));
+
+        // How many preimage bytes are being processed in this instruction
+        let num_preimage_bytes_read =
+            self.variable(Self::Position::ScratchState(RISCV_NUM_BYTES_READ_OFF));
+
+        // The chunk of at most 4 bytes that is being processed from the
+        // preimage in this instruction
+        let this_chunk = self.variable(Self::Position::ScratchState(RISCV_PREIMAGE_CHUNK_OFF));
+
+        // The preimage key composed of 248 bits
+        let preimage_key = self.variable(Self::Position::ScratchState(RISCV_PREIMAGE_KEY));
+
+        // The (at most) 4 bytes that are being processed from the preimage
+        let bytes: [_; RISCV_CHUNK_BYTES_LEN] = array::from_fn(|i| {
+            self.variable(Self::Position::ScratchState(RISCV_PREIMAGE_BYTES_OFF + i))
+        });
+
+        // The (at most) 4 bytes that are being read from the bytelength
+        let length_bytes: [_; RISCV_CHUNK_BYTES_LEN] = array::from_fn(|i| {
+            self.variable(Self::Position::ScratchState(RISCV_LENGTH_BYTES_OFF + i))
+        });
+
+        // Whether the preimage chunk read has at least n bytes (1, 2, 3, or 4).
+        // It will be all zero when the syscall reads the bytelength prefix.
+        let has_n_bytes: [_; RISCV_CHUNK_BYTES_LEN] = array::from_fn(|i| {
+            self.variable(Self::Position::ScratchState(RISCV_HAS_N_BYTES_OFF + i))
+        });
+
+        // The actual number of bytes read in this instruction, will be 0 <= x <= len <= 4
+        let actual_read_bytes = self.variable(pos);
+
+        // 5 Booleanity constraints
+        {
+            for var in has_n_bytes.iter() {
+                self.assert_boolean(var.clone());
+            }
+            self.assert_boolean(end_of_preimage.clone());
+        }
+
+        // + 4 constraints
+        {
+            // Expressions that are nonzero when the exact corresponding number
+            // of preimage bytes are read (case 0 bytes used when bytelength is read)
+            let preimage_1 = (num_preimage_bytes_read.clone())
+                * (num_preimage_bytes_read.clone() - Expr::from(2))
+                * (num_preimage_bytes_read.clone() - Expr::from(3))
+                * (num_preimage_bytes_read.clone() - Expr::from(4));
+            let preimage_2 = (num_preimage_bytes_read.clone())
+                * (num_preimage_bytes_read.clone() - Expr::from(1))
+                * (num_preimage_bytes_read.clone() - Expr::from(3))
+                * (num_preimage_bytes_read.clone() - Expr::from(4));
+            let preimage_3 = (num_preimage_bytes_read.clone())
+                * (num_preimage_bytes_read.clone() - Expr::from(1))
+                * (num_preimage_bytes_read.clone() - Expr::from(2))
+                * (num_preimage_bytes_read.clone() - Expr::from(4));
+            let preimage_4 = (num_preimage_bytes_read.clone())
+                * (num_preimage_bytes_read.clone() - Expr::from(1))
+                * (num_preimage_bytes_read.clone() - Expr::from(2))
+                * (num_preimage_bytes_read.clone() - Expr::from(3));
+
+            // Constrain the byte decomposition of the preimage chunk
+            // NOTE: these constraints also hold when 0 preimage bytes are read
+            {
+                // When only 1 preimage byte is read, the chunk equals byte[0]
+                self.add_constraint(preimage_1 * (this_chunk.clone() - bytes[0].clone()));
+                // When 2 bytes are read, the chunk is equal to the
+                // byte[0] * 2^8 + byte[1]
+                self.add_constraint(
+                    preimage_2
+                        * (this_chunk.clone()
+                            - (bytes[0].clone() * Expr::from(2u64.pow(8)) + bytes[1].clone())),
+                );
+                // When 3 bytes are read, the chunk is equal to
+                // byte[0] * 2^16 + byte[1] * 2^8 + byte[2]
+                self.add_constraint(
+                    preimage_3
+                        * (this_chunk.clone()
+                            - (bytes[0].clone() * Expr::from(2u64.pow(16))
+                                + bytes[1].clone() * Expr::from(2u64.pow(8))
+                                + bytes[2].clone())),
+                );
+                // When all 4 bytes are read, the chunk is equal to
+                // byte[0] * 2^24 + byte[1] * 2^16 + byte[2] * 2^8 + byte[3]
+                self.add_constraint(
+                    preimage_4
+                        * (this_chunk.clone()
+                            - (bytes[0].clone() * Expr::from(2u64.pow(24))
+                                + bytes[1].clone() * Expr::from(2u64.pow(16))
+                                + bytes[2].clone() * Expr::from(2u64.pow(8))
+                                + bytes[3].clone())),
+                );
+            }
+
+            // +4 constraints
+            // Constrain the bytes flags depending on the number of preimage
+            // bytes read in this row
+            {
+                // When at least has_1_byte, then any number of bytes can be
+                // read <=> Check that you can only read 1, 2, 3 or 4 bytes
+                self.add_constraint(
+                    has_n_bytes[0].clone()
+                        * (num_preimage_bytes_read.clone() - Expr::from(1))
+                        * (num_preimage_bytes_read.clone() - Expr::from(2))
+                        * (num_preimage_bytes_read.clone() - Expr::from(3))
+                        * (num_preimage_bytes_read.clone() - Expr::from(4)),
+                );
+
+                // When at least has_2_byte, then any number of bytes can be
+                // read from the preimage except 1
+                self.add_constraint(
+                    has_n_bytes[1].clone()
+                        * (num_preimage_bytes_read.clone() - Expr::from(2))
+                        * (num_preimage_bytes_read.clone() - Expr::from(3))
+                        * (num_preimage_bytes_read.clone() - Expr::from(4)),
+                );
+                // When at least has_3_byte, then any number of bytes can be
+                // read from the preimage except 1 nor 2
+                self.add_constraint(
+                    has_n_bytes[2].clone()
+                        * (num_preimage_bytes_read.clone() - Expr::from(3))
+                        * (num_preimage_bytes_read.clone() - Expr::from(4)),
+                );
+
+                // When has_4_byte, then only can read 4 preimage bytes
+                self.add_constraint(
+                    has_n_bytes[3].clone() * (num_preimage_bytes_read.clone() - Expr::from(4)),
+                );
+            }
+        }
+
+        // Byte checks with lookups: both preimage and length bytes are checked
+        for byte in bytes.iter() {
+            self.lookup_8bits(byte);
+        }
+        for b in length_bytes.iter() {
+            self.lookup_8bits(b);
+        }
+
+        // Check that 0 <= preimage read <= actual read <= len <= 4
+        self.lookup_2bits(len);
+        self.lookup_2bits(&actual_read_bytes);
+        self.lookup_2bits(&num_preimage_bytes_read);
+        self.lookup_2bits(&(len.clone() - actual_read_bytes.clone()));
+        self.lookup_2bits(&(actual_read_bytes.clone() - num_preimage_bytes_read.clone()));
+
+        // COMMUNICATION CHANNEL: Write preimage chunk (1, 2, 3, or 4 bytes)
+        for i in 0..RISCV_CHUNK_BYTES_LEN {
+            self.add_lookup(Lookup::write_if(
+                has_n_bytes[i].clone(),
+                LookupTableIDs::SyscallLookup,
+                vec![
+                    hash_counter.clone(),
+                    byte_counter.clone() + Expr::from(i as u64),
+                    bytes[i].clone(),
+                ],
+            ));
+        }
+
+        // COMMUNICATION CHANNEL: Read hash output
+        // If no more bytes left to be read, then the end of the preimage is
+        // true.
+        self.add_lookup(Lookup::read_if(
+            end_of_preimage,
+            LookupTableIDs::SyscallLookup,
+            vec![hash_counter.clone(), preimage_key],
+        ));
+
+        // Return actual length read as variable, stored in `pos`
+        actual_read_bytes
+    }
+
+    fn request_hint_write(&mut self, _addr: &Self::Variable, _len: &Self::Variable) {
+        // No-op, witness only
+    }
+
+    fn request_output_write(
+        &mut self,
+        _fd: &Self::Variable,
+        _addr: &Self::Variable,
+        _len: &Self::Variable,
+    ) {
+        // No-op, witness only
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.constraints.clear();
@@ -29,12 +29,56 @@
 //! most significant bit being the sign - 1 for negative, 0 for positive).
 //! By default, unsigned operations are performed.
 
-use super::registers::{REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_NEXT_IP};
+use super::{
+    registers::{
+        REGISTER_CURRENT_IP, REGISTER_HEAP_POINTER, REGISTER_NEXT_IP, REGISTER_PREIMAGE_KEY_END,
+        REGISTER_PREIMAGE_OFFSET, REGISTER_PROGRAM_BREAK,
+    },
+    PAGE_ADDRESS_SIZE,
+};
 use crate::lookups::{Lookup, LookupTableIDs};
 use ark_ff::{One, Zero};
 use strum::{EnumCount, IntoEnumIterator};
 use strum_macros::{EnumCount, EnumIter};
 
+pub const FD_STDIN: u32 = 0;
+pub const FD_STDOUT: u32 = 1;
+pub const FD_STDERR: u32 = 2;
+pub const FD_HINT_READ: u32 = 3;
+pub const FD_HINT_WRITE: u32 = 4;
+pub const FD_PREIMAGE_READ: u32 = 5;
+pub const FD_PREIMAGE_WRITE: u32 = 6;
+
+/// The instruction `ecall`. The syscall is given by the register `a7`, its
+/// arguments by the registers `a0` to `a5`, and its result is returned in
+/// `a0`, following the Linux ABI.
+pub const ECALL: u32 = 0x00000073;
+
+/// The opcode of the `SYSTEM` instructions, i.e. `ecall`, `ebreak` and the CSR
+/// instructions
+pub const OPCODE_SYSTEM: u32 = 0b1110011;
+
+/// The exit code reported when the program stops on a `SYSTEM` instruction
+/// other than `ecall`, e.g. `ebreak` or `unimp`
+pub const EXIT_CODE_ABORT: u32 = 1;
+
+/// Syscall used by the test programs to stop the execution successfully
+pub const SYSCALL_SUCCESS: u32 = 42;
+pub const SYSCALL_READ: u32 = 63;
+pub const SYSCALL_WRITE: u32 = 64;
+pub const SYSCALL_EXIT: u32 = 93;
+pub const SYSCALL_EXIT_GROUP: u32 = 94;
+pub const SYSCALL_BRK: u32 = 214;
+pub const SYSCALL_MMAP: u32 = 222;
+
+/// The error returned for an invalid file descriptor, i.e. `-EBADF`
+pub const ERROR_BAD_FD: u32 = -9i32 as u32;
+/// The error returned for a buffer that goes past the end of the address
+/// space, i.e. `-EFAULT`
+pub const ERROR_FAULT: u32 = -14i32 as u32;
+/// The error returned for an unsupported syscall, i.e. `-ENOSYS`
+pub const ERROR_NO_SYSCALL: u32 = -38i32 as u32;
+
 #[derive(Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Hash, Ord, PartialOrd)]
 pub enum Instruction {
     RType(RInstruction),
@@ -308,8 +352,36 @@ pub enum UJInstruction {
     Debug, Clone, Copy, Eq, PartialEq, EnumCount, EnumIter, Default, Hash, Ord, PartialOrd,
 )]
 pub enum SyscallInstruction {
+    /// Syscall `42`, used by the test programs to stop successfully
     #[default]
     SyscallSuccess,
+    /// Syscalls `exit` and `exit_group`, stopping with the status in `a0`
+    SyscallExit,
+    /// Syscall `read` of the hint file descriptor
+    SyscallReadHint,
+    /// Syscall `read` of the preimage file descriptor, reading the preimage
+    /// of the current key
+    SyscallReadPreimage,
+    /// Syscall `read` of the other file descriptors. The standard input is
+    /// always empty.
+    SyscallReadOther,
+    /// Syscall `write` to the hint file descriptor
+    SyscallWriteHint,
+    /// Syscall `write` to the preimage file descriptor, updating the key of
+    /// the preimage
+    SyscallWritePreimage,
+    /// Syscall `write` to the other file descriptors, i.e. the standard output
+    /// and the standard error
+    SyscallWriteOther,
+    /// Syscall `brk`, moving the end of the data segment
+    SyscallBrk,
+    /// Syscall `mmap`, allocating pages on the heap
+    SyscallMmap,
+    /// The syscalls that are not supported, returning `-ENOSYS`
+    SyscallOther,
+    /// The `SYSTEM` instructions other than `ecall`, i.e. `ebreak`, the CSR
+    /// instructions and `unimp`, stopping with the status [EXIT_CODE_ABORT]
+    SyscallAbort,
 }
 
 /// M extension instructions
@@ -598,6 +670,13 @@ pub trait InterpreterEnv {
         self.add_constraint(x - y);
     }
 
+    /// Assert that the values `x` and `y` are different, and add constraints in
+    /// the proof system, the inverse of their difference being the witness.
+    fn assert_not_equal(&mut self, x: &Self::Variable, y: &Self::Variable) {
+        let is_equal = self.equal(x, y);
+        self.assert_is_zero(is_equal);
+    }
+
     /// Check that the witness value `x` is a boolean (`0` or `1`); otherwise abort.
     fn check_boolean(x: &Self::Variable);
 
@@ -1398,6 +1477,31 @@ pub trait InterpreterEnv {
 
     fn report_exit(&mut self, exit_code: &Self::Variable);
 
+    /// Request the preimage oracle for `len` bytes and store the bytes starting
+    /// from `addr`, and it returns the number of bytes actually read.
+    /// The number of bytes actually read will be set into `pos`.
+    /// The first 8 bytes will be the length of the preimage, encoded as an
+    /// unsigned 64bits, and the rest will be the preimage.
+    fn request_preimage_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable;
+
+    fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable);
+
+    /// Write the `len` bytes starting from `addr` to the output given by the
+    /// file descriptor `fd`, i.e. the standard output or the standard error.
+    /// Nothing is written for the other file descriptors, or if the bytes go
+    /// past the end of the address space.
+    fn request_output_write(
+        &mut self,
+        fd: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    );
+
     fn reset(&mut self);
 }
 
@@ -2196,9 +2300,444 @@ pub fn interpret_ujtype<Env: InterpreterEnv>(env: &mut Env, instr: UJInstruction
     };
 }
 
-pub fn interpret_syscall<Env: InterpreterEnv>(env: &mut Env, _instr: SyscallInstruction) {
-    // FIXME: check if it is syscall success. There is only one syscall atm
-    env.set_halted(Env::constant(1));
+/// Interpret a syscall, i.e. the instruction `ecall`.
+/// The syscall is selected by the register `a7` (x17), its arguments are given
+/// by the registers `a0` (x10), `a1` (x11) and `a2` (x12), and its result is
+/// written in `a0`, following the Linux ABI. The errors are returned as the
+/// negated error numbers.
+/// The file descriptors and the preimage oracle follow the conventions of
+/// Cannon, as in the MIPS interpreter.
+pub fn interpret_syscall<Env: InterpreterEnv>(env: &mut Env, instr: SyscallInstruction) {
+    let instruction_pointer = env.get_instruction_pointer();
+    let next_instruction_pointer = env.get_next_instruction_pointer();
+
+    let instruction = {
+        let v0 = env.read_memory(&instruction_pointer);
+        let v1 = env.read_memory(&(instruction_pointer.clone() + Env::constant(1)));
+        let v2 = env.read_memory(&(instruction_pointer.clone() + Env::constant(2)));
+        let v3 = env.read_memory(&(instruction_pointer.clone() + Env::constant(3)));
+        (v3 * Env::constant(1 << 24))
+            + (v2 * Env::constant(1 << 16))
+            + (v1 * Env::constant(1 << 8))
+            + v0
+    };
+    if instr == SyscallInstruction::SyscallAbort {
+        let opcode = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&instruction, 7, 0, pos) }
+        };
+        env.range_check8(&opcode, 7);
+
+        let rd = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&instruction, 12, 7, pos) }
+        };
+        env.range_check8(&rd, 5);
+
+        let funct3 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&instruction, 15, 12, pos) }
+        };
+        env.range_check8(&funct3, 3);
+
+        let rs1 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&instruction, 20, 15, pos) }
+        };
+        env.range_check8(&rs1, 5);
+
+        let funct12 = {
+            let pos = env.alloc_scratch();
+            unsafe { env.bitmask(&instruction, 32, 20, pos) }
+        };
+        env.range_check16(&funct12, 12);
+
+        // Check decomposition of the SYSTEM instruction
+        env.add_constraint(
+            instruction.clone()
+                - (opcode.clone() * Env::constant(1 << 0))    // opcode at bits 0-6
+                - (rd.clone() * Env::constant(1 << 7))        // rd at bits 7-11
+                - (funct3.clone() * Env::constant(1 << 12))   // funct3 at bits 12-14
+                - (rs1.clone() * Env::constant(1 << 15))      // rs1 at bits 15-19
+                - (funct12.clone() * Env::constant(1 << 20)), // funct12 at bits 20-31
+        );
+        env.assert_equal(opcode, Env::constant(OPCODE_SYSTEM));
+        // The instruction is not `ecall`, i.e. funct3, rs1, rd and funct12 are
+        // not all zero
+        env.assert_not_equal(&instruction, &Env::constant(ECALL));
+    } else {
+        env.assert_equal(instruction, Env::constant(ECALL));
+    }
+
+    let syscall_number = env.read_register(&Env::constant(17));
+    let mut check_syscall_number = |expected: u32| {
+        env.assert_equal(syscall_number.clone(), Env::constant(expected));
+    };
+
+    match instr {
+        SyscallInstruction::SyscallSuccess => {
+            check_syscall_number(SYSCALL_SUCCESS);
+            env.report_exit(&Env::constant(0));
+            env.set_halted(Env::constant(1));
+        }
+        SyscallInstruction::SyscallAbort => {
+            env.report_exit(&Env::constant(EXIT_CODE_ABORT));
+            env.set_halted(Env::constant(1));
+        }
+        SyscallInstruction::SyscallExit => {
+            let is_exit_group = env.equal(&syscall_number, &Env::constant(SYSCALL_EXIT_GROUP));
+            env.assert_equal(syscall_number, Env::constant(SYSCALL_EXIT) + is_exit_group);
+            let exit_code = env.read_register(&Env::constant(10));
+            env.report_exit(&exit_code);
+            env.set_halted(Env::constant(1));
+        }
+        SyscallInstruction::SyscallReadHint => {
+            check_syscall_number(SYSCALL_READ);
+            let fd_id = env.read_register(&Env::constant(10));
+            env.assert_equal(fd_id, Env::constant(FD_HINT_READ));
+            // We don't really write here, since the value is unused, per the cannon
+            // implementation. Just claim that we wrote the correct length.
+            let length = env.read_register(&Env::constant(12));
+            env.write_register(&Env::constant(10), length);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallReadPreimage => {
+            check_syscall_number(SYSCALL_READ);
+            let fd_id = env.read_register(&Env::constant(10));
+            env.assert_equal(fd_id, Env::constant(FD_PREIMAGE_READ));
+            let addr = env.read_register(&Env::constant(11));
+            let length = env.read_register(&Env::constant(12));
+            let preimage_offset =
+                env.read_register(&Env::constant(REGISTER_PREIMAGE_OFFSET as u32));
+
+            let read_length = {
+                let pos = env.alloc_scratch();
+                env.request_preimage_write(&addr, &length, pos)
+            };
+            env.write_register(
+                &Env::constant(REGISTER_PREIMAGE_OFFSET as u32),
+                preimage_offset + read_length.clone(),
+            );
+            env.write_register(&Env::constant(10), read_length);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallReadOther => {
+            check_syscall_number(SYSCALL_READ);
+            let fd_id = env.read_register(&Env::constant(10));
+            // The reads of the hint and of the preimage are the other syscalls
+            env.assert_not_equal(&fd_id, &Env::constant(FD_HINT_READ));
+            env.assert_not_equal(&fd_id, &Env::constant(FD_PREIMAGE_READ));
+            let is_stdin = env.equal(&fd_id, &Env::constant(FD_STDIN));
+            let other_fd = Env::constant(1) - is_stdin;
+
+            // We're either reading stdin, in which case we get `0` (the end of
+            // the file) as desired, or we've hit a bad FD that we reject with
+            // EBADF.
+            let result = other_fd * Env::constant(ERROR_BAD_FD);
+
+            env.write_register(&Env::constant(10), result);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallWriteHint => {
+            check_syscall_number(SYSCALL_WRITE);
+            let fd_id = env.read_register(&Env::constant(10));
+            env.assert_equal(fd_id, Env::constant(FD_HINT_WRITE));
+            let addr = env.read_register(&Env::constant(11));
+            let length = env.read_register(&Env::constant(12));
+            env.request_hint_write(&addr, &length);
+            env.write_register(&Env::constant(10), length);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallWritePreimage => {
+            check_syscall_number(SYSCALL_WRITE);
+            let fd_id = env.read_register(&Env::constant(10));
+            env.assert_equal(fd_id, Env::constant(FD_PREIMAGE_WRITE));
+            let addr = env.read_register(&Env::constant(11));
+            let write_length = env.read_register(&Env::constant(12));
+
+            // Cannon assumes that the remaining `byte_length` represents how much remains to be
+            // read (i.e. all write calls send the full data in one syscall, and attempt to retry
+            // with the rest until there is a success). This also simplifies the implementation
+            // here, so we will follow suit.
+            let bytes_to_preserve_in_register = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&write_length, 2, 0, pos) }
+            };
+            env.lookup_2bits(&bytes_to_preserve_in_register);
+            let register_idx = {
+                let registers_left_to_write_after_this = {
+                    let pos = env.alloc_scratch();
+                    // The virtual register is 32 bits wide, so we can just read 6 bytes. If the
+                    // register has an incorrect value, it will be unprovable and we'll fault.
+                    unsafe { env.bitmask(&write_length, 6, 2, pos) }
+                };
+                env.range_check8(&registers_left_to_write_after_this, 4);
+                Env::constant(REGISTER_PREIMAGE_KEY_END as u32) - registers_left_to_write_after_this
+            };
+
+            let [r0, r1, r2, r3] = {
+                let register_value = {
+                    let initial_register_value = env.read_register(&register_idx);
+
+                    // We should clear the register if our offset into the read will replace all of its
+                    // bytes.
+                    let should_clear_register = env.is_zero(&bytes_to_preserve_in_register);
+
+                    let pos = env.alloc_scratch();
+                    env.copy(
+                        &((Env::constant(1) - should_clear_register) * initial_register_value),
+                        pos,
+                    )
+                };
+                [
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 32, 24, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 24, 16, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 16, 8, pos) }
+                    },
+                    {
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&register_value, 8, 0, pos) }
+                    },
+                ]
+            };
+            env.lookup_8bits(&r0);
+            env.lookup_8bits(&r1);
+            env.lookup_8bits(&r2);
+            env.lookup_8bits(&r3);
+
+            // We choose our read address so that the bytes we read come aligned with the target
+            // bytes in the register, to avoid an expensive bitshift.
+            let read_address = addr.clone() - bytes_to_preserve_in_register.clone();
+
+            let m0 = env.read_memory(&read_address);
+            let m1 = env.read_memory(&(read_address.clone() + Env::constant(1)));
+            let m2 = env.read_memory(&(read_address.clone() + Env::constant(2)));
+            let m3 = env.read_memory(&(read_address.clone() + Env::constant(3)));
+
+            // Now, for some complexity. From the perspective of the write operation, we should be
+            // reading the `4 - bytes_to_preserve_in_register`. However, to match cannon 1:1, we
+            // only want to read the bytes up to the end of the current word.
+            let [overwrite_0, overwrite_1, overwrite_2, overwrite_3] = {
+                let next_word_addr = {
+                    let byte_subaddr = {
+                        // FIXME: Requires a range check
+                        let pos = env.alloc_scratch();
+                        unsafe { env.bitmask(&addr, 2, 0, pos) }
+                    };
+                    env.lookup_2bits(&byte_subaddr);
+                    addr.clone() + Env::constant(4) - byte_subaddr
+                };
+                let overwrite_0 = {
+                    // We always write the first byte if we're not preserving it, since it will
+                    // have been read from `addr`.
+                    env.equal(&bytes_to_preserve_in_register, &Env::constant(0))
+                };
+                let overwrite_1 = {
+                    // We write the second byte if:
+                    //   we wrote the first byte
+                    overwrite_0.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_0`),
+                    - env.equal(&(read_address.clone() + Env::constant(1)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(1))
+                };
+                let overwrite_2 = {
+                    // We write the third byte if:
+                    //   we wrote the second byte
+                    overwrite_1.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_1`),
+                    - env.equal(&(read_address.clone() + Env::constant(2)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(2))
+                };
+                let overwrite_3 = {
+                    // We write the fourth byte if:
+                    //   we wrote the third byte
+                    overwrite_2.clone()
+                    //   and this isn't the start of the next word (which implies `overwrite_2`),
+                    - env.equal(&(read_address.clone() + Env::constant(3)), &next_word_addr)
+                    //   or this byte was read from `addr`
+                    + env.equal(&bytes_to_preserve_in_register, &Env::constant(3))
+                };
+                [overwrite_0, overwrite_1, overwrite_2, overwrite_3]
+            };
+
+            let value = {
+                let value = ((overwrite_0.clone() * m0
+                    + (Env::constant(1) - overwrite_0.clone()) * r0)
+                    * Env::constant(1 << 24))
+                    + ((overwrite_1.clone() * m1 + (Env::constant(1) - overwrite_1.clone()) * r1)
+                        * Env::constant(1 << 16))
+                    + ((overwrite_2.clone() * m2 + (Env::constant(1) - overwrite_2.clone()) * r2)
+                        * Env::constant(1 << 8))
+                    + (overwrite_3.clone() * m3 + (Env::constant(1) - overwrite_3.clone()) * r3);
+                let pos = env.alloc_scratch();
+                env.copy(&value, pos)
+            };
+
+            // Update the preimage key.
+            env.write_register(&register_idx, value);
+            // Reset the preimage offset.
+            env.write_register(
+                &Env::constant(REGISTER_PREIMAGE_OFFSET as u32),
+                Env::constant(0u32),
+            );
+            // Return the number of bytes read.
+            env.write_register(
+                &Env::constant(10),
+                overwrite_0 + overwrite_1 + overwrite_2 + overwrite_3,
+            );
+
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallWriteOther => {
+            check_syscall_number(SYSCALL_WRITE);
+            let fd_id = env.read_register(&Env::constant(10));
+            let addr = env.read_register(&Env::constant(11));
+            let write_length = env.read_register(&Env::constant(12));
+            // The writes of the hint and of the preimage are the other syscalls
+            env.assert_not_equal(&fd_id, &Env::constant(FD_HINT_WRITE));
+            env.assert_not_equal(&fd_id, &Env::constant(FD_PREIMAGE_WRITE));
+            let is_stdout = env.equal(&fd_id, &Env::constant(FD_STDOUT));
+            let is_stderr = env.equal(&fd_id, &Env::constant(FD_STDERR));
+            let known_fd = is_stdout + is_stderr;
+            let other_fd = Env::constant(1) - known_fd.clone();
+
+            // The buffer faults if it goes past the end of the address space,
+            // i.e. if the 32 bits addition of its address and of its length
+            // overflows
+            let (end, is_fault) = {
+                let end_pos = env.alloc_scratch();
+                let overflow_pos = env.alloc_scratch();
+                unsafe { env.add_witness(&addr, &write_length, end_pos, overflow_pos) }
+            };
+            env.assert_boolean(is_fault.clone());
+            let end_low = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&end, 16, 0, pos) }
+            };
+            env.lookup_16bits(&end_low);
+            let end_high = {
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&end, 32, 16, pos) }
+            };
+            env.lookup_16bits(&end_high);
+            env.add_constraint(end.clone() - end_high * Env::constant(1 << 16) - end_low);
+            env.add_constraint(
+                addr.clone() + write_length.clone()
+                    - end
+                    - is_fault.clone() * Env::constant(1 << 16) * Env::constant(1 << 16),
+            );
+
+            env.request_output_write(&fd_id, &addr, &write_length);
+
+            // We're either writing to stdout or stderr, in which case all the
+            // bytes are written unless the buffer faults, which we reject with
+            // EFAULT, or we've hit a bad FD that we reject with EBADF.
+            let written = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(is_fault.clone() * Env::constant(ERROR_FAULT)
+                        + (Env::constant(1) - is_fault) * write_length),
+                    pos,
+                )
+            };
+            let result = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(known_fd * written + other_fd * Env::constant(ERROR_BAD_FD)),
+                    pos,
+                )
+            };
+
+            env.write_register(&Env::constant(10), result);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallBrk => {
+            check_syscall_number(SYSCALL_BRK);
+            let requested_break = env.read_register(&Env::constant(10));
+            let program_break = env.read_register(&Env::constant(REGISTER_PROGRAM_BREAK as u32));
+            // The current break is returned when the requested one is `0`.
+            // FIXME: Linux also rejects a break below the end of the data
+            // segment of the program.
+            let requested_break_is_zero = env.is_zero(&requested_break);
+            let new_break = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(requested_break_is_zero.clone() * program_break
+                        + (Env::constant(1) - requested_break_is_zero) * requested_break),
+                    pos,
+                )
+            };
+            env.write_register(
+                &Env::constant(REGISTER_PROGRAM_BREAK as u32),
+                new_break.clone(),
+            );
+            env.write_register(&Env::constant(10), new_break);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallMmap => {
+            check_syscall_number(SYSCALL_MMAP);
+            let requested_alloc_size = env.read_register(&Env::constant(11));
+            let size_in_pages = {
+                // FIXME: Requires a range check
+                let pos = env.alloc_scratch();
+                unsafe { env.bitmask(&requested_alloc_size, 32, PAGE_ADDRESS_SIZE, pos) }
+            };
+            let requires_extra_page = {
+                let remainder = requested_alloc_size
+                    - (size_in_pages.clone() * Env::constant(1 << PAGE_ADDRESS_SIZE));
+                Env::constant(1) - env.is_zero(&remainder)
+            };
+            let actual_alloc_size =
+                (size_in_pages + requires_extra_page) * Env::constant(1 << PAGE_ADDRESS_SIZE);
+            let address = env.read_register(&Env::constant(10));
+            let address_is_zero = env.is_zero(&address);
+            let old_heap_ptr = env.increase_heap_pointer(&actual_alloc_size, &address_is_zero);
+            let return_position = {
+                let pos = env.alloc_scratch();
+                env.copy(
+                    &(address_is_zero.clone() * old_heap_ptr
+                        + (Env::constant(1) - address_is_zero) * address),
+                    pos,
+                )
+            };
+            env.write_register(&Env::constant(10), return_position);
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+        SyscallInstruction::SyscallOther => {
+            for supported in [
+                SYSCALL_SUCCESS,
+                SYSCALL_READ,
+                SYSCALL_WRITE,
+                SYSCALL_EXIT,
+                SYSCALL_EXIT_GROUP,
+                SYSCALL_BRK,
+                SYSCALL_MMAP,
+            ] {
+                env.assert_not_equal(&syscall_number, &Env::constant(supported));
+            }
+            env.write_register(&Env::constant(10), Env::constant(ERROR_NO_SYSCALL));
+            env.set_instruction_pointer(next_instruction_pointer.clone());
+            env.set_next_instruction_pointer(next_instruction_pointer + Env::constant(4u32));
+        }
+    }
 }
 
 /// Interpret an M-type instruction.
@@ -1,10 +1,16 @@
-/// The minimal number of columns required for the VM
+/// The minimal number of columns required for the VM, without the columns
+/// of the communication channel with the preimage oracle
 // FIXME: the value will be updated when the interpreter is fully
 // implemented. Using a small value for now.
-pub const SCRATCH_SIZE: usize = 80;
+pub const SCRATCH_SIZE_WITHOUT_KECCAK: usize = 80;
+
+/// The minimal number of columns required for the VM
+// VM + hash_counter + byte_counter + eof + num_bytes_read + chunk + bytes
+// + length + has_n_bytes + preimage
+pub const SCRATCH_SIZE: usize = SCRATCH_SIZE_WITHOUT_KECCAK + 5 + 4 + 4 + 4 + 1;
 
 /// Number of instructions in the ISA
-pub const INSTRUCTION_SET_SIZE: usize = 48;
+pub const INSTRUCTION_SET_SIZE: usize = 59;
 
 pub const PAGE_ADDRESS_SIZE: u32 = 12;
 pub const PAGE_SIZE: u32 = 1 << PAGE_ADDRESS_SIZE;
@@ -20,6 +26,9 @@ pub mod interpreter;
 /// All the registers used by the ISA
 pub mod registers;
 
+/// The state of a program loaded from an ELF file
+pub mod state;
+
 pub mod witness;
 
 #[cfg(test)]
@@ -7,6 +7,10 @@ pub const N_GP_REGISTERS: usize = 32;
 pub const REGISTER_CURRENT_IP: usize = N_GP_REGISTERS + 1;
 pub const REGISTER_NEXT_IP: usize = N_GP_REGISTERS + 2;
 pub const REGISTER_HEAP_POINTER: usize = N_GP_REGISTERS + 3;
+pub const REGISTER_PROGRAM_BREAK: usize = N_GP_REGISTERS + 4;
+pub const REGISTER_PREIMAGE_KEY_START: usize = N_GP_REGISTERS + 5;
+pub const REGISTER_PREIMAGE_KEY_END: usize = REGISTER_PREIMAGE_KEY_START + 8;
+pub const REGISTER_PREIMAGE_OFFSET: usize = REGISTER_PREIMAGE_KEY_END;
 
 /// This represents the internal state of the virtual machine.
 #[derive(Clone, Default, Debug, Serialize, Deserialize)]
@@ -28,7 +32,16 @@ pub struct Registers<T> {
     pub general_purpose: [T; N_GP_REGISTERS],
     pub current_instruction_pointer: T,
     pub next_instruction_pointer: T,
+    /// The end of the memory allocated with `mmap`
     pub heap_pointer: T,
+    /// The end of the data segment of the program, moved with `brk`
+    pub program_break: T,
+    /// The key of the preimage requested to the preimage oracle, as 8 words
+    /// of 32 bits
+    pub preimage_key: [T; 8],
+    /// The number of bytes of the preimage (prefixed by its length) already
+    /// read
+    pub preimage_offset: T,
 }
 
 impl<T: Clone> Index<usize> for Registers<T> {
@@ -43,6 +56,12 @@ impl<T: Clone> Index<usize> for Registers<T> {
             &self.next_instruction_pointer
         } else if index == REGISTER_HEAP_POINTER {
             &self.heap_pointer
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &self.program_break
+        } else if (REGISTER_PREIMAGE_KEY_START..REGISTER_PREIMAGE_KEY_END).contains(&index) {
+            &self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
+        } else if index == REGISTER_PREIMAGE_OFFSET {
+            &self.preimage_offset
         } else {
             panic!("Index out of bounds");
         }
@@ -59,6 +78,12 @@ impl<T: Clone> IndexMut<usize> for Registers<T> {
             &mut self.next_instruction_pointer
         } else if index == REGISTER_HEAP_POINTER {
             &mut self.heap_pointer
+        } else if index == REGISTER_PROGRAM_BREAK {
+            &mut self.program_break
+        } else if (REGISTER_PREIMAGE_KEY_START..REGISTER_PREIMAGE_KEY_END).contains(&index) {
+            &mut self.preimage_key[index - REGISTER_PREIMAGE_KEY_START]
+        } else if index == REGISTER_PREIMAGE_OFFSET {
+            &mut self.preimage_offset
         } else {
             panic!("Index out of bounds");
         }
@@ -0,0 +1,79 @@
+use crate::cannon::Page;
+use serde::{Deserialize, Serialize};
+
+/// The permissions of a segment of the memory, as given by the flags of its
+/// program header
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Permissions {
+    pub read: bool,
+    pub write: bool,
+    pub execute: bool,
+}
+
+impl std::fmt::Display for Permissions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let flag = |set: bool, c: char| if set { c } else { '-' };
+        write!(
+            f,
+            "{}{}{}",
+            flag(self.read, 'r'),
+            flag(self.write, 'w'),
+            flag(self.execute, 'x')
+        )
+    }
+}
+
+/// A segment of the memory loaded from the program, i.e. a `PT_LOAD` segment
+/// of the ELF file
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment {
+    /// The address of the first byte of the segment
+    pub start: u32,
+    /// The size of the segment in memory, including the bytes zeroed after
+    /// the data of the file (e.g. `.bss`)
+    pub size: u32,
+    pub permissions: Permissions,
+}
+
+impl Segment {
+    /// Return true if the segment contains the address `addr`
+    pub fn contains(&self, addr: u32) -> bool {
+        addr >= self.start && addr - self.start < self.size
+    }
+}
+
+/// The state of a RISC-V 32IM program, as loaded from its ELF file
+#[derive(Serialize, Deserialize, Debug)]
+pub struct State {
+    /// The pages of the memory, with the segments of the program and the
+    /// initial stack
+    pub memory: Vec<Page>,
+    /// The segments of the program, in ascending order of address
+    pub segments: Vec<Segment>,
+    /// The entry point of the program
+    pub entry: u32,
+    pub pc: u32,
+    pub next_pc: u32,
+    /// The general purpose registers, with the stack pointer set to the
+    /// arguments of the program
+    pub registers: [u32; 32],
+    /// The end of the data of the program, moved by the `brk` syscall
+    pub program_break: u32,
+    /// The address of the next memory mapping of the `mmap` syscall
+    pub heap: u32,
+    pub preimage_key: [u8; 32],
+    pub preimage_offset: u32,
+    pub preimage: Option<Vec<u8>>,
+    pub last_hint: Option<Vec<u8>>,
+    pub exit: u8,
+    pub exited: bool,
+    pub step: u64,
+}
+
+impl State {
+    /// Return the segment of the program containing the address `addr`, if
+    /// any
+    pub fn segment(&self, addr: u32) -> Option<&Segment> {
+        self.segments.iter().find(|segment| segment.contains(addr))
+    }
+}
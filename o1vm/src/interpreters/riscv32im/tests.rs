@@ -1,13 +1,32 @@
-use super::{registers::Registers, witness::Env, INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE};
-use crate::interpreters::riscv32im::{
-    constraints,
-    interpreter::{
-        IInstruction, Instruction, InterpreterEnv, MInstruction, RInstruction, SBInstruction,
-        SInstruction, SyscallInstruction, UInstruction, UJInstruction,
+use super::{
+    column::{Column, E},
+    registers::Registers,
+    witness::{Env, SyscallEnv},
+    INSTRUCTION_SET_SIZE, PAGE_SIZE, SCRATCH_SIZE,
+};
+use crate::{
+    cannon::{Hint, Preimage},
+    interpreters::riscv32im::{
+        constraints,
+        interpreter::{
+            interpret_instruction, IInstruction, Instruction, InterpreterEnv, MInstruction,
+            RInstruction, SBInstruction, SInstruction, SyscallInstruction, UInstruction,
+            UJInstruction, ECALL, ERROR_BAD_FD, ERROR_FAULT, EXIT_CODE_ABORT, FD_PREIMAGE_READ,
+            FD_PREIMAGE_WRITE, FD_STDERR, FD_STDIN, FD_STDOUT, SYSCALL_BRK, SYSCALL_EXIT,
+            SYSCALL_EXIT_GROUP, SYSCALL_MMAP, SYSCALL_READ, SYSCALL_SUCCESS, SYSCALL_WRITE,
+        },
+    },
+    preimage_oracle::PreImageOracleT,
+};
+use ark_ff::{Field, Zero};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallenges,
+        expr::{Constants, ExprInner, Operations, Variable},
     },
+    curve::KimchiCurve,
 };
-use ark_ff::Zero;
-use mina_curves::pasta::Fp;
+use mina_curves::pasta::{Fp, Vesta};
 use rand::{CryptoRng, Rng, RngCore};
 use strum::EnumCount;
 
@@ -38,10 +57,23 @@ fn test_regression_selectors_for_instructions() {
         .for_each(|c| assert!(c.degree(1, 0) == 2 || c.degree(1, 0) == 1));
 }
 
-pub fn dummy_env() -> Env<Fp> {
+/// A preimage oracle giving each key as its own preimage
+pub struct TestPreImageOracle;
+
+impl PreImageOracleT for TestPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        Preimage::create(key.to_vec())
+    }
+
+    fn hint(&mut self, _hint: Hint) {}
+}
+
+pub fn dummy_env() -> Env<Fp, TestPreImageOracle> {
     Env {
         instruction_counter: 0,
         memory: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
+        segments: vec![],
+        entry: 0,
         last_memory_accesses: [0; 3],
         memory_write_index: vec![(0, vec![0; PAGE_SIZE.try_into().unwrap()])],
         last_memory_write_index_accesses: [0; 3],
@@ -50,7 +82,13 @@ pub fn dummy_env() -> Env<Fp> {
         scratch_state_idx: 0,
         scratch_state: [Fp::zero(); SCRATCH_SIZE],
         halt: false,
+        syscall_env: SyscallEnv::default(),
         selector: INSTRUCTION_SET_SIZE,
+        preimage_oracle: TestPreImageOracle,
+        preimage: None,
+        preimage_bytes_read: 0,
+        preimage_key: None,
+        hash_counter: 0,
     }
 }
 
@@ -286,7 +324,7 @@ pub fn generate_random_and_instruction<RNG: RngCore + CryptoRng>(rng: &mut RNG)
 
 #[test]
 pub fn test_instruction_decoding_add() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_add_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -299,7 +337,7 @@ pub fn test_instruction_decoding_add() {
 
 #[test]
 pub fn test_instruction_decoding_sub() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sub_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -312,7 +350,7 @@ pub fn test_instruction_decoding_sub() {
 
 #[test]
 pub fn test_instruction_decoding_sll() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sll_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -325,7 +363,7 @@ pub fn test_instruction_decoding_sll() {
 
 #[test]
 pub fn test_instruction_decoding_slt() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_slt_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -338,7 +376,7 @@ pub fn test_instruction_decoding_slt() {
 
 #[test]
 pub fn test_instruction_decoding_sltu() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sltu_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -354,7 +392,7 @@ pub fn test_instruction_decoding_sltu() {
 
 #[test]
 pub fn test_instruction_decoding_xor() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_xor_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -367,7 +405,7 @@ pub fn test_instruction_decoding_xor() {
 
 #[test]
 pub fn test_instruction_decoding_srl() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_srl_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -380,7 +418,7 @@ pub fn test_instruction_decoding_srl() {
 
 #[test]
 pub fn test_instruction_decoding_sr1() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_sra_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -396,7 +434,7 @@ pub fn test_instruction_decoding_sr1() {
 
 #[test]
 pub fn test_instruction_decoding_or() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_or_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -409,7 +447,7 @@ pub fn test_instruction_decoding_or() {
 
 #[test]
 pub fn test_instruction_decoding_and() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     let mut rng = o1_utils::tests::make_test_rng(None);
     let instruction = generate_random_and_instruction(&mut rng);
     env.memory[0].1[0] = instruction[0];
@@ -422,7 +460,7 @@ pub fn test_instruction_decoding_and() {
 
 #[test]
 pub fn test_witness_bitmask_bounds() {
-    let mut env: Env<Fp> = dummy_env();
+    let mut env = dummy_env();
     // Checking that the bit position given as upper bound is not included in
     // the output, i.e. the output is v[LOWER_BOUND:UPPER_BOUND-1]
     {
@@ -447,3 +485,337 @@ pub fn test_witness_bitmask_bounds() {
         assert_eq!(output, exp_output);
     }
 }
+
+/// An environment whose first instruction is `ecall`, with the syscall
+/// `syscall` and the arguments `args` in the registers `a0`, `a1` and `a2`
+fn syscall_env(syscall: u32, args: [u32; 3]) -> Env<Fp, TestPreImageOracle> {
+    let mut env = dummy_env();
+    env.memory[0].1[0..4].copy_from_slice(&ECALL.to_le_bytes());
+    env.registers.next_instruction_pointer = 4;
+    env.registers.general_purpose[17] = syscall;
+    env.registers.general_purpose[10..13].copy_from_slice(&args);
+    env
+}
+
+#[test]
+pub fn test_instruction_decoding_syscalls() {
+    let cases = [
+        (SYSCALL_SUCCESS, 0, SyscallInstruction::SyscallSuccess),
+        (SYSCALL_EXIT, 0, SyscallInstruction::SyscallExit),
+        (SYSCALL_EXIT_GROUP, 0, SyscallInstruction::SyscallExit),
+        (SYSCALL_READ, 3, SyscallInstruction::SyscallReadHint),
+        (SYSCALL_READ, 5, SyscallInstruction::SyscallReadPreimage),
+        (SYSCALL_READ, FD_STDIN, SyscallInstruction::SyscallReadOther),
+        (SYSCALL_WRITE, 4, SyscallInstruction::SyscallWriteHint),
+        (SYSCALL_WRITE, 6, SyscallInstruction::SyscallWritePreimage),
+        (
+            SYSCALL_WRITE,
+            FD_STDOUT,
+            SyscallInstruction::SyscallWriteOther,
+        ),
+        (SYSCALL_BRK, 0, SyscallInstruction::SyscallBrk),
+        (SYSCALL_MMAP, 0, SyscallInstruction::SyscallMmap),
+        (1024, 0, SyscallInstruction::SyscallOther),
+    ];
+    for (syscall, fd, expected) in cases {
+        let mut env = syscall_env(syscall, [fd, 0, 0]);
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(opcode, Instruction::SyscallType(expected));
+    }
+}
+
+#[test]
+pub fn test_syscall_write_output() {
+    let message = b"Hello, world!";
+    let addr = 0x100;
+    for (fd, expected) in [(FD_STDOUT, message.len() as u32), (7, ERROR_BAD_FD)] {
+        let mut env = syscall_env(SYSCALL_WRITE, [fd, addr, message.len() as u32]);
+        env.memory[0].1[addr as usize..addr as usize + message.len()].copy_from_slice(message);
+        env.step();
+        assert_eq!(env.registers.general_purpose[10], expected);
+        assert_eq!(env.registers.current_instruction_pointer, 4);
+        assert_eq!(env.registers.next_instruction_pointer, 8);
+        assert!(!env.halt);
+        let expected_stdout: &[u8] = if fd == FD_STDOUT { message } else { &[] };
+        assert_eq!(env.syscall_env.stdout, expected_stdout);
+    }
+
+    let mut env = syscall_env(SYSCALL_WRITE, [FD_STDERR, addr, message.len() as u32]);
+    env.memory[0].1[addr as usize..addr as usize + message.len()].copy_from_slice(message);
+    env.step();
+    assert_eq!(env.syscall_env.stderr, message);
+}
+
+#[test]
+pub fn test_syscall_write_unmapped() {
+    // The pages that have not been allocated are read as zeros, without being
+    // allocated
+    let mut env = syscall_env(SYSCALL_WRITE, [FD_STDOUT, PAGE_SIZE, 4]);
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 4);
+    assert_eq!(env.syscall_env.stdout, vec![0; 4]);
+    assert_eq!(env.memory.len(), 1);
+
+    // The buffer wraps around the address space
+    let mut env = syscall_env(SYSCALL_WRITE, [FD_STDOUT, 0x100, u32::MAX]);
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], ERROR_FAULT);
+    assert_eq!(env.registers.next_instruction_pointer, 8);
+    assert!(env.syscall_env.stdout.is_empty());
+    assert_eq!(env.memory.len(), 1);
+}
+
+#[test]
+pub fn test_system_instructions_abort() {
+    // ebreak, csrrw x0, mstatus, x0 and unimp
+    for instruction in [0x00100073u32, 0x30001073, 0xC0001073] {
+        let mut env = dummy_env();
+        env.memory[0].1[0..4].copy_from_slice(&instruction.to_le_bytes());
+        env.registers.next_instruction_pointer = 4;
+        let (opcode, _instruction) = env.decode_instruction();
+        assert_eq!(
+            opcode,
+            Instruction::SyscallType(SyscallInstruction::SyscallAbort)
+        );
+        env.step();
+        assert!(env.halt);
+        assert_eq!(env.syscall_env.exit_code, Some(EXIT_CODE_ABORT));
+        assert_eq!(env.registers.current_instruction_pointer, 0);
+    }
+}
+
+#[test]
+pub fn test_syscall_read_other() {
+    // The standard input is empty
+    let mut env = syscall_env(SYSCALL_READ, [FD_STDIN, 0x100, 4]);
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 0);
+
+    let mut env = syscall_env(SYSCALL_READ, [9, 0x100, 4]);
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], ERROR_BAD_FD);
+}
+
+#[test]
+pub fn test_syscall_exit() {
+    let mut env = syscall_env(SYSCALL_EXIT_GROUP, [3, 0, 0]);
+    env.step();
+    assert!(env.halt);
+    assert_eq!(env.registers.current_instruction_pointer, 0);
+}
+
+#[test]
+pub fn test_syscall_brk() {
+    let mut env = syscall_env(SYSCALL_BRK, [0, 0, 0]);
+    env.registers.program_break = 0x2000;
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 0x2000);
+    assert_eq!(env.registers.program_break, 0x2000);
+
+    let mut env = syscall_env(SYSCALL_BRK, [0x3000, 0, 0]);
+    env.registers.program_break = 0x2000;
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 0x3000);
+    assert_eq!(env.registers.program_break, 0x3000);
+}
+
+#[test]
+pub fn test_syscall_mmap() {
+    // The size is rounded up to a number of pages
+    let mut env = syscall_env(SYSCALL_MMAP, [0, PAGE_SIZE + 1, 0]);
+    env.registers.heap_pointer = 0x10000;
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 0x10000);
+    assert_eq!(env.registers.heap_pointer, 0x10000 + 2 * PAGE_SIZE);
+
+    // A fixed address is returned as it is
+    let mut env = syscall_env(SYSCALL_MMAP, [0x20000, PAGE_SIZE, 0]);
+    env.registers.heap_pointer = 0x10000;
+    env.step();
+    assert_eq!(env.registers.general_purpose[10], 0x20000);
+    assert_eq!(env.registers.heap_pointer, 0x10000);
+}
+
+/// Run the syscall `syscall` until all the bytes have been processed, as a
+/// program retries with the remaining bytes
+fn run_syscall(
+    env: &mut Env<Fp, TestPreImageOracle>,
+    syscall: u32,
+    fd: u32,
+    mut addr: u32,
+    mut len: u32,
+) {
+    while len > 0 {
+        env.registers.current_instruction_pointer = 0;
+        env.registers.next_instruction_pointer = 4;
+        env.registers.general_purpose[17] = syscall;
+        env.registers.general_purpose[10] = fd;
+        env.registers.general_purpose[11] = addr;
+        env.registers.general_purpose[12] = len;
+        env.step();
+        let processed = env.registers.general_purpose[10];
+        assert!((1..=4).contains(&processed));
+        addr += processed;
+        len -= processed;
+    }
+}
+
+#[test]
+pub fn test_syscall_preimage() {
+    let key: [u8; 32] = core::array::from_fn(|i| i as u8 + 1);
+    let (key_addr, preimage_addr) = (0x100u32, 0x200u32);
+    let mut env = dummy_env();
+    env.memory[0].1[0..4].copy_from_slice(&ECALL.to_le_bytes());
+    env.memory[0].1[key_addr as usize..key_addr as usize + key.len()].copy_from_slice(&key);
+
+    run_syscall(
+        &mut env,
+        SYSCALL_WRITE,
+        FD_PREIMAGE_WRITE,
+        key_addr,
+        key.len() as u32,
+    );
+    let key_words: [u32; 8] =
+        core::array::from_fn(|i| u32::from_be_bytes(key[4 * i..4 * i + 4].try_into().unwrap()));
+    assert_eq!(env.registers.preimage_key, key_words);
+    assert_eq!(env.registers.preimage_offset, 0);
+
+    // The preimage is prefixed by its length, as 8 bytes
+    run_syscall(
+        &mut env,
+        SYSCALL_READ,
+        FD_PREIMAGE_READ,
+        preimage_addr,
+        8 + key.len() as u32,
+    );
+    assert_eq!(env.registers.preimage_offset, 8 + key.len() as u32);
+    assert_eq!(env.hash_counter, 1);
+    let read = &env.memory[0].1[preimage_addr as usize..preimage_addr as usize + 8 + key.len()];
+    assert_eq!(read[..8], (key.len() as u64).to_be_bytes());
+    assert_eq!(read[8..], key);
+}
+
+/// Evaluate the expression `expr` on the columns of a step of the witness
+/// environment `env`, the instruction counter being the one at the start of the
+/// step
+fn evaluate_step(
+    expr: &E<Fp>,
+    env: &Env<Fp, TestPreImageOracle>,
+    instruction_counter: u64,
+    constants: &Constants<Fp>,
+    challenges: &BerkeleyChallenges<Fp>,
+) -> Fp {
+    let eval = |x: &E<Fp>| evaluate_step(x, env, instruction_counter, constants, challenges);
+    match expr {
+        Operations::Atom(ExprInner::Constant(c)) => c.value(constants, challenges),
+        Operations::Atom(ExprInner::Cell(Variable { col, row: _ })) => match col {
+            Column::ScratchState(i) => env.scratch_state[*i],
+            Column::InstructionCounter => Fp::from(instruction_counter),
+            Column::Selector(_) => Fp::from((env.selector == usize::from(col.clone())) as u64),
+        },
+        Operations::Atom(atom) => panic!("Unexpected atom {atom:?}"),
+        Operations::Pow(x, n) => eval(x).pow([*n]),
+        Operations::Add(x, y) => eval(x) + eval(y),
+        Operations::Mul(x, y) => eval(x) * eval(y),
+        Operations::Sub(x, y) => eval(x) - eval(y),
+        Operations::Double(x) => eval(x).double(),
+        Operations::Square(x) => eval(x).square(),
+        Operations::Cache(_, x) => eval(x),
+        Operations::IfFeature(..) => panic!("Unexpected feature flag"),
+    }
+}
+
+/// Run a step of the witness environment and check that the constraints of
+/// the syscall `syscall` hold on its witness
+fn check_syscall_constraints(mut env: Env<Fp, TestPreImageOracle>, syscall: SyscallInstruction) {
+    let instruction_counter = env.instruction_counter;
+    assert_eq!(env.step(), Instruction::SyscallType(syscall));
+
+    let mut constraints_env = constraints::Env::<Fp>::default();
+    interpret_instruction(&mut constraints_env, Instruction::SyscallType(syscall));
+    let constants = Constants {
+        endo_coefficient: Fp::zero(),
+        mds: &Vesta::sponge_params().mds,
+        zk_rows: 0,
+    };
+    let challenges = BerkeleyChallenges {
+        alpha: Fp::zero(),
+        beta: Fp::zero(),
+        gamma: Fp::zero(),
+        joint_combiner: Fp::zero(),
+    };
+    for (i, constraint) in constraints_env.get_constraints().iter().enumerate() {
+        let value = evaluate_step(
+            constraint,
+            &env,
+            instruction_counter,
+            &constants,
+            &challenges,
+        );
+        assert_eq!(value, Fp::zero(), "Constraint {i} of {syscall:?} fails");
+    }
+}
+
+#[test]
+pub fn test_syscall_constraints() {
+    check_syscall_constraints(
+        syscall_env(SYSCALL_SUCCESS, [0, 0, 0]),
+        SyscallInstruction::SyscallSuccess,
+    );
+    check_syscall_constraints(
+        syscall_env(SYSCALL_EXIT, [3, 0, 0]),
+        SyscallInstruction::SyscallExit,
+    );
+    check_syscall_constraints(
+        syscall_env(SYSCALL_READ, [3, 0x100, 4]),
+        SyscallInstruction::SyscallReadHint,
+    );
+    for fd in [FD_STDIN, 9] {
+        check_syscall_constraints(
+            syscall_env(SYSCALL_READ, [fd, 0x100, 4]),
+            SyscallInstruction::SyscallReadOther,
+        );
+    }
+    check_syscall_constraints(
+        syscall_env(SYSCALL_WRITE, [4, 0x100, 4]),
+        SyscallInstruction::SyscallWriteHint,
+    );
+    check_syscall_constraints(
+        syscall_env(SYSCALL_WRITE, [FD_PREIMAGE_WRITE, 0x100, 4]),
+        SyscallInstruction::SyscallWritePreimage,
+    );
+    for (fd, len) in [(FD_STDOUT, 4), (FD_STDERR, u32::MAX), (9, 4)] {
+        check_syscall_constraints(
+            syscall_env(SYSCALL_WRITE, [fd, 0x100, len]),
+            SyscallInstruction::SyscallWriteOther,
+        );
+    }
+    check_syscall_constraints(
+        syscall_env(SYSCALL_BRK, [0x3000, 0, 0]),
+        SyscallInstruction::SyscallBrk,
+    );
+    check_syscall_constraints(
+        syscall_env(SYSCALL_MMAP, [0, PAGE_SIZE + 1, 0]),
+        SyscallInstruction::SyscallMmap,
+    );
+    check_syscall_constraints(
+        syscall_env(1234, [0, 0, 0]),
+        SyscallInstruction::SyscallOther,
+    );
+
+    // The preimage is read once its key has been written
+    let mut env = dummy_env();
+    env.memory[0].1[0..4].copy_from_slice(&ECALL.to_le_bytes());
+    run_syscall(&mut env, SYSCALL_WRITE, FD_PREIMAGE_WRITE, 0x100, 32);
+    env.registers.current_instruction_pointer = 0;
+    env.registers.next_instruction_pointer = 4;
+    env.registers.general_purpose[17] = SYSCALL_READ;
+    env.registers.general_purpose[10..13].copy_from_slice(&[FD_PREIMAGE_READ, 0x200, 4]);
+    check_syscall_constraints(env, SyscallInstruction::SyscallReadPreimage);
+
+    // ebreak
+    let mut env = dummy_env();
+    env.memory[0].1[0..4].copy_from_slice(&0x00100073u32.to_le_bytes());
+    env.registers.next_instruction_pointer = 4;
+    check_syscall_constraints(env, SyscallInstruction::SyscallAbort);
+}
@@ -1,20 +1,39 @@
 // TODO: do we want to be more restrictive and refer to the number of accesses
 //       to the SAME register/memory addrss?
 use super::{
-    column::Column,
+    column::{
+        Column, RISCV_BYTE_COUNTER_OFF, RISCV_CHUNK_BYTES_LEN, RISCV_END_OF_PREIMAGE_OFF,
+        RISCV_HASH_COUNTER_OFF, RISCV_HAS_N_BYTES_OFF, RISCV_LENGTH_BYTES_OFF,
+        RISCV_NUM_BYTES_READ_OFF, RISCV_PREIMAGE_BYTES_OFF, RISCV_PREIMAGE_CHUNK_OFF,
+        RISCV_PREIMAGE_KEY,
+    },
     interpreter::{
         self, IInstruction, Instruction, InterpreterEnv, RInstruction, SBInstruction, SInstruction,
-        SyscallInstruction, UInstruction, UJInstruction,
+        SyscallInstruction, UInstruction, UJInstruction, ECALL, FD_HINT_READ, FD_HINT_WRITE,
+        FD_PREIMAGE_READ, FD_PREIMAGE_WRITE, FD_STDERR, FD_STDOUT, SYSCALL_BRK, SYSCALL_EXIT,
+        SYSCALL_EXIT_GROUP, SYSCALL_MMAP, SYSCALL_READ, SYSCALL_SUCCESS, SYSCALL_WRITE,
     },
     registers::Registers,
+    state::{Segment, State},
     INSTRUCTION_SET_SIZE, SCRATCH_SIZE,
 };
 use crate::{
-    cannon::{State, PAGE_ADDRESS_MASK, PAGE_ADDRESS_SIZE, PAGE_SIZE},
+    cannon::{
+        Hint, Meta, Page, Start, StepFrequency, VmConfiguration, PAGE_ADDRESS_MASK,
+        PAGE_ADDRESS_SIZE, PAGE_SIZE,
+    },
     lookups::Lookup,
+    preimage_oracle::PreImageOracleT,
+    utils::memory_size,
 };
 use ark_ff::Field;
-use std::array;
+use kimchi::o1_utils::Two;
+use log::{debug, info};
+use std::{
+    array,
+    fs::File,
+    io::{self, BufWriter, Write},
+};
 
 /// Maximum number of register accesses per instruction (based on demo)
 // FIXME: can be different
@@ -31,14 +50,45 @@ pub const NUM_INSTRUCTION_LOOKUP_TERMS: usize = 5;
 pub const NUM_LOOKUP_TERMS: usize =
     NUM_GLOBAL_LOOKUP_TERMS + NUM_DECODING_LOOKUP_TERMS + NUM_INSTRUCTION_LOOKUP_TERMS;
 
+#[derive(Clone, Default)]
+pub struct SyscallEnv {
+    pub last_hint: Option<Vec<u8>>,
+    /// The bytes written by the program to its standard output
+    pub stdout: Vec<u8>,
+    /// The bytes written by the program to its standard error
+    pub stderr: Vec<u8>,
+    /// The exit code of the program, once it has exited
+    pub exit_code: Option<u32>,
+}
+
+impl SyscallEnv {
+    pub fn create(state: &State) -> Self {
+        SyscallEnv {
+            last_hint: state.last_hint.clone(),
+            stdout: vec![],
+            stderr: vec![],
+            exit_code: if state.exited {
+                Some(state.exit as u32)
+            } else {
+                None
+            },
+        }
+    }
+}
+
 /// This structure represents the environment the virtual machine state will use
 /// to transition. This environment will be used by the interpreter. The virtual
 /// machine has access to its internal state and some external memory. In
 /// addition to that, it has access to the environment of the Keccak interpreter
 /// that is used to verify the preimage requested during the execution.
-pub struct Env<Fp> {
+pub struct Env<Fp, PreImageOracle: PreImageOracleT> {
     pub instruction_counter: u64,
     pub memory: Vec<(u32, Vec<u8>)>,
+    /// The segments of the program, whose permissions are checked when the
+    /// memory is written
+    pub segments: Vec<Segment>,
+    /// The entry point of the program
+    pub entry: u32,
     pub last_memory_accesses: [usize; 3],
     pub memory_write_index: Vec<(u32, Vec<u64>)>,
     pub last_memory_write_index_accesses: [usize; 3],
@@ -47,14 +97,20 @@ pub struct Env<Fp> {
     pub scratch_state_idx: usize,
     pub scratch_state: [Fp; SCRATCH_SIZE],
     pub halt: bool,
+    pub syscall_env: SyscallEnv,
     pub selector: usize,
+    pub preimage_oracle: PreImageOracle,
+    pub preimage: Option<Vec<u8>>,
+    pub preimage_bytes_read: u64,
+    pub preimage_key: Option<[u8; 32]>,
+    pub hash_counter: u64,
 }
 
 fn fresh_scratch_state<Fp: Field, const N: usize>() -> [Fp; N] {
     array::from_fn(|_| Fp::zero())
 }
 
-impl<Fp: Field> InterpreterEnv for Env<Fp> {
+impl<Fp: Field, PreImageOracle: PreImageOracleT> InterpreterEnv for Env<Fp, PreImageOracle> {
     type Position = Column;
 
     fn alloc_scratch(&mut self) -> Self::Position {
@@ -174,6 +230,12 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
 
     unsafe fn push_memory(&mut self, addr: &Self::Variable, value: Self::Variable) {
         let addr: u32 = (*addr).try_into().unwrap();
+        if let Some(segment) = self.segments.iter().find(|segment| segment.contains(addr)) {
+            assert!(
+                segment.permissions.write,
+                "Write to the address {addr:#x} of a segment without write permission"
+            );
+        }
         let page = addr >> PAGE_ADDRESS_SIZE;
         let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
         let memory_page_idx = self.get_memory_page_index(page);
@@ -301,12 +363,21 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn equal(&mut self, x: &Self::Variable, y: &Self::Variable) -> Self::Variable {
-        // To avoid subtraction overflow in the witness interpreter for u32
-        if x > y {
-            self.is_zero(&(*x - *y))
+        // write the result
+        let pos = self.alloc_scratch();
+        let res = if x == y { 1 } else { 0 };
+        self.write_column(pos, res);
+        // write the non deterministic advice inv_or_zero, the inverse of the
+        // difference in the field, as `x - y` may not fit in an u64
+        let pos = self.alloc_scratch();
+        let inv_or_zero = if x == y {
+            Fp::zero()
         } else {
-            self.is_zero(&(*y - *x))
-        }
+            Fp::inverse(&(Fp::from(*x) - Fp::from(*y))).unwrap()
+        };
+        self.write_field_column(pos, inv_or_zero);
+        // return the result
+        res
     }
 
     unsafe fn test_less_than(
@@ -612,13 +683,226 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 
     fn report_exit(&mut self, exit_code: &Self::Variable) {
-        println!(
+        self.syscall_env.exit_code = Some(*exit_code as u32);
+        info!(
             "Exited with code {} at step {}",
             *exit_code,
             self.normalized_instruction_counter()
         );
     }
 
+    fn request_preimage_write(
+        &mut self,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+        pos: Self::Position,
+    ) -> Self::Variable {
+        // The beginning of the syscall
+        if self.registers.preimage_offset == 0 {
+            let mut preimage_key = [0u8; 32];
+            for i in 0..8 {
+                let bytes = u32::to_be_bytes(self.registers.preimage_key[i]);
+                for j in 0..4 {
+                    preimage_key[4 * i + j] = bytes[j]
+                }
+            }
+            let preimage = self.preimage_oracle.get_preimage(preimage_key).get();
+            self.preimage = Some(preimage.clone());
+            self.preimage_key = Some(preimage_key);
+        }
+
+        const LENGTH_SIZE: usize = 8;
+
+        let preimage = self
+            .preimage
+            .as_ref()
+            .expect("to have a preimage if we're requesting it at a non-zero offset");
+        let preimage_len = preimage.len();
+        let preimage_offset = self.registers.preimage_offset as u64;
+
+        let max_read_len =
+            std::cmp::min(preimage_offset + len, (preimage_len + LENGTH_SIZE) as u64)
+                - preimage_offset;
+
+        // We read at most 4 bytes, ensuring that we respect word alignment.
+        // Here, if the address is not aligned, the first call will read < 4
+        // but the next calls will be 4 bytes (because the actual address would
+        // be updated with the offset) until reaching the end of the preimage
+        // (where the last call could be less than 4 bytes).
+        let actual_read_len = std::cmp::min(max_read_len, 4 - (addr & 3));
+
+        // This variable will contain the amount of bytes read which belong to
+        // the actual preimage
+        let mut preimage_read_len = 0;
+        let mut chunk = 0;
+        for i in 0..actual_read_len {
+            let idx = (preimage_offset + i) as usize;
+            // The first 8 bytes of the read preimage are the preimage length,
+            // followed by the body of the preimage
+            if idx < LENGTH_SIZE {
+                // Compute the byte index read from the length
+                let len_i = idx % RISCV_CHUNK_BYTES_LEN;
+
+                let length_byte = u64::to_be_bytes(preimage_len as u64)[idx];
+
+                // Write the individual byte of the length to the witness
+                self.write_column(
+                    Column::ScratchState(RISCV_LENGTH_BYTES_OFF + len_i),
+                    length_byte as u64,
+                );
+
+                unsafe {
+                    self.push_memory(&(*addr + i), length_byte as u64);
+                    self.push_memory_access(&(*addr + i), self.next_instruction_counter());
+                }
+            } else {
+                // Compute the byte index in the chunk of at most 4 bytes read
+                // from the preimage
+                let byte_i = (idx - LENGTH_SIZE) % RISCV_CHUNK_BYTES_LEN;
+
+                // This should really be handled by the keccak oracle.
+                let preimage_byte = self.preimage.as_ref().unwrap()[idx - LENGTH_SIZE];
+
+                // Write the individual byte of the preimage to the witness
+                self.write_column(
+                    Column::ScratchState(RISCV_PREIMAGE_BYTES_OFF + byte_i),
+                    preimage_byte as u64,
+                );
+
+                // Update the chunk of at most 4 bytes read from the preimage
+                chunk = chunk << 8 | preimage_byte as u64;
+
+                // At most, it will be actual_read_len when the length is not
+                // read in this call
+                preimage_read_len += 1;
+
+                unsafe {
+                    self.push_memory(&(*addr + i), preimage_byte as u64);
+                    self.push_memory_access(&(*addr + i), self.next_instruction_counter());
+                }
+            }
+        }
+        // Update the chunk of at most 4 bytes read from the preimage
+        self.write_column(Column::ScratchState(RISCV_PREIMAGE_CHUNK_OFF), chunk);
+
+        // Update the number of bytes read from the oracle in this step (can
+        // include bytelength and preimage bytes)
+        self.write_column(pos, actual_read_len);
+
+        // Number of preimage bytes processed in this instruction
+        self.write_column(
+            Column::ScratchState(RISCV_NUM_BYTES_READ_OFF),
+            preimage_read_len,
+        );
+
+        // Update the flags to count how many bytes are contained at least
+        for i in 0..RISCV_CHUNK_BYTES_LEN {
+            if preimage_read_len > i as u64 {
+                // This amount is only nonzero when it has read some preimage
+                // bytes.
+                self.write_column(Column::ScratchState(RISCV_HAS_N_BYTES_OFF + i), 1);
+            }
+        }
+
+        // Update the total number of preimage bytes read so far
+        self.preimage_bytes_read += preimage_read_len;
+        self.write_column(
+            Column::ScratchState(RISCV_BYTE_COUNTER_OFF),
+            self.preimage_bytes_read,
+        );
+
+        // If we've read the entire preimage, end the hash in the communication
+        // channel with the Keccak interpreter
+        if self.preimage_bytes_read == preimage_len as u64 {
+            self.write_column(Column::ScratchState(RISCV_END_OF_PREIMAGE_OFF), 1);
+
+            // Store preimage key in the witness excluding the MSB as 248 bits
+            // so it can be used for the communication channel between Keccak
+            let bytes31 = (1..32).fold(Fp::zero(), |acc, i| {
+                acc * Fp::two_pow(8) + Fp::from(self.preimage_key.unwrap()[i])
+            });
+            self.write_field_column(Self::Position::ScratchState(RISCV_PREIMAGE_KEY), bytes31);
+
+            // FIXME: run the Keccak interpreter on the preimage, as the MIPS
+            // witness does, when the RISC-V prover supports it.
+            debug!("Preimage has been read entirely");
+
+            // Update hash counter column
+            self.write_column(
+                Column::ScratchState(RISCV_HASH_COUNTER_OFF),
+                self.hash_counter,
+            );
+
+            // Reset environment
+            self.preimage_bytes_read = 0;
+            self.preimage_key = None;
+            self.hash_counter += 1;
+        }
+        actual_read_len
+    }
+
+    fn request_hint_write(&mut self, addr: &Self::Variable, len: &Self::Variable) {
+        let mut last_hint = match std::mem::take(&mut self.syscall_env.last_hint) {
+            Some(mut last_hint) => {
+                last_hint.reserve(*len as usize);
+                last_hint
+            }
+            None => Vec::with_capacity(*len as usize),
+        };
+
+        // This should really be handled by the keccak oracle.
+        for i in 0..*len {
+            // Push memory access
+            unsafe { self.push_memory_access(&(*addr + i), self.next_instruction_counter()) };
+            // Fetch the value without allocating witness columns
+            let value = self.get_memory_direct((*addr + i) as u32);
+            last_hint.push(value);
+        }
+
+        let len = last_hint.len();
+        let mut idx = 0;
+
+        while idx + 4 <= len {
+            let hint_len = u32::from_be_bytes(last_hint[idx..idx + 4].try_into().unwrap()) as usize;
+            idx += 4;
+            if idx + hint_len <= len {
+                let hint = last_hint[idx..idx + hint_len].to_vec();
+                idx += hint_len;
+                self.preimage_oracle.hint(Hint::create(hint));
+            }
+        }
+
+        let remaining = last_hint[idx..len].to_vec();
+
+        self.syscall_env.last_hint = Some(remaining);
+    }
+
+    fn request_output_write(
+        &mut self,
+        fd: &Self::Variable,
+        addr: &Self::Variable,
+        len: &Self::Variable,
+    ) {
+        let fd: u32 = (*fd).try_into().unwrap();
+        let is_fault = (*addr as u32).checked_add(*len as u32).is_none();
+        if is_fault || (fd != FD_STDOUT && fd != FD_STDERR) {
+            return;
+        }
+        // Fetch the values without allocating witness columns nor pages
+        let bytes: Vec<u8> = (0..*len)
+            .map(|i| self.peek_memory_direct((*addr + i) as u32))
+            .collect();
+        // The output is a side effect of the execution, failing to print it
+        // does not change the state of the program.
+        if fd == FD_STDOUT {
+            let _ = io::stdout().write_all(&bytes);
+            self.syscall_env.stdout.extend(bytes);
+        } else {
+            let _ = io::stderr().write_all(&bytes);
+            self.syscall_env.stderr.extend(bytes);
+        }
+    }
+
     fn reset(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -626,13 +910,15 @@ impl<Fp: Field> InterpreterEnv for Env<Fp> {
     }
 }
 
-impl<Fp: Field> Env<Fp> {
-    pub fn create(page_size: usize, state: State) -> Self {
+impl<Fp: Field, PreImageOracle: PreImageOracleT> Env<Fp, PreImageOracle> {
+    pub fn create(page_size: usize, state: State, preimage_oracle: PreImageOracle) -> Self {
         let initial_instruction_pointer = state.pc;
         let next_instruction_pointer = state.next_pc;
 
         let selector = INSTRUCTION_SET_SIZE;
 
+        let syscall_env = SyscallEnv::create(&state);
+
         let mut initial_memory: Vec<(u32, Vec<u8>)> = state
             .memory
             .into_iter()
@@ -650,22 +936,32 @@ impl<Fp: Field> Env<Fp> {
             .map(|(offset, _)| *offset)
             .collect::<Vec<_>>();
 
-        let initial_registers = {
+        let registers = {
+            let preimage_key = {
+                let mut preimage_key = [0u32; 8];
+                for (i, preimage_key_word) in preimage_key.iter_mut().enumerate() {
+                    *preimage_key_word = u32::from_be_bytes(
+                        state.preimage_key[i * 4..(i + 1) * 4].try_into().unwrap(),
+                    )
+                }
+                preimage_key
+            };
             Registers {
                 general_purpose: state.registers,
                 current_instruction_pointer: initial_instruction_pointer,
                 next_instruction_pointer,
                 heap_pointer: state.heap,
+                program_break: state.program_break,
+                preimage_key,
+                preimage_offset: state.preimage_offset,
             }
         };
 
-        let mut registers = initial_registers.clone();
-        registers[2] = 0x408004f0;
-        // set the stack pointer to the top of the stack
-
         Env {
             instruction_counter: state.step,
             memory: initial_memory.clone(),
+            segments: state.segments,
+            entry: state.entry,
             last_memory_accesses: [0usize; 3],
             memory_write_index: memory_offsets
                 .iter()
@@ -677,7 +973,13 @@ impl<Fp: Field> Env<Fp> {
             scratch_state_idx: 0,
             scratch_state: fresh_scratch_state(),
             halt: state.exited,
+            syscall_env,
             selector,
+            preimage_oracle,
+            preimage: state.preimage,
+            preimage_bytes_read: 0,
+            preimage_key: None,
+            hash_counter: 0,
         }
     }
 
@@ -782,16 +1084,37 @@ impl<Fp: Field> Env<Fp> {
                     0b001 => Instruction::RType(RInstruction::FenceI),
                     _ => panic!("Unknown RType 0001111 (Fence) instruction with full inst {}", instruction),
                 },
-                // FIXME: we should implement more syscalls here, and check the register state.
-                // Even better, only one constructor call ecall, and in the
-                // interpreter, we do the action depending on it
-                0b1110011 => Instruction::SyscallType(SyscallInstruction::SyscallSuccess),
+                0b1110011 if instruction == ECALL => Instruction::SyscallType(self.decode_syscall()),
+                0b1110011 => Instruction::SyscallType(SyscallInstruction::SyscallAbort),
                 _ => panic!("Unknown instruction with full inst {:b}, and opcode {:b}", instruction, instruction & 0b1111111),
             }
         };
         (opcode, instruction)
     }
 
+    /// Decode the syscall of the instruction `ecall`, given by the register
+    /// `a7`, and by the file descriptor in `a0` for the reads and the writes
+    pub fn decode_syscall(&self) -> SyscallInstruction {
+        let fd = self.registers.general_purpose[10];
+        match self.registers.general_purpose[17] {
+            SYSCALL_SUCCESS => SyscallInstruction::SyscallSuccess,
+            SYSCALL_EXIT | SYSCALL_EXIT_GROUP => SyscallInstruction::SyscallExit,
+            SYSCALL_READ => match fd {
+                FD_HINT_READ => SyscallInstruction::SyscallReadHint,
+                FD_PREIMAGE_READ => SyscallInstruction::SyscallReadPreimage,
+                _ => SyscallInstruction::SyscallReadOther,
+            },
+            SYSCALL_WRITE => match fd {
+                FD_HINT_WRITE => SyscallInstruction::SyscallWriteHint,
+                FD_PREIMAGE_WRITE => SyscallInstruction::SyscallWritePreimage,
+                _ => SyscallInstruction::SyscallWriteOther,
+            },
+            SYSCALL_BRK => SyscallInstruction::SyscallBrk,
+            SYSCALL_MMAP => SyscallInstruction::SyscallMmap,
+            _ => SyscallInstruction::SyscallOther,
+        }
+    }
+
     /// Execute a single step in the RISCV32i program
     pub fn step(&mut self) -> Instruction {
         self.reset_scratch_state();
@@ -803,7 +1126,47 @@ impl<Fp: Field> Env<Fp> {
 
         // Integer division by MAX_ACC to obtain the actual instruction count
         if self.halt {
-            println!(
+            info!(
+                "Halted at step={} instruction={:?}",
+                self.normalized_instruction_counter(),
+                opcode
+            );
+        }
+        opcode
+    }
+
+    /// Execute a single step of the RISC-V program, following the frequencies
+    /// of `config` to log information, snapshot the state or stop.
+    /// Returns the instruction that was executed.
+    pub fn step_with_configuration(
+        &mut self,
+        config: &VmConfiguration,
+        metadata: &Meta,
+        start: &Start,
+    ) -> Instruction {
+        self.reset_scratch_state();
+        let (opcode, _instruction) = self.decode_instruction();
+
+        self.pp_info(&config.info_at, metadata, start);
+        self.snapshot_state_at(&config.snapshot_state_at, &config.snapshot_fmt);
+
+        // Force stops at given iteration
+        if self.should_trigger_at(&config.stop_at) {
+            self.halt = true;
+            info!(
+                "Halted as requested at step={} instruction={:?}",
+                self.normalized_instruction_counter(),
+                opcode
+            );
+            return opcode;
+        }
+
+        interpreter::interpret_instruction(self, opcode);
+
+        self.instruction_counter = self.next_instruction_counter();
+
+        if self.halt {
+            info!(
                 "Halted at step={} instruction={:?}",
                 self.normalized_instruction_counter(),
                 opcode
@@ -812,6 +1175,100 @@ impl<Fp: Field> Env<Fp> {
         opcode
     }
 
+    fn should_trigger_at(&self, at: &StepFrequency) -> bool {
+        let m: u64 = self.normalized_instruction_counter();
+        match at {
+            StepFrequency::Never => false,
+            StepFrequency::Always => true,
+            StepFrequency::Exactly(n) => *n == m,
+            StepFrequency::Every(n) => m % *n == 0,
+            StepFrequency::Range(lo, hi_opt) => {
+                m >= *lo && (hi_opt.is_none() || m < hi_opt.unwrap())
+            }
+        }
+    }
+
+    // Compute memory usage
+    fn memory_usage(&self) -> String {
+        let total = self.memory.len() * PAGE_SIZE as usize;
+        memory_size(total)
+    }
+
+    /// Return the current state of the program, as it would be loaded from
+    /// its ELF file
+    pub fn state(&self) -> State {
+        let mut preimage_key = [0u8; 32];
+        for (i, word) in self.registers.preimage_key.iter().enumerate() {
+            preimage_key[4 * i..4 * i + 4].copy_from_slice(&word.to_be_bytes());
+        }
+        State {
+            memory: self
+                .memory
+                .iter()
+                .map(|(index, data)| Page {
+                    index: *index,
+                    data: data.clone(),
+                })
+                .collect(),
+            segments: self.segments.clone(),
+            entry: self.entry,
+            pc: self.registers.current_instruction_pointer,
+            next_pc: self.registers.next_instruction_pointer,
+            registers: self.registers.general_purpose,
+            program_break: self.registers.program_break,
+            heap: self.registers.heap_pointer,
+            preimage_key,
+            preimage_offset: self.registers.preimage_offset,
+            preimage: self.preimage.clone(),
+            last_hint: self.syscall_env.last_hint.clone(),
+            exit: self.syscall_env.exit_code.unwrap_or(0) as u8,
+            exited: self.halt,
+            step: self.normalized_instruction_counter(),
+        }
+    }
+
+    fn snapshot_state_at(&mut self, at: &StepFrequency, fmt: &str) {
+        if self.should_trigger_at(at) {
+            let step = self.normalized_instruction_counter();
+            let filename = fmt.replace("%d", &step.to_string());
+            let file = File::create(&filename).expect("Impossible to open file");
+            let mut writer = BufWriter::new(file);
+            let _ = serde_json::to_writer(&mut writer, &self.state());
+            info!("Snapshot state in {}, step {}", filename, step);
+            writer.flush().expect("Flush writer failing")
+        }
+    }
+
+    fn pp_info(&mut self, at: &StepFrequency, meta: &Meta, start: &Start) {
+        if self.should_trigger_at(at) {
+            let elapsed = start.time.elapsed();
+            // Compute the step number removing the MAX_ACC factor
+            let step = self.normalized_instruction_counter();
+            let pc = self.registers.current_instruction_pointer;
+
+            // Get the 32-bits instruction, stored in little-endian
+            let insn =
+                u32::from_le_bytes(array::from_fn(|i| self.get_memory_direct(pc + i as u32)));
+
+            // Approximate instruction per seconds
+            let how_many_steps = step as usize - start.step;
+
+            let ips = how_many_steps as f64 / elapsed.as_secs() as f64;
+
+            let pages = self.memory.len();
+
+            let mem = self.memory_usage();
+            let name = meta
+                .find_address_symbol(pc)
+                .unwrap_or_else(|| "n/a".to_string());
+
+            info!(
+                "processing step={} pc={:010x} insn={:010x} ips={:.2} pages={} mem={} name={}",
+                step, pc, insn, ips, pages, mem, name
+            );
+        }
+    }
+
     pub fn reset_scratch_state(&mut self) {
         self.scratch_state_idx = 0;
         self.scratch_state = fresh_scratch_state();
@@ -890,6 +1347,17 @@ impl<Fp: Field> Env<Fp> {
         self.memory[memory_idx].1[page_address]
     }
 
+    /// Read the byte at `addr` without allocating its page, the pages that
+    /// have not been allocated being filled with zeros
+    pub fn peek_memory_direct(&self, addr: u32) -> u8 {
+        let page = addr >> PAGE_ADDRESS_SIZE;
+        let page_address = (addr & PAGE_ADDRESS_MASK) as usize;
+        self.memory
+            .iter()
+            .find(|(page_index, _)| *page_index == page)
+            .map_or(0, |(_, memory)| memory[page_address])
+    }
+
     /// The actual number of instructions executed results from dividing the
     /// instruction counter by MAX_ACC (floor).
     ///
@@ -0,0 +1,71 @@
+//! The command line interface of the verifier of the proofs written by
+//! `pickles_o1vm`, used by its `verify` subcommand and by the binary
+//! `pickles_o1vm_verify`.
+
+use clap::Arg;
+use log::{debug, error, info};
+use mina_curves::pasta::{Fp, Vesta};
+use std::{path::Path, time::Instant};
+
+use super::{
+    proof::{ConstraintsDigest, ProofFile},
+    setup, verifier, BaseSponge, ScalarSponge, DOMAIN_SIZE,
+};
+use crate::interpreters::mips::constraints::get_all_constraints;
+
+/// The command `app_name`, which takes the files of the proofs to verify
+pub fn verify_cli(app_name: &'static str) -> clap::Command {
+    clap::Command::new(app_name)
+        .version("0.1")
+        .about("verify proofs written by the prover")
+        .arg(
+            Arg::new("proofs")
+                .value_name("PROOF")
+                .num_args(1..)
+                .required(true),
+        )
+}
+
+/// Verify the proofs of the files `paths`, written by `pickles_o1vm`, against
+/// the constraints of the MIPS instructions, and log the result for each of
+/// them. Return true if all the proofs are valid.
+pub fn verify_files<P: AsRef<Path>>(paths: &[P]) -> bool {
+    let (domain_fp, srs) = setup();
+    let constraints = get_all_constraints::<Fp>();
+    let constraints_digest = ConstraintsDigest::new(DOMAIN_SIZE, &constraints);
+    let mut valid = true;
+    for path in paths {
+        let filename = path.as_ref().display();
+        let proof_file = match ProofFile::<Vesta>::from_file(path.as_ref()) {
+            Ok(proof_file) => proof_file,
+            Err(e) => {
+                error!("Could not read the proof in {filename}: {e}");
+                valid = false;
+                continue;
+            }
+        };
+        if proof_file.constraints_digest != constraints_digest {
+            error!("The proof in {filename} has been made for other constraints");
+            valid = false;
+            continue;
+        }
+        let start_iteration = Instant::now();
+        let verif = verifier::verify::<Vesta, BaseSponge, ScalarSponge>(
+            domain_fp,
+            &srs,
+            &constraints,
+            &proof_file.proof,
+        );
+        debug!(
+            "Verification done in {elapsed} μs",
+            elapsed = start_iteration.elapsed().as_micros()
+        );
+        if verif {
+            info!("The proof in {filename} is valid");
+        } else {
+            error!("The proof in {filename} is invalid");
+            valid = false;
+        }
+    }
+    valid
+}
@@ -1,39 +1,90 @@
 use ark_ff::UniformRand;
 use kimchi::circuits::domains::EvaluationDomains;
 use kimchi_msm::expr::E;
-use log::debug;
-use mina_curves::pasta::VestaParameters;
-use mina_poseidon::{
-    constants::PlonkSpongeConstantsKimchi,
-    sponge::{DefaultFqSponge, DefaultFrSponge},
-};
+use log::{debug, info};
 use o1vm::{
     cannon::{self, Meta, Start, State},
     cannon_cli,
     interpreters::mips::{
         column::N_MIPS_REL_COLS,
-        constraints as mips_constraints,
-        interpreter::{self, InterpreterEnv},
+        constraints::get_all_constraints,
         witness::{self as mips_witness},
         Instruction,
     },
-    pickles::{proof::ProofInputs, prover, verifier},
+    pickles::{
+        cli,
+        proof::{ConstraintsDigest, ProofFile, ProofInputs},
+        prover, setup, BaseSponge, ScalarSponge, DOMAIN_SIZE,
+    },
     preimage_oracle::PreImageOracle,
 };
-use poly_commitment::{ipa::SRS, SRS as _};
-use std::{fs::File, io::BufReader, process::ExitCode, time::Instant};
-use strum::IntoEnumIterator;
+use poly_commitment::ipa::SRS;
+use rand::{CryptoRng, RngCore};
+use std::{fs::File, io::BufReader, path::Path, process::ExitCode, time::Instant};
 
 use mina_curves::pasta::{Fp, Vesta};
 
-pub const DOMAIN_SIZE: usize = 1 << 15;
+/// Prove the steps of `inputs`, padded to [DOMAIN_SIZE] rows, and write the
+/// proof, with the digest of the constraints, in the file named by
+/// `proof_fmt`, where `%d` is replaced by `step`, the number of instructions
+/// executed
+fn prove_chunk<RNG: RngCore + CryptoRng>(
+    (domain_fp, srs): &(EvaluationDomains<Fp>, SRS<Vesta>),
+    constraints: &[E<Fp>],
+    constraints_digest: ConstraintsDigest,
+    mut inputs: ProofInputs<Vesta>,
+    proof_fmt: &str,
+    step: u64,
+    rng: &mut RNG,
+) {
+    let start_iteration = Instant::now();
+    debug!(
+        "Making a proof of {} steps, up to step {step}",
+        inputs.len()
+    );
+    inputs.pad(DOMAIN_SIZE);
+    let proof = prover::prove::<Vesta, BaseSponge, ScalarSponge, _>(
+        *domain_fp,
+        srs,
+        inputs,
+        constraints,
+        rng,
+    )
+    .unwrap();
+    debug!(
+        "Proof generated in {elapsed} μs",
+        elapsed = start_iteration.elapsed().as_micros()
+    );
+
+    let filename = proof_fmt.replace("%d", &step.to_string());
+    let proof_file = ProofFile {
+        constraints_digest,
+        proof,
+    };
+    proof_file
+        .to_file(Path::new(&filename))
+        .unwrap_or_else(|e| panic!("Could not write the proof in {filename}: {e}"));
+    info!("Proof of the steps up to {step} written in {filename}");
+}
 
 pub fn main() -> ExitCode {
-    let cli = cannon_cli::main_cli();
+    let matches = cannon_cli::main_cli()
+        .subcommand(cli::verify_cli("verify"))
+        .get_matches();
+
+    if let Some(("verify", verify_matches)) = matches.subcommand() {
+        env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+        let proofs: Vec<&String> = verify_matches.get_many("proofs").unwrap().collect();
+        return if cli::verify_files(&proofs) {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
+        };
+    }
 
     let mut rng = rand::thread_rng();
 
-    let configuration = cannon_cli::read_configuration(&cli.get_matches());
+    let configuration = cannon_cli::read_configuration(&matches);
 
     let file =
         File::open(&configuration.input_state_file).expect("Error opening input state file ");
@@ -64,40 +115,39 @@ pub fn main() -> ExitCode {
 
     env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
 
-    let domain_fp = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
-    let srs: SRS<Vesta> = {
-        let srs = SRS::create(DOMAIN_SIZE);
-        srs.get_lagrange_basis(domain_fp.d1);
-        srs
-    };
+    let setup = setup();
 
     // Initialize the environments
     let mut mips_wit_env =
         mips_witness::Env::<Fp, PreImageOracle>::create(cannon::PAGE_SIZE as usize, state, po);
 
-    let constraints = {
-        let mut mips_con_env = mips_constraints::Env::<Fp>::default();
-        let mut constraints = Instruction::iter()
-            .flat_map(|instr_typ| instr_typ.into_iter())
-            .fold(vec![], |mut acc, instr| {
-                interpreter::interpret_instruction(&mut mips_con_env, instr);
-                let selector = mips_con_env.get_selector();
-                let constraints_with_selector: Vec<E<Fp>> = mips_con_env
-                    .get_constraints()
-                    .into_iter()
-                    .map(|c| selector.clone() * c)
-                    .collect();
-                acc.extend(constraints_with_selector);
-                mips_con_env.reset();
-                acc
-            });
-        constraints.extend(mips_con_env.get_selector_constraints());
-        constraints
-    };
+    let constraints = get_all_constraints::<Fp>();
+    let constraints_digest = ConstraintsDigest::new(DOMAIN_SIZE, &constraints);
 
     let mut curr_proof_inputs: ProofInputs<Vesta> = ProofInputs::new(DOMAIN_SIZE);
     while !mips_wit_env.halt {
+        // The triggers are checked on the step about to be executed, as
+        // `step` increments the instruction counter. A proof requested at a
+        // step covers the instructions executed before it.
+        if mips_wit_env.should_trigger_at(&configuration.proof_at) && !curr_proof_inputs.is_empty()
+        {
+            let inputs = std::mem::replace(&mut curr_proof_inputs, ProofInputs::new(DOMAIN_SIZE));
+            prove_chunk(
+                &setup,
+                &constraints,
+                constraints_digest,
+                inputs,
+                &configuration.proof_fmt,
+                mips_wit_env.normalized_instruction_counter(),
+                &mut rng,
+            );
+        }
+        // The instruction is not executed when the execution is stopped
+        let stopped = mips_wit_env.should_trigger_at(&configuration.stop_at);
         let _instr: Instruction = mips_wit_env.step(&configuration, &meta, &start);
+        if stopped {
+            break;
+        }
         for (scratch, scratch_chunk) in mips_wit_env
             .scratch_state
             .iter()
@@ -124,40 +174,32 @@ pub fn main() -> ExitCode {
             .selector
             .push(Fp::from((mips_wit_env.selector - N_MIPS_REL_COLS) as u64));
 
-        if curr_proof_inputs.evaluations.instruction_counter.len() == DOMAIN_SIZE {
-            // FIXME
-            let start_iteration = Instant::now();
-            debug!("Limit of {DOMAIN_SIZE} reached. We make a proof, verify it (for testing) and start with a new chunk");
-            let proof = prover::prove::<
-                Vesta,
-                DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                _,
-            >(domain_fp, &srs, curr_proof_inputs, &constraints, &mut rng)
-            .unwrap();
-            // FIXME: check that the proof is correct. This is for testing purposes.
-            // Leaving like this for now.
-            debug!(
-                "Proof generated in {elapsed} μs",
-                elapsed = start_iteration.elapsed().as_micros()
+        if curr_proof_inputs.len() == DOMAIN_SIZE {
+            debug!("Limit of {DOMAIN_SIZE} reached. We make a proof and start with a new chunk");
+            let inputs = std::mem::replace(&mut curr_proof_inputs, ProofInputs::new(DOMAIN_SIZE));
+            prove_chunk(
+                &setup,
+                &constraints,
+                constraints_digest,
+                inputs,
+                &configuration.proof_fmt,
+                mips_wit_env.normalized_instruction_counter(),
+                &mut rng,
             );
-            {
-                let start_iteration = Instant::now();
-                let verif = verifier::verify::<
-                    Vesta,
-                    DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>,
-                    DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>,
-                >(domain_fp, &srs, &constraints, &proof);
-                debug!(
-                    "Verification done in {elapsed} μs",
-                    elapsed = start_iteration.elapsed().as_micros()
-                );
-                assert!(verif);
-            }
-
-            curr_proof_inputs = ProofInputs::new(DOMAIN_SIZE);
         }
     }
-    // TODO: Logic
+
+    // The last steps, which do not fill a chunk
+    if !curr_proof_inputs.is_empty() {
+        prove_chunk(
+            &setup,
+            &constraints,
+            constraints_digest,
+            curr_proof_inputs,
+            &configuration.proof_fmt,
+            mips_wit_env.normalized_instruction_counter(),
+            &mut rng,
+        );
+    }
     ExitCode::SUCCESS
 }
@@ -12,11 +12,27 @@
 //! O1VM_FLAVOR=pickles bash run-code.sh
 //! ```
 
+use kimchi::circuits::domains::EvaluationDomains;
+use mina_curves::pasta::{Fp, Vesta, VestaParameters};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    sponge::{DefaultFqSponge, DefaultFrSponge},
+};
+use poly_commitment::{ipa::SRS, SRS as _};
+
+pub mod cli;
 pub mod column_env;
 pub mod proof;
 pub mod prover;
 pub mod verifier;
 
+/// Number of rows of the proofs, i.e. the maximum number of instructions
+/// proven by each of them.
+pub const DOMAIN_SIZE: usize = 1 << 15;
+
+pub type BaseSponge = DefaultFqSponge<VestaParameters, PlonkSpongeConstantsKimchi>;
+pub type ScalarSponge = DefaultFrSponge<Fp, PlonkSpongeConstantsKimchi>;
+
 /// Maximum degree of the constraints.
 /// It does include the additional degree induced by the multiplication of the
 /// selectors.
@@ -33,5 +49,16 @@ pub const DEGREE_QUOTIENT_POLYNOMIAL: u64 = 7;
 /// added for the selectors.
 pub const TOTAL_NUMBER_OF_CONSTRAINTS: usize = 464;
 
+/// The domain and the SRS of the proofs, which only depend on [DOMAIN_SIZE].
+pub fn setup() -> (EvaluationDomains<Fp>, SRS<Vesta>) {
+    let domain_fp = EvaluationDomains::<Fp>::create(DOMAIN_SIZE).unwrap();
+    let srs: SRS<Vesta> = {
+        let srs = SRS::create(DOMAIN_SIZE);
+        srs.get_lagrange_basis(domain_fp.d1);
+        srs
+    };
+    (domain_fp, srs)
+}
+
 #[cfg(test)]
 mod tests;
@@ -1,16 +1,114 @@
-use kimchi::{curve::KimchiCurve, proof::PointEvaluations};
+use std::{
+    array,
+    fs::File,
+    io::{BufReader, BufWriter, Write},
+    path::Path,
+};
+
+use ark_ff::{BigInteger, PrimeField};
+use kimchi::{
+    circuits::{
+        berkeley_columns::BerkeleyChallengeTerm,
+        expr::{ConstantTerm, PolishToken},
+    },
+    curve::KimchiCurve,
+    proof::PointEvaluations,
+};
+use kimchi_msm::columns::Column;
+use o1_utils::{hasher::CryptoDigest, serialization::SerdeAs};
 use poly_commitment::{ipa::OpeningProof, PolyComm};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{
+    de::DeserializeAsWrap, ser::SerializeAsWrap, serde_as, DeserializeAs, SerializeAs,
+};
 
-use crate::interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE};
+use crate::{
+    interpreters::mips::column::{N_MIPS_SEL_COLS, SCRATCH_SIZE, SCRATCH_SIZE_INVERSE},
+    E,
+};
 
+#[serde_as]
+#[derive(Serialize, Deserialize)]
 pub struct WitnessColumns<G, S> {
+    #[serde_as(as = "[_; SCRATCH_SIZE]")]
     pub scratch: [G; SCRATCH_SIZE],
+    #[serde_as(as = "[_; SCRATCH_SIZE_INVERSE]")]
     pub scratch_inverse: [G; SCRATCH_SIZE_INVERSE],
     pub instruction_counter: G,
     pub error: G,
     pub selector: S,
 }
 
+impl<G, S> WitnessColumns<G, S> {
+    fn as_ref(&self) -> WitnessColumns<&G, &S> {
+        WitnessColumns {
+            scratch: array::from_fn(|i| &self.scratch[i]),
+            scratch_inverse: array::from_fn(|i| &self.scratch_inverse[i]),
+            instruction_counter: &self.instruction_counter,
+            error: &self.error,
+            selector: &self.selector,
+        }
+    }
+
+    fn map<H, T>(self, mut f: impl FnMut(G) -> H, g: impl FnOnce(S) -> T) -> WitnessColumns<H, T> {
+        let WitnessColumns {
+            scratch,
+            scratch_inverse,
+            instruction_counter,
+            error,
+            selector,
+        } = self;
+        WitnessColumns {
+            scratch: scratch.map(&mut f),
+            scratch_inverse: scratch_inverse.map(&mut f),
+            instruction_counter: f(instruction_counter),
+            error: f(error),
+            selector: g(selector),
+        }
+    }
+}
+
+// The columns are serialized with `GAs` and the selectors with `SAs`, e.g.
+// `WitnessColumns<SerdeAs, [SerdeAs; N_MIPS_SEL_COLS]>` for field elements.
+impl<G, S, GAs, SAs> SerializeAs<WitnessColumns<G, S>> for WitnessColumns<GAs, SAs>
+where
+    GAs: SerializeAs<G>,
+    SAs: SerializeAs<S>,
+{
+    fn serialize_as<Ser>(
+        source: &WitnessColumns<G, S>,
+        serializer: Ser,
+    ) -> Result<Ser::Ok, Ser::Error>
+    where
+        Ser: Serializer,
+    {
+        source
+            .as_ref()
+            .map(
+                SerializeAsWrap::<G, GAs>::new,
+                SerializeAsWrap::<S, SAs>::new,
+            )
+            .serialize(serializer)
+    }
+}
+
+impl<'de, G, S, GAs, SAs> DeserializeAs<'de, WitnessColumns<G, S>> for WitnessColumns<GAs, SAs>
+where
+    GAs: DeserializeAs<'de, G>,
+    SAs: DeserializeAs<'de, S>,
+{
+    fn deserialize_as<D>(deserializer: D) -> Result<WitnessColumns<G, S>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let columns =
+            WitnessColumns::<DeserializeAsWrap<G, GAs>, DeserializeAsWrap<S, SAs>>::deserialize(
+                deserializer,
+            )?;
+        Ok(columns.map(DeserializeAsWrap::into_inner, DeserializeAsWrap::into_inner))
+    }
+}
+
 pub struct ProofInputs<G: KimchiCurve> {
     pub evaluations: WitnessColumns<Vec<G::ScalarField>, Vec<G::ScalarField>>,
 }
@@ -27,15 +125,138 @@ impl<G: KimchiCurve> ProofInputs<G> {
             },
         }
     }
+
+    /// The number of rows of the inputs
+    pub fn len(&self) -> usize {
+        self.evaluations.instruction_counter.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Pad the inputs up to `domain_size` rows, by repeating the last row.
+    /// As the constraints only involve the current row, the padding rows
+    /// satisfy them as the last row does.
+    pub fn pad(&mut self, domain_size: usize) {
+        let WitnessColumns {
+            scratch,
+            scratch_inverse,
+            instruction_counter,
+            error,
+            selector,
+        } = &mut self.evaluations;
+        for column in scratch.iter_mut().chain(scratch_inverse.iter_mut()).chain([
+            instruction_counter,
+            error,
+            selector,
+        ]) {
+            let last = *column.last().expect("the inputs to have at least one row");
+            column.resize(domain_size, last);
+        }
+    }
 }
 
 // FIXME: should we blind the commitment?
+#[serde_as]
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
 pub struct Proof<G: KimchiCurve> {
+    #[serde_as(as = "WitnessColumns<_, [_; N_MIPS_SEL_COLS]>")]
     pub commitments: WitnessColumns<PolyComm<G>, [PolyComm<G>; N_MIPS_SEL_COLS]>,
+    #[serde_as(as = "WitnessColumns<SerdeAs, [SerdeAs; N_MIPS_SEL_COLS]>")]
     pub zeta_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
+    #[serde_as(as = "WitnessColumns<SerdeAs, [SerdeAs; N_MIPS_SEL_COLS]>")]
     pub zeta_omega_evaluations: WitnessColumns<G::ScalarField, [G::ScalarField; N_MIPS_SEL_COLS]>,
     pub quotient_commitment: PolyComm<G>,
     pub quotient_evaluations: PointEvaluations<Vec<G::ScalarField>>,
     /// IPA opening proof
     pub opening_proof: OpeningProof<G>,
 }
+
+/// The digest of a set of constraints, written with the proofs to check that
+/// they are verified against the constraints they have been made for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConstraintsDigest(#[serde(with = "hex")] pub [u8; 32]);
+
+/// The constraints and the size of the domain they are evaluated on, in the
+/// canonical form the digest is computed on: the constraints in reverse
+/// Polish notation, with the field elements in little endian
+#[derive(Serialize)]
+struct CanonicalConstraints {
+    domain_size: u64,
+    constraints: Vec<Vec<PolishToken<Vec<u8>, Column, BerkeleyChallengeTerm>>>,
+}
+
+impl CryptoDigest for CanonicalConstraints {
+    const PREFIX: &'static [u8; 15] = b"o1vm-constraint";
+}
+
+/// Replace the field elements of `token` by their little endian encodings
+fn canonical_token<F: PrimeField>(
+    token: PolishToken<F, Column, BerkeleyChallengeTerm>,
+) -> PolishToken<Vec<u8>, Column, BerkeleyChallengeTerm> {
+    match token {
+        PolishToken::Constant(constant) => PolishToken::Constant(match constant {
+            ConstantTerm::EndoCoefficient => ConstantTerm::EndoCoefficient,
+            ConstantTerm::Mds { row, col } => ConstantTerm::Mds { row, col },
+            ConstantTerm::Literal(x) => ConstantTerm::Literal(x.into_bigint().to_bytes_le()),
+        }),
+        PolishToken::Challenge(challenge) => PolishToken::Challenge(challenge),
+        PolishToken::Cell(variable) => PolishToken::Cell(variable),
+        PolishToken::Dup => PolishToken::Dup,
+        PolishToken::Pow(n) => PolishToken::Pow(n),
+        PolishToken::Add => PolishToken::Add,
+        PolishToken::Mul => PolishToken::Mul,
+        PolishToken::Sub => PolishToken::Sub,
+        PolishToken::VanishesOnZeroKnowledgeAndPreviousRows => {
+            PolishToken::VanishesOnZeroKnowledgeAndPreviousRows
+        }
+        PolishToken::UnnormalizedLagrangeBasis(offset) => {
+            PolishToken::UnnormalizedLagrangeBasis(offset)
+        }
+        PolishToken::Store => PolishToken::Store,
+        PolishToken::Load(i) => PolishToken::Load(i),
+        PolishToken::SkipIf(flag, n) => PolishToken::SkipIf(flag, n),
+        PolishToken::SkipIfNot(flag, n) => PolishToken::SkipIfNot(flag, n),
+    }
+}
+
+impl ConstraintsDigest {
+    /// The digest of `constraints`, evaluated on a domain of `domain_size`
+    /// rows
+    pub fn new<F: PrimeField>(domain_size: usize, constraints: &[E<F>]) -> Self {
+        let canonical = CanonicalConstraints {
+            domain_size: domain_size as u64,
+            constraints: constraints
+                .iter()
+                .map(|c| c.to_polish().into_iter().map(canonical_token).collect())
+                .collect(),
+        };
+        ConstraintsDigest(canonical.digest())
+    }
+}
+
+/// The content of a proof file: a proof and the digest of the constraints it
+/// has been made for
+#[derive(Serialize, Deserialize)]
+#[serde(bound = "G: ark_serialize::CanonicalDeserialize + ark_serialize::CanonicalSerialize")]
+pub struct ProofFile<G: KimchiCurve> {
+    pub constraints_digest: ConstraintsDigest,
+    pub proof: Proof<G>,
+}
+
+impl<G: KimchiCurve> ProofFile<G> {
+    /// Write the proof file in JSON to the file `path`
+    pub fn to_file(&self, path: &Path) -> std::io::Result<()> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        serde_json::to_writer(&mut writer, self)?;
+        writer.flush()
+    }
+
+    /// Read a proof file written by [ProofFile::to_file] from the file `path`
+    pub fn from_file(path: &Path) -> std::io::Result<Self> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+}
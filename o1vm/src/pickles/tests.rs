@@ -2,17 +2,14 @@ use std::time::Instant;
 
 use super::{
     super::interpreters::mips::column::SCRATCH_SIZE,
-    proof::{ProofInputs, WitnessColumns},
+    proof::{ConstraintsDigest, ProofFile, ProofInputs, WitnessColumns},
     prover::prove,
 };
 use crate::{
-    interpreters::mips::{
-        column::SCRATCH_SIZE_INVERSE,
-        constraints as mips_constraints,
-        interpreter::{self, InterpreterEnv},
-        Instruction,
+    interpreters::mips::{column::SCRATCH_SIZE_INVERSE, constraints::get_all_constraints},
+    pickles::{
+        verifier::verify, DOMAIN_SIZE, MAXIMUM_DEGREE_CONSTRAINTS, TOTAL_NUMBER_OF_CONSTRAINTS,
     },
-    pickles::{verifier::verify, MAXIMUM_DEGREE_CONSTRAINTS, TOTAL_NUMBER_OF_CONSTRAINTS},
 };
 use ark_ff::{Field, One, UniformRand, Zero};
 use kimchi::circuits::{domains::EvaluationDomains, expr::Expr, gate::CurrOrNext};
@@ -25,29 +22,10 @@ use mina_poseidon::{
 };
 use o1_utils::tests::make_test_rng;
 use poly_commitment::SRS;
-use strum::IntoEnumIterator;
 
 #[test]
 fn test_regression_constraints_with_selectors() {
-    let constraints = {
-        let mut mips_con_env = mips_constraints::Env::<Fp>::default();
-        let mut constraints = Instruction::iter()
-            .flat_map(|instr_typ| instr_typ.into_iter())
-            .fold(vec![], |mut acc, instr| {
-                interpreter::interpret_instruction(&mut mips_con_env, instr);
-                let selector = mips_con_env.get_selector();
-                let constraints_with_selector: Vec<E<Fp>> = mips_con_env
-                    .get_constraints()
-                    .into_iter()
-                    .map(|c| selector.clone() * c)
-                    .collect();
-                acc.extend(constraints_with_selector);
-                mips_con_env.reset();
-                acc
-            });
-        constraints.extend(mips_con_env.get_selector_constraints());
-        constraints
-    };
+    let constraints = get_all_constraints::<Fp>();
 
     assert_eq!(constraints.len(), TOTAL_NUMBER_OF_CONSTRAINTS);
 
@@ -59,32 +37,39 @@ fn zero_to_n_minus_one(n: usize) -> Vec<Fq> {
     (0..n).map(|i| Fq::from((i) as u64)).collect()
 }
 
-#[test]
-fn test_small_circuit() {
-    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
-    let srs = SRS::create(8);
+type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
+type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+
+/// The first `n` rows of a circuit with a single constraint summing all the
+/// columns, and the constraint
+fn small_circuit(n: usize) -> (ProofInputs<Pallas>, E<Fq>) {
     let proof_input = ProofInputs::<Pallas> {
         evaluations: WitnessColumns {
-            scratch: std::array::from_fn(|_| zero_to_n_minus_one(8)),
-            scratch_inverse: std::array::from_fn(|_| (0..8).map(|_| Fq::zero()).collect()),
-            instruction_counter: zero_to_n_minus_one(8)
+            scratch: std::array::from_fn(|_| zero_to_n_minus_one(n)),
+            scratch_inverse: std::array::from_fn(|_| (0..n).map(|_| Fq::zero()).collect()),
+            instruction_counter: zero_to_n_minus_one(n)
                 .into_iter()
                 .map(|x| x + Fq::one())
                 .collect(),
-            error: (0..8)
+            error: (0..n)
                 .map(|i| -Fq::from((i * SCRATCH_SIZE + (i + 1)) as u64))
                 .collect(),
-            selector: zero_to_n_minus_one(8),
+            selector: zero_to_n_minus_one(n),
         },
     };
     let mut expr = Expr::zero();
     for i in 0..SCRATCH_SIZE + SCRATCH_SIZE_INVERSE + 2 {
         expr += Expr::cell(Column::Relation(i), CurrOrNext::Curr);
     }
-    let mut rng = make_test_rng(None);
+    (proof_input, expr)
+}
 
-    type BaseSponge = DefaultFqSponge<PallasParameters, PlonkSpongeConstantsKimchi>;
-    type ScalarSponge = DefaultFrSponge<Fq, PlonkSpongeConstantsKimchi>;
+#[test]
+fn test_small_circuit() {
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let srs = SRS::create(8);
+    let (proof_input, expr) = small_circuit(8);
+    let mut rng = make_test_rng(None);
 
     let proof = prove::<Pallas, BaseSponge, ScalarSponge, _>(
         domain,
@@ -105,6 +90,90 @@ fn test_small_circuit() {
     assert!(verif, "Verification fails");
 }
 
+#[test]
+fn test_padded_partial_chunk() {
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let srs = SRS::create(8);
+    let (mut proof_input, expr) = small_circuit(5);
+    let mut rng = make_test_rng(None);
+
+    proof_input.pad(8);
+    assert_eq!(proof_input.len(), 8);
+    assert_eq!(proof_input.evaluations.selector[7], Fq::from(4u64));
+
+    let proof = prove::<Pallas, BaseSponge, ScalarSponge, _>(
+        domain,
+        &srs,
+        proof_input,
+        &[expr.clone()],
+        &mut rng,
+    )
+    .unwrap();
+    let verif = verify::<Pallas, BaseSponge, ScalarSponge>(domain, &srs, &[expr], &proof);
+    assert!(verif, "Verification of the padded chunk fails");
+}
+
+#[test]
+fn test_proof_file_serialization() {
+    let domain = EvaluationDomains::<Fq>::create(8).unwrap();
+    let srs = SRS::create(8);
+    let (proof_input, expr) = small_circuit(8);
+    let mut rng = make_test_rng(None);
+
+    let proof = prove::<Pallas, BaseSponge, ScalarSponge, _>(
+        domain,
+        &srs,
+        proof_input,
+        &[expr.clone()],
+        &mut rng,
+    )
+    .unwrap();
+    let constraints_digest = ConstraintsDigest::new(8, &[expr.clone()]);
+    let proof_file = ProofFile {
+        constraints_digest,
+        proof,
+    };
+
+    let serialized = serde_json::to_string(&proof_file).unwrap();
+    let deserialized: ProofFile<Pallas> = serde_json::from_str(&serialized).unwrap();
+    assert_eq!(deserialized.constraints_digest, constraints_digest);
+    assert_eq!(serde_json::to_string(&deserialized).unwrap(), serialized);
+
+    let verif = verify::<Pallas, BaseSponge, ScalarSponge>(
+        domain,
+        &srs,
+        &[expr.clone()],
+        &deserialized.proof,
+    );
+    assert!(verif, "Verification of the deserialized proof fails");
+}
+
+#[test]
+fn test_constraints_digest() {
+    let constraints = get_all_constraints::<Fp>();
+    let digest = ConstraintsDigest::new(DOMAIN_SIZE, &constraints);
+    assert_eq!(
+        digest,
+        ConstraintsDigest::new(DOMAIN_SIZE, &get_all_constraints::<Fp>())
+    );
+    assert_ne!(
+        digest,
+        ConstraintsDigest::new(DOMAIN_SIZE, &constraints[..constraints.len() - 1])
+    );
+    assert_ne!(
+        digest,
+        ConstraintsDigest::new(DOMAIN_SIZE / 2, &constraints)
+    );
+
+    // The constants of the constraints are part of the digest
+    let mut other_constraints = constraints.clone();
+    other_constraints[0] = other_constraints[0].clone() + E::<Fp>::from(1u64);
+    assert_ne!(
+        digest,
+        ConstraintsDigest::new(DOMAIN_SIZE, &other_constraints)
+    );
+}
+
 #[test]
 fn test_arkworks_batch_inversion_with_only_zeroes() {
     let input = vec![Fq::zero(); 8];
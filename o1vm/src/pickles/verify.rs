@@ -0,0 +1,17 @@
+use o1vm::pickles::cli;
+use std::process::ExitCode;
+
+/// Verify proofs written by `pickles_o1vm`, possibly on another machine, by
+/// recomputing the constraints of the MIPS instructions
+pub fn main() -> ExitCode {
+    let matches = cli::verify_cli("pickles_o1vm_verify").get_matches();
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let proofs: Vec<&String> = matches.get_many("proofs").unwrap().collect();
+    if cli::verify_files(&proofs) {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
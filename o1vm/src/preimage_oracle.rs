@@ -25,6 +25,21 @@ pub trait PreImageOracleT {
     fn hint(&mut self, hint: Hint);
 }
 
+/// A preimage oracle for the programs run without a host program, which do
+/// not request any preimage.
+pub struct NullPreImageOracle;
+
+impl PreImageOracleT for NullPreImageOracle {
+    fn get_preimage(&mut self, key: [u8; 32]) -> Preimage {
+        panic!(
+            "No preimage oracle to request the preimage of 0x{}",
+            hex::encode(key)
+        )
+    }
+
+    fn hint(&mut self, _hint: Hint) {}
+}
+
 pub struct ReadWrite<R, W> {
     pub reader: R,
     pub writer: W,
@@ -0,0 +1,133 @@
+use mina_curves::pasta::Fp;
+use o1vm::{
+    cannon::{self, Meta, Start, VmConfiguration},
+    cannon_cli,
+    elf_loader::load_riscv32,
+    interpreters::riscv32im::witness::Env,
+    preimage_oracle::{NullPreImageOracle, PreImageOracle, PreImageOracleT},
+};
+use serde::Serialize;
+use std::{
+    fs::File,
+    io::{self, BufWriter, Write},
+    path::Path,
+    process::ExitCode,
+};
+
+/// The state of the program when the execution ends
+#[derive(Serialize)]
+struct Output {
+    /// The number of instructions executed
+    step: u64,
+    /// True if the program has exited, false if it has been stopped
+    exited: bool,
+    exit_code: Option<u32>,
+    pc: u32,
+    next_pc: u32,
+    registers: [u32; 32],
+    /// True if the program has written to the standard output
+    #[serde(skip)]
+    wrote_stdout: bool,
+}
+
+/// Run the program of `env` until it exits or is stopped, printing the
+/// executed instructions if `trace` is set, with their symbols if `symbols` is
+/// set, and return its final state
+fn run<PreImageOracle: PreImageOracleT>(
+    mut env: Env<Fp, PreImageOracle>,
+    configuration: &VmConfiguration,
+    meta: &Meta,
+    trace: bool,
+    symbols: bool,
+) -> Output {
+    let start = Start::create(env.normalized_instruction_counter() as usize);
+    while !env.halt {
+        let step = env.normalized_instruction_counter();
+        let pc = env.registers.current_instruction_pointer;
+        let instruction_counter = env.instruction_counter;
+        let instruction = env.step_with_configuration(configuration, meta, &start);
+        // The instruction is not executed when the program is stopped
+        if trace && env.instruction_counter != instruction_counter {
+            let symbol = if symbols {
+                meta.find_address_symbol(pc)
+                    .map(|name| format!(" <{name}>"))
+                    .unwrap_or_default()
+            } else {
+                String::new()
+            };
+            eprintln!("{step:>10} {pc:#010x} {instruction}{symbol}");
+        }
+    }
+    Output {
+        step: env.normalized_instruction_counter(),
+        exited: env.syscall_env.exit_code.is_some(),
+        exit_code: env.syscall_env.exit_code,
+        pc: env.registers.current_instruction_pointer,
+        next_pc: env.registers.next_instruction_pointer,
+        registers: env.registers.general_purpose,
+        wrote_stdout: !env.syscall_env.stdout.is_empty(),
+    }
+}
+
+pub fn main() -> ExitCode {
+    let cli = cannon_cli::riscv_cli().get_matches();
+    let configuration = cannon_cli::read_riscv_configuration(&cli);
+
+    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+    let program = &configuration.input_state_file;
+    let args: Vec<&str> = std::iter::once(program.as_str())
+        .chain(
+            cli.get_many::<String>("arg")
+                .into_iter()
+                .flatten()
+                .map(String::as_str),
+        )
+        .collect();
+    let envs: Vec<&str> = cli
+        .get_many::<String>("env")
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect();
+    let (state, meta) = load_riscv32(Path::new(program), &args, &envs)
+        .unwrap_or_else(|e| panic!("Could not load the program {program}: {e}"));
+
+    let trace = cli.get_flag("trace");
+    let symbols = cli.get_flag("symbols");
+    let page_size = cannon::PAGE_SIZE as usize;
+    let output = if configuration.host.is_some() {
+        let mut po = PreImageOracle::create(&configuration.host);
+        let _child = po.start();
+        let env = Env::<Fp, PreImageOracle>::create(page_size, state, po);
+        run(env, &configuration, &meta, trace, symbols)
+    } else {
+        let env = Env::<Fp, NullPreImageOracle>::create(page_size, state, NullPreImageOracle);
+        run(env, &configuration, &meta, trace, symbols)
+    };
+
+    let mut writer: Box<dyn Write> = if configuration.output_state_file == "-" {
+        // The final state could not be told apart from the output of the
+        // program, so it is written on the standard error instead
+        if output.wrote_stdout {
+            eprintln!("The program has written to the standard output, writing the final state on the standard error");
+            Box::new(io::stderr())
+        } else {
+            Box::new(io::stdout())
+        }
+    } else {
+        let file =
+            File::create(&configuration.output_state_file).expect("Error creating the output file");
+        Box::new(BufWriter::new(file))
+    };
+    serde_json::to_writer_pretty(&mut writer, &output).expect("Error writing the output");
+    writeln!(writer)
+        .and_then(|_| writer.flush())
+        .expect("Error writing the output");
+
+    // The exit code of the program is the one of the o1vm
+    match output.exit_code {
+        Some(exit_code) => ExitCode::from(exit_code as u8),
+        None => ExitCode::SUCCESS,
+    }
+}
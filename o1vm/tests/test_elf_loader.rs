@@ -1,3 +1,15 @@
+use mina_curves::pasta::Fp;
+use o1vm::{
+    cannon::PAGE_SIZE,
+    elf_loader::{load_riscv32, MMAP_START, STACK_TOP},
+    interpreters::riscv32im::{
+        interpreter::InterpreterEnv,
+        state::{Permissions, Segment},
+        witness::Env,
+    },
+    preimage_oracle::NullPreImageOracle,
+};
+
 #[test]
 // This test is used to check that the elf loader is working correctly.
 // We must export the code used in this test in a function that can be called by
@@ -11,9 +23,144 @@ fn test_correctly_parsing_elf() {
 
     // This is the output we get by running objdump -d fibonacci
     assert_eq!(state.pc, 69932);
+    assert_eq!(state.entry, 69932);
+
+    // The pages of the two segments, the headers and the code, followed by the
+    // page of the stack
+    let pages: Vec<_> = state.memory.iter().map(|page| page.index).collect();
+    assert_eq!(pages, [16, 17, STACK_TOP / PAGE_SIZE - 1]);
+}
 
-    // We do have only one page of memory
-    assert_eq!(state.memory.len(), 1);
-    // Which is the 17th
-    assert_eq!(state.memory[0].index, 17);
+#[test]
+fn test_loading_segments() {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/fibonacci",
+    ));
+    let (state, meta) = load_riscv32(&path, &["fibonacci", "7"], &["RUST_LOG=info"]).unwrap();
+
+    // This is the output we get by running readelf -l fibonacci
+    let read_only = Permissions {
+        read: true,
+        write: false,
+        execute: false,
+    };
+    let executable = Permissions {
+        execute: true,
+        ..read_only
+    };
+    assert_eq!(
+        state.segments,
+        [
+            Segment {
+                start: 0x10000,
+                size: 0xd4,
+                permissions: read_only,
+            },
+            Segment {
+                start: 0x110d4,
+                size: 0xbc,
+                permissions: executable,
+            },
+        ]
+    );
+    assert_eq!(state.program_break, 0x12000);
+    assert_eq!(state.heap, MMAP_START);
+
+    // The code is loaded at the address of its segment: the first instruction
+    // of fibonacci is addi sp, sp, -16
+    let code = &state.memory[1].data[0xd4..0xd8];
+    assert_eq!(u32::from_le_bytes(code.try_into().unwrap()), 0xff010113);
+
+    // This is the output we get by running readelf -s fibonacci
+    assert_eq!(
+        meta.find_address_symbol(state.entry),
+        Some("_start".to_string())
+    );
+    assert_eq!(
+        meta.find_address_symbol(0x110d4),
+        Some("fibonacci".to_string())
+    );
+
+    // The stack pointer points to argc, argv, envp and the auxiliary vector
+    let sp = state.registers[2];
+    assert_eq!(sp % 16, 0);
+    let stack = &state.memory.last().unwrap().data;
+    let read_word = |addr: u32| {
+        let offset = (addr % PAGE_SIZE) as usize;
+        u32::from_le_bytes(stack[offset..offset + 4].try_into().unwrap())
+    };
+    let read_string = |addr: u32| {
+        let offset = (addr % PAGE_SIZE) as usize;
+        let len = stack[offset..].iter().position(|b| *b == 0).unwrap();
+        String::from_utf8(stack[offset..offset + len].to_vec()).unwrap()
+    };
+    assert_eq!(read_word(sp), 2);
+    assert_eq!(read_string(read_word(sp + 4)), "fibonacci");
+    assert_eq!(read_string(read_word(sp + 8)), "7");
+    assert_eq!(read_word(sp + 12), 0);
+    assert_eq!(read_string(read_word(sp + 16)), "RUST_LOG=info");
+    assert_eq!(read_word(sp + 20), 0);
+    // AT_PAGESZ, AT_ENTRY and AT_NULL
+    assert_eq!(read_word(sp + 24), 6);
+    assert_eq!(read_word(sp + 28), PAGE_SIZE);
+    assert_eq!(read_word(sp + 32), 9);
+    assert_eq!(read_word(sp + 36), state.entry);
+    assert_eq!(read_word(sp + 40), 0);
+}
+
+fn load_data_bss() -> Env<Fp, NullPreImageOracle> {
+    let curr_dir = std::env::current_dir().unwrap();
+    let path = curr_dir.join(std::path::PathBuf::from(
+        "resources/programs/riscv32im/bin/data-bss",
+    ));
+    let (state, _meta) = load_riscv32(&path, &["data-bss"], &[]).unwrap();
+    Env::<Fp, _>::create(PAGE_SIZE as usize, state, NullPreImageOracle)
+}
+
+#[test]
+fn test_loading_data_and_bss() {
+    let mut env = load_data_bss();
+
+    // This is the output we get by running readelf -l data-bss
+    let read_write = Permissions {
+        read: true,
+        write: true,
+        execute: false,
+    };
+    assert_eq!(
+        env.segments[2],
+        Segment {
+            start: 0x12104,
+            size: 0x2008,
+            permissions: read_write,
+        }
+    );
+    assert_eq!(env.registers.program_break, 0x15000);
+
+    // The initialized data: "o1vm" followed by the word 0x12345678
+    let data: Vec<u8> = (0x12104..0x1210c)
+        .map(|addr| env.get_memory_direct(addr))
+        .collect();
+    assert_eq!(data, b"o1vm\x78\x56\x34\x12");
+
+    // The bss is zeroed, including the end of the page of the data, whose
+    // bytes in the file are the ones of the following sections
+    assert!((0x1210c..0x1410c).all(|addr| env.get_memory_direct(addr) == 0));
+}
+
+#[test]
+fn test_writing_data_and_bss() {
+    let mut env = load_data_bss();
+    for addr in [0x12104, 0x1210c, 0x1410b] {
+        unsafe { env.push_memory(&addr, 0xab) };
+        assert_eq!(env.get_memory_direct(addr as u32), 0xab);
+    }
+}
+
+#[test]
+#[should_panic(expected = "Write to the address 0x110f8 of a segment without write permission")]
+fn test_writing_code() {
+    let mut env = load_data_bss();
+    unsafe { env.push_memory(&0x110f8, 0) };
 }
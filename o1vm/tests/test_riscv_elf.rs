@@ -1,8 +1,11 @@
 use mina_curves::pasta::Fp;
-use o1vm::interpreters::riscv32im::{
-    interpreter::{IInstruction, Instruction, RInstruction},
-    witness::Env,
-    PAGE_SIZE,
+use o1vm::{
+    interpreters::riscv32im::{
+        interpreter::{IInstruction, Instruction, RInstruction},
+        witness::Env,
+        PAGE_SIZE,
+    },
+    preimage_oracle::NullPreImageOracle,
 };
 
 #[test]
@@ -30,7 +33,8 @@ fn test_no_action() {
         "resources/programs/riscv32im/bin/no-action",
     ));
     let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
-    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    let mut witness =
+        Env::<Fp, _>::create(PAGE_SIZE.try_into().unwrap(), state, NullPreImageOracle);
     // This is the output we get by running objdump -d no-action
     assert_eq!(witness.registers.current_instruction_pointer, 69844);
     assert_eq!(witness.registers.next_instruction_pointer, 69848);
@@ -59,7 +63,8 @@ fn test_fibonacci_7() {
         "resources/programs/riscv32im/bin/fibonacci-7",
     ));
     let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
-    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    let mut witness =
+        Env::<Fp, _>::create(PAGE_SIZE.try_into().unwrap(), state, NullPreImageOracle);
     // This is the output we get by running objdump -d fibonacci-7
     assert_eq!(witness.registers.current_instruction_pointer, 69932);
     assert_eq!(witness.registers.next_instruction_pointer, 69936);
@@ -84,7 +89,8 @@ fn test_sll() {
         "resources/programs/riscv32im/bin/sll",
     ));
     let state = o1vm::elf_loader::parse_riscv32(&path).unwrap();
-    let mut witness = Env::<Fp>::create(PAGE_SIZE.try_into().unwrap(), state);
+    let mut witness =
+        Env::<Fp, _>::create(PAGE_SIZE.try_into().unwrap(), state, NullPreImageOracle);
 
     while !witness.halt {
         witness.step();
@@ -0,0 +1,115 @@
+use mina_curves::pasta::Fp;
+use o1vm::{
+    interpreters::riscv32im::{state::State, witness::Env, PAGE_SIZE},
+    preimage_oracle::NullPreImageOracle,
+};
+use serde_json::Value;
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    process::{Command, Output},
+};
+
+const NO_ACTION: &str = "resources/programs/riscv32im/bin/no-action";
+
+/// Run the binary `riscv_o1vm` on the program `elf` with the arguments `args`,
+/// writing the final state in the file `name` of the temporary directory
+fn riscv_o1vm(elf: &str, name: &str, args: &[&str]) -> (Output, Value) {
+    let output_file = tmp_path(name);
+    let output = Command::new(env!("CARGO_BIN_EXE_riscv_o1vm"))
+        .arg(elf)
+        .arg("--output")
+        .arg(&output_file)
+        .args(args)
+        .output()
+        .expect("Could not run riscv_o1vm");
+    let file = File::open(&output_file).expect("Missing output file");
+    let json = serde_json::from_reader(file).expect("Invalid output file");
+    (output, json)
+}
+
+fn tmp_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_TARGET_TMPDIR")).join(name)
+}
+
+#[test]
+fn test_riscv_o1vm_exit() {
+    let (output, json) = riscv_o1vm(NO_ACTION, "no-action-exit.json", &[]);
+    assert_eq!(output.status.code(), Some(0));
+    assert!(output.stdout.is_empty());
+    assert_eq!(json["step"], 9);
+    assert_eq!(json["exited"], true);
+    assert_eq!(json["exit_code"], 0);
+    // The syscall used to stop the program successfully
+    assert_eq!(json["registers"][17], 42);
+}
+
+#[test]
+fn test_riscv_o1vm_stop_at() {
+    let (output, json) = riscv_o1vm(
+        NO_ACTION,
+        "no-action-stop-at.json",
+        &["--stop-at", "=3", "--trace"],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    assert_eq!(json["step"], 3);
+    assert_eq!(json["exited"], false);
+    assert_eq!(json["exit_code"], Value::Null);
+
+    // Only the instructions that have been executed are traced
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let trace: Vec<_> = stderr
+        .lines()
+        .filter(|line| line.trim_start().starts_with(char::is_numeric))
+        .collect();
+    assert_eq!(trace.len(), 3);
+    assert!(trace[2].contains("0x000110dc addi"));
+}
+
+#[test]
+fn test_riscv_o1vm_snapshot() {
+    let (_, json) = riscv_o1vm(NO_ACTION, "no-action-full.json", &[]);
+    let snapshot_fmt = tmp_path("no-action-state-%d.json");
+    let (output, _) = riscv_o1vm(
+        NO_ACTION,
+        "no-action-snapshot.json",
+        &[
+            "--snapshot-state-at",
+            "=4",
+            "--snapshot-fmt",
+            snapshot_fmt.to_str().unwrap(),
+        ],
+    );
+    assert_eq!(output.status.code(), Some(0));
+
+    let file = File::open(tmp_path("no-action-state-4.json")).expect("Missing snapshot");
+    let state: State = serde_json::from_reader(file).expect("Invalid snapshot");
+    assert_eq!(state.step, 4);
+    assert!(!state.exited);
+    assert_eq!(state.pc, 69844 + 4 * 4);
+
+    // Resuming from the snapshot gives the same final state
+    let mut env = Env::<Fp, _>::create(PAGE_SIZE as usize, state, NullPreImageOracle);
+    while !env.halt {
+        env.step();
+    }
+    assert_eq!(env.syscall_env.exit_code, Some(0));
+    assert_eq!(
+        serde_json::to_value(env.registers.general_purpose).unwrap(),
+        json["registers"]
+    );
+    assert_eq!(env.registers.current_instruction_pointer, json["pc"]);
+}
+
+#[test]
+fn test_riscv_o1vm_output_stdout() {
+    // The program does not write to the standard output, which only contains
+    // the final state
+    let output = Command::new(env!("CARGO_BIN_EXE_riscv_o1vm"))
+        .args([NO_ACTION, "--output", "-"])
+        .output()
+        .expect("Could not run riscv_o1vm");
+    assert_eq!(output.status.code(), Some(0));
+    let json: Value = serde_json::from_slice(&output.stdout).expect("Invalid output");
+    assert_eq!(json["exit_code"], 0);
+}
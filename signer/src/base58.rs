@@ -0,0 +1,40 @@
+//! Base58check encoding helpers
+//!
+//! Mina serializes most of its binary values (memos, token ids, signatures) as base58 with a
+//! version byte and a 4-byte double SHA-256 checksum.
+
+use sha2::{Digest, Sha256};
+
+/// Version byte of base58 encoded memos
+pub(crate) const MEMO_VERSION: u8 = 0x14;
+/// Version byte of base58 encoded token ids
+pub(crate) const TOKEN_ID_VERSION: u8 = 0x1c;
+/// Version byte of base58 encoded signatures
+pub(crate) const SIGNATURE_VERSION: u8 = 0x9a;
+
+/// Encode `payload` prefixed with `version` and followed by its checksum
+pub(crate) fn encode(version: u8, payload: &[u8]) -> String {
+    let mut raw = vec![version];
+    raw.extend(payload);
+
+    let hash = Sha256::digest(&Sha256::digest(&raw[..])[..]);
+    raw.extend(&hash[..4]);
+
+    bs58::encode(raw).into_string()
+}
+
+/// Decode a string produced by [`encode`], checking its `version` and checksum
+pub(crate) fn decode(version: u8, encoded: &str) -> Option<Vec<u8>> {
+    let bytes = bs58::decode(encoded).into_vec().ok()?;
+    if bytes.len() < 5 {
+        return None;
+    }
+
+    let (raw, checksum) = bytes.split_at(bytes.len() - 4);
+    let hash = Sha256::digest(&Sha256::digest(raw)[..]);
+    if checksum != &hash[..4] || raw[0] != version {
+        return None;
+    }
+
+    Some(raw[1..].to_vec())
+}
@@ -0,0 +1,562 @@
+//! Threshold Schnorr signatures (FROST)
+//!
+//! An implementation of t-of-n threshold signing for the Mina signature algorithm following
+//! [FROST](https://eprint.iacr.org/2020/852) on the Pallas curve.  Any `threshold`
+//! participants out of `max_signers` can jointly produce a standard [`Signature`] that
+//! verifies with [`Signer::verify`](crate::Signer::verify) against the group public key,
+//! while no smaller coalition learns anything about the group secret key.
+//!
+//! The protocol consists of
+//!
+//! 1. a distributed key generation ([`DkgParticipant`]) producing for each participant a
+//!    [`KeyPackage`] and, for everyone, the [`PublicKeyPackage`] of the group,
+//! 2. a nonce commitment round ([`commit`]) run by each signer before every signature,
+//! 3. a signing round ([`sign`]) in which each signer computes a [`SignatureShare`] of the
+//!    message described by a [`SigningPackage`],
+//! 4. the aggregation ([`aggregate`]) of the shares into a [`Signature`].
+//!
+//! Mina requires the nonce point `R` to have an even y-coordinate.  Since `R` is only known
+//! once all commitments are collected, each signer negates its nonces when `R` has an odd
+//! y-coordinate, exactly like the single-party signer does.
+
+use ark_ec::{AffineRepr, CurveGroup};
+use ark_ff::{BigInteger, Field, One, PrimeField, UniformRand, Zero};
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2bVar,
+};
+use o1_utils::FieldHelpers;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+use crate::{CurvePoint, Hashable, PubKey, ScalarField, Schnorr, Signature};
+
+/// Identifier of a participant, between `1` and `max_signers`
+pub type ParticipantId = u16;
+
+/// FROST errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum FrostError {
+    /// Invalid threshold or number of participants
+    #[error("invalid threshold {threshold} for {max_signers} participants")]
+    InvalidParameters {
+        /// Threshold
+        threshold: u16,
+        /// Number of participants
+        max_signers: u16,
+    },
+    /// Invalid participant identifier
+    #[error("invalid participant identifier {0}")]
+    InvalidParticipant(ParticipantId),
+    /// Missing package from a participant
+    #[error("missing package from participant {0}")]
+    MissingPackage(ParticipantId),
+    /// Several packages from the same participant
+    #[error("duplicated package from participant {0}")]
+    DuplicatePackage(ParticipantId),
+    /// Package from a sender that is not expected to send one, i.e. that is not a
+    /// participant or, in the second round, that is the recipient
+    #[error("unexpected package from {0}")]
+    UnexpectedPackage(ParticipantId),
+    /// Invalid proof of knowledge of a participant's secret
+    #[error("invalid proof of knowledge from participant {0}")]
+    InvalidProofOfKnowledge(ParticipantId),
+    /// Secret share inconsistent with the sender's commitments
+    #[error("invalid secret share from participant {0}")]
+    InvalidSecretShare(ParticipantId),
+    /// Not enough signers
+    #[error("not enough signers: {signers} < {threshold}")]
+    NotEnoughSigners {
+        /// Number of signers
+        signers: usize,
+        /// Threshold
+        threshold: u16,
+    },
+    /// Signer is not part of the signing package
+    #[error("participant {0} is not part of the signing package")]
+    UnknownSigner(ParticipantId),
+    /// Signer's nonces do not match its commitments in the signing package
+    #[error("nonces of participant {0} do not match its commitments")]
+    NonceCommitmentMismatch(ParticipantId),
+    /// Invalid signature share
+    #[error("invalid signature share from participant {0}")]
+    InvalidSignatureShare(ParticipantId),
+}
+/// FROST result
+pub type Result<T> = std::result::Result<T, FrostError>;
+
+/// Package broadcast by each participant in the first round of the key generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round1Package {
+    /// Sender of the package
+    pub sender: ParticipantId,
+    /// Commitments `a_k * G` to the coefficients of the sender's secret polynomial
+    pub commitments: Vec<CurvePoint>,
+    /// Proof of knowledge `(R, z)` of the sender's secret `a_0`
+    pub proof: (CurvePoint, ScalarField),
+}
+
+/// Package privately sent by each participant to every other one in the second round of
+/// the key generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Round2Package {
+    /// Sender of the package
+    pub sender: ParticipantId,
+    /// Recipient of the package
+    pub recipient: ParticipantId,
+    /// Secret share `f(recipient)` of the sender's secret polynomial `f`
+    pub share: ScalarField,
+}
+
+/// Public keys of the group
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKeyPackage {
+    /// Group public key, against which the aggregated signatures verify
+    pub group_public: PubKey,
+    /// Public verifying share `x_i * G` of each participant
+    pub verifying_shares: BTreeMap<ParticipantId, CurvePoint>,
+    /// Minimum number of signers
+    pub threshold: u16,
+}
+
+/// Key material of a participant, resulting from the key generation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyPackage {
+    /// Participant
+    pub id: ParticipantId,
+    /// Secret signing share `x_i`
+    pub signing_share: ScalarField,
+    /// Public keys of the group
+    pub public: PublicKeyPackage,
+}
+
+/// Participant of the distributed key generation
+pub struct DkgParticipant {
+    id: ParticipantId,
+    threshold: u16,
+    max_signers: u16,
+    coefficients: Vec<ScalarField>,
+}
+
+impl DkgParticipant {
+    /// Start the key generation as participant `id` of a `threshold`-of-`max_signers` group,
+    /// returning the participant state and the [`Round1Package`] to broadcast
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `threshold` is not between 1 and `max_signers`, or if `id` is not
+    /// between 1 and `max_signers`.
+    pub fn new(
+        id: ParticipantId,
+        threshold: u16,
+        max_signers: u16,
+        rng: &mut (impl RngCore + CryptoRng),
+    ) -> Result<(Self, Round1Package)> {
+        if threshold == 0 || threshold > max_signers {
+            return Err(FrostError::InvalidParameters {
+                threshold,
+                max_signers,
+            });
+        }
+        if id == 0 || id > max_signers {
+            return Err(FrostError::InvalidParticipant(id));
+        }
+
+        let coefficients: Vec<ScalarField> =
+            (0..threshold).map(|_| ScalarField::rand(rng)).collect();
+        let commitments: Vec<CurvePoint> = coefficients.iter().map(|a| base_mul(*a)).collect();
+
+        // Proof of knowledge of a_0, preventing rogue key attacks
+        let k = ScalarField::rand(rng);
+        let r = base_mul(k);
+        let c = pok_challenge(id, &commitments[0], &r);
+        let z = k + coefficients[0] * c;
+
+        let package = Round1Package {
+            sender: id,
+            commitments,
+            proof: (r, z),
+        };
+        let participant = Self {
+            id,
+            threshold,
+            max_signers,
+            coefficients,
+        };
+
+        Ok((participant, package))
+    }
+
+    /// Index `packages` by sender, checking that each sender is a participant other than
+    /// `excluded` and sent a single package
+    fn index_by_sender<'a, P>(
+        &self,
+        packages: impl Iterator<Item = (ParticipantId, &'a P)>,
+        excluded: Option<ParticipantId>,
+    ) -> Result<BTreeMap<ParticipantId, &'a P>> {
+        let mut map = BTreeMap::new();
+        for (sender, pkg) in packages {
+            if sender == 0 || sender > self.max_signers || Some(sender) == excluded {
+                return Err(FrostError::UnexpectedPackage(sender));
+            }
+            if map.insert(sender, pkg).is_some() {
+                return Err(FrostError::DuplicatePackage(sender));
+            }
+        }
+        Ok(map)
+    }
+
+    /// Compute the [`Round2Package`]s to privately send to every other participant
+    pub fn round2(&self) -> Vec<Round2Package> {
+        (1..=self.max_signers)
+            .filter(|recipient| *recipient != self.id)
+            .map(|recipient| Round2Package {
+                sender: self.id,
+                recipient,
+                share: eval_polynomial(&self.coefficients, recipient),
+            })
+            .collect()
+    }
+
+    /// Finish the key generation given the [`Round1Package`]s broadcast by all participants
+    /// and the [`Round2Package`]s sent to this participant
+    ///
+    /// # Errors
+    ///
+    /// Will give error if a package is missing, duplicated or sent by an unexpected sender,
+    /// or if a participant's proof of knowledge or secret share is invalid.
+    pub fn finish(self, round1: &[Round1Package], round2: &[Round2Package]) -> Result<KeyPackage> {
+        let round1 = self.index_by_sender(round1.iter().map(|pkg| (pkg.sender, pkg)), None)?;
+        let round2 = self.index_by_sender(
+            round2
+                .iter()
+                .filter(|pkg| pkg.recipient == self.id)
+                .map(|pkg| (pkg.sender, pkg)),
+            Some(self.id),
+        )?;
+
+        let mut signing_share = eval_polynomial(&self.coefficients, self.id);
+        for sender in 1..=self.max_signers {
+            let pkg = round1
+                .get(&sender)
+                .ok_or(FrostError::MissingPackage(sender))?;
+            if pkg.commitments.len() != self.threshold as usize {
+                return Err(FrostError::MissingPackage(sender));
+            }
+
+            let (r, z) = pkg.proof;
+            let c = pok_challenge(sender, &pkg.commitments[0], &r);
+            if (CurvePoint::generator() * z - pkg.commitments[0] * c).into_affine() != r {
+                return Err(FrostError::InvalidProofOfKnowledge(sender));
+            }
+
+            if sender == self.id {
+                continue;
+            }
+            let share = round2
+                .get(&sender)
+                .ok_or(FrostError::MissingPackage(sender))?
+                .share;
+            if base_mul(share) != eval_commitments(&pkg.commitments, self.id) {
+                return Err(FrostError::InvalidSecretShare(sender));
+            }
+            signing_share += share;
+        }
+
+        let group_public = PubKey::from_point_unsafe(
+            round1
+                .values()
+                .fold(CurvePoint::zero().into_group(), |acc, pkg| {
+                    acc + pkg.commitments[0]
+                })
+                .into_affine(),
+        );
+        let verifying_shares = (1..=self.max_signers)
+            .map(|i| {
+                let share = round1
+                    .values()
+                    .fold(CurvePoint::zero().into_group(), |acc, pkg| {
+                        acc + eval_commitments(&pkg.commitments, i)
+                    });
+                (i, share.into_affine())
+            })
+            .collect();
+
+        Ok(KeyPackage {
+            id: self.id,
+            signing_share,
+            public: PublicKeyPackage {
+                group_public,
+                verifying_shares,
+                threshold: self.threshold,
+            },
+        })
+    }
+}
+
+/// Secret nonces of a signer, to be used for a single signature
+pub struct SigningNonces {
+    hiding: ScalarField,
+    binding: ScalarField,
+}
+
+/// Public commitments to the [`SigningNonces`] of a signer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningCommitments {
+    /// Signer
+    pub id: ParticipantId,
+    /// Commitment to the hiding nonce
+    pub hiding: CurvePoint,
+    /// Commitment to the binding nonce
+    pub binding: CurvePoint,
+}
+
+/// Generate the nonces of signer `id` for a new signature, together with the commitments
+/// to send to the coordinator
+pub fn commit(
+    id: ParticipantId,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> (SigningNonces, SigningCommitments) {
+    let nonces = SigningNonces {
+        hiding: ScalarField::rand(rng),
+        binding: ScalarField::rand(rng),
+    };
+    let commitments = SigningCommitments {
+        id,
+        hiding: base_mul(nonces.hiding),
+        binding: base_mul(nonces.binding),
+    };
+    (nonces, commitments)
+}
+
+/// Message to sign and commitments of the signers, as chosen by the coordinator
+#[derive(Clone)]
+pub struct SigningPackage<H: Hashable> {
+    commitments: BTreeMap<ParticipantId, SigningCommitments>,
+    message: H,
+}
+
+impl<H: 'static + Hashable> SigningPackage<H> {
+    /// Create the signing package of `message` for the signers of `commitments`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if several commitments come from the same signer.
+    pub fn new(commitments: Vec<SigningCommitments>, message: H) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        for c in commitments {
+            let id = c.id;
+            if map.insert(id, c).is_some() {
+                return Err(FrostError::DuplicatePackage(id));
+            }
+        }
+        Ok(Self {
+            commitments: map,
+            message,
+        })
+    }
+
+    /// Message to sign
+    pub fn message(&self) -> &H {
+        &self.message
+    }
+
+    /// Binding factor of each signer
+    fn binding_factors(&self) -> BTreeMap<ParticipantId, ScalarField> {
+        let mut encoded = self.message.to_roinput().to_bytes();
+        for c in self.commitments.values() {
+            encoded.extend(c.id.to_le_bytes());
+            encoded.extend(point_to_bytes(&c.hiding));
+            encoded.extend(point_to_bytes(&c.binding));
+        }
+
+        self.commitments
+            .keys()
+            .map(|id| {
+                (
+                    *id,
+                    hash_to_scalar(b"MinaFrostBinding", &[&id.to_le_bytes(), &encoded]),
+                )
+            })
+            .collect()
+    }
+
+    /// Group commitment `R`, and whether the nonces must be negated for `R` to have an even
+    /// y-coordinate
+    fn group_commitment(
+        &self,
+        binding_factors: &BTreeMap<ParticipantId, ScalarField>,
+    ) -> (CurvePoint, bool) {
+        let r = self
+            .commitments
+            .values()
+            .fold(CurvePoint::zero().into_group(), |acc, c| {
+                acc + c.hiding + c.binding * binding_factors[&c.id]
+            })
+            .into_affine();
+        if r.y.into_bigint().is_even() {
+            (r, false)
+        } else {
+            (-r, true)
+        }
+    }
+
+    /// Lagrange coefficient at zero of signer `id` among the signers of the package
+    fn lagrange_coefficient(&self, id: ParticipantId) -> ScalarField {
+        let xi = ScalarField::from(id as u64);
+        let (num, den) = self.commitments.keys().filter(|j| **j != id).fold(
+            (ScalarField::one(), ScalarField::one()),
+            |(num, den), j| {
+                let xj = ScalarField::from(*j as u64);
+                (num * xj, den * (xj - xi))
+            },
+        );
+        num * den.inverse().expect("signer identifiers are distinct")
+    }
+}
+
+/// Share of a signature computed by a signer
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureShare {
+    /// Signer
+    pub id: ParticipantId,
+    /// Share `z_i` of the signature's `s`
+    pub share: ScalarField,
+}
+
+/// Compute the signature share of `key` for `package` with the `nonces` committed for it,
+/// using `ctx` to compute the challenge
+///
+/// The nonces are consumed since reusing them for another signature leaks the signing share.
+///
+/// # Errors
+///
+/// Will give error if the signer is not part of the package or its nonce commitments differ.
+pub fn sign<H: 'static + Hashable>(
+    ctx: &mut Schnorr<H>,
+    package: &SigningPackage<H>,
+    nonces: SigningNonces,
+    key: &KeyPackage,
+) -> Result<SignatureShare> {
+    let commitments = package
+        .commitments
+        .get(&key.id)
+        .ok_or(FrostError::UnknownSigner(key.id))?;
+    if commitments.hiding != base_mul(nonces.hiding)
+        || commitments.binding != base_mul(nonces.binding)
+    {
+        return Err(FrostError::NonceCommitmentMismatch(key.id));
+    }
+
+    let binding_factors = package.binding_factors();
+    let (r, negate) = package.group_commitment(&binding_factors);
+    let c = ctx.message_hash(&key.public.group_public, r.x, &package.message);
+
+    let k = nonces.hiding + nonces.binding * binding_factors[&key.id];
+    let k = if negate { -k } else { k };
+    let share = k + package.lagrange_coefficient(key.id) * key.signing_share * c;
+
+    Ok(SignatureShare { id: key.id, share })
+}
+
+/// Aggregate the signature `shares` of the signers of `package` into a signature, checking
+/// each share against the signer's verifying share in `public`
+///
+/// # Errors
+///
+/// Will give error if there are not enough signers, if a share is missing or if a share
+/// is invalid.
+pub fn aggregate<H: 'static + Hashable>(
+    ctx: &mut Schnorr<H>,
+    package: &SigningPackage<H>,
+    shares: &[SignatureShare],
+    public: &PublicKeyPackage,
+) -> Result<Signature> {
+    if package.commitments.len() < public.threshold as usize {
+        return Err(FrostError::NotEnoughSigners {
+            signers: package.commitments.len(),
+            threshold: public.threshold,
+        });
+    }
+
+    let shares: BTreeMap<ParticipantId, ScalarField> =
+        shares.iter().map(|s| (s.id, s.share)).collect();
+
+    let binding_factors = package.binding_factors();
+    let (r, negate) = package.group_commitment(&binding_factors);
+    let c = ctx.message_hash(&public.group_public, r.x, &package.message);
+
+    let mut s = ScalarField::zero();
+    for (id, commitments) in &package.commitments {
+        let share = *shares.get(id).ok_or(FrostError::MissingPackage(*id))?;
+        let verifying_share = public
+            .verifying_shares
+            .get(id)
+            .ok_or(FrostError::UnknownSigner(*id))?;
+
+        // z_i * G == R_i + c * lambda_i * Y_i
+        let r_i = commitments.binding * binding_factors[id] + commitments.hiding;
+        let r_i = if negate { -r_i } else { r_i };
+        let expected = r_i + *verifying_share * (c * package.lagrange_coefficient(*id));
+        if base_mul(share) != expected.into_affine() {
+            return Err(FrostError::InvalidSignatureShare(*id));
+        }
+
+        s += share;
+    }
+
+    Ok(Signature::new(r.x, s))
+}
+
+fn base_mul(s: ScalarField) -> CurvePoint {
+    (CurvePoint::generator() * s).into_affine()
+}
+
+/// Evaluate the polynomial with `coefficients` at `x`
+fn eval_polynomial(coefficients: &[ScalarField], x: ParticipantId) -> ScalarField {
+    let x = ScalarField::from(x as u64);
+    coefficients
+        .iter()
+        .rev()
+        .fold(ScalarField::zero(), |acc, a| acc * x + a)
+}
+
+/// Evaluate the polynomial committed to by `commitments` at `x`, in the exponent
+fn eval_commitments(commitments: &[CurvePoint], x: ParticipantId) -> CurvePoint {
+    let x = ScalarField::from(x as u64);
+    commitments
+        .iter()
+        .rev()
+        .fold(CurvePoint::zero().into_group(), |acc, c| acc * x + c)
+        .into_affine()
+}
+
+fn point_to_bytes(p: &CurvePoint) -> Vec<u8> {
+    [p.x.to_bytes(), p.y.to_bytes()].concat()
+}
+
+fn pok_challenge(id: ParticipantId, a0: &CurvePoint, r: &CurvePoint) -> ScalarField {
+    hash_to_scalar(
+        b"MinaFrostKeygen",
+        &[&id.to_le_bytes(), &point_to_bytes(a0), &point_to_bytes(r)],
+    )
+}
+
+/// Hash `parts` with domain separation `domain` into a scalar field element
+fn hash_to_scalar(domain: &[u8], parts: &[&[u8]]) -> ScalarField {
+    let mut blake_hasher = Blake2bVar::new(32).unwrap();
+    blake_hasher.update(domain);
+    for part in parts {
+        blake_hasher.update(part);
+    }
+
+    let mut bytes = [0; 32];
+    blake_hasher
+        .finalize_variable(&mut bytes)
+        .expect("incorrect output size");
+    // Drop the top two bits to convert into a scalar field element (see nonce derivation)
+    bytes[bytes.len() - 1] &= 0b0011_1111;
+
+    ScalarField::from_random_bytes(&bytes[..]).expect("failed to create scalar from bytes")
+}
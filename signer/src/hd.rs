@@ -0,0 +1,456 @@
+//! Hierarchical deterministic key derivation
+//!
+//! Mina wallets derive their keys from a [BIP39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki)
+//! mnemonic, following [BIP32](https://github.com/bitcoin/bips/blob/master/bip-0032.mediawiki)
+//! over secp256k1 along the [BIP44](https://github.com/bitcoin/bips/blob/master/bip-0044.mediawiki)
+//! path `m/44'/12586'/account'/0/0` (see [`DerivationPath::mina`]), as done by the Mina
+//! Ledger application.
+//!
+//! The 32 bytes of a derived secp256k1 secret key do not always encode a Pallas scalar.
+//! They are turned into one by clearing their two most significant bits (see
+//! [`ExtendedSecKey::to_sec_key`]), which must be done exactly this way to obtain the same
+//! addresses as other Mina wallets.
+//!
+//! ```
+//! use mina_signer::hd::{DerivationPath, ExtendedSecKey, Mnemonic};
+//!
+//! let mnemonic = Mnemonic::parse(
+//!     "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+//! )
+//! .expect("valid mnemonic");
+//! let master = ExtendedSecKey::from_seed(&mnemonic.to_seed("")).expect("valid seed");
+//! let path = DerivationPath::mina(0).expect("valid account");
+//! let keypair = master.derive_path(&path).expect("valid path").to_keypair();
+//!
+//! assert!(keypair.get_address().starts_with("B62q"));
+//! ```
+
+use crate::{Keypair, SecKey};
+use hmac::{Hmac, Mac};
+use rand::{CryptoRng, RngCore};
+use ripemd::Ripemd160;
+use secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use sha2::{Digest, Sha256, Sha512};
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+/// BIP44 coin type of Mina
+pub const MINA_COIN_TYPE: u32 = 12586;
+
+/// Offset of hardened child indices
+pub const HARDENED_OFFSET: u32 = 1 << 31;
+
+/// Length of a BIP39 seed in bytes
+pub const SEED_LEN: usize = 64;
+
+/// Length of a decoded extended key in bytes
+const EXTENDED_KEY_LEN: usize = 78;
+
+/// Version bytes of serialized extended secret keys (`xprv`)
+const EXTENDED_SEC_KEY_VERSION: [u8; 4] = [0x04, 0x88, 0xad, 0xe4];
+
+/// HMAC key of the master key derivation
+const MASTER_KEY_HMAC_KEY: &[u8] = b"Bitcoin seed";
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// HD derivation error
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum HdError {
+    /// Invalid mnemonic
+    #[error("invalid mnemonic")]
+    Mnemonic,
+    /// Invalid mnemonic word count
+    #[error("invalid mnemonic word count")]
+    MnemonicWordCount,
+    /// Invalid seed length
+    #[error("invalid seed length")]
+    SeedLength,
+    /// Invalid derivation path
+    #[error("invalid derivation path")]
+    DerivationPath,
+    /// Derived key is invalid, the next child index should be used instead
+    #[error("invalid derived key")]
+    DerivedKey,
+    /// Maximum derivation depth exceeded
+    #[error("maximum derivation depth exceeded")]
+    Depth,
+    /// Invalid base58 extended key
+    #[error("invalid extended key base58")]
+    ExtendedKeyBase58,
+    /// Invalid extended key checksum
+    #[error("invalid extended key checksum")]
+    ExtendedKeyChecksum,
+    /// Invalid extended key version
+    #[error("invalid extended key version")]
+    ExtendedKeyVersion,
+    /// Invalid extended key bytes
+    #[error("invalid extended key bytes")]
+    ExtendedKeyBytes,
+}
+/// HD derivation result
+pub type Result<T> = std::result::Result<T, HdError>;
+
+/// BIP39 mnemonic (English word list)
+#[derive(Clone, PartialEq, Eq)] // No Debug
+pub struct Mnemonic(bip39::Mnemonic);
+
+impl Mnemonic {
+    /// Generate a random mnemonic of `word_count` words
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `word_count` is not one of 12, 15, 18, 21 or 24.
+    pub fn generate(rng: &mut (impl RngCore + CryptoRng), word_count: usize) -> Result<Self> {
+        if ![12, 15, 18, 21, 24].contains(&word_count) {
+            return Err(HdError::MnemonicWordCount);
+        }
+        // Each group of 3 words encodes 32 bits of entropy and 1 bit of checksum
+        let mut entropy = vec![0u8; word_count / 3 * 4];
+        rng.fill_bytes(&mut entropy);
+        Self::from_entropy(&entropy)
+    }
+
+    /// Create a mnemonic from `entropy`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `entropy` is not 16, 20, 24, 28 or 32 bytes long.
+    pub fn from_entropy(entropy: &[u8]) -> Result<Self> {
+        bip39::Mnemonic::from_entropy(entropy)
+            .map(Self)
+            .map_err(|_| HdError::MnemonicWordCount)
+    }
+
+    /// Parse a mnemonic `phrase`, checking its words and checksum
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `phrase` is not a valid English mnemonic.
+    pub fn parse(phrase: &str) -> Result<Self> {
+        bip39::Mnemonic::parse_in(bip39::Language::English, phrase)
+            .map(Self)
+            .map_err(|_| HdError::Mnemonic)
+    }
+
+    /// Get the number of words of the mnemonic
+    pub fn word_count(&self) -> usize {
+        self.0.word_count()
+    }
+
+    /// Get the seed of the mnemonic protected by `passphrase` (possibly empty)
+    pub fn to_seed(&self, passphrase: &str) -> [u8; SEED_LEN] {
+        self.0.to_seed(passphrase)
+    }
+}
+
+impl fmt::Display for Mnemonic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// BIP32 derivation path
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Create a derivation path from child indices
+    pub fn new(indices: Vec<u32>) -> Self {
+        Self(indices)
+    }
+
+    /// Get the path `m/44'/12586'/account'/0/0` of the Mina `account`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `account` is not below [`HARDENED_OFFSET`].
+    pub fn mina(account: u32) -> Result<Self> {
+        if account >= HARDENED_OFFSET {
+            return Err(HdError::DerivationPath);
+        }
+        Ok(Self(vec![
+            44 + HARDENED_OFFSET,
+            MINA_COIN_TYPE + HARDENED_OFFSET,
+            account + HARDENED_OFFSET,
+            0,
+            0,
+        ]))
+    }
+
+    /// Borrow the child indices of the path
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = HdError;
+
+    /// Parse a path such as `m/44'/12586'/0'/0/0`, where hardened indices are marked by
+    /// `'`, `h` or `H`
+    fn from_str(path: &str) -> Result<Self> {
+        let mut components = path.split('/');
+        if components.next() != Some("m") {
+            return Err(HdError::DerivationPath);
+        }
+
+        components
+            .map(|component| {
+                let (index, hardened) = match component.strip_suffix(['\'', 'h', 'H']) {
+                    Some(index) => (index, true),
+                    None => (component, false),
+                };
+                // Reject signs and other forms accepted by u32::from_str
+                if index.is_empty() || !index.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(HdError::DerivationPath);
+                }
+                let index: u32 = index.parse().map_err(|_| HdError::DerivationPath)?;
+                if index >= HARDENED_OFFSET {
+                    return Err(HdError::DerivationPath);
+                }
+                Ok(if hardened {
+                    index + HARDENED_OFFSET
+                } else {
+                    index
+                })
+            })
+            .collect::<Result<Vec<u32>>>()
+            .map(Self)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            if *index >= HARDENED_OFFSET {
+                write!(f, "/{}'", index - HARDENED_OFFSET)?;
+            } else {
+                write!(f, "/{index}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// BIP32 extended secret key
+#[derive(Clone, PartialEq, Eq)] // No Debug
+pub struct ExtendedSecKey {
+    depth: u8,
+    parent_fingerprint: [u8; 4],
+    child_number: u32,
+    chain_code: [u8; 32],
+    secret: SecretKey,
+}
+
+impl ExtendedSecKey {
+    /// Derive the master extended secret key from a BIP39 (or any BIP32) `seed`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `seed` is not between 16 and 64 bytes long, or in the
+    /// (negligible probability) case it does not give a valid secret key.
+    pub fn from_seed(seed: &[u8]) -> Result<Self> {
+        if seed.len() < 16 || seed.len() > SEED_LEN {
+            return Err(HdError::SeedLength);
+        }
+
+        let (secret, chain_code) = hmac_sha512(MASTER_KEY_HMAC_KEY, &[seed]);
+        let secret = SecretKey::from_slice(&secret).map_err(|_| HdError::DerivedKey)?;
+
+        Ok(Self {
+            depth: 0,
+            parent_fingerprint: [0; 4],
+            child_number: 0,
+            chain_code,
+            secret,
+        })
+    }
+
+    /// Derive the child extended secret key of number `index`; indices from
+    /// [`HARDENED_OFFSET`] on give hardened children
+    ///
+    /// # Errors
+    ///
+    /// Will give error if the maximum depth is reached, or in the (negligible probability)
+    /// case the child key is invalid, in which case the next index should be used.
+    pub fn derive_child(&self, index: u32) -> Result<Self> {
+        let depth = self.depth.checked_add(1).ok_or(HdError::Depth)?;
+
+        let index_bytes = index.to_be_bytes();
+        let (tweak, chain_code) = if index >= HARDENED_OFFSET {
+            hmac_sha512(
+                &self.chain_code,
+                &[&[0], &self.secret.secret_bytes(), &index_bytes],
+            )
+        } else {
+            hmac_sha512(&self.chain_code, &[&self.public_key_bytes(), &index_bytes])
+        };
+
+        let tweak = Scalar::from_be_bytes(tweak).map_err(|_| HdError::DerivedKey)?;
+        let secret = self
+            .secret
+            .add_tweak(&tweak)
+            .map_err(|_| HdError::DerivedKey)?;
+
+        Ok(Self {
+            depth,
+            parent_fingerprint: self.fingerprint(),
+            child_number: index,
+            chain_code,
+            secret,
+        })
+    }
+
+    /// Derive the extended secret key at `path` from this one
+    ///
+    /// # Errors
+    ///
+    /// Will give error if one of the derivation steps fails, see [`Self::derive_child`].
+    pub fn derive_path(&self, path: &DerivationPath) -> Result<Self> {
+        path.indices()
+            .iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Get the depth of the key, 0 for the master key
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Get the child number of the key, 0 for the master key
+    pub fn child_number(&self) -> u32 {
+        self.child_number
+    }
+
+    /// Borrow the chain code of the key
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Get the fingerprint of the key, i.e. the first 4 bytes of the HASH160 of its
+    /// compressed secp256k1 public key
+    pub fn fingerprint(&self) -> [u8; 4] {
+        let hash = Ripemd160::digest(Sha256::digest(self.public_key_bytes()));
+        [hash[0], hash[1], hash[2], hash[3]]
+    }
+
+    /// Get the big-endian bytes of the secp256k1 secret key
+    pub fn secret_bytes(&self) -> [u8; 32] {
+        self.secret.secret_bytes()
+    }
+
+    /// Convert into a Mina secret key
+    ///
+    /// The two most significant bits of the secp256k1 secret key are cleared so that the
+    /// resulting 254-bit integer is below the order of the Pallas scalar field.
+    pub fn to_sec_key(&self) -> SecKey {
+        let mut bytes = self.secret_bytes();
+        bytes[0] &= 0x3f;
+        SecKey::from_bytes(&bytes).expect("254-bit integers are Pallas scalars")
+    }
+
+    /// Convert into a Mina keypair
+    pub fn to_keypair(&self) -> Keypair {
+        Keypair::from_secret_key(self.to_sec_key())
+            .expect("public key of a valid secret key is on the curve")
+    }
+
+    /// Serialize extended secret key into base58 (`xprv...`)
+    pub fn to_base58(&self) -> String {
+        let mut raw = Vec::with_capacity(EXTENDED_KEY_LEN + 4);
+        raw.extend(EXTENDED_SEC_KEY_VERSION);
+        raw.push(self.depth);
+        raw.extend(self.parent_fingerprint);
+        raw.extend(self.child_number.to_be_bytes());
+        raw.extend(self.chain_code);
+        raw.push(0);
+        raw.extend(self.secret.secret_bytes());
+
+        let checksum = Sha256::digest(&Sha256::digest(&raw[..])[..]);
+        raw.extend(&checksum[..4]);
+
+        bs58::encode(raw).into_string()
+    }
+
+    /// Deserialize base58 encoded extended secret key
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `base58` string is not a valid extended secret key.
+    pub fn from_base58(base58: &str) -> Result<Self> {
+        let bytes = bs58::decode(base58)
+            .into_vec()
+            .map_err(|_| HdError::ExtendedKeyBase58)?;
+        if bytes.len() != EXTENDED_KEY_LEN + 4 {
+            return Err(HdError::ExtendedKeyBytes);
+        }
+
+        let (raw, checksum) = bytes.split_at(EXTENDED_KEY_LEN);
+        let hash = Sha256::digest(&Sha256::digest(raw)[..]);
+        if checksum != &hash[..4] {
+            return Err(HdError::ExtendedKeyChecksum);
+        }
+
+        if raw[..4] != EXTENDED_SEC_KEY_VERSION {
+            return Err(HdError::ExtendedKeyVersion);
+        }
+
+        let depth = raw[4];
+        let parent_fingerprint: [u8; 4] = raw[5..9].try_into().expect("4 bytes");
+        let child_number = u32::from_be_bytes(raw[9..13].try_into().expect("4 bytes"));
+        let chain_code: [u8; 32] = raw[13..45].try_into().expect("32 bytes");
+
+        // Master keys have neither parent nor child number
+        if depth == 0 && (parent_fingerprint != [0; 4] || child_number != 0) {
+            return Err(HdError::ExtendedKeyBytes);
+        }
+        if raw[45] != 0 {
+            return Err(HdError::ExtendedKeyBytes);
+        }
+        let secret = SecretKey::from_slice(&raw[46..]).map_err(|_| HdError::ExtendedKeyBytes)?;
+
+        Ok(Self {
+            depth,
+            parent_fingerprint,
+            child_number,
+            chain_code,
+            secret,
+        })
+    }
+
+    /// Compressed secp256k1 public key of the key
+    fn public_key_bytes(&self) -> [u8; 33] {
+        PublicKey::from_secret_key(&Secp256k1::signing_only(), &self.secret).serialize()
+    }
+}
+
+impl Keypair {
+    /// Derive the keypair of Mina `account` from a BIP39 `mnemonic` and its `passphrase`
+    /// (possibly empty), along the path given by [`DerivationPath::mina`]
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `account` is not below [`HARDENED_OFFSET`].
+    pub fn from_mnemonic(mnemonic: &Mnemonic, passphrase: &str, account: u32) -> Result<Self> {
+        let path = DerivationPath::mina(account)?;
+        let key = ExtendedSecKey::from_seed(&mnemonic.to_seed(passphrase))?.derive_path(&path)?;
+        Ok(key.to_keypair())
+    }
+}
+
+/// HMAC-SHA512 of the concatenation of `data` under `key`, split in two halves
+fn hmac_sha512(key: &[u8], data: &[&[u8]]) -> ([u8; 32], [u8; 32]) {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC accepts keys of any length");
+    for d in data {
+        mac.update(d);
+    }
+    let output = mac.finalize().into_bytes();
+
+    let mut left = [0u8; 32];
+    let mut right = [0u8; 32];
+    left.copy_from_slice(&output[..32]);
+    right.copy_from_slice(&output[32..]);
+    (left, right)
+}
@@ -1,19 +1,27 @@
 #![deny(missing_docs)]
 #![doc = include_str!("../README.md")]
 
+mod base58;
+pub mod frost;
+pub mod hd;
 pub mod keypair;
+pub mod nonce;
 pub mod pubkey;
 pub mod schnorr;
 pub mod seckey;
 pub mod signature;
+pub mod transaction;
+pub mod zkapp;
 
 use mina_hasher::{DomainParameter, Hashable};
 
 pub use keypair::Keypair;
 pub use pubkey::{CompressedPubKey, PubKey};
-pub use schnorr::Schnorr;
+pub use schnorr::{Schnorr, SigningTranscript};
 pub use seckey::SecKey;
 pub use signature::Signature;
+pub use transaction::{Memo, Payment, StakeDelegation};
+pub use zkapp::ZkappCommand;
 
 use ark_ec::AffineRepr;
 
@@ -26,25 +34,84 @@ pub type BaseField = <CurvePoint as AffineRepr>::BaseField;
 /// Scalar field element type
 pub type ScalarField = <CurvePoint as AffineRepr>::ScalarField;
 
+/// Maximum length of a domain string (see [`Hashable::domain_string`])
+const MAX_DOMAIN_STRING_LEN: usize = 20;
+
 /// Mina network (or blockchain) identifier
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum NetworkId {
     /// Id for all testnets
-    TESTNET = 0x00,
+    TESTNET,
 
     /// Id for mainnet
-    MAINNET = 0x01,
+    MAINNET,
+
+    /// Id of a custom (e.g. private) network, given by its string identifier
+    ///
+    /// Custom networks are domain separated by their identifier, which replaces the single
+    /// network byte of [`NetworkId::TESTNET`] and [`NetworkId::MAINNET`] in the domain
+    /// parameter and is prepended to their domain strings (see
+    /// [`NetworkId::into_domain_string`]).
+    Custom(String),
 }
 
-impl From<NetworkId> for u8 {
-    fn from(id: NetworkId) -> u8 {
-        id as u8
+impl TryFrom<NetworkId> for u8 {
+    type Error = NetworkId;
+
+    /// Get the network byte of `id`, giving back `id` if it is a custom network
+    fn try_from(id: NetworkId) -> Result<u8, NetworkId> {
+        match id {
+            NetworkId::TESTNET => Ok(0x00),
+            NetworkId::MAINNET => Ok(0x01),
+            NetworkId::Custom(_) => Err(id),
+        }
     }
 }
 
 impl DomainParameter for NetworkId {
     fn into_bytes(self) -> Vec<u8> {
-        vec![self as u8]
+        match self {
+            NetworkId::TESTNET => vec![0x00],
+            NetworkId::MAINNET => vec![0x01],
+            NetworkId::Custom(id) => id.into_bytes(),
+        }
+    }
+}
+
+impl NetworkId {
+    /// Create the id of the custom network identified by `id`
+    pub fn custom(id: impl Into<String>) -> Self {
+        NetworkId::Custom(id.into())
+    }
+
+    /// Get the domain string of signatures on this network
+    ///
+    /// This is `"MinaSignatureMainnet"` on mainnet, `"CodaSignature"` on testnets and the
+    /// network identifier followed by `"Signature"` on custom networks, truncated to the
+    /// maximum domain string length.
+    pub fn into_domain_string(self) -> String {
+        self.select_domain_string("MinaSignatureMainnet", "CodaSignature", "Signature")
+    }
+
+    /// Select the `mainnet` or `testnet` domain string, or build the custom network one by
+    /// appending `custom_suffix` to the network identifier
+    pub(crate) fn select_domain_string(
+        self,
+        mainnet: &str,
+        testnet: &str,
+        custom_suffix: &str,
+    ) -> String {
+        match self {
+            NetworkId::MAINNET => mainnet.to_string(),
+            NetworkId::TESTNET => testnet.to_string(),
+            NetworkId::Custom(id) => {
+                let mut domain_string = id + custom_suffix;
+                while domain_string.len() > MAX_DOMAIN_STRING_LEN {
+                    domain_string.pop();
+                }
+                domain_string
+            }
+        }
     }
 }
 
@@ -58,6 +125,20 @@ pub trait Signer<H: Hashable> {
     /// Verify that the signature `sig` on `input` (see [`Hashable`]) is signed with the secret key corresponding to `pub_key`.
     /// Return `true` if the signature is valid and `false` otherwise.
     fn verify(&mut self, sig: &Signature, pub_key: &PubKey, input: &H) -> bool;
+
+    /// Verify a batch of `(signature, public key, input)` triples (see [`Signer::verify`]).
+    /// Return `Ok(())` if every signature is valid and `Err(i)` otherwise, where `i` is the
+    /// index of the first invalid signature in `batch`.
+    ///
+    /// The default implementation verifies each signature individually.
+    fn batch_verify(&mut self, batch: &[(Signature, PubKey, H)]) -> Result<(), usize> {
+        for (i, (sig, pub_key, input)) in batch.iter().enumerate() {
+            if !self.verify(sig, pub_key, input) {
+                return Err(i);
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Create a legacy signer context with domain parameters initialized with `domain_param`
@@ -0,0 +1,126 @@
+//! Nonce derivation for the Mina signature algorithm
+//!
+//! A [`Schnorr`](crate::Schnorr) signer derives the nonce of each signature with a
+//! [`NonceDerivation`].  The default is [`Blake2bNonce`], which is what Mina has always
+//! used.  [`Rfc6979Nonce`] is an alternative deterministic construction following
+//! [RFC 6979](https://www.rfc-editor.org/rfc/rfc6979) that other implementations can
+//! reproduce with standard HMAC-SHA256 primitives.
+
+use ark_ff::{BigInteger, Field, PrimeField, Zero};
+use blake2::{
+    digest::{Update, VariableOutput},
+    Blake2bVar,
+};
+use hmac::{Hmac, Mac};
+use mina_hasher::ROInput;
+use o1_utils::FieldHelpers;
+use sha2::{Digest, Sha256};
+
+use crate::{Keypair, ScalarField};
+
+/// Interface for deriving the nonce of a signature
+///
+/// It is crucial for security that no two different messages share the same nonce.
+pub trait NonceDerivation {
+    /// Derive the nonce used to sign `input` with keypair `kp`, where `domain_param` are the
+    /// bytes of the signer's domain parameter (see [`DomainParameter`](mina_hasher::DomainParameter)).
+    fn derive_nonce(&self, kp: &Keypair, input: &ROInput, domain_param: &[u8]) -> ScalarField;
+}
+
+/// Default nonce derivation based on Blake2b
+///
+/// The nonce is the Blake2b-256 hash of the input, the public key, the secret key and the
+/// domain parameter, with the top two bits dropped.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Blake2bNonce;
+
+impl NonceDerivation for Blake2bNonce {
+    fn derive_nonce(&self, kp: &Keypair, input: &ROInput, domain_param: &[u8]) -> ScalarField {
+        let mut blake_hasher = Blake2bVar::new(32).unwrap();
+
+        let roi = input
+            .clone()
+            .append_field(kp.public.point().x)
+            .append_field(kp.public.point().y)
+            .append_scalar(*kp.secret.scalar())
+            .append_bytes(domain_param);
+
+        blake_hasher.update(&roi.to_bytes());
+
+        let mut bytes = [0; 32];
+        blake_hasher
+            .finalize_variable(&mut bytes)
+            .expect("incorrect output size");
+        // Drop the top two bits to convert into a scalar field element
+        //   N.B. Since the order of Pallas's scalar field p is very close to 2^m
+        //   for some m, truncating only creates a tiny amount of bias that should
+        //   be insignificant and better than reduction modulo p.
+        bytes[bytes.len() - 1] &= 0b0011_1111;
+
+        ScalarField::from_random_bytes(&bytes[..]).expect("failed to create scalar from bytes")
+    }
+}
+
+/// Deterministic nonce derivation following RFC 6979 (section 3.2) with HMAC-SHA256
+///
+/// The hashed message `h1` is the SHA-256 hash of `input.to_bytes()` followed by the
+/// domain parameter bytes.  Integers are encoded big-endian on 32 bytes and, since the
+/// scalar field has 255 bits, `bits2int` keeps the leftmost 255 bits of its input.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Rfc6979Nonce;
+
+impl NonceDerivation for Rfc6979Nonce {
+    fn derive_nonce(&self, kp: &Keypair, input: &ROInput, domain_param: &[u8]) -> ScalarField {
+        let h1: [u8; 32] = Sha256::new()
+            .chain_update(input.to_bytes())
+            .chain_update(domain_param)
+            .finalize()
+            .into();
+
+        let x = kp.secret.scalar().into_bigint().to_bytes_be();
+        let h1 = ScalarField::from_be_bytes_mod_order(&bits2int(&h1))
+            .into_bigint()
+            .to_bytes_be();
+
+        let mut v = [0x01u8; 32];
+        let mut k = [0x00u8; 32];
+        k = hmac_sha256(&k, &[&v[..], &[0x00], &x[..], &h1[..]]);
+        v = hmac_sha256(&k, &[&v[..]]);
+        k = hmac_sha256(&k, &[&v[..], &[0x01], &x[..], &h1[..]]);
+        v = hmac_sha256(&k, &[&v[..]]);
+
+        loop {
+            v = hmac_sha256(&k, &[&v[..]]);
+            let mut candidate = bits2int(&v);
+            candidate.reverse();
+            if let Ok(nonce) = ScalarField::from_bytes(&candidate) {
+                if !nonce.is_zero() {
+                    return nonce;
+                }
+            }
+            k = hmac_sha256(&k, &[&v[..], &[0x00]]);
+            v = hmac_sha256(&k, &[&v[..]]);
+        }
+    }
+}
+
+/// Keep the leftmost `ScalarField::MODULUS_BIT_SIZE` bits of a 256-bit big-endian value
+fn bits2int(bytes: &[u8; 32]) -> [u8; 32] {
+    let shift = 256 - ScalarField::MODULUS_BIT_SIZE;
+    let mut res = [0u8; 32];
+    let mut prev = 0u8;
+    for (r, b) in res.iter_mut().zip(bytes) {
+        *r = (prev << (8 - shift)) | (b >> shift);
+        prev = *b;
+    }
+    res
+}
+
+/// HMAC-SHA256 (RFC 2104) of the concatenation of `data` under `key`
+fn hmac_sha256(key: &[u8; 32], data: &[&[u8]]) -> [u8; 32] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("HMAC takes keys of any size");
+    for d in data {
+        Mac::update(&mut mac, d);
+    }
+    mac.finalize().into_bytes().into()
+}
@@ -6,6 +6,7 @@ use ark_ec::{short_weierstrass::Affine, AffineRepr, CurveGroup};
 use ark_ff::{BigInteger, PrimeField, Zero};
 use bs58;
 use core::fmt;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use sha2::{Digest, Sha256};
 use std::ops::{Mul, Neg};
 use thiserror::Error;
@@ -338,3 +339,16 @@ impl CompressedPubKey {
         hex::encode(self.to_bytes())
     }
 }
+
+impl Serialize for CompressedPubKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.into_address())
+    }
+}
+
+impl<'de> Deserialize<'de> for CompressedPubKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let address = String::deserialize(deserializer)?;
+        CompressedPubKey::from_address(&address).map_err(de::Error::custom)
+    }
+}
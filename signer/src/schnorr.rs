@@ -10,18 +10,18 @@ use ark_ec::{
 };
 use ark_ff::{
     BigInteger, // for is_even()
-    Field,      // for from_random_bytes()
     PrimeField, // for from_repr()
     Zero,
 };
-use blake2::{
-    digest::{Update, VariableOutput},
-    Blake2bVar,
-};
 use mina_hasher::{self, DomainParameter, Hasher, ROInput};
+use poly_commitment::commitment::PolyComm;
+use rand::Rng;
 use std::ops::{Add, Neg};
 
-use crate::{BaseField, CurvePoint, Hashable, Keypair, PubKey, ScalarField, Signature, Signer};
+use crate::{
+    nonce::{Blake2bNonce, NonceDerivation},
+    BaseField, CurvePoint, Hashable, Keypair, PubKey, ScalarField, Signature, Signer,
+};
 
 /// Schnorr signer context for the Mina signature algorithm
 ///
@@ -29,6 +29,25 @@ use crate::{BaseField, CurvePoint, Hashable, Keypair, PubKey, ScalarField, Signa
 pub struct Schnorr<H: Hashable> {
     hasher: Box<dyn Hasher<Message<H>>>,
     domain_param: H::D,
+    nonce: Box<dyn NonceDerivation>,
+}
+
+/// Intermediate values computed while signing
+///
+/// These are exported by [`Schnorr::sign_with_transcript`] so that other signer
+/// implementations can be cross-tested step by step.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SigningTranscript {
+    /// Nonce `k`, negated if needed so that `k * G` has an even y-coordinate
+    pub nonce: ScalarField,
+    /// Nonce point `R = k * G`, whose x-coordinate is the signature's `rx`
+    pub nonce_point: CurvePoint,
+    /// Field elements absorbed by the hasher to compute the challenge (see [`ROInput::to_fields`])
+    pub message_fields: Vec<BaseField>,
+    /// Challenge `e`
+    pub challenge: ScalarField,
+    /// Resulting signature
+    pub signature: Signature,
 }
 
 #[derive(Clone)]
@@ -57,16 +76,7 @@ impl<H: Hashable> Hashable for Message<H> {
 
 impl<H: 'static + Hashable> Signer<H> for Schnorr<H> {
     fn sign(&mut self, kp: &Keypair, input: &H) -> Signature {
-        let k: ScalarField = self.derive_nonce(kp, input);
-        let r: CurvePoint = CurvePoint::generator()
-            .mul_bigint(k.into_bigint())
-            .into_affine();
-        let k: ScalarField = if r.y.into_bigint().is_even() { k } else { -k };
-
-        let e: ScalarField = self.message_hash(&kp.public, r.x, input);
-        let s: ScalarField = k + e * kp.secret.scalar();
-
-        Signature::new(r.x, s)
+        self.sign_with_transcript(kp, input).signature
     }
 
     fn verify(&mut self, sig: &Signature, public: &PubKey, input: &H) -> bool {
@@ -86,53 +96,139 @@ impl<H: 'static + Hashable> Signer<H> for Schnorr<H> {
 
         rv.y.into_bigint().is_even() && rv.x == sig.rx
     }
+
+    /// Batch verification checks that `sum_i r_i * (s_i * G - e_i * P_i - R_i) = 0` for
+    /// random 128-bit coefficients `r_i`, where `R_i` is the point with x-coordinate `rx_i`
+    /// and even y-coordinate, using a single multi-scalar multiplication.
+    ///
+    /// If the combined check fails, or some `rx_i` is not the x-coordinate of a curve
+    /// point, the signatures are verified one by one in order to report the index of the
+    /// first invalid one.
+    fn batch_verify(&mut self, batch: &[(Signature, PubKey, H)]) -> Result<(), usize> {
+        let mut rng = rand::thread_rng();
+
+        let mut g_scalar = ScalarField::zero();
+        let mut points = Vec::with_capacity(2 * batch.len() + 1);
+        let mut scalars = Vec::with_capacity(2 * batch.len() + 1);
+
+        // Whether all the rx are x-coordinates of curve points
+        let mut all_lifted = true;
+        for (sig, public, input) in batch {
+            let Some(r) = lift_x_even(sig.rx) else {
+                // This signature cannot be valid, but an earlier one may be invalid too,
+                // so the first invalid one is looked for below
+                all_lifted = false;
+                break;
+            };
+            let ev: ScalarField = self.message_hash(public, sig.rx, input);
+            let coeff = ScalarField::from(rng.gen::<u128>());
+
+            g_scalar += coeff * sig.s;
+            points.push(PolyComm::new(vec![*public.point()]));
+            scalars.push(-(coeff * ev));
+            points.push(PolyComm::new(vec![r]));
+            scalars.push(-coeff);
+        }
+        if all_lifted {
+            points.push(PolyComm::new(vec![CurvePoint::generator()]));
+            scalars.push(g_scalar);
+
+            let points: Vec<_> = points.iter().collect();
+            let combined = PolyComm::multi_scalar_mul(&points, &scalars);
+            if combined.get_first_chunk().is_zero() {
+                return Ok(());
+            }
+        }
+
+        // Pinpoint the first invalid signature
+        for (i, (sig, public, input)) in batch.iter().enumerate() {
+            if !self.verify(sig, public, input) {
+                return Err(i);
+            }
+        }
+        Ok(())
+    }
 }
 
-pub(crate) fn create_legacy<H: 'static + Hashable>(domain_param: H::D) -> impl Signer<H> {
-    Schnorr::<H> {
-        hasher: Box::new(mina_hasher::create_legacy::<Message<H>>(
-            domain_param.clone(),
-        )),
-        domain_param,
+/// Return the curve point with x-coordinate `x` and even y-coordinate, if any
+fn lift_x_even(x: BaseField) -> Option<CurvePoint> {
+    let mut pt = CurvePoint::get_point_from_x_unchecked(x, false)?;
+    if !pt.y.into_bigint().is_even() {
+        pt.y = pt.y.neg();
     }
+    Some(pt)
+}
+
+pub(crate) fn create_legacy<H: 'static + Hashable>(domain_param: H::D) -> impl Signer<H> {
+    Schnorr::<H>::new_legacy(domain_param)
 }
 
 pub(crate) fn create_kimchi<H: 'static + Hashable>(domain_param: H::D) -> impl Signer<H> {
-    Schnorr::<H> {
-        hasher: Box::new(mina_hasher::create_kimchi::<Message<H>>(
-            domain_param.clone(),
-        )),
-        domain_param,
-    }
+    Schnorr::<H>::new_kimchi(domain_param)
 }
 
 impl<H: 'static + Hashable> Schnorr<H> {
-    /// This function uses a cryptographic hash function to create a uniformly and
-    /// randomly distributed nonce.  It is crucial for security that no two different
-    /// messages share the same nonce.
-    fn derive_nonce(&self, kp: &Keypair, input: &H) -> ScalarField {
-        let mut blake_hasher = Blake2bVar::new(32).unwrap();
+    /// Create a legacy signer context with domain parameters initialized with `domain_param`
+    /// and the default [`Blake2bNonce`] nonce derivation
+    pub fn new_legacy(domain_param: H::D) -> Self {
+        Self {
+            hasher: Box::new(mina_hasher::create_legacy::<Message<H>>(
+                domain_param.clone(),
+            )),
+            domain_param,
+            nonce: Box::new(Blake2bNonce),
+        }
+    }
+
+    /// Create an experimental kimchi signer context with domain parameters initialized with
+    /// `domain_param` and the default [`Blake2bNonce`] nonce derivation
+    pub fn new_kimchi(domain_param: H::D) -> Self {
+        Self {
+            hasher: Box::new(mina_hasher::create_kimchi::<Message<H>>(
+                domain_param.clone(),
+            )),
+            domain_param,
+            nonce: Box::new(Blake2bNonce),
+        }
+    }
+
+    /// Replace the nonce derivation used when signing (see [`NonceDerivation`])
+    pub fn set_nonce_derivation(mut self, nonce: impl NonceDerivation + 'static) -> Self {
+        self.nonce = Box::new(nonce);
+        self
+    }
+
+    /// Sign `input` using keypair `kp`, returning the signature together with the
+    /// intermediate values computed along the way (see [`SigningTranscript`])
+    pub fn sign_with_transcript(&mut self, kp: &Keypair, input: &H) -> SigningTranscript {
+        let k: ScalarField = self.nonce.derive_nonce(
+            kp,
+            &input.to_roinput(),
+            &self.domain_param.clone().into_bytes(),
+        );
+        let r: CurvePoint = CurvePoint::generator()
+            .mul_bigint(k.into_bigint())
+            .into_affine();
+        let (k, r) = if r.y.into_bigint().is_even() {
+            (k, r)
+        } else {
+            (-k, -r)
+        };
 
-        let roi = input
+        let message_fields = self
+            .message(&kp.public, r.x, input)
             .to_roinput()
-            .append_field(kp.public.point().x)
-            .append_field(kp.public.point().y)
-            .append_scalar(*kp.secret.scalar())
-            .append_bytes(&self.domain_param.clone().into_bytes());
-
-        blake_hasher.update(&roi.to_bytes());
-
-        let mut bytes = [0; 32];
-        blake_hasher
-            .finalize_variable(&mut bytes)
-            .expect("incorrect output size");
-        // Drop the top two bits to convert into a scalar field element
-        //   N.B. Since the order of Pallas's scalar field p is very close to 2^m
-        //   for some m, truncating only creates a tiny amount of bias that should
-        //   be insignificant and better than reduction modulo p.
-        bytes[bytes.len() - 1] &= 0b0011_1111;
-
-        ScalarField::from_random_bytes(&bytes[..]).expect("failed to create scalar from bytes")
+            .to_fields();
+        let e: ScalarField = self.message_hash(&kp.public, r.x, input);
+        let s: ScalarField = k + e * kp.secret.scalar();
+
+        SigningTranscript {
+            nonce: k,
+            nonce_point: r,
+            message_fields,
+            challenge: e,
+            signature: Signature::new(r.x, s),
+        }
     }
 
     /// This function uses a cryptographic hash function (based on a sponge construction) to
@@ -140,17 +236,26 @@ impl<H: 'static + Hashable> Schnorr<H> {
     /// randomly distributed scalar field element.  It uses Mina's variant of the Poseidon
     /// SNARK-friendly cryptographic hash function.
     /// Details: <https://github.com/o1-labs/cryptography-rfcs/blob/httpsnapps-notary-signatures/mina/001-poseidon-sponge.md>
-    fn message_hash(&mut self, pub_key: &PubKey, rx: BaseField, input: &H) -> ScalarField {
-        let schnorr_input = Message::<H> {
-            input: input.clone(),
-            pub_key_x: pub_key.point().x,
-            pub_key_y: pub_key.point().y,
-            rx,
-        };
+    pub(crate) fn message_hash(
+        &mut self,
+        pub_key: &PubKey,
+        rx: BaseField,
+        input: &H,
+    ) -> ScalarField {
+        let schnorr_input = self.message(pub_key, rx, input);
 
         // Squeeze and convert from base field element to scalar field element
         // Since the difference in modulus between the two fields is < 2^125, w.h.p., a
         // random value from one field will fit in the other field.
         ScalarField::from(self.hasher.hash(&schnorr_input).into_bigint())
     }
+
+    fn message(&self, pub_key: &PubKey, rx: BaseField, input: &H) -> Message<H> {
+        Message::<H> {
+            input: input.clone(),
+            pub_key_x: pub_key.point().x,
+            pub_key_y: pub_key.point().y,
+            rx,
+        }
+    }
 }
@@ -1,9 +1,13 @@
 //! Mina signature structure and associated helpers
 
-use crate::{BaseField, ScalarField};
+use crate::{base58, BaseField, ScalarField};
 use o1_utils::FieldHelpers;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use std::fmt;
 
+/// Version of the binary signature encoding used by [`Signature::to_base58`]
+const SIGNATURE_ENCODING_VERSION: u8 = 0x01;
+
 /// Signature structure
 #[derive(Clone, Eq, fmt::Debug, PartialEq)]
 pub struct Signature {
@@ -19,6 +23,39 @@ impl Signature {
     pub fn new(rx: BaseField, s: ScalarField) -> Self {
         Self { rx, s }
     }
+
+    /// Serialize signature into Mina's base58 encoding (e.g. as used by GraphQL)
+    pub fn to_base58(&self) -> String {
+        let mut payload = vec![SIGNATURE_ENCODING_VERSION];
+        payload.extend(self.rx.to_bytes());
+        payload.extend(self.s.to_bytes());
+        base58::encode(base58::SIGNATURE_VERSION, &payload)
+    }
+
+    /// Deserialize signature from Mina's base58 encoding
+    pub fn from_base58(encoded: &str) -> Option<Self> {
+        let payload = base58::decode(base58::SIGNATURE_VERSION, encoded)?;
+        let field_size = BaseField::size_in_bytes();
+        if payload.len() != 1 + 2 * field_size || payload[0] != SIGNATURE_ENCODING_VERSION {
+            return None;
+        }
+        let rx = BaseField::from_bytes(&payload[1..1 + field_size]).ok()?;
+        let s = ScalarField::from_bytes(&payload[1 + field_size..]).ok()?;
+        Some(Self::new(rx, s))
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base58())
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Signature::from_base58(&encoded).ok_or_else(|| de::Error::custom("invalid signature"))
+    }
 }
 
 impl fmt::Display for Signature {
@@ -0,0 +1,359 @@
+//! Mina transaction structures
+//!
+//! Definition of the signed command transactions (payments and stake delegations) and of
+//! transaction memos.  Signed commands are hashed and signed with a legacy signer
+//! (see [`create_legacy`](crate::create_legacy)).
+//!
+//! The JSON (de)serialization of [`Payment`] and [`StakeDelegation`] follows the GraphQL
+//! `SendPaymentInput` and `SendDelegationInput` formats.
+
+use mina_hasher::{Hashable, ROInput};
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, DisplayFromStr};
+use thiserror::Error;
+
+use crate::{base58, CompressedPubKey, NetworkId};
+
+/// Length of an encoded memo in bytes
+pub const MEMO_BYTES: usize = 34;
+
+/// Maximum length of the user supplied part of a memo in bytes
+pub const MEMO_MAX_LEN: usize = MEMO_BYTES - 2;
+
+/// Memo tag of user supplied bytes
+const MEMO_BYTES_TAG: u8 = 0x01;
+
+const TAG_BITS: usize = 3;
+const PAYMENT_TX_TAG: [bool; TAG_BITS] = [false, false, false];
+const DELEGATION_TX_TAG: [bool; TAG_BITS] = [false, false, true];
+
+/// Default token id of signed commands
+const DEFAULT_TOKEN_ID: u64 = 1;
+
+/// Transaction errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum TransactionError {
+    /// Memo is too long
+    #[error("memo is longer than {MEMO_MAX_LEN} bytes")]
+    MemoLength,
+    /// Invalid memo base58
+    #[error("invalid memo base58")]
+    MemoBase58,
+    /// Invalid memo bytes
+    #[error("invalid memo bytes")]
+    MemoBytes,
+}
+/// Transaction result
+pub type Result<T> = std::result::Result<T, TransactionError>;
+
+/// Transaction memo
+///
+/// A memo is encoded on [`MEMO_BYTES`] bytes: a tag byte, a length byte and up to
+/// [`MEMO_MAX_LEN`] bytes of user supplied data padded with zeros.  It is serialized
+/// as base58, e.g. when appearing in a zkApp command.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Memo([u8; MEMO_BYTES]);
+
+impl Default for Memo {
+    fn default() -> Self {
+        Self(std::array::from_fn(|i| (i == 0) as u8))
+    }
+}
+
+impl Memo {
+    /// Create a memo from user supplied `bytes`
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` is longer than [`MEMO_MAX_LEN`].
+    pub fn new(bytes: &[u8]) -> Result<Self> {
+        if bytes.len() > MEMO_MAX_LEN {
+            return Err(TransactionError::MemoLength);
+        }
+        let mut memo = Self::default();
+        memo.0[1] = bytes.len() as u8;
+        memo.0[2..2 + bytes.len()].copy_from_slice(bytes);
+        Ok(memo)
+    }
+
+    /// Create a memo from a string, truncating it to [`MEMO_MAX_LEN`] bytes
+    pub fn from_str_truncate(memo: &str) -> Self {
+        let bytes = memo.as_bytes();
+        Self::new(&bytes[..std::cmp::min(bytes.len(), MEMO_MAX_LEN)])
+            .expect("memo has been truncated")
+    }
+
+    /// Create a memo from its [`MEMO_BYTES`] bytes encoding
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `bytes` is not a valid memo encoding.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let bytes: [u8; MEMO_BYTES] = bytes.try_into().map_err(|_| TransactionError::MemoBytes)?;
+        if bytes[0] != MEMO_BYTES_TAG || bytes[1] as usize > MEMO_MAX_LEN {
+            return Err(TransactionError::MemoBytes);
+        }
+        Ok(Self(bytes))
+    }
+
+    /// Borrow the [`MEMO_BYTES`] bytes encoding of the memo
+    pub fn as_bytes(&self) -> &[u8; MEMO_BYTES] {
+        &self.0
+    }
+
+    /// Get the user supplied part of the memo
+    pub fn data(&self) -> &[u8] {
+        &self.0[2..2 + self.0[1] as usize]
+    }
+
+    /// Serialize memo into base58
+    pub fn to_base58(&self) -> String {
+        base58::encode(base58::MEMO_VERSION, &self.0)
+    }
+
+    /// Deserialize memo from base58
+    ///
+    /// # Errors
+    ///
+    /// Will give error if `encoded` is not a base58 encoded memo.
+    pub fn from_base58(encoded: &str) -> Result<Self> {
+        let bytes =
+            base58::decode(base58::MEMO_VERSION, encoded).ok_or(TransactionError::MemoBase58)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl Serialize for Memo {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_base58())
+    }
+}
+
+impl<'de> Deserialize<'de> for Memo {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        Memo::from_base58(&encoded).map_err(de::Error::custom)
+    }
+}
+
+/// Serialization of a memo as plain text, as used by GraphQL signed command inputs
+mod memo_text {
+    use super::Memo;
+    use serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(memo: &Memo, serializer: S) -> Result<S::Ok, S::Error> {
+        let text = std::str::from_utf8(memo.data())
+            .map_err(|_| ser::Error::custom("memo is not valid UTF-8"))?;
+        serializer.serialize_str(text)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Memo, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Memo::new(text.as_bytes()).map_err(de::Error::custom)
+    }
+}
+
+fn default_valid_until() -> u32 {
+    u32::MAX
+}
+
+/// Payment transaction
+///
+/// The sender `from` is both the fee payer and the source of the payment.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Payment {
+    /// Sender
+    pub from: CompressedPubKey,
+    /// Receiver
+    pub to: CompressedPubKey,
+    /// Amount in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub amount: u64,
+    /// Fee in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub fee: u64,
+    /// Nonce of the sender's account
+    #[serde_as(as = "DisplayFromStr")]
+    pub nonce: u32,
+    /// Global slot after which the transaction is no longer valid
+    #[serde_as(as = "DisplayFromStr")]
+    #[serde(default = "default_valid_until")]
+    pub valid_until: u32,
+    /// Memo
+    #[serde(with = "memo_text", default)]
+    pub memo: Memo,
+}
+
+impl Payment {
+    /// Create a new payment of `amount` from `from` to `to`
+    pub fn new(
+        from: CompressedPubKey,
+        to: CompressedPubKey,
+        amount: u64,
+        fee: u64,
+        nonce: u32,
+    ) -> Self {
+        Self {
+            from,
+            to,
+            amount,
+            fee,
+            nonce,
+            valid_until: default_valid_until(),
+            memo: Memo::default(),
+        }
+    }
+
+    /// Set the global slot after which the payment is no longer valid
+    pub fn set_valid_until(mut self, global_slot: u32) -> Self {
+        self.valid_until = global_slot;
+
+        self
+    }
+
+    /// Set the memo
+    pub fn set_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+
+        self
+    }
+}
+
+impl Hashable for Payment {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        signed_command_roinput(SignedCommand {
+            fee: self.fee,
+            fee_payer_pk: &self.from,
+            nonce: self.nonce,
+            valid_until: self.valid_until,
+            memo: &self.memo,
+            tag: PAYMENT_TX_TAG,
+            receiver_pk: &self.to,
+            amount: self.amount,
+        })
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        signed_command_domain_string(network_id)
+    }
+}
+
+/// Stake delegation transaction
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StakeDelegation {
+    /// Delegator
+    pub from: CompressedPubKey,
+    /// New delegate
+    pub to: CompressedPubKey,
+    /// Fee in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub fee: u64,
+    /// Nonce of the delegator's account
+    #[serde_as(as = "DisplayFromStr")]
+    pub nonce: u32,
+    /// Global slot after which the transaction is no longer valid
+    #[serde_as(as = "DisplayFromStr")]
+    #[serde(default = "default_valid_until")]
+    pub valid_until: u32,
+    /// Memo
+    #[serde(with = "memo_text", default)]
+    pub memo: Memo,
+}
+
+impl StakeDelegation {
+    /// Create a new delegation of `from`'s stake to `to`
+    pub fn new(from: CompressedPubKey, to: CompressedPubKey, fee: u64, nonce: u32) -> Self {
+        Self {
+            from,
+            to,
+            fee,
+            nonce,
+            valid_until: default_valid_until(),
+            memo: Memo::default(),
+        }
+    }
+
+    /// Set the global slot after which the delegation is no longer valid
+    pub fn set_valid_until(mut self, global_slot: u32) -> Self {
+        self.valid_until = global_slot;
+
+        self
+    }
+
+    /// Set the memo
+    pub fn set_memo(mut self, memo: Memo) -> Self {
+        self.memo = memo;
+
+        self
+    }
+}
+
+impl Hashable for StakeDelegation {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        signed_command_roinput(SignedCommand {
+            fee: self.fee,
+            fee_payer_pk: &self.from,
+            nonce: self.nonce,
+            valid_until: self.valid_until,
+            memo: &self.memo,
+            tag: DELEGATION_TX_TAG,
+            receiver_pk: &self.to,
+            amount: 0,
+        })
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        signed_command_domain_string(network_id)
+    }
+}
+
+/// Fields of a signed command that are hashed
+struct SignedCommand<'a> {
+    fee: u64,
+    fee_payer_pk: &'a CompressedPubKey,
+    nonce: u32,
+    valid_until: u32,
+    memo: &'a Memo,
+    tag: [bool; TAG_BITS],
+    receiver_pk: &'a CompressedPubKey,
+    amount: u64,
+}
+
+fn signed_command_roinput(cmd: SignedCommand) -> ROInput {
+    // The fee payer is also the source of the command
+    let source_pk = cmd.fee_payer_pk;
+
+    let mut roi = ROInput::new()
+        .append_field(cmd.fee_payer_pk.x)
+        .append_field(source_pk.x)
+        .append_field(cmd.receiver_pk.x)
+        .append_u64(cmd.fee)
+        .append_u64(DEFAULT_TOKEN_ID)
+        .append_bool(cmd.fee_payer_pk.is_odd)
+        .append_u32(cmd.nonce)
+        .append_u32(cmd.valid_until)
+        .append_bytes(cmd.memo.as_bytes());
+
+    for tag_bit in cmd.tag {
+        roi = roi.append_bool(tag_bit);
+    }
+
+    roi.append_bool(source_pk.is_odd)
+        .append_bool(cmd.receiver_pk.is_odd)
+        .append_u64(DEFAULT_TOKEN_ID)
+        .append_u64(cmd.amount)
+        // Token locked
+        .append_bool(false)
+}
+
+fn signed_command_domain_string(network_id: NetworkId) -> Option<String> {
+    Some(network_id.into_domain_string())
+}
@@ -0,0 +1,1046 @@
+//! Mina zkApp command structures
+//!
+//! A zkApp command consists of a fee payer, a list of account updates and a memo.  The
+//! account updates form a call forest (encoded by their `call_depth`) whose hash, the
+//! *commitment*, is signed by account updates with `use_full_commitment` unset.  The
+//! *full commitment* additionally binds the memo and the fee payer and is signed by the
+//! fee payer and by the other account updates.  Both are signed with a kimchi signer
+//! (see [`create_kimchi`](crate::create_kimchi)) over a [`TransactionCommitment`].
+//!
+//! Account update bodies are hashed with the layout of Mina's `Account_update.Body`, in
+//! its field order, and the fee payer is hashed as the account update body Mina derives
+//! from it (see [`AccountUpdateBody::of_fee_payer`]).  Optional values are `None` when
+//! they are kept (updates) or ignored (preconditions).
+//!
+//! The JSON (de)serialization follows the GraphQL `ZkappCommandInput` format.  Unknown
+//! fields are rejected, so that a command is never signed without all of its contents.
+
+use ark_ff::{Field, One, PrimeField, Zero};
+use mina_hasher::{create_kimchi, DomainParameter, Fp, Hashable, Hasher, ROInput};
+use mina_poseidon::{
+    constants::PlonkSpongeConstantsKimchi,
+    pasta::fp_kimchi,
+    poseidon::{ArithmeticSponge, Sponge},
+};
+use o1_utils::FieldHelpers;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use serde_with::{serde_as, DeserializeAs, DisplayFromStr, SerializeAs};
+use std::str::FromStr;
+
+use crate::{base58, transaction::Memo, CompressedPubKey, Keypair, NetworkId, Signature, Signer};
+
+/// Number of field elements of a zkApp's state
+pub const ZKAPP_STATE_LENGTH: usize = 8;
+
+/// Maximum length of a token symbol in bytes
+pub const TOKEN_SYMBOL_MAX_LEN: usize = 6;
+
+/// Version of the transaction logic of verification keys set without a version
+const TXN_VERSION: u32 = 3;
+
+/// Hash of the dummy verification key, used by account updates that are not proved
+const DUMMY_VERIFICATION_KEY_HASH: &str =
+    "3392518251768960475377392625298437850623664973002200885669375116181514017494";
+
+/// Random oracle input with Mina's chunked layout
+///
+/// Unlike [`ROInput`], whose bits are packed little endian 254 at a time, the packed
+/// values are packed most significant first into field elements of at most 254 bits, a
+/// value that does not fit starting a new field element.
+#[derive(Clone, Default)]
+struct Input {
+    fields: Vec<Fp>,
+    packed: Vec<(Fp, u32)>,
+}
+
+impl Input {
+    fn field(mut self, f: Fp) -> Self {
+        self.fields.push(f);
+        self
+    }
+
+    fn packed(mut self, x: Fp, bits: u32) -> Self {
+        self.packed.push((x, bits));
+        self
+    }
+
+    fn bool(self, b: bool) -> Self {
+        self.packed(Fp::from(b), 1)
+    }
+
+    fn u32(self, x: u32) -> Self {
+        self.packed(Fp::from(x), 32)
+    }
+
+    fn u64(self, x: u64) -> Self {
+        self.packed(Fp::from(x), 64)
+    }
+
+    fn append(mut self, mut other: Input) -> Self {
+        self.fields.append(&mut other.fields);
+        self.packed.append(&mut other.packed);
+        self
+    }
+
+    /// Optional value, flagged by whether it is present
+    fn flagged(self, is_some: bool, data: Input) -> Self {
+        self.bool(is_some).append(data)
+    }
+
+    fn to_fields(&self) -> Vec<Fp> {
+        let mut fields = self.fields.clone();
+        let mut current = Fp::zero();
+        let mut current_bits = 0;
+        for (x, bits) in &self.packed {
+            if current_bits + bits < Fp::MODULUS_BIT_SIZE {
+                current = current * Fp::from(2u64).pow([*bits as u64]) + x;
+                current_bits += bits;
+            } else {
+                fields.push(current);
+                current = *x;
+                current_bits = *bits;
+            }
+        }
+        if current_bits > 0 {
+            fields.push(current);
+        }
+        fields
+    }
+
+    fn into_roinput(self) -> ROInput {
+        self.to_fields()
+            .into_iter()
+            .fold(ROInput::new(), |roi, f| roi.append_field(f))
+    }
+}
+
+/// Hash prefix of the inputs hashed by [`hash_with_prefix`]
+#[derive(Clone)]
+struct HashPrefix(&'static str);
+
+impl DomainParameter for HashPrefix {
+    fn into_bytes(self) -> Vec<u8> {
+        self.0.as_bytes().to_vec()
+    }
+}
+
+/// Field elements hashed with a hash prefix
+#[derive(Clone)]
+struct PrefixedFields(Vec<Fp>);
+
+impl Hashable for PrefixedFields {
+    type D = HashPrefix;
+
+    fn to_roinput(&self) -> ROInput {
+        self.0
+            .iter()
+            .fold(ROInput::new(), |roi, f| roi.append_field(*f))
+    }
+
+    fn domain_string(prefix: HashPrefix) -> Option<String> {
+        Some(prefix.0.to_string())
+    }
+}
+
+/// Hash of `fields` with the hash prefix `prefix`
+fn hash_with_prefix(prefix: &'static str, fields: Vec<Fp>) -> Fp {
+    create_kimchi::<PrefixedFields>(HashPrefix(prefix)).hash(&PrefixedFields(fields))
+}
+
+/// Digest of the sponge initialized with the salt `phrase`, as used by Mina for the hashes
+/// of empty lists
+fn salt(phrase: &str) -> Fp {
+    let mut bytes = phrase.as_bytes().to_vec();
+    bytes.resize(Fp::size_in_bytes(), 0);
+    let mut sponge =
+        ArithmeticSponge::<Fp, PlonkSpongeConstantsKimchi>::new(fp_kimchi::static_params());
+    sponge.absorb(&[Fp::from_bytes(&bytes).expect("salt phrase is too long")]);
+    sponge.squeeze()
+}
+
+/// Hash of an empty list of actions, i.e. the initial action state of a zkApp account
+pub fn empty_action_state() -> Fp {
+    salt("MinaZkappActionStateEmptyElt")
+}
+
+/// Serialization of field elements as decimal strings
+struct FieldDecimal;
+
+impl SerializeAs<Fp> for FieldDecimal {
+    fn serialize_as<S: Serializer>(f: &Fp, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&f.into_bigint().to_string())
+    }
+}
+
+impl<'de> DeserializeAs<'de, Fp> for FieldDecimal {
+    fn deserialize_as<D: Deserializer<'de>>(deserializer: D) -> Result<Fp, D::Error> {
+        let decimal = String::deserialize(deserializer)?;
+        Fp::from_str(&decimal).map_err(|_| de::Error::custom("invalid field element"))
+    }
+}
+
+fn public_key_input(public_key: &CompressedPubKey) -> Input {
+    Input::default().field(public_key.x).bool(public_key.is_odd)
+}
+
+/// Public key of the dummy values of absent public keys
+fn empty_public_key() -> CompressedPubKey {
+    CompressedPubKey {
+        x: Fp::zero(),
+        is_odd: false,
+    }
+}
+
+/// Token id of an account update
+///
+/// It is serialized as base58.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenId(pub Fp);
+
+impl Default for TokenId {
+    /// The default (MINA) token id
+    fn default() -> Self {
+        Self(Fp::one())
+    }
+}
+
+impl Serialize for TokenId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&base58::encode(
+            base58::TOKEN_ID_VERSION,
+            &self.0.to_bytes(),
+        ))
+    }
+}
+
+impl<'de> Deserialize<'de> for TokenId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let encoded = String::deserialize(deserializer)?;
+        base58::decode(base58::TOKEN_ID_VERSION, &encoded)
+            .and_then(|bytes| Fp::from_bytes(&bytes).ok())
+            .map(Self)
+            .ok_or_else(|| de::Error::custom("invalid token id"))
+    }
+}
+
+/// Sign of a balance change
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Sign {
+    /// Positive sign
+    #[default]
+    Positive,
+    /// Negative sign
+    Negative,
+}
+
+/// Signed amount of a balance change in nanomina
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceChange {
+    /// Absolute value of the change
+    #[serde_as(as = "DisplayFromStr")]
+    pub magnitude: u64,
+    /// Sign of the change
+    pub sgn: Sign,
+}
+
+impl BalanceChange {
+    fn to_input(self) -> Input {
+        Input::default()
+            .u64(self.magnitude)
+            .bool(self.sgn == Sign::Positive)
+    }
+}
+
+/// Closed interval `[lower, upper]` of a numeric precondition
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, bound(serialize = "T: std::fmt::Display"))]
+#[serde(bound(deserialize = "T: FromStr, T::Err: std::fmt::Display"))]
+pub struct ClosedInterval<T> {
+    /// Lower bound
+    #[serde_as(as = "DisplayFromStr")]
+    pub lower: T,
+    /// Upper bound
+    #[serde_as(as = "DisplayFromStr")]
+    pub upper: T,
+}
+
+impl<T: Copy> ClosedInterval<T> {
+    /// The interval containing only `x`
+    pub fn singleton(x: T) -> Self {
+        Self { lower: x, upper: x }
+    }
+}
+
+fn u32_interval_input(interval: &Option<ClosedInterval<u32>>) -> Input {
+    let ClosedInterval { lower, upper } = interval.unwrap_or(ClosedInterval {
+        lower: 0,
+        upper: u32::MAX,
+    });
+    Input::default().flagged(interval.is_some(), Input::default().u32(lower).u32(upper))
+}
+
+fn u64_interval_input(interval: &Option<ClosedInterval<u64>>) -> Input {
+    let ClosedInterval { lower, upper } = interval.unwrap_or(ClosedInterval {
+        lower: 0,
+        upper: u64::MAX,
+    });
+    Input::default().flagged(interval.is_some(), Input::default().u64(lower).u64(upper))
+}
+
+fn field_option_input(f: &Option<Fp>, dummy: Fp) -> Input {
+    Input::default().flagged(f.is_some(), Input::default().field(f.unwrap_or(dummy)))
+}
+
+fn bool_option_input(b: &Option<bool>) -> Input {
+    Input::default().flagged(b.is_some(), Input::default().bool(b.unwrap_or(false)))
+}
+
+fn public_key_option_input(public_key: &Option<CompressedPubKey>) -> Input {
+    let data = match public_key {
+        Some(public_key) => public_key_input(public_key),
+        None => public_key_input(&empty_public_key()),
+    };
+    Input::default().flagged(public_key.is_some(), data)
+}
+
+/// Authorization required to perform an action on an account
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AuthRequired {
+    /// No authorization
+    #[default]
+    None,
+    /// Proof or signature
+    Either,
+    /// Proof
+    Proof,
+    /// Signature
+    Signature,
+    /// The action is impossible
+    Impossible,
+}
+
+impl AuthRequired {
+    fn to_input(self) -> Input {
+        // Encoded as (constant, signature necessary, signature sufficient)
+        let (constant, signature_necessary, signature_sufficient) = match self {
+            AuthRequired::None => (true, false, true),
+            AuthRequired::Either => (false, false, true),
+            AuthRequired::Proof => (false, false, false),
+            AuthRequired::Signature => (false, true, true),
+            AuthRequired::Impossible => (true, true, false),
+        };
+        Input::default()
+            .bool(constant)
+            .bool(signature_necessary)
+            .bool(signature_sufficient)
+    }
+}
+
+/// Authorization required to set the verification key of an account, for a version of
+/// the transaction logic
+#[serde_as]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct VerificationKeyPermission {
+    /// Authorization required
+    pub auth: AuthRequired,
+    /// Version of the transaction logic the authorization applies to
+    #[serde_as(as = "DisplayFromStr")]
+    pub txn_version: u32,
+}
+
+impl Default for VerificationKeyPermission {
+    fn default() -> Self {
+        Self {
+            auth: AuthRequired::None,
+            txn_version: TXN_VERSION,
+        }
+    }
+}
+
+/// Permissions of an account
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Permissions {
+    /// Authorization to edit the zkApp state
+    pub edit_state: AuthRequired,
+    /// Authorization to access the account
+    pub access: AuthRequired,
+    /// Authorization to send from the account
+    pub send: AuthRequired,
+    /// Authorization to receive to the account
+    pub receive: AuthRequired,
+    /// Authorization to set the delegate
+    pub set_delegate: AuthRequired,
+    /// Authorization to set the permissions
+    pub set_permissions: AuthRequired,
+    /// Authorization to set the verification key
+    pub set_verification_key: VerificationKeyPermission,
+    /// Authorization to set the zkApp URI
+    pub set_zkapp_uri: AuthRequired,
+    /// Authorization to edit the action state
+    pub edit_action_state: AuthRequired,
+    /// Authorization to set the token symbol
+    pub set_token_symbol: AuthRequired,
+    /// Authorization to increment the nonce
+    pub increment_nonce: AuthRequired,
+    /// Authorization to set the voting for field
+    pub set_voting_for: AuthRequired,
+    /// Authorization to set the timing
+    pub set_timing: AuthRequired,
+}
+
+impl Permissions {
+    fn to_input(self) -> Input {
+        Input::default()
+            .append(self.edit_state.to_input())
+            .append(self.access.to_input())
+            .append(self.send.to_input())
+            .append(self.receive.to_input())
+            .append(self.set_delegate.to_input())
+            .append(self.set_permissions.to_input())
+            .append(self.set_verification_key.auth.to_input())
+            .u32(self.set_verification_key.txn_version)
+            .append(self.set_zkapp_uri.to_input())
+            .append(self.edit_action_state.to_input())
+            .append(self.set_token_symbol.to_input())
+            .append(self.increment_nonce.to_input())
+            .append(self.set_voting_for.to_input())
+            .append(self.set_timing.to_input())
+    }
+}
+
+/// Verification key with its hash
+///
+/// Only the hash is part of the commitments.
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct VerificationKeyWithHash {
+    /// Base64 encoded verification key
+    pub data: String,
+    /// Hash of the verification key
+    #[serde_as(as = "FieldDecimal")]
+    pub hash: Fp,
+}
+
+/// Token symbol of an account, of at most [`TOKEN_SYMBOL_MAX_LEN`] bytes
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct TokenSymbol(String);
+
+impl TryFrom<String> for TokenSymbol {
+    type Error = String;
+
+    fn try_from(symbol: String) -> Result<Self, Self::Error> {
+        if symbol.len() > TOKEN_SYMBOL_MAX_LEN {
+            return Err(format!(
+                "token symbol is longer than {TOKEN_SYMBOL_MAX_LEN} bytes"
+            ));
+        }
+        Ok(Self(symbol))
+    }
+}
+
+impl From<TokenSymbol> for String {
+    fn from(symbol: TokenSymbol) -> Self {
+        symbol.0
+    }
+}
+
+impl TokenSymbol {
+    fn to_input(&self) -> Input {
+        let mut bytes = self.0.as_bytes().to_vec();
+        bytes.resize(Fp::size_in_bytes(), 0);
+        let symbol = Fp::from_bytes(&bytes).expect("token symbol is at most 6 bytes");
+        Input::default().packed(symbol, 8 * TOKEN_SYMBOL_MAX_LEN as u32)
+    }
+}
+
+/// Hash of the zkApp URI `uri`, or of the absent URI if `None`
+fn zkapp_uri_hash(uri: Option<&str>) -> Fp {
+    let fields = match uri {
+        // A final `true` bit ensures that trailing null bytes do not alias
+        Some(uri) => uri
+            .as_bytes()
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| byte >> i & 1 == 1))
+            .chain(std::iter::once(true))
+            .fold(Input::default(), |input, bit| input.bool(bit))
+            .to_fields(),
+        None => vec![Fp::zero(), Fp::zero()],
+    };
+    hash_with_prefix("MinaZkappUri", fields)
+}
+
+/// Vesting schedule of an account
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Timing {
+    /// Initial minimum balance in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub initial_minimum_balance: u64,
+    /// Global slot at which the cliff amount vests
+    #[serde_as(as = "DisplayFromStr")]
+    pub cliff_time: u32,
+    /// Amount vesting at the cliff time in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub cliff_amount: u64,
+    /// Number of slots between vesting increments
+    #[serde_as(as = "DisplayFromStr")]
+    pub vesting_period: u32,
+    /// Amount vesting every vesting period in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub vesting_increment: u64,
+}
+
+impl Timing {
+    fn to_input(self) -> Input {
+        Input::default()
+            .u64(self.initial_minimum_balance)
+            .u32(self.cliff_time)
+            .u64(self.cliff_amount)
+            .u32(self.vesting_period)
+            .u64(self.vesting_increment)
+    }
+}
+
+/// Updates of an account's fields, `None` keeping the current value
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Update {
+    /// zkApp state
+    #[serde_as(as = "[Option<FieldDecimal>; ZKAPP_STATE_LENGTH]")]
+    pub app_state: [Option<Fp>; ZKAPP_STATE_LENGTH],
+    /// Delegate
+    pub delegate: Option<CompressedPubKey>,
+    /// Verification key
+    pub verification_key: Option<VerificationKeyWithHash>,
+    /// Permissions
+    pub permissions: Option<Permissions>,
+    /// zkApp URI
+    pub zkapp_uri: Option<String>,
+    /// Token symbol
+    pub token_symbol: Option<TokenSymbol>,
+    /// Timing
+    pub timing: Option<Timing>,
+    /// State hash the account votes for
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub voting_for: Option<Fp>,
+}
+
+impl Update {
+    fn to_input(&self) -> Input {
+        let app_state = self.app_state.iter().fold(Input::default(), |input, f| {
+            input.append(field_option_input(f, Fp::zero()))
+        });
+        let verification_key_hash = self.verification_key.as_ref().map(|vk| vk.hash);
+        let permissions = self.permissions.unwrap_or_default();
+        let zkapp_uri = zkapp_uri_hash(self.zkapp_uri.as_deref());
+        let token_symbol = self.token_symbol.clone().unwrap_or_default();
+        let timing = self.timing.unwrap_or_default();
+
+        app_state
+            .append(public_key_option_input(&self.delegate))
+            .append(field_option_input(&verification_key_hash, Fp::zero()))
+            .flagged(self.permissions.is_some(), permissions.to_input())
+            .flagged(self.zkapp_uri.is_some(), Input::default().field(zkapp_uri))
+            .flagged(self.token_symbol.is_some(), token_symbol.to_input())
+            .flagged(self.timing.is_some(), timing.to_input())
+            .append(field_option_input(&self.voting_for, Fp::zero()))
+    }
+}
+
+/// Hash of a list of events or actions, each of which is a list of field elements
+fn events_hash(events: &[Vec<Fp>], empty_salt: &str, prefix: &'static str) -> Fp {
+    events.iter().rev().fold(salt(empty_salt), |hash, event| {
+        let event_hash = hash_with_prefix("MinaZkappEvent", event.clone());
+        hash_with_prefix(prefix, vec![hash, event_hash])
+    })
+}
+
+/// Preconditions on the ledger of an epoch
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EpochLedgerPrecondition {
+    /// Hash of the ledger
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub hash: Option<Fp>,
+    /// Total currency in nanomina
+    pub total_currency: Option<ClosedInterval<u64>>,
+}
+
+/// Preconditions on the data of an epoch
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct EpochDataPrecondition {
+    /// Ledger of the epoch
+    pub ledger: EpochLedgerPrecondition,
+    /// Seed of the epoch
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub seed: Option<Fp>,
+    /// Start checkpoint of the epoch
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub start_checkpoint: Option<Fp>,
+    /// Lock checkpoint of the epoch
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub lock_checkpoint: Option<Fp>,
+    /// Length of the epoch
+    pub epoch_length: Option<ClosedInterval<u32>>,
+}
+
+impl EpochDataPrecondition {
+    fn to_input(self) -> Input {
+        field_option_input(&self.ledger.hash, Fp::zero())
+            .append(u64_interval_input(&self.ledger.total_currency))
+            .append(field_option_input(&self.seed, Fp::zero()))
+            .append(field_option_input(&self.start_checkpoint, Fp::zero()))
+            .append(field_option_input(&self.lock_checkpoint, Fp::zero()))
+            .append(u32_interval_input(&self.epoch_length))
+    }
+}
+
+/// Preconditions on the protocol state
+#[serde_as]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct NetworkPrecondition {
+    /// Hash of the snarked ledger
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub snarked_ledger_hash: Option<Fp>,
+    /// Length of the blockchain
+    pub blockchain_length: Option<ClosedInterval<u32>>,
+    /// Minimum window density
+    pub min_window_density: Option<ClosedInterval<u32>>,
+    /// Total currency in nanomina
+    pub total_currency: Option<ClosedInterval<u64>>,
+    /// Global slot since genesis
+    pub global_slot_since_genesis: Option<ClosedInterval<u32>>,
+    /// Data of the staking epoch
+    pub staking_epoch_data: EpochDataPrecondition,
+    /// Data of the next epoch
+    pub next_epoch_data: EpochDataPrecondition,
+}
+
+impl NetworkPrecondition {
+    fn to_input(self) -> Input {
+        field_option_input(&self.snarked_ledger_hash, Fp::zero())
+            .append(u32_interval_input(&self.blockchain_length))
+            .append(u32_interval_input(&self.min_window_density))
+            .append(u64_interval_input(&self.total_currency))
+            .append(u32_interval_input(&self.global_slot_since_genesis))
+            .append(self.staking_epoch_data.to_input())
+            .append(self.next_epoch_data.to_input())
+    }
+}
+
+/// Preconditions on the account of an account update
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountPrecondition {
+    /// Balance in nanomina
+    pub balance: Option<ClosedInterval<u64>>,
+    /// Nonce
+    pub nonce: Option<ClosedInterval<u32>>,
+    /// Receipt chain hash
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub receipt_chain_hash: Option<Fp>,
+    /// Delegate
+    pub delegate: Option<CompressedPubKey>,
+    /// zkApp state
+    #[serde_as(as = "[Option<FieldDecimal>; ZKAPP_STATE_LENGTH]")]
+    pub state: [Option<Fp>; ZKAPP_STATE_LENGTH],
+    /// Action state
+    #[serde_as(as = "Option<FieldDecimal>")]
+    pub action_state: Option<Fp>,
+    /// Whether the state has been proved
+    pub proved_state: Option<bool>,
+    /// Whether the account is new
+    pub is_new: Option<bool>,
+}
+
+impl AccountPrecondition {
+    fn to_input(&self) -> Input {
+        let state = self.state.iter().fold(Input::default(), |input, f| {
+            input.append(field_option_input(f, Fp::zero()))
+        });
+        u64_interval_input(&self.balance)
+            .append(u32_interval_input(&self.nonce))
+            .append(field_option_input(&self.receipt_chain_hash, Fp::zero()))
+            .append(public_key_option_input(&self.delegate))
+            .append(state)
+            .append(field_option_input(&self.action_state, empty_action_state()))
+            .append(bool_option_input(&self.proved_state))
+            .append(bool_option_input(&self.is_new))
+    }
+}
+
+/// Preconditions of an account update, `None` ignoring the precondition
+#[serde_as]
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Preconditions {
+    /// Preconditions on the protocol state
+    pub network: NetworkPrecondition,
+    /// Preconditions on the account
+    pub account: AccountPrecondition,
+    /// Global slots during which the account update is valid
+    pub valid_while: Option<ClosedInterval<u32>>,
+}
+
+impl Preconditions {
+    fn to_input(&self) -> Input {
+        self.network
+            .to_input()
+            .append(self.account.to_input())
+            .append(u32_interval_input(&self.valid_while))
+    }
+}
+
+/// Whether an account update may use the token of its parent
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct MayUseToken {
+    /// The parent owns the token of the account update
+    pub parents_own_token: bool,
+    /// The account update inherits the token permission of its parent
+    pub inherit_from_parent: bool,
+}
+
+/// Kind of authorization an account update carries
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AuthorizationKind {
+    /// Whether the account update is signed
+    pub is_signed: bool,
+    /// Whether the account update is proved
+    pub is_proved: bool,
+    /// Hash of the verification key of proved account updates, or of the dummy
+    /// verification key otherwise
+    #[serde_as(as = "FieldDecimal")]
+    pub verification_key_hash: Fp,
+}
+
+impl Default for AuthorizationKind {
+    fn default() -> Self {
+        Self {
+            is_signed: false,
+            is_proved: false,
+            verification_key_hash: Fp::from_str(DUMMY_VERIFICATION_KEY_HASH)
+                .expect("invalid dummy verification key hash"),
+        }
+    }
+}
+
+impl AuthorizationKind {
+    /// Authorization by signature
+    pub fn signature() -> Self {
+        Self {
+            is_signed: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Authorization of an account update
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Control {
+    /// Base64 encoded proof, if proved
+    pub proof: Option<String>,
+    /// Signature, if signed
+    pub signature: Option<Signature>,
+}
+
+/// Body of an account update
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct AccountUpdateBody {
+    /// Account's public key
+    pub public_key: CompressedPubKey,
+    /// Account's token id
+    pub token_id: TokenId,
+    /// Updates of the account's fields
+    pub update: Update,
+    /// Change of the account's balance
+    pub balance_change: BalanceChange,
+    /// Whether the account's nonce is incremented
+    pub increment_nonce: bool,
+    /// Events, each a list of field elements
+    #[serde_as(as = "Vec<Vec<FieldDecimal>>")]
+    pub events: Vec<Vec<Fp>>,
+    /// Actions, each a list of field elements
+    #[serde_as(as = "Vec<Vec<FieldDecimal>>")]
+    pub actions: Vec<Vec<Fp>>,
+    /// Call data passed to the zkApp
+    #[serde_as(as = "FieldDecimal")]
+    pub call_data: Fp,
+    /// Depth of the account update in the call forest
+    pub call_depth: usize,
+    /// Preconditions
+    pub preconditions: Preconditions,
+    /// Whether the account update is authorized against the full commitment
+    pub use_full_commitment: bool,
+    /// Whether the account creation fee is paid by this account update
+    pub implicit_account_creation_fee: bool,
+    /// Whether the account update may use the token of its parent
+    pub may_use_token: MayUseToken,
+    /// Kind of authorization
+    pub authorization_kind: AuthorizationKind,
+}
+
+impl AccountUpdateBody {
+    /// Create a body updating `public_key`'s balance by `balance_change` with default
+    /// values for all other fields
+    pub fn new(public_key: CompressedPubKey, balance_change: BalanceChange) -> Self {
+        Self {
+            public_key,
+            token_id: TokenId::default(),
+            update: Update::default(),
+            balance_change,
+            increment_nonce: false,
+            events: vec![],
+            actions: vec![],
+            call_data: Fp::zero(),
+            call_depth: 0,
+            preconditions: Preconditions::default(),
+            use_full_commitment: false,
+            implicit_account_creation_fee: false,
+            may_use_token: MayUseToken::default(),
+            authorization_kind: AuthorizationKind::default(),
+        }
+    }
+
+    /// Account update body of the fee payer `fee_payer`, which is hashed into the full
+    /// commitment
+    ///
+    /// It pays the fee, increments the nonce and checks that the nonce is
+    /// `fee_payer.nonce` and that the global slot is at most `fee_payer.valid_until`.
+    pub fn of_fee_payer(fee_payer: &FeePayerBody) -> Self {
+        let mut body = Self::new(
+            fee_payer.public_key.clone(),
+            BalanceChange {
+                magnitude: fee_payer.fee,
+                sgn: Sign::Negative,
+            },
+        );
+        body.increment_nonce = true;
+        body.preconditions.network.global_slot_since_genesis = Some(ClosedInterval {
+            lower: 0,
+            upper: fee_payer.valid_until.unwrap_or(u32::MAX),
+        });
+        body.preconditions.account.nonce = Some(ClosedInterval::singleton(fee_payer.nonce));
+        body.use_full_commitment = true;
+        body.implicit_account_creation_fee = true;
+        body.authorization_kind = AuthorizationKind::signature();
+        body
+    }
+}
+
+impl Hashable for AccountUpdateBody {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        public_key_input(&self.public_key)
+            .append(self.update.to_input())
+            .field(self.token_id.0)
+            .append(self.balance_change.to_input())
+            .bool(self.increment_nonce)
+            .field(events_hash(
+                &self.events,
+                "MinaZkappEventsEmpty",
+                "MinaZkappEvents",
+            ))
+            .field(events_hash(
+                &self.actions,
+                "MinaZkappActionsEmpty",
+                "MinaZkappSeqEvents",
+            ))
+            .field(self.call_data)
+            .append(self.preconditions.to_input())
+            .bool(self.use_full_commitment)
+            .bool(self.implicit_account_creation_fee)
+            .bool(self.may_use_token.parents_own_token)
+            .bool(self.may_use_token.inherit_from_parent)
+            .bool(self.authorization_kind.is_signed)
+            .bool(self.authorization_kind.is_proved)
+            .field(self.authorization_kind.verification_key_hash)
+            .into_roinput()
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        Some(network_id.select_domain_string("MainnetZkappBody", "TestnetZkappBody", "ZkappBody"))
+    }
+}
+
+/// Account update
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountUpdate {
+    /// Body
+    pub body: AccountUpdateBody,
+    /// Authorization
+    pub authorization: Control,
+}
+
+/// Body of the fee payer of a zkApp command
+#[serde_as]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct FeePayerBody {
+    /// Fee payer's public key
+    pub public_key: CompressedPubKey,
+    /// Fee in nanomina
+    #[serde_as(as = "DisplayFromStr")]
+    pub fee: u64,
+    /// Global slot after which the command is no longer valid
+    #[serde_as(as = "Option<DisplayFromStr>")]
+    pub valid_until: Option<u32>,
+    /// Nonce of the fee payer's account
+    #[serde_as(as = "DisplayFromStr")]
+    pub nonce: u32,
+}
+
+/// Fee payer of a zkApp command
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct FeePayer {
+    /// Body
+    pub body: FeePayerBody,
+    /// Signature of the full commitment
+    pub authorization: Option<Signature>,
+}
+
+/// zkApp command
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ZkappCommand {
+    /// Fee payer
+    pub fee_payer: FeePayer,
+    /// Account updates, in call forest pre-order
+    pub account_updates: Vec<AccountUpdate>,
+    /// Memo
+    pub memo: Memo,
+}
+
+/// Commitment to a zkApp command (see [`ZkappCommand::commitment`] and
+/// [`ZkappCommand::full_commitment`]) that is signed by its account updates
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TransactionCommitment(pub Fp);
+
+impl Hashable for TransactionCommitment {
+    type D = NetworkId;
+
+    fn to_roinput(&self) -> ROInput {
+        ROInput::new().append_field(self.0)
+    }
+
+    fn domain_string(network_id: NetworkId) -> Option<String> {
+        Some(network_id.into_domain_string())
+    }
+}
+
+/// Hashes of the account update bodies of a call forest
+struct CallForestHasher {
+    body: Box<dyn Hasher<AccountUpdateBody>>,
+}
+
+impl CallForestHasher {
+    fn new(network_id: NetworkId) -> Self {
+        Self {
+            body: Box::new(create_kimchi::<AccountUpdateBody>(network_id)),
+        }
+    }
+
+    /// Hash of a forest given in pre-order, whose roots are at the depth of the first account update
+    fn forest_hash(&mut self, account_updates: &[AccountUpdate]) -> Fp {
+        let Some(first) = account_updates.first() else {
+            return Fp::zero();
+        };
+        let depth = first.body.call_depth;
+
+        // Split into trees, each starting with a root at depth `depth`
+        let mut roots: Vec<usize> = account_updates
+            .iter()
+            .enumerate()
+            .filter(|(_, update)| update.body.call_depth == depth)
+            .map(|(i, _)| i)
+            .collect();
+        roots.push(account_updates.len());
+
+        roots.windows(2).rev().fold(Fp::zero(), |rest, tree| {
+            let update_hash = self.body.hash(&account_updates[tree[0]].body);
+            let calls_hash = self.forest_hash(&account_updates[tree[0] + 1..tree[1]]);
+            let tree_hash = hash_with_prefix("MinaAcctUpdateNode", vec![update_hash, calls_hash]);
+            hash_with_prefix("MinaAcctUpdateCons", vec![tree_hash, rest])
+        })
+    }
+}
+
+impl ZkappCommand {
+    /// Commitment to the account updates, i.e. the hash of their call forest
+    pub fn commitment(&self, network_id: NetworkId) -> TransactionCommitment {
+        TransactionCommitment(CallForestHasher::new(network_id).forest_hash(&self.account_updates))
+    }
+
+    /// Commitment to the account updates, the memo and the fee payer
+    pub fn full_commitment(&self, network_id: NetworkId) -> TransactionCommitment {
+        let commitment = self.commitment(network_id.clone());
+        // The bits of the memo, least significant first, are packed one by one
+        let memo_fields = self
+            .memo
+            .as_bytes()
+            .iter()
+            .flat_map(|byte| (0..8).map(move |i| byte >> i & 1 == 1))
+            .fold(Input::default(), |input, bit| input.bool(bit))
+            .to_fields();
+        let memo_hash = hash_with_prefix("MinaZkappMemo", memo_fields);
+        let fee_payer_hash = create_kimchi::<AccountUpdateBody>(network_id)
+            .hash(&AccountUpdateBody::of_fee_payer(&self.fee_payer.body));
+
+        TransactionCommitment(hash_with_prefix(
+            "MinaAcctUpdateCons",
+            vec![memo_hash, fee_payer_hash, commitment.0],
+        ))
+    }
+
+    /// Sign the parts of the command that `kp` must authorize with signer `ctx`
+    ///
+    /// This sets the fee payer's signature if `kp` is the fee payer, and the signature of
+    /// every account update of `kp` whose authorization kind is a signature.
+    pub fn sign(
+        &mut self,
+        ctx: &mut impl Signer<TransactionCommitment>,
+        kp: &Keypair,
+        network_id: NetworkId,
+    ) {
+        let public_key = kp.public.into_compressed();
+        let commitment = self.commitment(network_id.clone());
+        let full_commitment = self.full_commitment(network_id);
+
+        if self.fee_payer.body.public_key == public_key {
+            self.fee_payer.authorization = Some(ctx.sign(kp, &full_commitment));
+        }
+
+        for update in self.account_updates.iter_mut().filter(|update| {
+            update.body.public_key == public_key && update.body.authorization_kind.is_signed
+        }) {
+            let message = if update.body.use_full_commitment {
+                &full_commitment
+            } else {
+                &commitment
+            };
+            update.authorization.signature = Some(ctx.sign(kp, message));
+        }
+    }
+}
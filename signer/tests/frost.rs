@@ -0,0 +1,250 @@
+pub mod transaction;
+
+use mina_signer::{
+    frost::{self, DkgParticipant, FrostError, KeyPackage, SigningPackage},
+    NetworkId, PubKey, Schnorr, Signer,
+};
+use transaction::Transaction;
+
+fn keygen(threshold: u16, max_signers: u16) -> Vec<KeyPackage> {
+    let mut rng = rand::rngs::OsRng;
+    let (participants, round1): (Vec<_>, Vec<_>) = (1..=max_signers)
+        .map(|id| {
+            DkgParticipant::new(id, threshold, max_signers, &mut rng)
+                .expect("failed to start key generation")
+        })
+        .unzip();
+    let round2: Vec<_> = participants.iter().flat_map(|p| p.round2()).collect();
+
+    participants
+        .into_iter()
+        .map(|p| p.finish(&round1, &round2).expect("key generation failed"))
+        .collect()
+}
+
+fn payment(group_public: &PubKey) -> Transaction {
+    Transaction::new_payment(
+        group_public.clone(),
+        PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address"),
+        1729000000000,
+        2000000000,
+        16,
+    )
+}
+
+fn threshold_sign(
+    ctx: &mut Schnorr<Transaction>,
+    keys: &[&KeyPackage],
+    tx: &Transaction,
+) -> Result<mina_signer::Signature, FrostError> {
+    let mut rng = rand::rngs::OsRng;
+    let (nonces, commitments): (Vec<_>, Vec<_>) = keys
+        .iter()
+        .map(|key| frost::commit(key.id, &mut rng))
+        .unzip();
+    let package = SigningPackage::new(commitments, tx.clone())?;
+    let shares = nonces
+        .into_iter()
+        .zip(keys)
+        .map(|(nonces, key)| frost::sign(ctx, &package, nonces, key))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    frost::aggregate(ctx, &package, &shares, &keys[0].public)
+}
+
+#[test]
+fn frost_keygen_test() {
+    let keys = keygen(2, 3);
+
+    // All participants agree on the group public key
+    assert!(keys.iter().all(|key| key.public == keys[0].public));
+    assert_eq!(keys[0].public.verifying_shares.len(), 3);
+
+    assert_eq!(
+        DkgParticipant::new(1, 4, 3, &mut rand::rngs::OsRng).err(),
+        Some(FrostError::InvalidParameters {
+            threshold: 4,
+            max_signers: 3
+        })
+    );
+    assert_eq!(
+        DkgParticipant::new(4, 2, 3, &mut rand::rngs::OsRng).err(),
+        Some(FrostError::InvalidParticipant(4))
+    );
+}
+
+#[test]
+fn frost_keygen_invalid_share_test() {
+    let mut rng = rand::rngs::OsRng;
+    let (participants, round1): (Vec<_>, Vec<_>) = (1..=3)
+        .map(|id| DkgParticipant::new(id, 2, 3, &mut rng).expect("failed to start key generation"))
+        .unzip();
+    let mut round2: Vec<_> = participants.iter().flat_map(|p| p.round2()).collect();
+
+    // Corrupt the share sent from 2 to 1
+    let pkg = round2
+        .iter_mut()
+        .find(|pkg| pkg.sender == 2 && pkg.recipient == 1)
+        .expect("missing package");
+    pkg.share += pkg.share;
+
+    let mut participants = participants.into_iter();
+    let first = participants.next().expect("missing participant");
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::InvalidSecretShare(2))
+    );
+    let second = participants.next().expect("missing participant");
+    assert_eq!(
+        second.finish(&round1[1..], &round2).err(),
+        Some(FrostError::MissingPackage(1))
+    );
+}
+
+#[test]
+fn frost_keygen_unexpected_package_test() {
+    let mut rng = rand::rngs::OsRng;
+    let dkg = |rng: &mut rand::rngs::OsRng| {
+        let (participants, round1): (Vec<_>, Vec<_>) = (1..=3)
+            .map(|id| DkgParticipant::new(id, 2, 3, rng).expect("failed to start key generation"))
+            .unzip();
+        let round2: Vec<_> = participants.iter().flat_map(|p| p.round2()).collect();
+        (participants.into_iter().next().unwrap(), round1, round2)
+    };
+    // Participant of a larger group
+    let (outsider, outsider_round1) =
+        DkgParticipant::new(4, 2, 4, &mut rng).expect("failed to start key generation");
+
+    // Round 1 package from a sender that is not a participant
+    let (first, mut round1, round2) = dkg(&mut rng);
+    round1.push(outsider_round1);
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::UnexpectedPackage(4))
+    );
+
+    // Round 2 package from a sender that is not a participant
+    let (first, round1, mut round2) = dkg(&mut rng);
+    round2.extend(outsider.round2());
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::UnexpectedPackage(4))
+    );
+
+    // Round 2 package from the recipient itself
+    let (first, round1, mut round2) = dkg(&mut rng);
+    let mut own = round2[0].clone();
+    own.sender = 1;
+    own.recipient = 1;
+    round2.push(own);
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::UnexpectedPackage(1))
+    );
+
+    // Duplicated packages
+    let (first, mut round1, round2) = dkg(&mut rng);
+    round1.push(round1[2].clone());
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::DuplicatePackage(3))
+    );
+    let (first, round1, mut round2) = dkg(&mut rng);
+    let pkg = round2
+        .iter()
+        .find(|pkg| pkg.recipient == 1)
+        .expect("missing package")
+        .clone();
+    round2.push(pkg.clone());
+    assert_eq!(
+        first.finish(&round1, &round2).err(),
+        Some(FrostError::DuplicatePackage(pkg.sender))
+    );
+}
+
+#[test]
+fn frost_sign_test() {
+    let keys = keygen(2, 3);
+    let group_public = &keys[0].public.group_public;
+    let tx = payment(group_public);
+
+    for network_id in [NetworkId::TESTNET, NetworkId::MAINNET] {
+        for mut ctx in [
+            Schnorr::<Transaction>::new_legacy(network_id.clone()),
+            Schnorr::<Transaction>::new_kimchi(network_id.clone()),
+        ] {
+            for signers in [[0, 1], [0, 2], [1, 2]] {
+                let sig = threshold_sign(&mut ctx, &[&keys[signers[0]], &keys[signers[1]]], &tx)
+                    .expect("threshold signing failed");
+                assert!(ctx.verify(&sig, group_public, &tx));
+            }
+
+            // All participants can sign too
+            let sig = threshold_sign(&mut ctx, &keys.iter().collect::<Vec<_>>(), &tx)
+                .expect("threshold signing failed");
+            assert!(ctx.verify(&sig, group_public, &tx));
+        }
+    }
+}
+
+#[test]
+fn frost_sign_failure_test() {
+    let keys = keygen(3, 4);
+    let tx = payment(&keys[0].public.group_public);
+    let mut ctx = Schnorr::<Transaction>::new_kimchi(NetworkId::TESTNET);
+
+    assert_eq!(
+        threshold_sign(&mut ctx, &[&keys[0], &keys[1]], &tx).err(),
+        Some(FrostError::NotEnoughSigners {
+            signers: 2,
+            threshold: 3
+        })
+    );
+
+    // Tampered signature share
+    let mut rng = rand::rngs::OsRng;
+    let signers = [&keys[0], &keys[2], &keys[3]];
+    let (nonces, commitments): (Vec<_>, Vec<_>) = signers
+        .iter()
+        .map(|key| frost::commit(key.id, &mut rng))
+        .unzip();
+    let package = SigningPackage::new(commitments, tx).expect("invalid signing package");
+    let mut shares = nonces
+        .into_iter()
+        .zip(signers)
+        .map(|(nonces, key)| frost::sign(&mut ctx, &package, nonces, key))
+        .collect::<Result<Vec<_>, _>>()
+        .expect("failed to sign");
+    let first_share = shares[0].share;
+    shares[1].share += first_share;
+    assert_eq!(
+        frost::aggregate(&mut ctx, &package, &shares, &keys[0].public).err(),
+        Some(FrostError::InvalidSignatureShare(3))
+    );
+
+    // Signer that did not commit
+    let (nonces, _) = frost::commit(keys[1].id, &mut rng);
+    assert_eq!(
+        frost::sign(&mut ctx, &package, nonces, &keys[1]).err(),
+        Some(FrostError::UnknownSigner(2))
+    );
+
+    // Nonces that do not match the signer's commitments
+    let (nonces, _) = frost::commit(keys[0].id, &mut rng);
+    assert_eq!(
+        frost::sign(&mut ctx, &package, nonces, &keys[0]).err(),
+        Some(FrostError::NonceCommitmentMismatch(1))
+    );
+
+    // Several commitments from the same signer
+    let (_, commitments) = frost::commit(keys[0].id, &mut rng);
+    assert_eq!(
+        SigningPackage::new(
+            vec![commitments.clone(), commitments],
+            payment(&keys[0].public.group_public)
+        )
+        .err(),
+        Some(FrostError::DuplicatePackage(1))
+    );
+}
@@ -0,0 +1,208 @@
+use mina_signer::{
+    hd::{DerivationPath, ExtendedSecKey, HdError, Mnemonic, HARDENED_OFFSET},
+    Keypair,
+};
+use rand::SeedableRng;
+
+const MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+#[test]
+fn mnemonic_to_seed() {
+    let mnemonic = Mnemonic::parse(MNEMONIC).expect("failed to parse mnemonic");
+    assert_eq!(mnemonic.word_count(), 12);
+    assert_eq!(mnemonic.to_string(), MNEMONIC);
+
+    assert_eq!(
+        hex::encode(mnemonic.to_seed("")),
+        "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc19a5ac40b389cd370d086206dec8aa6c43daea6690f20ad3d8d48b2d2ce9e38e4"
+    );
+    assert_eq!(
+        hex::encode(mnemonic.to_seed("TREZOR")),
+        "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04"
+    );
+}
+
+#[test]
+fn mnemonic_invalid() {
+    // Bad checksum
+    assert!(matches!(
+        Mnemonic::parse(&MNEMONIC.replace("about", "abandon")),
+        Err(HdError::Mnemonic)
+    ));
+    // Unknown word
+    assert!(matches!(
+        Mnemonic::parse(&MNEMONIC.replace("about", "mina")),
+        Err(HdError::Mnemonic)
+    ));
+    assert!(matches!(
+        Mnemonic::from_entropy(&[0; 15]),
+        Err(HdError::MnemonicWordCount)
+    ));
+}
+
+#[test]
+fn mnemonic_generate() {
+    let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+    for word_count in [12, 15, 18, 21, 24] {
+        let mnemonic = Mnemonic::generate(&mut rng, word_count).expect("failed to generate");
+        assert_eq!(mnemonic.word_count(), word_count);
+        assert!(Mnemonic::parse(&mnemonic.to_string()).expect("failed to parse") == mnemonic);
+    }
+
+    for word_count in [0, 11, 13, 27] {
+        assert!(matches!(
+            Mnemonic::generate(&mut rng, word_count),
+            Err(HdError::MnemonicWordCount)
+        ));
+    }
+}
+
+#[test]
+fn derivation_path() {
+    let path = DerivationPath::mina(3).expect("failed to create path");
+    assert_eq!(path.to_string(), "m/44'/12586'/3'/0/0");
+    assert_eq!("m/44'/12586'/3'/0/0".parse(), Ok(path.clone()));
+    assert_eq!("m/44h/12586H/3'/0/0".parse(), Ok(path));
+
+    assert_eq!("m".parse(), Ok(DerivationPath::new(vec![])));
+    assert_eq!(
+        "m/0'/2147483647".parse(),
+        Ok(DerivationPath::new(vec![
+            HARDENED_OFFSET,
+            HARDENED_OFFSET - 1
+        ]))
+    );
+
+    for invalid in [
+        "",
+        "44'/0",
+        "m/",
+        "m//0",
+        "m/+1",
+        "m/-1",
+        "m/1''",
+        "m/2147483648",
+    ] {
+        assert_eq!(
+            invalid.parse::<DerivationPath>(),
+            Err(HdError::DerivationPath),
+            "{invalid}"
+        );
+    }
+    assert_eq!(
+        DerivationPath::mina(HARDENED_OFFSET),
+        Err(HdError::DerivationPath)
+    );
+}
+
+#[test]
+fn bip32_test_vector() {
+    // BIP32 test vector 1
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").expect("valid hex");
+    let master = ExtendedSecKey::from_seed(&seed).expect("failed to derive master key");
+    assert_eq!(
+        master.to_base58(),
+        "xprv9s21ZrQH143K3QTDL4LXw2F7HEK3wJUD2nW2nRk4stbPy6cq3jPPqjiChkVvvNKmPGJxWUtg6LnF5kejMRNNU3TGtRBeJgk33yuGBxrMPHi"
+    );
+
+    let child = master
+        .derive_child(HARDENED_OFFSET)
+        .expect("failed to derive child");
+    assert_eq!(child.depth(), 1);
+    assert_eq!(child.child_number(), HARDENED_OFFSET);
+    assert_eq!(
+        child.to_base58(),
+        "xprv9uHRZZhk6KAJC1avXpDAp4MDc3sQKNxDiPvvkX8Br5ngLNv1TxvUxt4cV1rGL5hj6KCesnDYUhd7oWgT11eZG7XnxHrnYeSvkzY7d2bhkJ7"
+    );
+
+    let path = "m/0'/1/2'/2/1000000000".parse().expect("valid path");
+    assert_eq!(
+        master
+            .derive_path(&path)
+            .expect("failed to derive path")
+            .to_base58(),
+        "xprvA41z7zogVVwxVSgdKUHDy1SKmdb533PjDz7J6N6mV6uS3ze1ai8FHa8kmHScGpWmj4WggLyQjgPie1rFSruoUihUZREPSL39UNdE3BBDu76"
+    );
+}
+
+#[test]
+fn extended_key_base58() {
+    let seed = Mnemonic::parse(MNEMONIC)
+        .expect("failed to parse mnemonic")
+        .to_seed("");
+    let master = ExtendedSecKey::from_seed(&seed).expect("failed to derive master key");
+    let key = master
+        .derive_path(&DerivationPath::mina(0).expect("valid account"))
+        .expect("failed to derive path");
+
+    let encoded = key.to_base58();
+    assert_eq!(
+        encoded,
+        "xprvA3MnyVDszgJLqdbTSsZXWnmSSu5WsHdB6FQHwvc6Mws5GxfqLAoCUUfrEGP3doJcxppV3vZB5XA7tXpHaRCTCyoCG69i8UfQEiLGY6icVzh"
+    );
+    let decoded = ExtendedSecKey::from_base58(&encoded).expect("failed to decode");
+    assert!(decoded == key);
+    assert!(ExtendedSecKey::from_base58(&master.to_base58()).expect("failed to decode") == master);
+
+    // Bad checksum
+    let mut bad = encoded.clone();
+    bad.pop();
+    bad.push('i');
+    assert!(matches!(
+        ExtendedSecKey::from_base58(&bad),
+        Err(HdError::ExtendedKeyChecksum)
+    ));
+    assert!(matches!(
+        ExtendedSecKey::from_base58("xprv0"),
+        Err(HdError::ExtendedKeyBase58)
+    ));
+    assert!(matches!(
+        ExtendedSecKey::from_base58("xprv"),
+        Err(HdError::ExtendedKeyBytes)
+    ));
+    // Extended public key
+    assert!(matches!(
+        ExtendedSecKey::from_base58("xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8"),
+        Err(HdError::ExtendedKeyVersion)
+    ));
+}
+
+#[test]
+fn mina_keypairs() {
+    macro_rules! assert_keypair_eq {
+        ($passphrase:expr, $account:expr, $sec_key_hex:expr, $address:expr) => {
+            let mnemonic = Mnemonic::parse(MNEMONIC).expect("failed to parse mnemonic");
+            let kp = Keypair::from_mnemonic(&mnemonic, $passphrase, $account)
+                .expect("failed to derive keypair");
+            assert_eq!(kp.to_hex(), $sec_key_hex);
+            assert_eq!(kp.get_address(), $address);
+        };
+    }
+
+    assert_keypair_eq!(
+        "",
+        0,
+        "16db55140eb2f45c11b63e41bf8369686abcf103f574abe4fe359a982e7a0372",
+        "B62qpqCoBci3mKNrfCnLkKS2SSV9QyrPbPBABe4stVWnRRfkG8sn3t4"
+    );
+    // The secp256k1 key f771e440... has its two most significant bits cleared
+    assert_keypair_eq!(
+        "",
+        1,
+        "3771e440aa74aff511f63b006981d5e85392ecad4f51df6837fb0880b5c5e3b7",
+        "B62qpjWMCLaj72Gbe4dBqsphHR4zSuawTZzKWq5xwgcpPXFV757V9dU"
+    );
+    assert_keypair_eq!(
+        "TREZOR",
+        0,
+        "0c5945e616b03c6a9d49f196543f5053d0f4c6d6e6dcd55dd3a13219981290e1",
+        "B62qmEuxXdF4Q12jhgQnR77zHV7m2XBwiAbHM2x1pAfB3EC3PrA116J"
+    );
+    assert_keypair_eq!(
+        "TREZOR",
+        1,
+        "1570a1eff29ab006a0f4c895eb6917fe66f0ad8035b89e1c98afdc11726718a8",
+        "B62qozm1mvugAsYoUBBdkhySNRUCz5je2NdRC1xszbXs1PDeBewWbhV"
+    );
+}
@@ -1,7 +1,13 @@
 pub mod transaction;
 
 use ark_ff::Zero;
-use mina_signer::{self, BaseField, Keypair, NetworkId, PubKey, ScalarField, Signer};
+use mina_hasher::{DomainParameter, Hashable};
+use mina_signer::{
+    self,
+    nonce::{NonceDerivation, Rfc6979Nonce},
+    BaseField, Keypair, NetworkId, PubKey, ScalarField, Schnorr, Signer,
+};
+use o1_utils::FieldHelpers;
 pub use transaction::Transaction;
 
 enum TransactionType {
@@ -256,3 +262,210 @@ fn sign_delegation_test_4() {
         /* mainnet signature */ "093f9ef0e4e051279da0a3ded85553847590ab739ee1bfd59e5bb30f98ed8a001a7a60d8506e2572164b7a525617a09f17e1756ac37555b72e01b90f37271595"
     );
 }
+
+fn batch_signer_test<S: Signer<Transaction>>(mut ctx: S) {
+    let receiver = PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+        .expect("invalid address");
+    let mut batch: Vec<(mina_signer::Signature, PubKey, Transaction)> = (0..8)
+        .map(|i| {
+            let kp = Keypair::rand(&mut rand::rngs::OsRng).expect("failed to create keypair");
+            let tx = Transaction::new_payment(
+                kp.public.clone(),
+                receiver.clone(),
+                1729000000000 + i,
+                2000000000,
+                i as u32,
+            );
+            (ctx.sign(&kp, &tx), kp.public, tx)
+        })
+        .collect();
+
+    assert_eq!(ctx.batch_verify(&[]), Ok(()));
+    assert_eq!(ctx.batch_verify(&batch), Ok(()));
+
+    // Tamper with a single input
+    batch[5].2.amount += 1;
+    assert_eq!(ctx.batch_verify(&batch), Err(5));
+
+    // Signature whose rx is not the x-coordinate of a curve point
+    batch[5].2.amount -= 1;
+    batch[2].0.rx = BaseField::zero();
+    assert_eq!(ctx.batch_verify(&batch), Err(2));
+
+    // Signature made with a different key
+    batch[2].0 = ctx.sign(
+        &Keypair::rand(&mut rand::rngs::OsRng).expect("failed to create keypair"),
+        &batch[2].2,
+    );
+    assert_eq!(ctx.batch_verify(&batch), Err(2));
+
+    // The first invalid signature is reported, even when a later rx is not the
+    // x-coordinate of a curve point
+    batch[4].0.rx = BaseField::zero();
+    assert_eq!(ctx.batch_verify(&batch), Err(2));
+}
+
+#[test]
+fn batch_verify_legacy_test() {
+    batch_signer_test(mina_signer::create_legacy::<Transaction>(
+        NetworkId::TESTNET,
+    ));
+    batch_signer_test(mina_signer::create_legacy::<Transaction>(
+        NetworkId::MAINNET,
+    ));
+}
+
+#[test]
+fn batch_verify_kimchi_test() {
+    batch_signer_test(mina_signer::create_kimchi::<Transaction>(
+        NetworkId::TESTNET,
+    ));
+    batch_signer_test(mina_signer::create_kimchi::<Transaction>(
+        NetworkId::MAINNET,
+    ));
+}
+
+#[test]
+fn signing_transcript_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let tx = Transaction::new_payment(
+        kp.public.clone(),
+        PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address"),
+        1729000000000,
+        2000000000,
+        16,
+    )
+    .set_valid_until(271828)
+    .set_memo_str("Hello Mina!");
+
+    let mut ctx = Schnorr::<Transaction>::new_legacy(NetworkId::TESTNET);
+    let transcript = ctx.sign_with_transcript(&kp, &tx);
+
+    // Same signature as signer_test_raw
+    assert_eq!(transcript.signature.to_string(),
+                "11a36a8dfe5b857b95a2a7b7b17c62c3ea33411ae6f4eb3a907064aecae353c60794f1d0288322fe3f8bb69d6fabd4fd7c15f8d09f8783b2f087a80407e299af");
+    assert!(ctx.verify(&transcript.signature, &kp.public, &tx));
+
+    // Intermediate values are consistent with the signature
+    assert_eq!(transcript.nonce_point.x, transcript.signature.rx);
+    assert_eq!(
+        transcript.signature.s,
+        transcript.nonce + transcript.challenge * kp.secret.scalar()
+    );
+    let tx_fields = tx.to_roinput().to_fields();
+    assert!(transcript.message_fields.len() > tx_fields.len());
+    assert!(transcript.message_fields.windows(3).any(|w| w
+        == [
+            kp.public.point().x,
+            kp.public.point().y,
+            transcript.signature.rx
+        ]));
+}
+
+#[test]
+fn rfc6979_nonce_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let tx = Transaction::new_payment(
+        kp.public.clone(),
+        PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address"),
+        1729000000000,
+        2000000000,
+        16,
+    );
+
+    // Deterministic and domain separated
+    let testnet_nonce = Rfc6979Nonce.derive_nonce(&kp, &tx.to_roinput(), &[0x00]);
+    let mainnet_nonce = Rfc6979Nonce.derive_nonce(&kp, &tx.to_roinput(), &[0x01]);
+    assert_eq!(
+        testnet_nonce,
+        Rfc6979Nonce.derive_nonce(&kp, &tx.to_roinput(), &[0x00])
+    );
+    assert_ne!(testnet_nonce, mainnet_nonce);
+
+    // Known answer, computed with an independent implementation of RFC 6979 over the
+    // Pallas scalar field, using Python's hmac and hashlib, with the message
+    // `tx.to_roinput().to_bytes()` followed by the domain parameter
+    assert_eq!(
+        testnet_nonce.to_hex(),
+        "cae1855b7af334240565aa995d7eee8725af6becaef3a31f9d4e8e80d24ac912"
+    );
+
+    for network_id in [NetworkId::TESTNET, NetworkId::MAINNET] {
+        let mut default_ctx = Schnorr::<Transaction>::new_kimchi(network_id.clone());
+        let mut ctx =
+            Schnorr::<Transaction>::new_kimchi(network_id).set_nonce_derivation(Rfc6979Nonce);
+
+        let sig = ctx.sign(&kp, &tx);
+        assert_eq!(sig, ctx.sign(&kp, &tx));
+        assert_ne!(sig, default_ctx.sign(&kp, &tx));
+
+        // Signatures verify regardless of how the nonce was derived
+        assert!(ctx.verify(&sig, &kp.public, &tx));
+        assert!(default_ctx.verify(&sig, &kp.public, &tx));
+    }
+}
+
+#[test]
+fn custom_network_test() {
+    assert_eq!(
+        NetworkId::MAINNET.into_domain_string(),
+        "MinaSignatureMainnet"
+    );
+    assert_eq!(NetworkId::TESTNET.into_domain_string(), "CodaSignature");
+    assert_eq!(
+        NetworkId::custom("devnet").into_domain_string(),
+        "devnetSignature"
+    );
+    // Truncated to the maximum domain string length
+    assert_eq!(
+        NetworkId::custom("private-network").into_domain_string(),
+        "private-networkSigna"
+    );
+
+    assert_eq!(NetworkId::TESTNET.into_bytes(), vec![0x00]);
+    assert_eq!(NetworkId::MAINNET.into_bytes(), vec![0x01]);
+    assert_eq!(NetworkId::custom("devnet").into_bytes(), b"devnet".to_vec());
+    assert_eq!(u8::try_from(NetworkId::MAINNET), Ok(0x01));
+    assert_eq!(
+        u8::try_from(NetworkId::custom("devnet")),
+        Err(NetworkId::custom("devnet"))
+    );
+
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let tx = Transaction::new_payment(
+        kp.public.clone(),
+        PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address"),
+        1729000000000,
+        2000000000,
+        16,
+    );
+
+    let mut testnet_ctx = mina_signer::create_legacy::<Transaction>(NetworkId::TESTNET);
+    let mut mainnet_ctx = mina_signer::create_legacy::<Transaction>(NetworkId::MAINNET);
+    let mut devnet_ctx = mina_signer::create_legacy::<Transaction>(NetworkId::custom("devnet"));
+    let mut other_ctx = mina_signer::create_legacy::<Transaction>(NetworkId::custom("other"));
+
+    let sig = devnet_ctx.sign(&kp, &tx);
+    assert_eq!(sig, devnet_ctx.sign(&kp, &tx));
+    assert!(devnet_ctx.verify(&sig, &kp.public, &tx));
+
+    // Signatures on custom networks are domain separated from each other and from
+    // testnet and mainnet signatures
+    assert_ne!(sig, testnet_ctx.sign(&kp, &tx));
+    assert_ne!(sig, mainnet_ctx.sign(&kp, &tx));
+    assert!(!testnet_ctx.verify(&sig, &kp.public, &tx));
+    assert!(!mainnet_ctx.verify(&sig, &kp.public, &tx));
+    assert!(!other_ctx.verify(&sig, &kp.public, &tx));
+
+    let mut devnet_ctx = mina_signer::create_kimchi::<Transaction>(NetworkId::custom("devnet"));
+    let mut testnet_ctx = mina_signer::create_kimchi::<Transaction>(NetworkId::TESTNET);
+    let sig = devnet_ctx.sign(&kp, &tx);
+    assert!(devnet_ctx.verify(&sig, &kp.public, &tx));
+    assert!(!testnet_ctx.verify(&sig, &kp.public, &tx));
+}
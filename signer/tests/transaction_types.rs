@@ -0,0 +1,474 @@
+pub mod transaction;
+
+use mina_hasher::Hashable;
+use mina_signer::{
+    self,
+    transaction::{TransactionError, MEMO_BYTES},
+    zkapp::{
+        self, AccountUpdate, AccountUpdateBody, AuthorizationKind, BalanceChange, ClosedInterval,
+        Control, FeePayer, FeePayerBody, Sign, TransactionCommitment,
+    },
+    CompressedPubKey, Keypair, Memo, NetworkId, Payment, PubKey, Signer, StakeDelegation,
+    ZkappCommand,
+};
+use transaction::Transaction;
+
+#[test]
+fn memo_test() {
+    let memo = Memo::default();
+    assert_eq!(memo.data(), b"");
+    assert_eq!(
+        memo.to_base58(),
+        "E4YM2vTHhWEg66xpj52JErHUBU4pZ1yageL4TVDDpTTSsv8mK6YaH"
+    );
+    assert_eq!(
+        Memo::from_base58("E4YM2vTHhWEg66xpj52JErHUBU4pZ1yageL4TVDDpTTSsv8mK6YaH"),
+        Ok(memo)
+    );
+
+    let memo = Memo::new(b"Hello Mina!").expect("failed to create memo");
+    assert_eq!(memo.data(), b"Hello Mina!");
+    assert_eq!(Memo::from_base58(&memo.to_base58()), Ok(memo.clone()));
+    assert_eq!(Memo::from_bytes(memo.as_bytes()), Ok(memo));
+
+    assert_eq!(
+        Memo::new(&[0; MEMO_BYTES - 1]),
+        Err(TransactionError::MemoLength)
+    );
+    assert_eq!(
+        Memo::from_str_truncate("012345678901234567890123456789012345").data(),
+        b"01234567890123456789012345678901"
+    );
+    assert_eq!(
+        Memo::from_base58("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt"),
+        Err(TransactionError::MemoBase58)
+    );
+}
+
+#[test]
+fn payment_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let receiver = PubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+        .expect("invalid address");
+
+    let payment = Payment::new(
+        kp.public.into_compressed(),
+        receiver.into_compressed(),
+        1729000000000,
+        2000000000,
+        16,
+    )
+    .set_valid_until(271828)
+    .set_memo(Memo::from_str_truncate("Hello Mina!"));
+    let tx = Transaction::new_payment(kp.public.clone(), receiver, 1729000000000, 2000000000, 16)
+        .set_valid_until(271828)
+        .set_memo_str("Hello Mina!");
+    assert_eq!(payment.to_roinput(), tx.to_roinput());
+
+    let mut testnet_ctx = mina_signer::create_legacy::<Payment>(NetworkId::TESTNET);
+    let mut mainnet_ctx = mina_signer::create_legacy::<Payment>(NetworkId::MAINNET);
+    let testnet_sig = testnet_ctx.sign(&kp, &payment);
+    let mainnet_sig = mainnet_ctx.sign(&kp, &payment);
+    assert_eq!(testnet_sig.to_string(), "11a36a8dfe5b857b95a2a7b7b17c62c3ea33411ae6f4eb3a907064aecae353c60794f1d0288322fe3f8bb69d6fabd4fd7c15f8d09f8783b2f087a80407e299af");
+    assert_eq!(mainnet_sig.to_string(), "124c592178ed380cdffb11a9f8e1521bf940e39c13f37ba4c55bb4454ea69fba3c3595a55b06dac86261bb8ab97126bf3f7fff70270300cb97ff41401a5ef789");
+    assert!(testnet_ctx.verify(&testnet_sig, &kp.public, &payment));
+    assert!(mainnet_ctx.verify(&mainnet_sig, &kp.public, &payment));
+}
+
+#[test]
+fn stake_delegation_test() {
+    let kp = Keypair::from_hex("20f84123a26e58dd32b0ea3c80381f35cd01bc22a20346cc65b0a67ae48532ba")
+        .expect("failed to create keypair");
+    let delegate = PubKey::from_address("B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV")
+        .expect("invalid address");
+
+    let delegation = StakeDelegation::new(
+        kp.public.into_compressed(),
+        delegate.into_compressed(),
+        2000000000,
+        0,
+    );
+    let tx = Transaction::new_delegation(kp.public.clone(), delegate, 2000000000, 0);
+    assert_eq!(delegation.to_roinput(), tx.to_roinput());
+
+    let mut testnet_ctx = mina_signer::create_legacy::<StakeDelegation>(NetworkId::TESTNET);
+    let mut mainnet_ctx = mina_signer::create_legacy::<StakeDelegation>(NetworkId::MAINNET);
+    assert_eq!(testnet_ctx.sign(&kp, &delegation).to_string(), "07e9f88fc671ed06781f9edb233fdbdee20fa32303015e795747ad9e43fcb47b3ce34e27e31f7c667756403df3eb4ce670d9175dd0ae8490b273485b71c56066");
+    assert_eq!(mainnet_ctx.sign(&kp, &delegation).to_string(), "2406ab43f8201bd32bdd81b361fdb7871979c0eec4e3b7a91edf87473963c8a4069f4811ebc5a0e85cbb4951bffe93b638e230ce5a250cb08d2c250113a1967c");
+}
+
+#[test]
+fn signed_command_json_test() {
+    let json = r#"{
+        "from": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV",
+        "to": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+        "amount": "1729000000000",
+        "fee": "2000000000",
+        "nonce": "16",
+        "validUntil": "271828",
+        "memo": "Hello Mina!"
+    }"#;
+    let payment: Payment = serde_json::from_str(json).expect("failed to parse payment");
+    assert_eq!(payment.amount, 1729000000000);
+    assert_eq!(payment.valid_until, 271828);
+    assert_eq!(payment.memo.data(), b"Hello Mina!");
+    assert_eq!(
+        payment.from.into_address(),
+        "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV"
+    );
+    let serialized = serde_json::to_string(&payment).expect("failed to serialize payment");
+    assert_eq!(
+        serde_json::from_str::<Payment>(&serialized).expect("failed to parse payment"),
+        payment
+    );
+
+    // Optional fields
+    let json = r#"{
+        "from": "B62qkiT4kgCawkSEF84ga5kP9QnhmTJEYzcfgGuk6okAJtSBfVcjm1M",
+        "to": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV",
+        "fee": "2000000000",
+        "nonce": "0"
+    }"#;
+    let delegation: StakeDelegation =
+        serde_json::from_str(json).expect("failed to parse delegation");
+    assert_eq!(delegation.valid_until, u32::MAX);
+    assert_eq!(delegation.memo, Memo::default());
+}
+
+fn zkapp_command(fee_payer: &Keypair, receiver: CompressedPubKey) -> ZkappCommand {
+    let sender = fee_payer.public.into_compressed();
+    let mut sender_body = AccountUpdateBody::new(
+        sender,
+        BalanceChange {
+            magnitude: 1000,
+            sgn: Sign::Negative,
+        },
+    );
+    sender_body.use_full_commitment = true;
+    sender_body.authorization_kind = AuthorizationKind::signature();
+    let mut receiver_body = AccountUpdateBody::new(
+        receiver,
+        BalanceChange {
+            magnitude: 1000,
+            sgn: Sign::Positive,
+        },
+    );
+    receiver_body.call_depth = 1;
+
+    ZkappCommand {
+        fee_payer: FeePayer {
+            body: FeePayerBody {
+                public_key: fee_payer.public.into_compressed(),
+                fee: 100000000,
+                valid_until: None,
+                nonce: 1,
+            },
+            authorization: None,
+        },
+        account_updates: vec![
+            AccountUpdate {
+                body: sender_body,
+                authorization: Control::default(),
+            },
+            AccountUpdate {
+                body: receiver_body,
+                authorization: Control::default(),
+            },
+        ],
+        memo: Memo::from_str_truncate("zkApp"),
+    }
+}
+
+#[test]
+fn zkapp_command_commitment_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let receiver =
+        CompressedPubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address");
+    let cmd = zkapp_command(&kp, receiver);
+
+    // Commitments are domain separated
+    let commitment = cmd.commitment(NetworkId::TESTNET);
+    let full_commitment = cmd.full_commitment(NetworkId::TESTNET);
+    assert_ne!(commitment, full_commitment);
+    assert_ne!(commitment, cmd.commitment(NetworkId::MAINNET));
+    assert_ne!(commitment, cmd.commitment(NetworkId::custom("testnet")));
+
+    // The commitment does not depend on the memo and the fee payer
+    let mut other = cmd.clone();
+    other.memo = Memo::default();
+    other.fee_payer.body.fee += 1;
+    assert_eq!(other.commitment(NetworkId::TESTNET), commitment);
+    assert_ne!(other.full_commitment(NetworkId::TESTNET), full_commitment);
+
+    // The commitment depends on the call forest structure
+    let mut other = cmd.clone();
+    other.account_updates[1].body.call_depth = 0;
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+    let mut other = cmd;
+    other.account_updates.swap(0, 1);
+    other.account_updates[0].body.call_depth = 0;
+    other.account_updates[1].body.call_depth = 1;
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+}
+
+#[test]
+fn zkapp_command_sign_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let receiver =
+        CompressedPubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address");
+
+    for network_id in [
+        NetworkId::TESTNET,
+        NetworkId::MAINNET,
+        NetworkId::custom("devnet"),
+    ] {
+        let mut cmd = zkapp_command(&kp, receiver.clone());
+        let mut ctx = mina_signer::create_kimchi::<TransactionCommitment>(network_id.clone());
+        cmd.sign(&mut ctx, &kp, network_id.clone());
+
+        let full_commitment = cmd.full_commitment(network_id);
+        let fee_payer_sig = cmd
+            .fee_payer
+            .authorization
+            .clone()
+            .expect("missing fee payer signature");
+        assert!(ctx.verify(&fee_payer_sig, &kp.public, &full_commitment));
+        let sender_sig = cmd.account_updates[0]
+            .authorization
+            .signature
+            .clone()
+            .expect("missing account update signature");
+        assert!(ctx.verify(&sender_sig, &kp.public, &full_commitment));
+        assert_eq!(cmd.account_updates[1].authorization.signature, None);
+
+        // JSON roundtrip
+        let json = serde_json::to_string(&cmd).expect("failed to serialize zkApp command");
+        assert_eq!(
+            serde_json::from_str::<ZkappCommand>(&json).expect("failed to parse zkApp command"),
+            cmd
+        );
+    }
+}
+
+/// Body of an account update in the GraphQL `ZkappCommandInput` format, with every
+/// update kept and every precondition ignored
+const ACCOUNT_UPDATE_BODY_JSON: &str = r#"{
+    "publicKey": "B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt",
+    "tokenId": "wSHV2S4qX9jFsLjQo8r1BsMLH2ZRKsZx6EJd1sbozGPieEC4Jf",
+    "update": {
+        "appState": [null, null, null, null, null, null, null, null],
+        "delegate": null,
+        "verificationKey": null,
+        "permissions": null,
+        "zkappUri": null,
+        "tokenSymbol": null,
+        "timing": null,
+        "votingFor": null
+    },
+    "balanceChange": { "magnitude": "1000", "sgn": "Positive" },
+    "incrementNonce": false,
+    "events": [],
+    "actions": [],
+    "callData": "0",
+    "callDepth": 0,
+    "preconditions": {
+        "network": {
+            "snarkedLedgerHash": null,
+            "blockchainLength": null,
+            "minWindowDensity": null,
+            "totalCurrency": null,
+            "globalSlotSinceGenesis": null,
+            "stakingEpochData": {
+                "ledger": { "hash": null, "totalCurrency": null },
+                "seed": null,
+                "startCheckpoint": null,
+                "lockCheckpoint": null,
+                "epochLength": null
+            },
+            "nextEpochData": {
+                "ledger": { "hash": null, "totalCurrency": null },
+                "seed": null,
+                "startCheckpoint": null,
+                "lockCheckpoint": null,
+                "epochLength": null
+            }
+        },
+        "account": {
+            "balance": null,
+            "nonce": null,
+            "receiptChainHash": null,
+            "delegate": null,
+            "state": [null, null, null, null, null, null, null, null],
+            "actionState": null,
+            "provedState": null,
+            "isNew": null
+        },
+        "validWhile": null
+    },
+    "useFullCommitment": false,
+    "implicitAccountCreationFee": true,
+    "mayUseToken": { "parentsOwnToken": false, "inheritFromParent": false },
+    "authorizationKind": {
+        "isSigned": false,
+        "isProved": false,
+        "verificationKeyHash": "3392518251768960475377392625298437850623664973002200885669375116181514017494"
+    }
+}"#;
+
+fn zkapp_command_json(body: &str) -> String {
+    format!(
+        r#"{{
+            "feePayer": {{
+                "body": {{
+                    "publicKey": "B62qnzbXmRNo9q32n4SNu2mpB8e7FYYLH8NmaX6oFCBYjjQ8SbD7uzV",
+                    "fee": "100000000",
+                    "validUntil": null,
+                    "nonce": "1"
+                }},
+                "authorization": null
+            }},
+            "accountUpdates": [
+                {{
+                    "body": {body},
+                    "authorization": {{ "proof": null, "signature": null }}
+                }}
+            ],
+            "memo": "E4YM2vTHhWEg66xpj52JErHUBU4pZ1yageL4TVDDpTTSsv8mK6YaH"
+        }}"#
+    )
+}
+
+#[test]
+fn zkapp_command_json_test() {
+    let json = zkapp_command_json(ACCOUNT_UPDATE_BODY_JSON);
+    let cmd: ZkappCommand = serde_json::from_str(&json).expect("failed to parse zkApp command");
+    assert_eq!(cmd.fee_payer.body.fee, 100000000);
+    assert_eq!(cmd.memo, Memo::default());
+    assert_eq!(cmd.account_updates.len(), 1);
+    let body = &cmd.account_updates[0].body;
+    assert_eq!(body.token_id, Default::default());
+    assert_eq!(body.balance_change.sgn, Sign::Positive);
+    assert!(body.implicit_account_creation_fee);
+
+    // Kept updates and ignored preconditions are the defaults
+    let mut expected = AccountUpdateBody::new(
+        body.public_key.clone(),
+        BalanceChange {
+            magnitude: 1000,
+            sgn: Sign::Positive,
+        },
+    );
+    expected.implicit_account_creation_fee = true;
+    assert_eq!(body, &expected);
+
+    // JSON roundtrip
+    let json = serde_json::to_string(&cmd).expect("failed to serialize zkApp command");
+    assert_eq!(
+        serde_json::from_str::<ZkappCommand>(&json).expect("failed to parse zkApp command"),
+        cmd
+    );
+}
+
+#[test]
+fn zkapp_command_json_rejects_unknown_fields_test() {
+    // Unknown field in the body
+    let body = ACCOUNT_UPDATE_BODY_JSON.replacen('{', r#"{ "unknownField": "0","#, 1);
+    assert!(serde_json::from_str::<ZkappCommand>(&zkapp_command_json(&body)).is_err());
+
+    // Unknown field in a precondition
+    let body = ACCOUNT_UPDATE_BODY_JSON.replace(
+        r#""validWhile": null"#,
+        r#""validWhile": null, "unknownField": null"#,
+    );
+    assert!(serde_json::from_str::<ZkappCommand>(&zkapp_command_json(&body)).is_err());
+
+    // Missing field
+    let body = ACCOUNT_UPDATE_BODY_JSON.replace(r#""events": [],"#, "");
+    assert!(serde_json::from_str::<ZkappCommand>(&zkapp_command_json(&body)).is_err());
+
+    // Token symbol too long
+    let body =
+        ACCOUNT_UPDATE_BODY_JSON.replace(r#""tokenSymbol": null"#, r#""tokenSymbol": "SEVENCH""#);
+    assert!(serde_json::from_str::<ZkappCommand>(&zkapp_command_json(&body)).is_err());
+}
+
+#[test]
+fn zkapp_command_body_fields_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let receiver =
+        CompressedPubKey::from_address("B62qicipYxyEHu7QjUqS7QvBipTs5CzgkYZZZkPoKVYBu6tnDUcE9Zt")
+            .expect("invalid address");
+    let cmd = zkapp_command(&kp, receiver);
+    let commitment = cmd.commitment(NetworkId::TESTNET);
+    let full_commitment = cmd.full_commitment(NetworkId::TESTNET);
+
+    // Every part of the body is committed to
+    let mut other = cmd.clone();
+    other.account_updates[0].body.update.app_state[0] = Some(0u64.into());
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+    let mut other = cmd.clone();
+    other.account_updates[0].body.events = vec![vec![1u64.into()]];
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+    let mut other = cmd.clone();
+    other.account_updates[0].body.actions = vec![vec![1u64.into()]];
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+    let mut other = cmd.clone();
+    other.account_updates[0].body.preconditions.account.nonce = Some(ClosedInterval::singleton(1));
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+    let mut other = cmd.clone();
+    other.account_updates[0]
+        .body
+        .may_use_token
+        .inherit_from_parent = true;
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+
+    // Checking the action state against the empty one is not ignoring it
+    let mut other = cmd.clone();
+    other.account_updates[0]
+        .body
+        .preconditions
+        .account
+        .action_state = Some(zkapp::empty_action_state());
+    assert_ne!(other.commitment(NetworkId::TESTNET), commitment);
+
+    // The validity and the nonce of the fee payer are part of the full commitment
+    let mut other = cmd.clone();
+    other.fee_payer.body.valid_until = Some(100);
+    assert_eq!(other.commitment(NetworkId::TESTNET), commitment);
+    assert_ne!(other.full_commitment(NetworkId::TESTNET), full_commitment);
+    other.fee_payer.body.valid_until = Some(u32::MAX);
+    assert_eq!(other.full_commitment(NetworkId::TESTNET), full_commitment);
+    let mut other = cmd;
+    other.fee_payer.body.nonce += 1;
+    assert_ne!(other.full_commitment(NetworkId::TESTNET), full_commitment);
+}
+
+#[test]
+fn empty_action_state_test() {
+    // Action state of the zkApp accounts without actions, as reported by the Mina node
+    assert_eq!(
+        zkapp::empty_action_state().to_string(),
+        "25079927036070901246064867767436987657692091363973573142121686150614948079097"
+    );
+}
+
+#[test]
+fn memo_text_test() {
+    let kp = Keypair::from_hex("164244176fddb5d769b7de2027469d027ad428fadcc0c02396e6280142efb718")
+        .expect("failed to create keypair");
+    let mut payment = Payment::new(
+        kp.public.into_compressed(),
+        kp.public.into_compressed(),
+        1,
+        1,
+        0,
+    );
+    payment.memo = Memo::new(&[0xff, 0xfe]).expect("failed to create memo");
+    assert!(serde_json::to_string(&payment).is_err());
+}
@@ -20,6 +20,23 @@ if (witness) {
     }
 }
 
+// display the constraints that the witness does not satisfy, if any
+const failures = {};
+if (report) {
+    report.failures.forEach((failure) => {
+        const [kind, details] = Object.entries(failure)[0];
+        const row = details.row ?? details.src.row;
+        if (!(row in failures)) {
+            failures[row] = [];
+        }
+        failures[row].push(describeFailure(kind, details));
+    });
+
+    if (report.failures.length > 0) {
+        document.querySelector("#gates thead tr").innerHTML += '<th scope="col" class="px-6 py-3 text-left text-xs font-medium text-gray-500 uppercase tracking-wider">Failures</th > ';
+    }
+}
+
 // display gates
 let poseidon = false
 cs.gates.forEach((g, row) => {
@@ -58,12 +75,18 @@ cs.gates.forEach((g, row) => {
         });
     }
 
-    const gate = `<tr>
+    let failures_col = '';
+    if (row in failures) {
+        failures_col = `<td class="px-6 py-4 whitespace-nowrap text-xs text-red-700"><ul>${failures[row].map((f) => `<li>${f}</li>`).join('')}</ul></td>`;
+    }
+
+    const gate = `<tr class="${row in failures ? 'bg-red-100' : ''}">
                 <td class="px-6 py-4 whitespace-nowrap">${row}</td>
                 <td class="px-6 py-4 whitespace-nowrap ${gateColor(g.typ)}">${typ}</td>
                 <td class="px-6 py-4 whitespace-nowrap">${wiring}</td>
                 ${coeffs}
                 ${witness_cols}
+                ${failures_col}
             </tr>`;
     document.querySelector("#gates tbody").innerHTML += gate;
 });
@@ -103,6 +126,21 @@ function parseWiring(row, wires) {
     }
 }
 
+// describe a failure of the witness report
+function describeFailure(kind, f) {
+    if (kind == "Gate") {
+        return `constraint ${f.constraint} evaluates to ${toTruncatedHex(f.evaluation)}`;
+    } else if (kind == "CopyConstraint") {
+        return `copy constraint col ${f.src.col} -> (row: ${f.dst.row}, col: ${f.dst.col}): ${toTruncatedHex(f.src_value)} != ${toTruncatedHex(f.dst_value)}`;
+    } else if (kind == "WireColumn") {
+        return `col ${f.src.col} is wired to col ${f.dst.col}, which is not permuted`;
+    } else if (kind == "IncorrectPublic") {
+        return "incorrect public input gate";
+    } else { // Lookup
+        return `lookup of (${f.entry.map(toTruncatedHex).join(', ')}) is not in the tables`;
+    }
+}
+
 // bytearrays to hex
 function toHexString(byteArray) {
     if (byteArray == 0) {
@@ -81,6 +81,16 @@ pub fn visu<G: KimchiCurve>(
 ) where
     G::BaseField: PrimeField,
 {
+    // report the constraints that the witness does not satisfy
+    let report = witness.as_ref().map(|witness| {
+        let public: Vec<_> = witness.as_inner()[0]
+            .iter()
+            .take(index.cs.public)
+            .copied()
+            .collect();
+        index.witness_report(witness.as_inner(), &public, &[])
+    });
+
     // serialize index
     let index = serde_json::to_string(index).expect("couldn't serialize index");
     let mut data = format!("const index = {index};");
@@ -93,6 +103,14 @@ pub fn visu<G: KimchiCurve>(
         data.push_str("const witness = null;");
     }
 
+    // serialize report
+    if let Some(report) = report {
+        let report = serde_json::to_string(&report).expect("couldn't serialize report");
+        data = format!("{data}const report = {report};");
+    } else {
+        data.push_str("const report = null;");
+    }
+
     // serialize constraints
     let constraints = latex_constraints::<G>();
     let constraints = serde_json::to_string(&constraints).expect("couldn't serialize constraints");
@@ -51,8 +51,8 @@ fn main() {
 
             let poseidon_output = &mut gates[row].wires;
             poseidon_output[0] = Wire { row: 0, col: 0 };
-            poseidon_output[1] = Wire { row: 0, col: 1 };
-            poseidon_output[2] = Wire { row: 0, col: 2 };
+            poseidon_output[1] = Wire { row: 1, col: 0 };
+            poseidon_output[2] = Wire { row: 2, col: 0 };
         }
 
         (gates, row)
@@ -66,6 +66,11 @@ fn main() {
     let input = [1u32.into(), 2u32.into(), 3u32.into()];
     generate_witness(3, poseidon_params, &mut witness, input);
 
+    // the public input is the output of poseidon
+    for i in 0..public {
+        witness[0][i] = witness[i][row];
+    }
+
     // create the HTML
     visu(&index, Some(witness.into()));
 }
@@ -34,6 +34,11 @@ where
     pub fn inner(self) -> [Vec<F>; COLUMNS] {
         self.inner
     }
+
+    /// Returns a reference to the inner witness.
+    pub fn as_inner(&self) -> &[Vec<F>; COLUMNS] {
+        &self.inner
+    }
 }
 
 impl<F> From<[Vec<F>; COLUMNS]> for Witness<F>